@@ -1,4 +1,6 @@
 // SPDX-License-Identifier: GPL-3.0-only
+mod dead_code_report;
+mod extract_audio;
 mod shuffle_textures;
 
 use clap::ValueEnum;
@@ -15,6 +17,9 @@ pub enum Action {
     SerializeTexturesBz2Qoi,
     OptimizeMemory,
     ShuffleTextures,
+    DeadCodeReport,
+    ExtractAudio,
+    SizeBreakdown,
     Most,
 }
 
@@ -29,6 +34,9 @@ impl Action {
             Self::SerializeTexturesBz2Qoi => serialize_textures(data, Format::Bz2Qoi)?,
             Self::OptimizeMemory => data.optimize_memory(),
             Self::ShuffleTextures => shuffle_textures::shuffle_textures(data),
+            Self::DeadCodeReport => dead_code_report::dead_code_report(data)?,
+            Self::ExtractAudio => extract_audio::extract_audio(data)?,
+            Self::SizeBreakdown => println!("{}", data.size_breakdown()?),
             Self::Most => data.post_deserialize()?,
         }
         Ok(())
@@ -0,0 +1,238 @@
+// SPDX-License-Identifier: GPL-3.0-only
+//! Reports assets without any inbound references, as candidates for stripping.
+//!
+//! Assets referenced by their plain index in code (which is how asset
+//! references were compiled before GMS 2.3) cannot be detected.
+//! Assets whose name is used as a string literal in code are flagged,
+//! since they may still be looked up using `asset_get_index`.
+
+use std::any::Any;
+use std::borrow::Cow;
+use std::collections::HashSet;
+
+use libgm::gml::Code;
+use libgm::gml::instruction::PushValue;
+use libgm::prelude::*;
+use libgm::wad::elem::game_object::EventGroups;
+use libgm::wad::elem::game_object::GameObject;
+use libgm::wad::elem::game_object::event::Action;
+use libgm::wad::elem::sound::Sound;
+use libgm::wad::elem::sprite::Sprite;
+use libgm::wad::visit_asset_refs;
+
+#[derive(Debug, Default)]
+struct References<'a> {
+    sprites: HashSet<GMRef<Sprite>>,
+    sounds: HashSet<GMRef<Sound>>,
+    objects: HashSet<GMRef<GameObject>>,
+    codes: HashSet<GMRef<Code>>,
+    /// All string literals pushed in code.
+    strings: HashSet<&'a str>,
+}
+
+impl References<'_> {
+    fn add_actions(&mut self, actions: &[Action]) {
+        for action in actions {
+            self.codes.insert(action.code);
+        }
+    }
+
+    fn add_events(&mut self, events: &EventGroups) {
+        macro_rules! add {
+            ($($field:ident),*) => {
+                $(for event in &events.$field {
+                    self.add_actions(&event.actions);
+                })*
+            };
+        }
+        add!(
+            create,
+            destroy,
+            alarm,
+            step,
+            collision,
+            keyboard,
+            mouse,
+            other,
+            draw,
+            key_press,
+            key_release,
+            trigger,
+            cleanup,
+            gesture,
+            pre_create
+        );
+    }
+}
+
+/// Collects all sprite, sound and game object references.
+fn collect_asset_references(data: &mut GMData, refs: &mut References) {
+    // Texture groups list every sprite they contain, which is not a use.
+    let texture_group_infos = std::mem::take(&mut data.texture_group_infos);
+    visit_asset_refs(data, &mut |any: &mut dyn Any| {
+        if let Some(sprite) = any.downcast_mut::<GMRef<Sprite>>() {
+            refs.sprites.insert(*sprite);
+        } else if let Some(sound) = any.downcast_mut::<GMRef<Sound>>() {
+            refs.sounds.insert(*sound);
+        } else if let Some(object) = any.downcast_mut::<GMRef<GameObject>>() {
+            refs.objects.insert(*object);
+        }
+    });
+    data.texture_group_infos = texture_group_infos;
+}
+
+/// Collects all code references and string literals.
+fn collect_references<'a>(data: &'a GMData, refs: &mut References<'a>) -> Result<()> {
+    for object in data.game_objects.elements() {
+        refs.add_events(&object.events);
+    }
+
+    for room in data.rooms.elements() {
+        refs.codes.insert(room.creation_code);
+        for game_object in &room.game_objects {
+            refs.codes.insert(game_object.creation_code);
+            refs.codes.insert(game_object.pre_create_code);
+        }
+    }
+
+    for script in &data.scripts.elems {
+        refs.codes.insert(script.code);
+    }
+    for timeline in data.timelines.elements() {
+        for moment in &timeline.moments {
+            refs.add_actions(&moment.actions);
+        }
+    }
+    refs.codes.extend(&data.global_init_scripts.elems);
+    refs.codes.extend(&data.game_end_scripts.elems);
+
+    for code in &data.codes.elems {
+        for instruction in &code.instructions {
+            if let Some(PushValue::String(string)) = instruction.push_value() {
+                refs.strings.insert(data.strings.by_ref(string)?);
+            }
+        }
+    }
+
+    // Child code entries of referenced code entries are referenced as well.
+    for (code_ref, code) in data.codes.element_refs() {
+        if refs.codes.contains(&code.parent()) {
+            refs.codes.insert(code_ref);
+        }
+    }
+
+    Ok(())
+}
+
+struct Entry<'a> {
+    name: &'a str,
+    size: Option<usize>,
+    maybe_by_string: bool,
+    /// The audio group of sounds outside the default audio group.
+    audio_group: Option<Cow<'a, str>>,
+}
+
+fn print_category(category: &str, entries: &[Entry]) {
+    let total: usize = entries.iter().filter_map(|entry| entry.size).sum();
+    println!(
+        "===== Unreferenced {category} ({}, {total} bytes) =====",
+        entries.len()
+    );
+
+    for entry in entries {
+        print!("{}", entry.name);
+        if let Some(size) = entry.size {
+            print!(" ({size} bytes)");
+        }
+        if entry.maybe_by_string {
+            print!(" [maybe referenced by string]");
+        }
+        if let Some(audio_group) = &entry.audio_group {
+            print!(" [audio group {audio_group}]");
+        }
+        println!();
+    }
+    println!();
+}
+
+pub fn dead_code_report(data: &mut GMData) -> Result<()> {
+    let mut refs = References::default();
+    collect_asset_references(data, &mut refs);
+    let data: &GMData = data;
+    collect_references(data, &mut refs).ctx("collecting code references")?;
+    let entry = |name: GMRef<String>, size: Option<usize>| -> Result<Entry> {
+        let name: &str = data.strings.by_ref(name)?;
+        let maybe_by_string = refs.strings.contains(name);
+        Ok(Entry {
+            name,
+            size,
+            maybe_by_string,
+            audio_group: None,
+        })
+    };
+
+    let mut sprites: Vec<Entry> = Vec::new();
+    for (sprite_ref, sprite) in data.sprites.element_refs() {
+        if refs.sprites.contains(&sprite_ref) {
+            continue;
+        }
+        let mut size: usize = 0;
+        for &texture in &sprite.textures {
+            if let Ok(item) = data.texture_page_items.by_ref(texture) {
+                size += usize::from(item.source_width) * usize::from(item.source_height) * 4;
+            }
+        }
+        sprites.push(entry(sprite.name, Some(size))?);
+    }
+
+    let mut sounds: Vec<Entry> = Vec::new();
+    let mut group_sounds: Vec<Entry> = Vec::new();
+    for (sound_ref, sound) in data.sounds.element_refs() {
+        if refs.sounds.contains(&sound_ref) {
+            continue;
+        }
+        let audio_group = sound.effective_audio_group();
+        if audio_group != GMRef::new(0) {
+            // The audio data is stored in an external audio group file.
+            // A dangling audio group should not abort the whole report.
+            let group_name: Cow<str> = match data.audio_groups.by_ref(audio_group) {
+                Ok(group) => Cow::Borrowed(data.strings.by_ref(group.name)?),
+                Err(_) => {
+                    let index = audio_group.index().unwrap_or_default();
+                    Cow::Owned(format!("#{index} (unknown)"))
+                }
+            };
+            let mut entry = entry(sound.name, None)?;
+            entry.audio_group = Some(group_name);
+            group_sounds.push(entry);
+            continue;
+        }
+        let size: Option<usize> = data
+            .audios
+            .by_ref(sound.audio)
+            .ok()
+            .map(|audio| audio.data.len());
+        sounds.push(entry(sound.name, size)?);
+    }
+
+    let mut objects: Vec<Entry> = Vec::new();
+    for (object_ref, object) in data.game_objects.element_refs() {
+        if !refs.objects.contains(&object_ref) {
+            objects.push(entry(object.name, None)?);
+        }
+    }
+
+    let mut codes: Vec<Entry> = Vec::new();
+    for (code_ref, code) in data.codes.element_refs() {
+        if !refs.codes.contains(&code_ref) {
+            codes.push(entry(code.name, Some(code.length() as usize))?);
+        }
+    }
+
+    print_category("Sprites", &sprites);
+    print_category("Sounds", &sounds);
+    print_category("Sounds in Audio Groups", &group_sounds);
+    print_category("Game Objects", &objects);
+    print_category("Code Entries", &codes);
+    Ok(())
+}
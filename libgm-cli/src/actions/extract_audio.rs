@@ -0,0 +1,30 @@
+// SPDX-License-Identifier: GPL-3.0-only
+//! Extracts all embedded sounds to the `sounds` directory.
+
+use libgm::prelude::*;
+
+pub fn extract_audio(data: &GMData) -> Result<()> {
+    let dir_name = "sounds";
+    std::fs::create_dir_all(dir_name).ctx_any("creating sounds directory")?;
+
+    for (sound_ref, sound) in data.sounds.element_refs() {
+        sound
+            .validate_name(&data.strings)
+            .ctx_any("validating sound name")?;
+        let name = data.strings.by_ref(sound.name)?;
+
+        let Some(audio) = data.sound_audio(sound_ref)? else {
+            log::info!("Skipping sound {name:?} since its audio is not embedded in this file");
+            continue;
+        };
+        let Some(extension) = audio.container_extension() else {
+            log::warn!("Skipping sound {name:?} since its audio format could not be detected");
+            continue;
+        };
+
+        std::fs::write(format!("{dir_name}/{name}.{extension}"), &*audio.data)
+            .ctx_any(|| format!("writing audio of sound {name:?}"))?;
+    }
+
+    Ok(())
+}
@@ -56,6 +56,10 @@ pub struct Args {
     #[arg(long)]
     pub dump_sprites: bool,
 
+    /// Whether to dump all sprites as horizontal spritesheets.
+    #[arg(long)]
+    pub dump_spritesheets: bool,
+
     /// Whether to dump all texture pages.
     #[arg(long)]
     pub dump_texture_pages: bool,
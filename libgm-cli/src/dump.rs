@@ -1,6 +1,8 @@
 // TODO: more dumping options
 mod sprites;
+mod spritesheets;
 mod texture_pages;
 
 pub use self::sprites::dump_sprites;
+pub use self::spritesheets::dump_spritesheets;
 pub use self::texture_pages::dump_texture_pages;
@@ -0,0 +1,21 @@
+use libgm::prelude::*;
+
+pub fn dump_spritesheets(data: &GMData) -> Result<()> {
+    let dir_name = "spritesheets";
+    std::fs::create_dir_all(dir_name).ctx_any("creating spritesheets directory")?;
+
+    for (sprite_ref, sprite) in data.sprites.element_refs() {
+        sprite
+            .validate_name(&data.strings)
+            .ctx_any("validating sprite name")?;
+        let name = data.strings.by_ref(sprite.name)?;
+        if sprite.textures.is_empty() || sprite.width == 0 || sprite.height == 0 {
+            log::warn!("Skipping sprite {name:?} since it has no frames or an empty size");
+            continue;
+        }
+        data.export_sprite_spritesheet(sprite_ref, format!("{dir_name}/{name}.png"))
+            .ctx(|| format!("dumping spritesheet of sprite {name:?}"))?;
+    }
+
+    Ok(())
+}
@@ -68,6 +68,9 @@ fn run(mut args: cli::Args) -> Result<()> {
         if args.dump_sprites {
             dump::dump_sprites(&data)?;
         }
+        if args.dump_spritesheets {
+            dump::dump_spritesheets(&data)?;
+        }
         if args.dump_texture_pages {
             dump::dump_texture_pages(&data)?;
         }
@@ -79,16 +82,6 @@ fn run(mut args: cli::Args) -> Result<()> {
         //     std::fs::write(format!("asm/{n}.txt"), asm).unwrap();
         // }
 
-        // std::fs::create_dir_all("sounds").unwrap();
-        // for snd in data.sounds.elements() {
-        //     let name = data.strings.by_ref(snd.name)?;
-        //     if snd.audio_file.is_none() {
-        //         continue;
-        //     }
-        //     let audio = data.audios.by_ref(snd.audio_file)?;
-        //     std::fs::write(format!("sounds/{name}.wav"), &audio.audio_data).unwrap();
-        // }
-
         if let Some(out_file) = &args.out {
             log::info!("Building data file {}", out_file.display());
             build_file(&data, out_file)?;
@@ -0,0 +1,80 @@
+// SPDX-License-Identifier: GPL-3.0-only
+//! Compares the peak memory usage of `build_bytes` and `GMData::into_bytes`.
+//!
+//! By default, `GMData::default()` with 64 MiB of embedded audio is used.
+//! To measure a real game instead, set `LIBGM_BENCH_FILE` to the path of its data file.
+//!
+//! Peak memory is measured by a counting global allocator, relative to the memory in use
+//! right before building (which includes the parsed data).
+use std::alloc::GlobalAlloc;
+use std::alloc::Layout;
+use std::alloc::System;
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering;
+
+use libgm::prelude::*;
+use libgm::wad::Blob;
+use libgm::wad::build_bytes;
+use libgm::wad::elem::audio::Audio;
+use libgm::wad::parse::ParsingOptions;
+
+struct CountingAllocator;
+
+static CURRENT: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let current = CURRENT.fetch_add(layout.size(), Ordering::Relaxed) + layout.size();
+        PEAK.fetch_max(current, Ordering::Relaxed);
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        CURRENT.fetch_sub(layout.size(), Ordering::Relaxed);
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+fn load_data() -> GMData {
+    if let Ok(path) = std::env::var("LIBGM_BENCH_FILE") {
+        return ParsingOptions::STRICT
+            .parse_file(&path)
+            .unwrap_or_else(|e| panic!("Could not parse data file {path}: {}", e.chain()));
+    }
+
+    let mut data = GMData::default();
+    for _ in 0..64 {
+        data.audios
+            .push(Audio { data: Blob::new(vec![0x55; 1024 * 1024]) });
+    }
+    data
+}
+
+/// Runs the closure and returns the peak amount of allocated memory while it ran.
+fn measure_peak(func: impl FnOnce() -> Vec<u8>) -> usize {
+    PEAK.store(CURRENT.load(Ordering::Relaxed), Ordering::Relaxed);
+    let raw_data = func();
+    let peak = PEAK.load(Ordering::Relaxed);
+    drop(raw_data);
+    peak
+}
+
+#[expect(clippy::cast_precision_loss)]
+fn format_mib(bytes: usize) -> String {
+    format!("{:.1} MiB", bytes as f64 / (1024.0 * 1024.0))
+}
+
+fn main() {
+    let data = load_data();
+    let data_size = CURRENT.load(Ordering::Relaxed);
+    let borrowed_peak = measure_peak(|| build_bytes(&data).expect("Could not build data file"));
+    let owned_peak = measure_peak(|| data.into_bytes().expect("Could not build data file"));
+
+    println!("Memory in use before building: {}", format_mib(data_size));
+    println!("build_bytes peak memory: {}", format_mib(borrowed_peak));
+    println!("into_bytes peak memory:  {}", format_mib(owned_peak));
+}
@@ -0,0 +1,67 @@
+// SPDX-License-Identifier: GPL-3.0-only
+//! Benchmarks for parsing and building data files.
+//!
+//! By default, a data file generated from `GMData::default()` is used (parsed leniently).
+//! To benchmark a real game instead, set `LIBGM_BENCH_FILE` to the path of its data file.
+//!
+//! With the `chunk-timings` feature enabled, the parse and build duration of every chunk is
+//! printed once before benchmarking.
+use std::hint::black_box;
+
+use criterion::Criterion;
+use criterion::Throughput;
+use criterion::criterion_group;
+use criterion::criterion_main;
+use libgm::prelude::*;
+use libgm::wad::build_bytes;
+use libgm::wad::parse::ParsingOptions;
+
+fn load_data_file() -> (Vec<u8>, ParsingOptions) {
+    if let Ok(path) = std::env::var("LIBGM_BENCH_FILE") {
+        let raw_data =
+            std::fs::read(&path).unwrap_or_else(|e| panic!("Could not read data file {path}: {e}"));
+        return (raw_data, ParsingOptions::STRICT);
+    }
+
+    // The default data has a made up IDE version, which would fail strict parsing.
+    let raw_data = build_bytes(&GMData::default()).expect("Could not build default data file");
+    (raw_data, ParsingOptions::LENIENT)
+}
+
+#[cfg(feature = "chunk-timings")]
+fn print_chunk_timings(data: &GMData) {
+    for (chunk_name, duration) in &data.meta.chunk_timings {
+        eprintln!("Parsing chunk {chunk_name} took {duration:.2?}");
+    }
+    let (_, build_timings) =
+        libgm::wad::build::build_bytes_timed(data).expect("Could not build data file");
+    for (chunk_name, duration) in build_timings {
+        eprintln!("Building chunk {chunk_name} took {duration:.2?}");
+    }
+}
+
+fn bench_parse_build(c: &mut Criterion) {
+    let (raw_data, options) = load_data_file();
+    let data: GMData = options
+        .parse_bytes(&raw_data)
+        .expect("Could not parse data file");
+
+    #[cfg(feature = "chunk-timings")]
+    print_chunk_timings(&data);
+
+    let mut group = c.benchmark_group("data_file");
+    group.throughput(Throughput::Bytes(raw_data.len() as u64));
+
+    group.bench_function("parse_bytes", |b| {
+        b.iter(|| options.parse_bytes(black_box(&raw_data)).unwrap());
+    });
+
+    group.bench_function("build_bytes", |b| {
+        b.iter(|| build_bytes(black_box(&data)).unwrap());
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_parse_build);
+criterion_main!(benches);
@@ -1,2 +1,4 @@
 // SPDX-License-Identifier: GPL-3.0-only
+mod reorder;
+mod strings_csv;
 mod toggle_debug;
@@ -0,0 +1,150 @@
+// SPDX-License-Identifier: GPL-3.0-only
+//! Reordering asset lists without breaking references into them.
+
+use std::any::Any;
+
+use crate::prelude::*;
+use crate::wad::chunk::ChunkName;
+use crate::wad::elem::animation_curve::AnimationCurve;
+use crate::wad::elem::font::Font;
+use crate::wad::elem::game_object::GameObject;
+use crate::wad::elem::particle_emitter::ParticleEmitter;
+use crate::wad::elem::particle_system::ParticleSystem;
+use crate::wad::elem::path::Path;
+use crate::wad::elem::room::Room;
+use crate::wad::elem::script::Script;
+use crate::wad::elem::sequence::Sequence;
+use crate::wad::elem::shader::Shader;
+use crate::wad::elem::sound::Sound;
+use crate::wad::elem::sprite::Sprite;
+use crate::wad::elem::tileset::Tileset;
+use crate::wad::elem::timeline::Timeline;
+use crate::wad::visit_asset_refs;
+
+impl GMData {
+    /// Reorders the elements of an asset list and rewrites every reference
+    /// into that list accordingly.
+    ///
+    /// After reordering, the element at position `i` is the element that was
+    /// previously at position `permutation[i]`.
+    ///
+    /// The following chunks are supported: `SPRT`, `SOND`, `OBJT`, `ROOM`,
+    /// `PATH`, `SCPT`, `FONT`, `TMLN`, `SHDR`, `SEQN`, `ACRV`, `PSYS`, `PSEM`
+    /// and `BGND`.
+    ///
+    /// Asset IDs that are pushed as plain integers in code (which is the
+    /// case before GameMaker 2023.8) cannot be distinguished from other
+    /// numbers, so they are not rewritten.
+    ///
+    /// Returns an error if the chunk is not supported or the permutation is
+    /// not a bijection of the list's indices.
+    /// In that case, nothing is modified.
+    pub fn reorder_list(&mut self, kind: ChunkName, permutation: &[usize]) -> Result<()> {
+        match kind {
+            ChunkName::SPRT => {
+                reorder::<Sprite, _>(self, |data| &mut data.sprites.elems, permutation)
+            }
+            ChunkName::SOND => {
+                reorder::<Sound, _>(self, |data| &mut data.sounds.elems, permutation)
+            }
+            ChunkName::OBJT => {
+                reorder::<GameObject, _>(self, |data| &mut data.game_objects.elems, permutation)
+            }
+            ChunkName::ROOM => reorder::<Room, _>(self, |data| &mut data.rooms.elems, permutation),
+            ChunkName::PATH => reorder::<Path, _>(self, |data| &mut data.paths.elems, permutation),
+            ChunkName::SCPT => {
+                reorder::<Script, _>(self, |data| &mut data.scripts.elems, permutation)
+            }
+            ChunkName::FONT => reorder::<Font, _>(self, |data| &mut data.fonts.elems, permutation),
+            ChunkName::TMLN => {
+                reorder::<Timeline, _>(self, |data| &mut data.timelines.elems, permutation)
+            }
+            ChunkName::SHDR => {
+                reorder::<Shader, _>(self, |data| &mut data.shaders.elems, permutation)
+            }
+            ChunkName::SEQN => {
+                reorder::<Sequence, _>(self, |data| &mut data.sequences.elems, permutation)
+            }
+            ChunkName::ACRV => reorder::<AnimationCurve, _>(
+                self,
+                |data| &mut data.animation_curves.elems,
+                permutation,
+            ),
+            ChunkName::PSYS => reorder::<ParticleSystem, _>(
+                self,
+                |data| &mut data.particle_systems.elems,
+                permutation,
+            ),
+            ChunkName::PSEM => reorder::<ParticleEmitter, _>(
+                self,
+                |data| &mut data.particle_emitters.elems,
+                permutation,
+            ),
+            ChunkName::BGND => {
+                reorder::<Tileset, _>(self, |data| &mut data.tilesets.elems, permutation)
+            }
+            _ => bail!("Reordering chunk {kind} is not supported"),
+        }
+        .ctx(|| format!("reordering chunk {kind}"))
+    }
+}
+
+/// Reorders the element list and rewrites all `GMRef<T>`s pointing into it.
+///
+/// `E` is the list's element type, which is either `T` or `Option<T>`.
+fn reorder<T: 'static, E>(
+    data: &mut GMData,
+    elems: fn(&mut GMData) -> &mut Vec<E>,
+    permutation: &[usize],
+) -> Result<()> {
+    let old_to_new: Vec<usize> = invert_permutation(permutation, elems(data).len())?;
+
+    let list: &mut Vec<E> = elems(data);
+    let mut indexed: Vec<(usize, E)> = std::mem::take(list)
+        .into_iter()
+        .enumerate()
+        .map(|(old, elem)| (old_to_new[old], elem))
+        .collect();
+    indexed.sort_unstable_by_key(|(new, _)| *new);
+    *list = indexed.into_iter().map(|(_, elem)| elem).collect();
+
+    visit_asset_refs(data, &mut |any: &mut dyn Any| {
+        let Some(gm_ref) = any.downcast_mut::<GMRef<T>>() else {
+            return;
+        };
+        // Dangling references are left as they are.
+        if let Some(index) = gm_ref.index()
+            && let Some(&new) = old_to_new.get(index)
+        {
+            *gm_ref = GMRef::from(new);
+        }
+    });
+    Ok(())
+}
+
+/// Returns the inverse permutation, mapping old indices to new indices.
+fn invert_permutation(permutation: &[usize], len: usize) -> Result<Vec<usize>> {
+    if permutation.len() != len {
+        bail!(
+            "Permutation has {} entries but the list has {len} elements",
+            permutation.len(),
+        );
+    }
+
+    let mut inverse: Vec<Option<usize>> = vec![None; len];
+    for (new, &old) in permutation.iter().enumerate() {
+        let Some(slot) = inverse.get_mut(old) else {
+            bail!(
+                "Permutation entry {new} refers to index {old} which is out of range for {len} \
+                 elements"
+            );
+        };
+        if slot.is_some() {
+            bail!("Permutation contains index {old} more than once");
+        }
+        *slot = Some(new);
+    }
+
+    // Every slot is filled since there are `len` distinct in-range entries.
+    Ok(inverse.into_iter().flatten().collect())
+}
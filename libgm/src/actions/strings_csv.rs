@@ -0,0 +1,166 @@
+// SPDX-License-Identifier: GPL-3.0-only
+//! Exporting and importing strings as CSV, for translation.
+//!
+//! The CSV format follows RFC 4180: Fields containing commas, quotes or line
+//! breaks are enclosed in double quotes and quotes are escaped by doubling
+//! them. Files are encoded in UTF-8 with a byte order mark, so that
+//! spreadsheet programs like Excel detect the encoding properly.
+
+use std::path::Path;
+
+use crate::prelude::*;
+
+const HEADER: [&str; 2] = ["index", "text"];
+const BYTE_ORDER_MARK: char = '\u{FEFF}';
+
+impl GMData {
+    /// Exports all strings to a CSV file with the columns `index` and `text`.
+    ///
+    /// For more information, see [`Self::export_strings_csv`].
+    pub fn export_strings(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path: &Path = path.as_ref();
+        std::fs::write(path, self.export_strings_csv())
+            .ctx_any(|| format!("writing strings CSV file {}", path.display()))
+    }
+
+    /// Imports edited strings from a CSV file.
+    ///
+    /// For more information, see [`Self::import_strings_csv`].
+    pub fn import_strings(&mut self, path: impl AsRef<Path>) -> Result<usize> {
+        let path: &Path = path.as_ref();
+        let csv: String = std::fs::read_to_string(path)
+            .ctx_any(|| format!("reading strings CSV file {}", path.display()))?;
+        self.import_strings_csv(&csv)
+            .ctx(|| format!("importing strings CSV file {}", path.display()))
+    }
+
+    /// Exports all strings as CSV with the columns `index` and `text`.
+    ///
+    /// The first row is a header row. Every string gets its own row, in the
+    /// order of the `STRG` chunk.
+    #[must_use]
+    pub fn export_strings_csv(&self) -> String {
+        let mut csv = String::new();
+        csv.push(BYTE_ORDER_MARK);
+        write_row(&mut csv, HEADER);
+        for (i, string) in self.strings.elems.iter().enumerate() {
+            write_row(&mut csv, [&i.to_string(), string]);
+        }
+        csv
+    }
+
+    /// Imports edited strings from CSV with the columns `index` and `text`,
+    /// as exported by [`Self::export_strings_csv`].
+    ///
+    /// Every row replaces the string at the specified index. Rows may be
+    /// omitted or reordered, but new strings cannot be added this way.
+    /// The header row and the byte order mark are optional.
+    ///
+    /// Returns an error if a row is malformed or its index is out of range.
+    /// In that case, no strings are modified.
+    ///
+    /// Returns the number of strings that were changed.
+    pub fn import_strings_csv(&mut self, csv: &str) -> Result<usize> {
+        let csv: &str = csv.strip_prefix(BYTE_ORDER_MARK).unwrap_or(csv);
+        let mut replacements: Vec<(usize, String)> = Vec::new();
+
+        for (i, row) in parse_rows(csv)?.into_iter().enumerate() {
+            let row_number: usize = i + 1;
+            if i == 0 && row == HEADER {
+                continue;
+            }
+            let [index, text]: [String; 2] = row.try_into().map_err(|row: Vec<String>| {
+                err!("Row {row_number} has {} fields instead of 2", row.len())
+            })?;
+            let index: usize = index
+                .trim()
+                .parse()
+                .map_err(|_| err!("Row {row_number} has invalid string index {index:?}"))?;
+            if index >= self.strings.elems.len() {
+                bail!(
+                    "Row {row_number} has string index {index} which is out of range for {} \
+                     strings",
+                    self.strings.elems.len(),
+                );
+            }
+            replacements.push((index, text));
+        }
+
+        let mut changed: usize = 0;
+        for (index, text) in replacements {
+            let string: &mut String = &mut self.strings.elems[index];
+            if *string != text {
+                *string = text;
+                changed += 1;
+            }
+        }
+        Ok(changed)
+    }
+}
+
+fn write_row(csv: &mut String, fields: [&str; 2]) {
+    for (i, field) in fields.into_iter().enumerate() {
+        if i > 0 {
+            csv.push(',');
+        }
+        if field.contains([',', '"', '\n', '\r']) {
+            csv.push('"');
+            csv.push_str(&field.replace('"', "\"\""));
+            csv.push('"');
+        } else {
+            csv.push_str(field);
+        }
+    }
+    csv.push_str("\r\n");
+}
+
+fn parse_rows(csv: &str) -> Result<Vec<Vec<String>>> {
+    let mut rows: Vec<Vec<String>> = Vec::new();
+    let mut row: Vec<String> = Vec::new();
+    let mut field = String::new();
+    let mut chars = csv.chars().peekable();
+
+    // Whether the current field was quoted; used to keep empty quoted fields.
+    let mut quoted: bool = false;
+
+    while let Some(char) = chars.next() {
+        match char {
+            '"' if field.is_empty() && !quoted => {
+                quoted = true;
+                loop {
+                    match chars.next() {
+                        Some('"') if chars.peek() == Some(&'"') => {
+                            chars.next();
+                            field.push('"');
+                        }
+                        Some('"') => break,
+                        Some(char) => field.push(char),
+                        None => bail!("Unterminated quoted field in row {}", rows.len() + 1),
+                    }
+                }
+            }
+            ',' => {
+                row.push(std::mem::take(&mut field));
+                quoted = false;
+            }
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\n' => {
+                row.push(std::mem::take(&mut field));
+                rows.push(std::mem::take(&mut row));
+                quoted = false;
+            }
+            _ if quoted => bail!(
+                "Unexpected character {char:?} after quoted field in row {}",
+                rows.len() + 1,
+            ),
+            _ => field.push(char),
+        }
+    }
+
+    // The last row may not end with a line break.
+    if !field.is_empty() || quoted || !row.is_empty() {
+        row.push(field);
+        rows.push(row);
+    }
+    Ok(rows)
+}
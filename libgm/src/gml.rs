@@ -5,13 +5,24 @@
 
 pub mod analysis;
 pub mod assembly;
+mod bytecode_version;
+pub mod call_graph;
+pub mod cfg;
 pub mod instruction;
 mod name_validation;
 pub mod opcodes;
 
+use std::collections::HashSet;
 use std::ops::Range;
 
+pub use crate::gml::bytecode_version::IncompatibleInstruction;
+pub use crate::gml::bytecode_version::IncompatibleInstructions;
+pub use crate::gml::bytecode_version::SetBytecodeVersionError;
+use crate::gml::instruction::AssetCounts;
+use crate::gml::instruction::AssetReference;
 pub use crate::gml::instruction::Instruction;
+use crate::gml::instruction::PushValue;
+pub(crate) use crate::gml::name_validation::validate_code_name;
 use crate::prelude::*;
 use crate::wad::elem::function::CodeLocal;
 
@@ -142,6 +153,400 @@ impl Code {
 }
 
 impl GMData {
+    /// Resolves the names of all code entries, in order.
+    ///
+    /// This is a shorthand for [`Codes::names`].
+    ///
+    /// [`Codes::names`]: crate::wad::elem::code::Codes::names
+    pub fn code_names(&self) -> Result<Vec<&str>> {
+        self.codes.names(&self.strings)
+    }
+
+    /// Lists every string literal pushed in code, together with the code entry
+    /// pushing it.
+    ///
+    /// Entries are in code entry order and then in instruction order.
+    /// A string pushed multiple times is listed once per push.
+    ///
+    /// This reveals strings used dynamically in code, such as asset names
+    /// passed to `asset_get_index` or text assembled in scripts.
+    #[must_use]
+    pub fn code_string_literals(&self) -> Vec<(GMRef<Code>, GMRef<String>)> {
+        let mut literals = Vec::new();
+        for (code_ref, code) in self.codes.element_refs() {
+            for instruction in &code.instructions {
+                if let Some(PushValue::String(string)) = instruction.push_value() {
+                    literals.push((code_ref, string));
+                }
+            }
+        }
+        literals
+    }
+
+    /// Groups code entries with identical instructions.
+    ///
+    /// Only the instructions are compared; names and [`ModernData`] (such as
+    /// local counts or execution offsets) are ignored.
+    /// Child code entries share their parent's bytecode and are therefore
+    /// skipped, as are code entries without any instructions.
+    ///
+    /// Only groups with at least two code entries are returned.
+    /// Groups are ordered by their first code entry and code entries within
+    /// a group are in code entry order.
+    #[doc(alias = "deduplicate_code")]
+    #[must_use]
+    pub fn find_duplicate_code(&self) -> Vec<Vec<GMRef<Code>>> {
+        let mut groups: Vec<(&[Instruction], Vec<GMRef<Code>>)> = Vec::new();
+        for (code_ref, code) in self.codes.element_refs() {
+            if !code.is_root() || code.instructions.is_empty() {
+                continue;
+            }
+            let existing = groups
+                .iter_mut()
+                .find(|(instructions, _)| *instructions == code.instructions.as_slice());
+            match existing {
+                Some((_, group)) => group.push(code_ref),
+                None => groups.push((&code.instructions, vec![code_ref])),
+            }
+        }
+        groups
+            .into_iter()
+            .map(|(_, group)| group)
+            .filter(|group| group.len() > 1)
+            .collect()
+    }
+
+    /// Replaces all instructions of the code entry with the specified name.
+    ///
+    /// All instructions are validated first: Referenced functions, variables
+    /// and strings have to exist in this data file and branches have to target
+    /// an instruction within the new instructions (or the end of them).
+    ///
+    /// For WAD 15+, the local and argument counts of the code entry are
+    /// preserved. If the new instructions need more locals or arguments,
+    /// you have to update [`ModernData`] yourself.
+    ///
+    /// Child code entries and code entries with children cannot be replaced,
+    /// since the execution offsets of the child entries would be invalidated.
+    pub fn replace_code(&mut self, name: &str, instructions: Vec<Instruction>) -> Result<()> {
+        replace_code(self, name, instructions).ctx(|| format!("replacing code entry {name:?}"))
+    }
+
+    /// Finds the code entry and instruction index at the specified absolute
+    /// position in the data file.
+    ///
+    /// This is useful for mapping bytecode positions from crash logs or
+    /// profilers back to instructions. The position can point anywhere within
+    /// the instruction, including its extra data.
+    ///
+    /// This uses the instruction positions recorded while parsing
+    /// (see [`Metadata::code_positions`](crate::wad::data::Metadata::code_positions))
+    /// together with the instruction sizes. The instructions of code entries
+    /// modified after parsing may no longer match the original data file.
+    ///
+    /// In WAD 15+, child code entries share the instructions of their parent,
+    /// so the parent code entry is returned for those positions.
+    ///
+    /// Returns [`None`] if the position is not inside any instruction.
+    /// Returns an error if the code entry positions are unknown, which is the
+    /// case if the data was not parsed from a file or code entries were
+    /// added or removed since.
+    pub fn locate_bytecode_offset(&self, position: u32) -> Result<Option<(GMRef<Code>, usize)>> {
+        let positions: &[u32] = &self.meta.code_positions;
+        if positions.len() != self.codes.len() {
+            bail!(
+                "Code entry positions are unknown: {} positions were recorded while parsing, but \
+                 there are {} code entries",
+                positions.len(),
+                self.codes.len(),
+            );
+        }
+
+        for ((code_ref, code), &start) in self.codes.element_refs().zip(positions) {
+            // Child code entries have no instructions of their own.
+            let mut pos: u32 = start;
+            for (index, instruction) in code.instructions.iter().enumerate() {
+                let end: u32 = pos + instruction.size();
+                if (pos..end).contains(&position) {
+                    return Ok(Some((code_ref, index)));
+                }
+                pos = end;
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Inserts instructions into the specified root code entry at the
+    /// specified instruction index.
+    ///
+    /// Branches crossing the insertion point are adjusted, see
+    /// [`insert_instructions`]. Child code entries starting after the
+    /// insertion point have their execution offsets moved accordingly.
+    /// Child code entries starting exactly at the insertion point keep their
+    /// offset, so they will begin with the inserted instructions.
+    pub fn insert_code_instructions(
+        &mut self,
+        code: GMRef<Code>,
+        index: u32,
+        insertion: &[Instruction],
+    ) -> Result<()> {
+        splice_code(self, code, index..index, insertion).ctx(|| {
+            format!(
+                "inserting {} instructions at index {index} of code entry {code:?}",
+                insertion.len(),
+            )
+        })?;
+        Ok(())
+    }
+
+    /// Removes a range of instructions from the specified root code entry
+    /// and returns them.
+    ///
+    /// Branches crossing the removed range are adjusted, see
+    /// [`remove_instructions`]. Child code entries starting after the
+    /// removed range have their execution offsets moved accordingly.
+    ///
+    /// Returns an error if a child code entry starts within the removed
+    /// range (but not at its first instruction), since the child would
+    /// lose its entry point.
+    pub fn remove_code_instructions(
+        &mut self,
+        code: GMRef<Code>,
+        range: Range<u32>,
+    ) -> Result<Vec<Instruction>> {
+        let (start, end) = (range.start, range.end);
+        splice_code(self, code, range, &[])
+            .ctx(|| format!("removing instructions {start}..{end} of code entry {code:?}"))
+    }
+
+    /// Sets the execution offset of a child code entry to the start of the
+    /// instruction with the specified index in its parent's instructions.
+    ///
+    /// An index equal to the parent's instruction count points to the end
+    /// of the parent's instructions.
+    pub fn set_instruction_offset(&mut self, child: GMRef<Code>, index: usize) -> Result<()> {
+        let code: &Code = self.codes.by_ref(child)?;
+        let parent: &Code = self
+            .codes
+            .by_ref(code.parent())
+            .ctx("resolving parent code entry")?;
+        let Some(instructions) = parent.instructions.get(..index) else {
+            bail!(
+                "Instruction index {index} is out of bounds for parent code entry with {} \
+                 instructions",
+                parent.instructions.len(),
+            );
+        };
+        let offset: u32 = instructions_size(instructions);
+        if let Some(modern_data) = &mut self.codes.by_ref_mut(child)?.modern_data {
+            modern_data.execution_offset = offset;
+        }
+        Ok(())
+    }
+
+    /// Checks that the execution offset of every child code entry lands on an
+    /// instruction boundary (or the end) of its parent's instructions.
+    ///
+    /// This does nothing before WAD 15.
+    pub fn validate_child_code_offsets(&self) -> Result<()> {
+        for code in self.codes.elements() {
+            if code.is_root() {
+                continue;
+            }
+            let offset: u32 = code.execution_offset();
+            let ctx = || {
+                format!(
+                    "validating child code entry {:?}",
+                    code.name.display(&self.strings)
+                )
+            };
+            let parent: &Code = self.codes.by_ref(code.parent()).ctx(ctx)?;
+
+            let mut pos: u32 = 0;
+            for instruction in &parent.instructions {
+                if pos >= offset {
+                    break;
+                }
+                pos += instruction.size();
+            }
+            if pos != offset {
+                return Err(err!(
+                    "Execution offset {offset} is not on an instruction boundary of parent code \
+                     entry {:?}",
+                    parent.name.display(&self.strings),
+                ))
+                .ctx(ctx);
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks that every child code entry comes after its parent code entry
+    /// and that every parent code entry is a root entry.
+    ///
+    /// In WAD 15+, child code entries share the bytecode of their parent.
+    /// When building, a child entry repeats the instructions pointer of its
+    /// parent, which therefore has to be serialized first. When parsing,
+    /// code entries are assigned to the first (root) entry with the same
+    /// instructions pointer. Any other order would either fail to build or
+    /// silently attach child entries to the wrong parent.
+    ///
+    /// This does nothing before WAD 15.
+    pub fn validate_code_entry_order(&self) -> Result<()> {
+        for (code_ref, code) in self.codes.element_refs() {
+            if code.is_root() {
+                continue;
+            }
+            let ctx = || {
+                format!(
+                    "validating order of child code entry {:?}",
+                    code.name.display(&self.strings)
+                )
+            };
+            let parent_ref: GMRef<Code> = code.parent();
+            let parent: &Code = self.codes.by_ref(parent_ref).ctx(ctx)?;
+            if parent_ref.index() >= code_ref.index() {
+                return Err(err!(
+                    "Parent code entry {:?} comes after its child code entry",
+                    parent.name.display(&self.strings)
+                ))
+                .ctx(ctx);
+            }
+            if !parent.is_root() {
+                return Err(err!(
+                    "Parent code entry {:?} is a child code entry itself",
+                    parent.name.display(&self.strings)
+                ))
+                .ctx(ctx);
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks that every asset referenced by a [`PushReference`] instruction
+    /// exists in the asset list of its type.
+    ///
+    /// Asset references are serialized as a type and an index, so they are
+    /// only resolved when the game runs. An out of bounds index would either
+    /// crash the runner or silently refer to a different asset after
+    /// changing the asset type encoding (see [`AssetReference::build_old`]).
+    ///
+    /// The bounds are checked the same way as when detecting the 2024.4
+    /// asset type encoding.
+    /// [`AssetReference::RoomInstance`] references are checked against the
+    /// instance IDs placed in rooms and [`AssetReference::Function`]
+    /// references against the functions (`FUNC` chunk).
+    ///
+    /// Unlike the other checks of [`GMData::validate`], this does not run in
+    /// [`GMData::post_deserialize`], since real games may contain dangling
+    /// asset references (for example to instances of stripped rooms).
+    ///
+    /// [`PushReference`]: Instruction::PushReference
+    #[doc(alias = "validate_asset_reference_types")]
+    pub fn validate_asset_references(&self) -> Result<()> {
+        let instance_ids: HashSet<i32> = self
+            .rooms
+            .elements()
+            .flat_map(|room| &room.game_objects)
+            .map(|instance| instance.instance_id.0)
+            .collect();
+
+        let count = |len: usize| Some(len as u32);
+        let counts = AssetCounts {
+            objects: count(self.game_objects.len()),
+            sprites: count(self.sprites.len()),
+            sounds: count(self.sounds.len()),
+            rooms: count(self.rooms.len()),
+            backgrounds: count(self.tilesets.len()),
+            paths: count(self.paths.len()),
+            scripts: count(self.scripts.len()),
+            fonts: count(self.fonts.len()),
+            timelines: count(self.timelines.len()),
+            shaders: count(self.shaders.len()),
+            sequences: count(self.sequences.len()),
+            anim_curves: count(self.animation_curves.len()),
+            particle_systems: count(self.particle_systems.len()),
+        };
+
+        for code in self.codes.elements() {
+            for (index, instruction) in code.instructions.iter().enumerate() {
+                let Instruction::PushReference { asset_reference } = instruction else {
+                    continue;
+                };
+                self.validate_asset_reference(*asset_reference, &counts, &instance_ids)
+                    .ctx(|| {
+                        format!(
+                            "validating asset reference of instruction #{index} in code entry {:?}",
+                            code.name.display(&self.strings)
+                        )
+                    })?;
+            }
+        }
+        Ok(())
+    }
+
+    fn validate_asset_reference(
+        &self,
+        asset_reference: AssetReference,
+        counts: &AssetCounts,
+        instance_ids: &HashSet<i32>,
+    ) -> Result<()> {
+        match asset_reference {
+            AssetReference::Function(gm_ref) => self.functions.by_ref(gm_ref).map(drop),
+            AssetReference::RoomInstance(id) => {
+                if instance_ids.contains(&id) {
+                    Ok(())
+                } else {
+                    Err(err!("Instance ID {id} is not placed in any room"))
+                }
+            }
+            _ => {
+                let asset_id: u32 = asset_reference.asset_id();
+                if counts.contains(asset_reference) == Some(true) {
+                    Ok(())
+                } else {
+                    Err(err!(
+                        "Asset ID {asset_id} is out of bounds for {} assets",
+                        asset_reference.kind()
+                    ))
+                }
+            }
+        }
+        .ctx(|| format!("resolving {asset_reference:?}"))
+    }
+
+    /// Sets the argument count and the weird local flag of a code entry.
+    ///
+    /// The VM uses the argument count to set up the `argument` array when
+    /// the code entry is called as a function, so code entries for new GML
+    /// functions need this to match their declared parameters.
+    /// For when to set the flag, see [`ModernData::weird_local_flag`].
+    ///
+    /// Returns an error if the code entry does not exist, if the data file is
+    /// older than WAD 15 (which does not store argument counts) or if the
+    /// argument count does not fit in 15 bits.
+    pub fn set_code_arguments_count(
+        &mut self,
+        code_ref: GMRef<Code>,
+        count: u16,
+        weird_local_flag: bool,
+    ) -> Result<()> {
+        let wad_version: u8 = self.general_info.wad_version;
+        let code: &mut Code = self.codes.by_ref_mut(code_ref)?;
+        let Some(modern_data) = &mut code.modern_data else {
+            bail!("Code entries do not have argument counts in WAD version {wad_version}");
+        };
+        modern_data
+            .set_arguments_count(count, weird_local_flag)
+            .ctx(|| {
+                format!(
+                    "setting argument count of {:?}",
+                    code.name.display(&self.strings)
+                )
+            })
+    }
+
     pub fn make_code(&mut self, name: &str, instructions: Vec<Instruction>) -> GMRef<Code> {
         if let Ok(code) = self.codes.ref_by_name(name, &self.strings) {
             return code;
@@ -162,6 +567,113 @@ impl GMData {
     }
 }
 
+fn replace_code(data: &mut GMData, name: &str, instructions: Vec<Instruction>) -> Result<()> {
+    let code_ref: GMRef<Code> = data.codes.ref_by_name(name, &data.strings)?;
+    let code: &Code = data.codes.by_ref(code_ref)?;
+
+    if !code.is_root() {
+        bail!("Code entry is a child code entry; replace its parent code entry instead");
+    }
+    let children: Vec<GMRef<Code>> = Code::find_children(code_ref, data);
+    if !children.is_empty() {
+        bail!(
+            "Code entry has {} child code entries whose execution offsets would be invalidated",
+            children.len(),
+        );
+    }
+
+    validate_instructions(data, &instructions)?;
+    data.codes.by_ref_mut(code_ref)?.instructions = instructions;
+    Ok(())
+}
+
+fn splice_code(
+    data: &mut GMData,
+    code_ref: GMRef<Code>,
+    range: Range<u32>,
+    replace_with: &[Instruction],
+) -> Result<Vec<Instruction>> {
+    let code: &Code = data.codes.by_ref(code_ref)?;
+    if !code.is_root() {
+        bail!("Code entry is a child code entry; edit its parent code entry instead");
+    }
+    let Some(removed) = code
+        .instructions
+        .get(range.start as usize..range.end as usize)
+    else {
+        bail!(
+            "Range {range:?} out of bounds for code entry with {} instructions",
+            code.instructions.len(),
+        );
+    };
+
+    let start_pos: u32 = instructions_size(&code.instructions[..range.start as usize]);
+    let removal_size: u32 = instructions_size(removed);
+    let removal_end: u32 = start_pos + removal_size;
+    let insertion_size: u32 = instructions_size(replace_with);
+
+    let mut new_offsets: Vec<(GMRef<Code>, u32)> = Vec::new();
+    for child_ref in Code::find_children(code_ref, data) {
+        let child: &Code = data.codes.by_ref(child_ref)?;
+        let offset: u32 = child.execution_offset();
+        if offset <= start_pos {
+            continue;
+        }
+        if offset < removal_end {
+            bail!(
+                "Child code entry {:?} starts within the removed instructions",
+                child.name.display(&data.strings),
+            );
+        }
+        new_offsets.push((child_ref, offset - removal_size + insertion_size));
+    }
+
+    let instructions = &mut data.codes.by_ref_mut(code_ref)?.instructions;
+    let removed = splice_instructions(instructions, range, replace_with, true)?;
+
+    for (child_ref, offset) in new_offsets {
+        if let Some(modern_data) = &mut data.codes.by_ref_mut(child_ref)?.modern_data {
+            modern_data.execution_offset = offset;
+        }
+    }
+    Ok(removed.unwrap_or_default())
+}
+
+fn validate_instructions(data: &GMData, instructions: &[Instruction]) -> Result<()> {
+    let mut positions: Vec<i32> = Vec::with_capacity(instructions.len() + 1);
+    let mut pos: i32 = 0;
+    for instruction in instructions {
+        positions.push(pos);
+        pos += instruction.size4() as i32;
+    }
+    positions.push(pos);
+
+    for (i, instruction) in instructions.iter().enumerate() {
+        let ctx = || format!("validating instruction #{i}: {instruction:?}");
+
+        if let Some(function) = instruction.function() {
+            data.functions.by_ref(function).ctx(ctx)?;
+        }
+        if let Some(variable) = instruction.variable() {
+            data.variables.by_ref(variable.variable).ctx(ctx)?;
+        }
+        if let Some(PushValue::String(string)) = instruction.push_value() {
+            data.strings.by_ref(string).ctx(ctx)?;
+        }
+        if let Some(jump_offset) = instruction.jump_offset() {
+            let target: i32 = positions[i] + jump_offset;
+            if positions.binary_search(&target).is_err() {
+                return Err(err!(
+                    "Branch target {target} is not the start of an instruction"
+                ))
+                .ctx(ctx);
+            }
+        }
+    }
+
+    Ok(())
+}
+
 /// Extra data for code entries in WAD Version 15 and higher.
 #[derive(Debug, Clone, Default, PartialEq, Eq)]
 pub struct ModernData {
@@ -173,7 +685,12 @@ pub struct ModernData {
 
     /// A flag set on certain code entries, which usually don't have locals attached to them.
     ///
-    /// DOCME: more info pls
+    /// It is stored in the highest bit of the argument count (see
+    /// [`ModernData::arguments_count_raw`]). Its exact meaning is unknown;
+    /// in practice, it appears on code entries without a code locals entry of
+    /// their own, which is mostly the case for child code entries (such as GML
+    /// functions declared inside a script). Leave it unset for new root code
+    /// entries; for new child code entries, copy it from an existing sibling.
     pub weird_local_flag: bool,
 
     /// Offset, **in bytes**, where code should begin executing from within the
@@ -185,6 +702,46 @@ pub struct ModernData {
     pub parent: GMRef<Code>,
 }
 
+impl ModernData {
+    /// The highest argument count that can be stored.
+    ///
+    /// The highest bit of the stored value is the [`ModernData::weird_local_flag`].
+    pub const MAX_ARGUMENT_COUNT: u16 = 0x7FFF;
+
+    /// Packs the argument count and the weird local flag into the value
+    /// stored in the data file.
+    ///
+    /// Returns an error if the argument count exceeds
+    /// [`ModernData::MAX_ARGUMENT_COUNT`], since it would overwrite the flag.
+    pub fn arguments_count_raw(&self) -> Result<u16> {
+        if self.argument_count > Self::MAX_ARGUMENT_COUNT {
+            bail!(
+                "Argument count {} exceeds the maximum of {}",
+                self.argument_count,
+                Self::MAX_ARGUMENT_COUNT,
+            );
+        }
+        let flag: u16 = if self.weird_local_flag { 0x8000 } else { 0 };
+        Ok(self.argument_count | flag)
+    }
+
+    /// Sets the argument count and the weird local flag.
+    ///
+    /// Returns an error if the argument count does not fit in 15 bits
+    /// (see [`ModernData::MAX_ARGUMENT_COUNT`]).
+    pub fn set_arguments_count(&mut self, count: u16, weird_local_flag: bool) -> Result<()> {
+        if count > Self::MAX_ARGUMENT_COUNT {
+            bail!(
+                "Argument count {count} exceeds the maximum of {}",
+                Self::MAX_ARGUMENT_COUNT
+            );
+        }
+        self.argument_count = count;
+        self.weird_local_flag = weird_local_flag;
+        Ok(())
+    }
+}
+
 /// Gets the total (cumulative) size of all instructions, in bytes.
 ///
 /// This function simply calls [`Instruction::size`] on each instruction and
@@ -218,7 +775,7 @@ fn splice_instructions(
 
     let insertion_size = instructions_size(replace_with);
     let removal_size = instructions_size(&haystack[start..end]);
-    let fixed_offset = (insertion_size - removal_size) as i32 / 4;
+    let fixed_offset = (insertion_size as i32 - removal_size as i32) / 4;
 
     let first_half_size = instructions_size(&haystack[..start]) as i32 / 4;
 
@@ -303,3 +860,106 @@ pub fn remove_instructions(
 pub fn remove_instruction(haystack: &mut Vec<Instruction>, index: u32) -> Result<Vec<Instruction>> {
     remove_instructions(haystack, index..index + 1)
 }
+
+/// Builds the instructions for a GML `with` statement.
+///
+/// There is no GML compiler in LibGM yet, so this only lowers an already
+/// assembled `with` block. The `instance` instructions are emitted first and
+/// should push exactly one value (an object ID, instance ID or special instance
+/// type) onto the stack. After that, the body is bracketed by a
+/// [`Instruction::PushWithContext`] jumping to the matching
+/// [`Instruction::PopWithContext`], which in turn loops back to the start of
+/// the body for every remaining instance.
+///
+/// Every [`Instruction::Exit`] in the body gets a
+/// [`Instruction::PopWithContextExit`] inserted in front of it, since the
+/// `with` context has to be dropped before leaving the code entry.
+/// Branches within the body are adjusted accordingly. A branch to the very
+/// end of the body will target the [`Instruction::PopWithContext`] (like
+/// `continue` would).
+///
+/// [`Instruction::Return`] is not supported yet, since the return value has
+/// to be moved out of the `with` context first.
+///
+/// ```
+/// use libgm::gml::Instruction;
+/// use libgm::gml::build_with_statement;
+/// use libgm::gml::instruction::DataType;
+///
+/// // with (7) { 1; }
+/// let instance = [Instruction::PushImmediate { integer: 7 }];
+/// let body = [
+///     Instruction::PushImmediate { integer: 1 },
+///     Instruction::PopDiscard { data_type: DataType::Int32 },
+/// ];
+/// let instructions = build_with_statement(&instance, &body)?;
+///
+/// assert_eq!(instructions, [
+///     Instruction::PushImmediate { integer: 7 },
+///     Instruction::PushWithContext { jump_offset: 3 },
+///     Instruction::PushImmediate { integer: 1 },
+///     Instruction::PopDiscard { data_type: DataType::Int32 },
+///     Instruction::PopWithContext { jump_offset: -2 },
+/// ]);
+/// # Ok::<(), libgm::error::Error>(())
+/// ```
+pub fn build_with_statement(
+    instance: &[Instruction],
+    body: &[Instruction],
+) -> Result<Vec<Instruction>> {
+    let body = insert_with_exits(body).ctx("inserting with context exits")?;
+    let body_size = instructions_size(&body) as i32 / 4;
+
+    let mut instructions: Vec<Instruction> = Vec::with_capacity(instance.len() + body.len() + 2);
+    instructions.extend_from_slice(instance);
+    instructions.push(Instruction::PushWithContext { jump_offset: body_size + 1 });
+    instructions.extend(body);
+    instructions.push(Instruction::PopWithContext { jump_offset: -body_size });
+    Ok(instructions)
+}
+
+/// Inserts a [`Instruction::PopWithContextExit`] before every
+/// [`Instruction::Exit`] and remaps all branches in the body.
+fn insert_with_exits(body: &[Instruction]) -> Result<Vec<Instruction>> {
+    // Positions (in multiples of 4 bytes) of each instruction, plus the end position.
+    let mut old_positions: Vec<i32> = Vec::with_capacity(body.len() + 1);
+    let mut new_positions: Vec<i32> = Vec::with_capacity(body.len() + 1);
+    let mut old_pos: i32 = 0;
+    let mut new_pos: i32 = 0;
+
+    for instruction in body {
+        // Branches to an exit should also execute the inserted context exit,
+        // so the exit's new position is the one of the inserted instruction.
+        old_positions.push(old_pos);
+        new_positions.push(new_pos);
+        match instruction {
+            Instruction::Return => {
+                bail!("Return statements within with statements are not supported yet")
+            }
+            Instruction::Exit => new_pos += Instruction::PopWithContextExit.size4() as i32,
+            _ => {}
+        }
+        old_pos += instruction.size4() as i32;
+        new_pos += instruction.size4() as i32;
+    }
+    old_positions.push(old_pos);
+    new_positions.push(new_pos);
+
+    let mut instructions: Vec<Instruction> = Vec::with_capacity(body.len());
+    for (i, instruction) in body.iter().enumerate() {
+        let mut instruction = instruction.clone();
+        if let Some(jump_offset) = instruction.jump_offset_mut() {
+            let target: i32 = old_positions[i] + *jump_offset;
+            let index = old_positions.binary_search(&target).map_err(|_| {
+                err!("Branch target {target} of instruction #{i} is not within the with body")
+            })?;
+            *jump_offset = new_positions[index] - new_positions[i];
+        }
+        if matches!(instruction, Instruction::Exit) {
+            instructions.push(Instruction::PopWithContextExit);
+        }
+        instructions.push(instruction);
+    }
+
+    Ok(instructions)
+}
@@ -1,4 +1,6 @@
 // SPDX-License-Identifier: GPL-3.0-only
+use std::collections::HashMap;
+
 use crate::gml::Instruction;
 use crate::gml::instruction::DataType;
 use crate::prelude::*;
@@ -76,3 +78,100 @@ pub fn analyze(data: &GMData) -> CodeAnalysis {
 
     analysis
 }
+
+/// Statistics about the instructions used in a game's code.
+///
+/// Created by [`GMData::instruction_histogram`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct InstructionHistogram {
+    /// How often each instruction mnemonic (e.g. `"push"`) occurs.
+    ///
+    /// See [`Instruction::mnemonic`].
+    pub opcodes: HashMap<&'static str, u64>,
+
+    /// How often each data type is pushed onto the stack.
+    ///
+    /// This covers all instructions of [`Category::Push`] that have
+    /// a data type.
+    ///
+    /// [`Category::Push`]: crate::gml::instruction::Category::Push
+    pub push_types: HashMap<DataType, u64>,
+
+    /// How often each data type is popped off the stack,
+    /// by `pop` and `popz` instructions.
+    pub pop_types: HashMap<DataType, u64>,
+
+    /// The number of code entries with instructions.
+    ///
+    /// Child code entries (which share their parent's instructions)
+    /// are not counted.
+    pub code_count: usize,
+
+    /// The total number of instructions.
+    pub instruction_count: u64,
+
+    /// The highest number of instructions in a single code entry.
+    pub max_instructions: usize,
+}
+
+impl InstructionHistogram {
+    /// The average number of instructions per code entry.
+    ///
+    /// Returns zero if there are no code entries.
+    #[must_use]
+    #[expect(clippy::cast_precision_loss)]
+    pub fn average_instructions(&self) -> f64 {
+        if self.code_count == 0 {
+            return 0.0;
+        }
+        self.instruction_count as f64 / self.code_count as f64
+    }
+}
+
+impl GMData {
+    /// Counts all instructions in all code entries by their mnemonic,
+    /// along with some other statistics.
+    ///
+    /// This has to loop over every instruction in every code entry.
+    #[must_use]
+    pub fn instruction_histogram(&self) -> InstructionHistogram {
+        let mut histogram = InstructionHistogram::default();
+
+        for code in self.codes.elements() {
+            if code.instructions.is_empty() {
+                continue;
+            }
+            histogram.code_count += 1;
+            histogram.instruction_count += code.instructions.len() as u64;
+            histogram.max_instructions = histogram.max_instructions.max(code.instructions.len());
+
+            for instruction in &code.instructions {
+                *histogram.opcodes.entry(instruction.mnemonic()).or_default() += 1;
+                let data_types = match instruction {
+                    Instruction::Pop { type1, .. } => Some((&mut histogram.pop_types, *type1)),
+                    Instruction::PopDiscard { data_type } => {
+                        Some((&mut histogram.pop_types, *data_type))
+                    }
+                    _ if instruction.is_push() => instruction
+                        .type1()
+                        .map(|data_type| (&mut histogram.push_types, data_type)),
+                    _ => None,
+                };
+                if let Some((map, data_type)) = data_types {
+                    *map.entry(data_type).or_default() += 1;
+                }
+            }
+        }
+
+        histogram
+    }
+
+    /// Counts all instructions in all code entries by their mnemonic.
+    ///
+    /// This is a shorthand for [`GMData::instruction_histogram`] if only
+    /// the opcode counts are of interest.
+    #[must_use]
+    pub fn opcode_histogram(&self) -> HashMap<&'static str, u64> {
+        self.instruction_histogram().opcodes
+    }
+}
@@ -263,6 +263,16 @@ fn parse_instruction(
             let asset_reference = parse_asset_reference(reader, gm_data)?;
             Instruction::PushReference { asset_reference }
         }
+        "unknown" => {
+            types.assert_count(0, mnemonic)?;
+            let (opcode, raw) = reader
+                .clear()
+                .split_once(' ')
+                .ok_or("Expected opcode and raw instruction word")?;
+            let opcode: u8 = parse_int(opcode)?;
+            let raw: u32 = parse_int(raw)?;
+            Instruction::Unknown { opcode, raw }
+        }
         _ => bail!("Invalid opcode mnemonic {mnemonic:?}"),
     };
 
@@ -3,7 +3,6 @@ use crate::gml::Code;
 use crate::gml::instruction::AssetReference;
 use crate::gml::instruction::CodeVariable;
 use crate::gml::instruction::ComparisonType;
-use crate::gml::instruction::DataType;
 use crate::gml::instruction::InstanceType;
 use crate::gml::instruction::Instruction;
 use crate::gml::instruction::PushValue;
@@ -243,14 +242,21 @@ pub fn disassemble_instruction_to_buffer(
             write!(buffer, "{mnemonic} ");
             write_asset_reference(asset_reference, buffer, gm_data)?;
         }
+
+        Instruction::Unknown { opcode, raw } => {
+            write!(buffer, "{mnemonic} {opcode} {raw}");
+        }
     }
 
     Ok(())
 }
 
 impl Instruction {
+    /// The assembly mnemonic of this instruction's opcode, e.g. `"push"`.
+    ///
+    /// `PopSwap` has two mnemonics depending on `is_array`.
     #[must_use]
-    const fn mnemonic(&self) -> &'static str {
+    pub const fn mnemonic(&self) -> &'static str {
         match self {
             Self::Convert { .. } => "conv",
             Self::Multiply { .. } => "mul",
@@ -299,21 +305,7 @@ impl Instruction {
             Self::RestoreArrayReference => "restorearef",
             Self::IsNullishValue => "isnullish",
             Self::PushReference { .. } => "pushref",
-        }
-    }
-}
-
-impl DataType {
-    #[must_use]
-    const fn as_char(self) -> char {
-        match self {
-            Self::Int16 => 'e',
-            Self::Int32 => 'i',
-            Self::Int64 => 'l',
-            Self::Double => 'd',
-            Self::Bool => 'b',
-            Self::String => 's',
-            Self::Variable => 'v',
+            Self::Unknown { .. } => "unknown",
         }
     }
 }
@@ -0,0 +1,231 @@
+// SPDX-License-Identifier: GPL-3.0-only
+//! Changing the bytecode (WAD) version of a data file.
+
+use std::fmt::Display;
+use std::fmt::Formatter;
+
+use crate::gml::Code;
+use crate::gml::Instruction;
+use crate::gml::opcodes;
+use crate::prelude::*;
+use crate::wad::GMVersion;
+
+/// An instruction which cannot be represented in a bytecode version.
+///
+/// See [`GMData::incompatible_instructions`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct IncompatibleInstruction {
+    /// The code entry containing the instruction.
+    pub code: GMRef<Code>,
+
+    /// The index of the instruction within the code entry.
+    pub index: usize,
+
+    /// The incompatible instruction.
+    pub instruction: Instruction,
+
+    /// The lowest bytecode version supporting this instruction.
+    pub required_version: u8,
+}
+
+/// The instructions preventing a bytecode version change.
+///
+/// See [`GMData::set_bytecode_version`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct IncompatibleInstructions {
+    /// The bytecode version which was attempted to be set.
+    pub bytecode_version: u8,
+
+    /// All instructions which cannot be represented in this bytecode version.
+    pub instructions: Vec<IncompatibleInstruction>,
+
+    /// The names of the code entries containing the instructions.
+    /// Resolved on creation, since the error may outlive the data file borrow.
+    code_names: Vec<String>,
+}
+
+impl Display for IncompatibleInstructions {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        write!(
+            f,
+            "{} instructions cannot be represented in bytecode version {}:",
+            self.instructions.len(),
+            self.bytecode_version,
+        )?;
+        for (instruction, code_name) in self.instructions.iter().zip(&self.code_names) {
+            write!(
+                f,
+                "\n* Instruction #{} ({:?}) in code entry {code_name:?} requires bytecode version \
+                 {}",
+                instruction.index, instruction.instruction, instruction.required_version,
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for IncompatibleInstructions {}
+
+/// An error returned by [`GMData::set_bytecode_version`].
+#[derive(Debug)]
+pub enum SetBytecodeVersionError {
+    /// The data file cannot be changed to this bytecode version at all,
+    /// for example because its format version is too new.
+    Invalid(Error),
+
+    /// Some instructions cannot be represented in this bytecode version.
+    Incompatible(IncompatibleInstructions),
+}
+
+impl Display for SetBytecodeVersionError {
+    fn fmt(&self, f: &mut Formatter) -> std::fmt::Result {
+        match self {
+            Self::Invalid(error) => f.write_str(&error.chain()),
+            Self::Incompatible(incompatible) => incompatible.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for SetBytecodeVersionError {}
+
+impl From<Error> for SetBytecodeVersionError {
+    fn from(error: Error) -> Self {
+        Self::Invalid(error)
+    }
+}
+
+impl From<SetBytecodeVersionError> for Error {
+    fn from(error: SetBytecodeVersionError) -> Self {
+        match error {
+            SetBytecodeVersionError::Invalid(error) => error,
+            SetBytecodeVersionError::Incompatible(incompatible) => {
+                let bytecode_version: u8 = incompatible.bytecode_version;
+                Self::new(incompatible.to_string())
+                    .with_context(format!("setting bytecode version to {bytecode_version}"))
+            }
+        }
+    }
+}
+
+/// The lowest bytecode version supporting the instruction.
+const fn required_version(instruction: &Instruction) -> u8 {
+    match instruction {
+        _ if instruction.opcode() == opcodes::EXTENDED => 17,
+        Instruction::PushImmediate { .. }
+        | Instruction::PushLocal { .. }
+        | Instruction::PushGlobal { .. }
+        | Instruction::PushBuiltin { .. } => 15,
+        _ => 13,
+    }
+}
+
+impl GMData {
+    /// Finds all instructions which cannot be represented in the specified
+    /// bytecode (WAD) version.
+    ///
+    /// Extended instructions (array, static and reference handling) require
+    /// WAD 17. Immediate pushes and specialized variable pushes require WAD 15.
+    #[must_use]
+    pub fn incompatible_instructions(&self, bytecode_version: u8) -> Vec<IncompatibleInstruction> {
+        let mut incompatible: Vec<IncompatibleInstruction> = Vec::new();
+        for (code_ref, code) in self.codes.element_refs() {
+            for (index, instruction) in code.instructions.iter().enumerate() {
+                let required_version: u8 = required_version(instruction);
+                if bytecode_version < required_version {
+                    incompatible.push(IncompatibleInstruction {
+                        code: code_ref,
+                        index,
+                        instruction: instruction.clone(),
+                        required_version,
+                    });
+                }
+            }
+        }
+        incompatible
+    }
+
+    /// Changes the bytecode (WAD) version of this data file.
+    ///
+    /// All code entries are validated before anything is changed:
+    /// * Every instruction has to be representable in the new version,
+    ///   see [`GMData::incompatible_instructions`].
+    /// * Below WAD 15, child code entries cannot exist.
+    /// * From WAD 15 on, every code entry needs [`ModernData`].
+    ///
+    /// Below WAD 16, the format version is changed to match the bytecode
+    /// version, since instructions are encoded differently before WAD 15.
+    /// GameMaker Studio 2 data files can only switch between WAD 16 and 17.
+    ///
+    /// If any instructions cannot be represented in the new version, nothing
+    /// is changed and [`SetBytecodeVersionError::Incompatible`] listing all of
+    /// them is returned. To get these instructions without changing anything,
+    /// use [`GMData::incompatible_instructions`].
+    ///
+    /// [`ModernData`]: crate::gml::ModernData
+    pub fn set_bytecode_version(
+        &mut self,
+        bytecode_version: u8,
+    ) -> std::result::Result<(), SetBytecodeVersionError> {
+        check_bytecode_version(self, bytecode_version)
+            .ctx(|| format!("setting bytecode version to {bytecode_version}"))?;
+
+        let instructions: Vec<IncompatibleInstruction> =
+            self.incompatible_instructions(bytecode_version);
+        if !instructions.is_empty() {
+            let code_names: Vec<String> = instructions
+                .iter()
+                .map(|instruction| {
+                    self.codes.by_ref(instruction.code).map_or_else(
+                        |_| format!("{:?}", instruction.code),
+                        |code| code.name.display(&self.strings).to_owned(),
+                    )
+                })
+                .collect();
+            return Err(SetBytecodeVersionError::Incompatible(
+                IncompatibleInstructions {
+                    bytecode_version,
+                    instructions,
+                    code_names,
+                },
+            ));
+        }
+
+        self.meta.version = format_version(self, bytecode_version)?;
+        self.general_info.wad_version = bytecode_version;
+        Ok(())
+    }
+}
+
+/// The format version to use for the bytecode version.
+fn format_version(data: &GMData, bytecode_version: u8) -> Result<GMVersion> {
+    Ok(match bytecode_version {
+        13 => GMVersion::Wad13,
+        14 => GMVersion::Wad14,
+        15 => GMVersion::Wad15,
+        16 | 17 if data.meta.version < GMVersion::Wad16Old => GMVersion::Wad16Old,
+        16 | 17 => data.meta.version,
+        _ => bail!("Unsupported bytecode version {bytecode_version}"),
+    })
+}
+
+/// Checks everything except instruction compatibility.
+fn check_bytecode_version(data: &GMData, bytecode_version: u8) -> Result<()> {
+    format_version(data, bytecode_version)?;
+    if bytecode_version < 16 && data.meta.version >= GMVersion::GMS2 {
+        bail!(
+            "Data file with format version {} cannot be downgraded below WAD 16",
+            data.meta.version,
+        );
+    }
+
+    for code in data.codes.elements() {
+        let name = || code.name.display(&data.strings);
+        if bytecode_version < 15 && !code.is_root() {
+            bail!("Child code entry {:?} requires WAD 15", name());
+        }
+        if bytecode_version >= 15 && code.modern_data.is_none() {
+            bail!("Code entry {:?} is missing its WAD 15+ data", name());
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,278 @@
+// SPDX-License-Identifier: GPL-3.0-only
+//! Static call graphs of GML code.
+//!
+//! A [`CallGraph`] connects code entries by the [`Instruction::Call`]
+//! instructions found in their bytecode. It can be used to find the callers
+//! of a script, to estimate the impact of changing it, or to detect scripts
+//! which are never called (see [`CallGraph::reachable`]).
+
+use std::collections::HashMap;
+use std::collections::HashSet;
+
+use crate::gml::Code;
+use crate::gml::Instruction;
+use crate::gml::cfg;
+use crate::gml::cfg::Cfg;
+use crate::prelude::*;
+use crate::wad::elem::GMNamedElement;
+use crate::wad::elem::function::Function;
+
+/// The static call relationships between code entries.
+///
+/// Nodes are code entries. A function (or pre-GMS 2.3 script) is represented
+/// by the code entry of its body; see [`CallGraph::code_of_function`].
+/// Calls to functions without a code entry (built-in runner functions and
+/// extension functions) are not part of the graph.
+///
+/// Functions can also be called dynamically with
+/// [`Instruction::CallVariable`], for example methods, callbacks or functions
+/// stored in variables. These calls cannot be resolved statically, so
+/// functions only called that way have no incoming edges.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CallGraph {
+    /// All static calls as `(caller, callee)` pairs, sorted and without
+    /// duplicates.
+    pub edges: Vec<(GMRef<Code>, GMRef<Code>)>,
+
+    /// The code entries where reachability analysis starts, sorted.
+    ///
+    /// These are all code entries which are not the body of a function or
+    /// script, since the runner executes them directly (like object events,
+    /// room creation code or global scripts), as well as all code entries
+    /// containing dynamic calls (see [`CallGraph::dynamic_callers`]).
+    pub roots: Vec<GMRef<Code>>,
+
+    /// The code entries containing [`Instruction::CallVariable`], sorted.
+    pub dynamic_callers: Vec<GMRef<Code>>,
+
+    /// The code entry of every function with one.
+    functions: HashMap<GMRef<Function>, GMRef<Code>>,
+}
+
+impl CallGraph {
+    /// The code entry containing the body of the specified function.
+    ///
+    /// Returns [`None`] for built-in functions and extension functions.
+    #[must_use]
+    pub fn code_of_function(&self, function: GMRef<Function>) -> Option<GMRef<Code>> {
+        self.functions.get(&function).copied()
+    }
+
+    /// The code entries statically calling the specified code entry.
+    pub fn callers_of(&self, callee: GMRef<Code>) -> impl Iterator<Item = GMRef<Code>> {
+        self.edges
+            .iter()
+            .filter(move |edge| edge.1 == callee)
+            .map(|edge| edge.0)
+    }
+
+    /// The code entries statically called by the specified code entry.
+    pub fn callees_of(&self, caller: GMRef<Code>) -> impl Iterator<Item = GMRef<Code>> {
+        self.edges
+            .iter()
+            .filter(move |edge| edge.0 == caller)
+            .map(|edge| edge.1)
+    }
+
+    /// Determines which code entries can be reached from the
+    /// [roots](CallGraph::roots) by following static calls.
+    ///
+    /// Functions missing from this set are never called statically.
+    /// They may still be dead code candidates rather than dead code, since
+    /// they could be called dynamically (see [`CallGraph`]).
+    #[must_use]
+    pub fn reachable(&self) -> HashSet<GMRef<Code>> {
+        let mut reachable: HashSet<GMRef<Code>> = HashSet::new();
+        let mut stack: Vec<GMRef<Code>> = self.roots.clone();
+        while let Some(code) = stack.pop() {
+            if reachable.insert(code) {
+                stack.extend(self.callees_of(code));
+            }
+        }
+        reachable
+    }
+}
+
+impl GMData {
+    /// Builds the static call graph of all code entries.
+    ///
+    /// Child code entries (such as GML functions declared inside a script)
+    /// share their parent's instructions. Every call instruction is
+    /// attributed to the code entries whose entry point reaches it in the
+    /// control flow graph (see [`GMData::code_cfg`]), so calls inside a
+    /// function body belong to that function instead of the surrounding
+    /// script.
+    ///
+    /// Returns an error if the control flow graph of a code entry cannot be
+    /// built.
+    #[doc(alias = "function_call_graph")]
+    pub fn call_graph(&self) -> Result<CallGraph> {
+        let functions: HashMap<GMRef<Function>, GMRef<Code>> = self.function_codes();
+        let bodies: HashSet<GMRef<Code>> = functions.values().copied().collect();
+
+        let mut children: HashMap<GMRef<Code>, Vec<GMRef<Code>>> = HashMap::new();
+        for (code_ref, code) in self.codes.element_refs() {
+            if !code.is_root() {
+                children.entry(code.parent()).or_default().push(code_ref);
+            }
+        }
+
+        let mut edges: Vec<(GMRef<Code>, GMRef<Code>)> = Vec::new();
+        let mut dynamic_callers: Vec<GMRef<Code>> = Vec::new();
+        for (code_ref, code) in self.codes.element_refs() {
+            if !code.is_root() {
+                continue;
+            }
+            let ctx = || format!("analyzing calls of {:?}", code.name.display(&self.strings));
+            let mut entries: Vec<(GMRef<Code>, u32)> = vec![(code_ref, 0)];
+            for &child_ref in children.get(&code_ref).into_iter().flatten() {
+                let child: &Code = self.codes.by_ref(child_ref)?;
+                entries.push((child_ref, child.execution_offset()));
+            }
+            let offsets: Vec<u32> = entries[1..].iter().map(|entry| entry.1).collect();
+            let graph: Cfg = cfg::build(&code.instructions, &offsets).ctx(ctx)?;
+
+            for (entry_ref, offset) in entries {
+                let Some(block) = entry_block(&graph, &code.instructions, offset) else {
+                    continue;
+                };
+                let reachable: Vec<bool> = graph.reachable_from(block);
+                let instructions = graph
+                    .blocks
+                    .iter()
+                    .zip(reachable)
+                    .filter(|(_, reachable)| *reachable)
+                    .flat_map(|(block, _)| &code.instructions[block.instructions.clone()]);
+                for instruction in instructions {
+                    match instruction {
+                        Instruction::Call { function, .. } => {
+                            if let Some(&callee) = functions.get(function) {
+                                edges.push((entry_ref, callee));
+                            }
+                        }
+                        Instruction::CallVariable { .. } => dynamic_callers.push(entry_ref),
+                        _ => {}
+                    }
+                }
+            }
+        }
+
+        edges.sort_unstable_by_key(|&(caller, callee)| (caller.index, callee.index));
+        edges.dedup();
+        dynamic_callers.sort_unstable_by_key(|code| code.index);
+        dynamic_callers.dedup();
+
+        let mut roots: Vec<GMRef<Code>> = self
+            .codes
+            .element_refs()
+            .map(|(code_ref, _)| code_ref)
+            .filter(|code_ref| !bodies.contains(code_ref))
+            .chain(dynamic_callers.iter().copied())
+            .collect();
+        roots.sort_unstable_by_key(|code| code.index);
+        roots.dedup();
+
+        Ok(CallGraph { edges, roots, dynamic_callers, functions })
+    }
+
+    /// Collects the names of all functions called by any code entry with
+    /// [`Instruction::Call`].
+    ///
+    /// This includes built-in runner functions, extension functions and GML
+    /// functions alike, which makes it useful for comparing against the
+    /// functions available in a specific GameMaker version.
+    /// Dynamic calls ([`Instruction::CallVariable`]) cannot be resolved and
+    /// are not included.
+    ///
+    /// Returns an error if a called function does not exist or has an
+    /// invalid name reference.
+    pub fn functions_called(&self) -> Result<HashSet<&str>> {
+        let mut names: HashSet<&str> = HashSet::new();
+        for code in self.codes.elements() {
+            for instruction in &code.instructions {
+                if let Instruction::Call { function, .. } = *instruction {
+                    let function: &Function = self.functions.by_ref(function).ctx(|| {
+                        format!(
+                            "resolving called function in code entry {:?}",
+                            code.name.display(&self.strings)
+                        )
+                    })?;
+                    names.insert(function.name(&self.strings)?);
+                }
+            }
+        }
+        Ok(names)
+    }
+
+    /// Whether any code entry calls the function with the specified name
+    /// (usually a built-in runner function).
+    ///
+    /// Stops scanning at the first matching [`Instruction::Call`].
+    /// For more information, see [`GMData::functions_called`].
+    ///
+    /// Returns an error if a function has an invalid name reference.
+    pub fn code_uses_builtin(&self, function_name: &str) -> Result<bool> {
+        let mut targets: HashSet<GMRef<Function>> = HashSet::new();
+        for (function_ref, function) in self.functions.element_refs() {
+            if function.name(&self.strings)? == function_name {
+                targets.insert(function_ref);
+            }
+        }
+        if targets.is_empty() {
+            return Ok(false);
+        }
+
+        let is_target_call = |instruction: &Instruction| match instruction {
+            Instruction::Call { function, .. } => targets.contains(function),
+            _ => false,
+        };
+        let uses_builtin: bool = self
+            .codes
+            .elements()
+            .any(|code| code.instructions.iter().any(is_target_call));
+        Ok(uses_builtin)
+    }
+
+    /// Maps functions to the code entries of their bodies.
+    ///
+    /// Since GMS 2.3, functions have the same name as their code entry
+    /// (`gml_Script_<name>`). Before that, functions are called by their script
+    /// name, which links to the code entry.
+    fn function_codes(&self) -> HashMap<GMRef<Function>, GMRef<Code>> {
+        let mut codes_by_name: HashMap<&str, GMRef<Code>> = HashMap::new();
+        for (code_ref, code) in self.codes.element_refs() {
+            codes_by_name
+                .entry(code.name.display(&self.strings))
+                .or_insert(code_ref);
+        }
+        for script in self.scripts.elements() {
+            if script.code.is_some() {
+                codes_by_name
+                    .entry(script.name.display(&self.strings))
+                    .or_insert(script.code);
+            }
+        }
+
+        let mut functions: HashMap<GMRef<Function>, GMRef<Code>> = HashMap::new();
+        for (function_ref, function) in self.functions.element_refs() {
+            if let Some(&code) = codes_by_name.get(function.name.display(&self.strings)) {
+                functions.insert(function_ref, code);
+            }
+        }
+        functions
+    }
+}
+
+/// The block starting at the specified entry offset (in bytes).
+///
+/// Returns [`None`] if the offset is at the end of the instructions.
+fn entry_block(graph: &Cfg, instructions: &[Instruction], offset: u32) -> Option<usize> {
+    let mut position: u32 = 0;
+    for (index, instruction) in instructions.iter().enumerate() {
+        if position >= offset {
+            return graph.block_of(index);
+        }
+        position += instruction.size();
+    }
+    None
+}
@@ -0,0 +1,284 @@
+// SPDX-License-Identifier: GPL-3.0-only
+//! Control flow graphs of GML bytecode.
+//!
+//! A [`Cfg`] splits the instructions of a code entry into basic blocks:
+//! runs of instructions which are always executed from start to end.
+//! Blocks are connected by [`Edge`]s for falling through to the next block,
+//! taken branches and `with` loops.
+//!
+//! This is the foundation for analyses like dead code detection
+//! (see [`Cfg::reachable_blocks`]) or stack balance checking
+//! (see [`Instruction::stack_effect`]).
+
+use std::ops::Range;
+
+use crate::gml::Code;
+use crate::gml::Instruction;
+use crate::prelude::*;
+
+/// A control flow graph of the instructions of a code entry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cfg {
+    /// The basic blocks, ordered by their position in the instructions.
+    ///
+    /// Together, they cover all instructions without overlapping.
+    pub blocks: Vec<BasicBlock>,
+
+    /// All edges between blocks, ordered by their source block.
+    ///
+    /// Branches to the very end of the instructions (which stop execution)
+    /// do not have an edge, just like falling through the last block.
+    pub edges: Vec<Edge>,
+
+    /// The blocks where execution can start.
+    ///
+    /// This is the first block, followed by the entry points of child code
+    /// entries (if built with [`GMData::code_cfg`]).
+    pub entries: Vec<usize>,
+}
+
+/// A run of instructions that is always executed from start to end.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BasicBlock {
+    /// The indices of the instructions in this block.
+    pub instructions: Range<usize>,
+}
+
+/// A possible transfer of control from the end of one block to the start of
+/// another.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Edge {
+    /// The index of the source block.
+    pub from: usize,
+
+    /// The index of the target block.
+    pub to: usize,
+
+    /// Why control is transferred.
+    pub kind: EdgeKind,
+}
+
+/// The reason for an [`Edge`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EdgeKind {
+    /// Execution continues with the next instruction.
+    /// This includes conditional branches which are not taken.
+    FallThrough,
+
+    /// A taken [`Instruction::Branch`], [`Instruction::BranchIf`] or
+    /// [`Instruction::BranchUnless`].
+    Branch,
+
+    /// An [`Instruction::PushWithContext`] skipping the `with` body
+    /// since there are no instances to iterate over.
+    WithContextSkip,
+
+    /// An [`Instruction::PopWithContext`] looping back to the start of the
+    /// `with` body for the next instance.
+    WithContextLoop,
+}
+
+impl Cfg {
+    /// The index of the block containing the specified instruction.
+    #[must_use]
+    pub fn block_of(&self, instruction: usize) -> Option<usize> {
+        let index = self
+            .blocks
+            .partition_point(|block| block.instructions.end <= instruction);
+        self.blocks
+            .get(index)
+            .filter(|block| block.instructions.contains(&instruction))
+            .map(|_| index)
+    }
+
+    /// The edges leaving the specified block.
+    pub fn successors(&self, block: usize) -> impl Iterator<Item = &Edge> {
+        self.edges.iter().filter(move |edge| edge.from == block)
+    }
+
+    /// The edges entering the specified block.
+    pub fn predecessors(&self, block: usize) -> impl Iterator<Item = &Edge> {
+        self.edges.iter().filter(move |edge| edge.to == block)
+    }
+
+    /// Determines which blocks can be reached from any entry block.
+    ///
+    /// The returned vector is indexed by block.
+    /// Unreachable blocks are dead code.
+    #[must_use]
+    pub fn reachable_blocks(&self) -> Vec<bool> {
+        self.reachable(self.entries.clone())
+    }
+
+    /// Determines which blocks can be reached from the specified block
+    /// (including itself).
+    ///
+    /// The returned vector is indexed by block.
+    /// This is useful for finding the instructions belonging to a single
+    /// entry point, like the body of a child code entry.
+    #[must_use]
+    pub fn reachable_from(&self, block: usize) -> Vec<bool> {
+        self.reachable(vec![block])
+    }
+
+    fn reachable(&self, mut stack: Vec<usize>) -> Vec<bool> {
+        let mut reachable: Vec<bool> = vec![false; self.blocks.len()];
+        while let Some(block) = stack.pop() {
+            if std::mem::replace(&mut reachable[block], true) {
+                continue;
+            }
+            stack.extend(self.successors(block).map(|edge| edge.to));
+        }
+        reachable
+    }
+}
+
+/// Builds the control flow graph of a code entry's instructions.
+///
+/// Child code entries do not have instructions of their own; use
+/// [`GMData::code_cfg`] for them instead.
+///
+/// Returns an error if a branch target is not an instruction boundary within
+/// the code entry.
+pub fn build_cfg(code: &Code) -> Result<Cfg> {
+    build(&code.instructions, &[])
+}
+
+impl GMData {
+    /// Builds the control flow graph of the specified code entry.
+    ///
+    /// Unlike [`build_cfg`], this also works for child code entries,
+    /// by using the instructions of their parent. Either way, the entry points
+    /// of all child code entries (such as the bodies of GML functions
+    /// declared inside a script) start their own block and are listed in
+    /// [`Cfg::entries`].
+    pub fn code_cfg(&self, code_ref: GMRef<Code>) -> Result<Cfg> {
+        let code: &Code = self.codes.by_ref(code_ref)?;
+        let root: GMRef<Code> = if code.is_root() {
+            code_ref
+        } else {
+            code.parent()
+        };
+        let code: &Code = self.codes.by_ref(root).ctx("resolving parent code entry")?;
+        let entry_offsets: Vec<u32> = Code::find_children(root, self)
+            .into_iter()
+            .map(|child| self.codes.by_ref(child).map(Code::execution_offset))
+            .collect::<Result<_>>()?;
+        build(&code.instructions, &entry_offsets).ctx("building control flow graph")
+    }
+}
+
+pub(crate) fn build(instructions: &[Instruction], entry_offsets: &[u32]) -> Result<Cfg> {
+    // Positions (in multiples of 4 bytes) of each instruction, plus the end position.
+    let mut positions: Vec<i64> = Vec::with_capacity(instructions.len() + 1);
+    let mut position: i64 = 0;
+    for instruction in instructions {
+        positions.push(position);
+        position += i64::from(instruction.size4());
+    }
+    positions.push(position);
+
+    let index_at = |position: i64| -> Result<usize> {
+        positions
+            .binary_search(&position)
+            .map_err(|_| err!("Position {position} is not an instruction boundary"))
+    };
+
+    // Branch target instruction index (or the end) of every branching instruction.
+    let mut targets: Vec<Option<usize>> = Vec::with_capacity(instructions.len());
+    for (i, instruction) in instructions.iter().enumerate() {
+        let target = match instruction.jump_offset() {
+            Some(offset) => Some(
+                index_at(positions[i] + i64::from(offset))
+                    .ctx(|| format!("resolving branch target of instruction #{i}"))?,
+            ),
+            None => None,
+        };
+        targets.push(target);
+    }
+
+    let mut entry_indices: Vec<usize> = vec![0];
+    for &offset in entry_offsets {
+        if !offset.is_multiple_of(4) {
+            bail!("Child code entry point at offset {offset} is not a multiple of 4");
+        }
+        let index = index_at(i64::from(offset / 4))
+            .ctx(|| format!("resolving child code entry point at offset {offset}"))?;
+        entry_indices.push(index);
+    }
+
+    let mut is_leader: Vec<bool> = vec![false; instructions.len() + 1];
+    for &index in &entry_indices {
+        is_leader[index] = true;
+    }
+    for (i, instruction) in instructions.iter().enumerate() {
+        if let Some(target) = targets[i] {
+            is_leader[target] = true;
+        }
+        if ends_block(instruction) {
+            is_leader[i + 1] = true;
+        }
+    }
+
+    let mut blocks: Vec<BasicBlock> = Vec::new();
+    let mut block_of: Vec<usize> = vec![0; instructions.len() + 1];
+    for i in 0..instructions.len() {
+        if is_leader[i] {
+            blocks.push(BasicBlock { instructions: i..i });
+        }
+        let index = blocks.len() - 1;
+        blocks[index].instructions.end = i + 1;
+        block_of[i] = index;
+    }
+
+    let mut edges: Vec<Edge> = Vec::new();
+    for (from, block) in blocks.iter().enumerate() {
+        let Some(last) = block.instructions.end.checked_sub(1) else {
+            continue;
+        };
+        let instruction = &instructions[last];
+        let taken_kind = match instruction {
+            Instruction::PushWithContext { .. } => Some(EdgeKind::WithContextSkip),
+            Instruction::PopWithContext { .. } => Some(EdgeKind::WithContextLoop),
+            _ if targets[last].is_some() => Some(EdgeKind::Branch),
+            _ => None,
+        };
+        if let (Some(kind), Some(target)) = (taken_kind, targets[last])
+            && target < instructions.len()
+        {
+            edges.push(Edge { from, to: block_of[target], kind });
+        }
+        let falls_through = !matches!(
+            instruction,
+            Instruction::Branch { .. } | Instruction::Exit | Instruction::Return
+        );
+        if falls_through && block.instructions.end < instructions.len() {
+            edges.push(Edge {
+                from,
+                to: from + 1,
+                kind: EdgeKind::FallThrough,
+            });
+        }
+    }
+
+    let entries: Vec<usize> = entry_indices
+        .into_iter()
+        .filter(|&index| index < instructions.len())
+        .map(|index| block_of[index])
+        .collect();
+    Ok(Cfg { blocks, edges, entries })
+}
+
+/// Whether the instruction transfers control, which ends a basic block.
+const fn ends_block(instruction: &Instruction) -> bool {
+    matches!(
+        instruction,
+        Instruction::Branch { .. }
+            | Instruction::BranchIf { .. }
+            | Instruction::BranchUnless { .. }
+            | Instruction::PushWithContext { .. }
+            | Instruction::PopWithContext { .. }
+            | Instruction::Exit
+            | Instruction::Return
+    )
+}
@@ -3,21 +3,30 @@
 //! including the important [`Instruction`] type.
 
 mod asset_reference;
+mod canonical;
 mod category;
 mod code_variable;
 mod comparison_type;
 mod data_type;
 mod instance_type;
 mod push_value;
+mod resolved;
+mod stack_effect;
 mod variable_type;
 
+pub(crate) use self::asset_reference::AssetCounts;
 pub use self::asset_reference::AssetReference;
+pub use self::canonical::CanonicalInstruction;
 pub use self::category::Category;
 pub use self::code_variable::CodeVariable;
 pub use self::comparison_type::ComparisonType;
 pub use self::data_type::DataType;
 pub use self::instance_type::InstanceType;
+pub use self::instance_type::ROOM_INSTANCE_ID_OFFSET;
 pub use self::push_value::PushValue;
+pub use self::resolved::Operand;
+pub use self::resolved::ResolvedInstruction;
+pub use self::stack_effect::StackEffect;
 pub use self::variable_type::VariableType;
 use crate::wad::GMRef;
 use crate::wad::elem::function::Function;
@@ -181,10 +190,10 @@ pub enum Instruction {
     /// It only influences a multiplication factor of how many bytes to
     /// clone, since different data types have different sizes on the stack.
     ///
-    /// To get the duplication value *count* (instead of byte size) for a
-    /// homogenous stack data type, use `instr.size /
-    /// instr.data_type.size()`. For safety, it is probably a good idea to
-    /// verify that the remainder is zero.
+    /// The encoded size is one less than the number of values of the specified
+    /// data type to duplicate: `dup.i 1` duplicates two `Int32` values
+    /// (such as an instance and an array index). For the total byte size,
+    /// use `(instr.size + 1) * instr.data_type.size()`.
     Duplicate { data_type: DataType, size: u8 },
 
     /// **Swaps** values around on the stack.
@@ -455,9 +464,11 @@ pub enum Instruction {
     /// Presumed to be used for garbage collection purposes.
     RestoreArrayReference,
 
-    /// Pops a value from the stack, and pushes a boolean result.
-    /// The result is true if a "nullish" value, such as `undefined` or GML's
-    /// `pointer_null`.
+    /// Pushes a boolean result, which is true if the stack top is a "nullish"
+    /// value, such as `undefined` or GML's `pointer_null`.
+    ///
+    /// The checked value is not popped; this is used for the `??` operator,
+    /// which discards it with [`Instruction::PopDiscard`] if it is nullish.
     IsNullishValue,
 
     /// Pushes an asset reference to the stack, encoded in an integer.
@@ -469,14 +480,35 @@ pub enum Instruction {
     ///
     /// This instruction is used in more modern versions of GameMaker.
     PushReference { asset_reference: AssetReference },
+
+    /// An instruction with an opcode LibGM does not know (yet),
+    /// such as one introduced in a newer GameMaker version.
+    ///
+    /// This is only produced when parsing with
+    /// [`ParsingOptions::allow_unknown_instructions`] enabled.
+    /// The raw instruction word is kept so that it is written back verbatim
+    /// when building.
+    ///
+    /// The execution semantics of these instructions are unknown:
+    /// LibGM assumes that they are exactly one word (4 bytes) long and have
+    /// no effect on the stack, which may well be wrong.
+    ///
+    /// [`ParsingOptions::allow_unknown_instructions`]: crate::wad::parse::ParsingOptions::allow_unknown_instructions
+    Unknown {
+        /// The (WAD15+ normalized) opcode of this instruction.
+        opcode: u8,
+
+        /// The raw instruction word, including the opcode.
+        raw: u32,
+    },
 }
 
 impl Instruction {
     /// Gets the instruction size in multiples of 4 bytes.
     /// This unit is used by `jump_offset`s in branch instructions.
     ///
-    /// For example, [`Instruction::Push`] with a [`PushValue::Int16`] has a
-    /// size of 5.
+    /// For example, [`Instruction::Push`] with a [`PushValue::Int32`] has a
+    /// size of 2.
     #[must_use]
     pub const fn size4(&self) -> u32 {
         match self {
@@ -499,8 +531,8 @@ impl Instruction {
     /// This size includes extra data like integers, floats, variable
     /// references, etc.
     ///
-    /// For example, [`Instruction::Push`] with a [`PushValue::Int16`] has a
-    /// size of 20.
+    /// For example, [`Instruction::Push`] with a [`PushValue::Int32`] has a
+    /// size of 8.
     #[must_use]
     pub const fn size(&self) -> u32 {
         self.size4() * 4
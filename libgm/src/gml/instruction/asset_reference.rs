@@ -147,6 +147,29 @@ impl AssetReference {
         }
     }
 
+    /// The asset kind of this asset reference, as used in the assembly
+    /// syntax (e.g. `"object"` for `pushref (object)obj_player`).
+    #[must_use]
+    pub const fn kind(self) -> &'static str {
+        match self {
+            Self::Object(_) => "object",
+            Self::Sprite(_) => "sprite",
+            Self::Sound(_) => "sound",
+            Self::Room(_) => "room",
+            Self::Path(_) => "path",
+            Self::Script(_) => "script",
+            Self::Font(_) => "font",
+            Self::Timeline(_) => "timeline",
+            Self::Shader(_) => "shader",
+            Self::Sequence(_) => "sequence",
+            Self::AnimCurve(_) => "animcurve",
+            Self::ParticleSystem(_) => "particlesystem",
+            Self::Background(_) => "background",
+            Self::RoomInstance(_) => "roominstance",
+            Self::Function(_) => "function",
+        }
+    }
+
     /// The u24 asset id (aka index) of this asset reference.
     #[must_use]
     pub const fn asset_id(self) -> u32 {
@@ -174,7 +197,7 @@ impl AssetReference {
     pub const fn build(self) -> u32 {
         let id = self.asset_id() & 0xFF_FFFF;
         let ty = self.asset_type() as u32;
-        id & (ty << 24)
+        id | (ty << 24)
     }
 
     /// The old (pre-2024.4) serialized form of this asset reference.
@@ -182,6 +205,58 @@ impl AssetReference {
     pub const fn build_old(self) -> u32 {
         let id = self.asset_id() & 0xFF_FFFF;
         let ty = self.asset_type_old() as u32;
-        id & (ty << 24)
+        id | (ty << 24)
+    }
+}
+
+/// The number of assets of each asset type, for checking whether the IDs of
+/// asset references are in bounds.
+///
+/// Counts which are [`None`] are unknown.
+#[derive(Debug, Clone, Default)]
+pub struct AssetCounts {
+    pub objects: Option<u32>,
+    pub sprites: Option<u32>,
+    pub sounds: Option<u32>,
+    pub rooms: Option<u32>,
+    pub backgrounds: Option<u32>,
+    pub paths: Option<u32>,
+    pub scripts: Option<u32>,
+    pub fonts: Option<u32>,
+    pub timelines: Option<u32>,
+    pub shaders: Option<u32>,
+    pub sequences: Option<u32>,
+    pub anim_curves: Option<u32>,
+    pub particle_systems: Option<u32>,
+}
+
+impl AssetCounts {
+    /// Whether the asset ID of the asset reference is in bounds.
+    ///
+    /// Returns [`None`] if the number of assets of its type is unknown.
+    /// Room instances and functions do not have an asset count,
+    /// so this is always [`None`] for them.
+    #[must_use]
+    pub const fn contains(&self, asset_reference: AssetReference) -> Option<bool> {
+        let count: Option<u32> = match asset_reference {
+            AssetReference::Object(_) => self.objects,
+            AssetReference::Sprite(_) => self.sprites,
+            AssetReference::Sound(_) => self.sounds,
+            AssetReference::Room(_) => self.rooms,
+            AssetReference::Background(_) => self.backgrounds,
+            AssetReference::Path(_) => self.paths,
+            AssetReference::Script(_) => self.scripts,
+            AssetReference::Font(_) => self.fonts,
+            AssetReference::Timeline(_) => self.timelines,
+            AssetReference::Shader(_) => self.shaders,
+            AssetReference::Sequence(_) => self.sequences,
+            AssetReference::AnimCurve(_) => self.anim_curves,
+            AssetReference::ParticleSystem(_) => self.particle_systems,
+            AssetReference::RoomInstance(_) | AssetReference::Function(_) => None,
+        };
+        match count {
+            Some(count) => Some(asset_reference.asset_id() < count),
+            None => None,
+        }
     }
 }
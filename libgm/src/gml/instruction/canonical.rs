@@ -0,0 +1,59 @@
+// SPDX-License-Identifier: GPL-3.0-only
+use crate::gml::instruction::Instruction;
+use crate::gml::instruction::PushValue;
+
+/// An [`Instruction`] with bytecode version specific encodings normalized away.
+///
+/// Two instructions that are logically equal compare equal in their canonical
+/// form, even if one of them was parsed from a WAD 14 data file and the other
+/// one from a WAD 15+ data file.
+///
+/// The following is normalized:
+/// * `PushLocal`, `PushGlobal` and `PushBuiltin` (`pushloc`, `pushglb` and
+///   `pushbltn`; WAD 15+) become a `Push` of the variable (`push.v`).
+///   The instance type is kept in the [`CodeVariable`], so no information is
+///   lost.
+/// * `PushImmediate` (`pushi.e`; WAD 15+) becomes a `Push` of the
+///   [`PushValue::Int16`] (`push.e`).
+///
+/// Everything else (data types, comparison types, jump offsets, variable
+/// and instance types) is kept as is.
+///
+/// Created by [`Instruction::canonical`].
+///
+/// [`CodeVariable`]: crate::gml::instruction::CodeVariable
+#[derive(Debug, Clone, PartialEq)]
+pub struct CanonicalInstruction(Instruction);
+
+impl CanonicalInstruction {
+    /// The normalized instruction.
+    #[must_use]
+    pub const fn instruction(&self) -> &Instruction {
+        &self.0
+    }
+
+    /// Consumes this canonical instruction, returning the normalized
+    /// instruction.
+    #[must_use]
+    pub const fn into_instruction(self) -> Instruction {
+        self.0
+    }
+}
+
+impl Instruction {
+    /// Normalizes bytecode version specific encodings of this instruction,
+    /// so that it can be compared across bytecode versions.
+    ///
+    /// For more information, see [`CanonicalInstruction`].
+    #[must_use]
+    pub fn canonical(&self) -> CanonicalInstruction {
+        let instruction = match *self {
+            Self::PushLocal { variable }
+            | Self::PushGlobal { variable }
+            | Self::PushBuiltin { variable } => Self::Push { value: PushValue::Variable(variable) },
+            Self::PushImmediate { integer } => Self::Push { value: PushValue::Int16(integer) },
+            _ => self.clone(),
+        };
+        CanonicalInstruction(instruction)
+    }
+}
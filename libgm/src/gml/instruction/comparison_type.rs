@@ -1,4 +1,7 @@
 // SPDX-License-Identifier: GPL-3.0-only
+use std::fmt::Display;
+use std::fmt::Formatter;
+
 use crate::prelude::*;
 
 /// How to compare values.
@@ -50,4 +53,25 @@ impl ComparisonType {
             Self::GreaterThan => 6,
         }
     }
+
+    /// The operator symbol of this comparison type, like `<` or `!=`.
+    #[must_use]
+    pub const fn as_symbol(self) -> &'static str {
+        match self {
+            Self::LessThan => "<",
+            Self::LessOrEqual => "<=",
+            Self::Equal => "==",
+            Self::NotEqual => "!=",
+            Self::GreaterOrEqual => ">=",
+            Self::GreaterThan => ">",
+        }
+    }
+}
+
+/// Displays the operator symbol of this comparison type.
+/// For more information, see [`ComparisonType::as_symbol`].
+impl Display for ComparisonType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_symbol())
+    }
 }
@@ -1,4 +1,7 @@
 // SPDX-License-Identifier: GPL-3.0-only
+use std::fmt::Display;
+use std::fmt::Formatter;
+
 use crate::prelude::*;
 
 /// A primitive data type used in instructions.
@@ -98,6 +101,23 @@ impl DataType {
         }
     }
 
+    /// The single character suffix of this data type, as used in assembly.
+    ///
+    /// For example, [`DataType::Double`] is `d` and [`DataType::Int32`] is `i`.
+    /// This notation is the same as in UndertaleModTool.
+    #[must_use]
+    pub const fn as_char(self) -> char {
+        match self {
+            Self::Int16 => 'e',
+            Self::Int32 => 'i',
+            Self::Int64 => 'l',
+            Self::Double => 'd',
+            Self::Bool => 'b',
+            Self::String => 's',
+            Self::Variable => 'v',
+        }
+    }
+
     /// The size of a value of this data type on the VM Stack, in multiples of 4
     /// bytes. This is the unit used in `jump_offset` of branch instructions.
     #[must_use]
@@ -116,6 +136,14 @@ impl DataType {
     }
 }
 
+/// Displays the single character suffix of this data type.
+/// For more information, see [`DataType::as_char`].
+impl Display for DataType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_char())
+    }
+}
+
 // These data types exist in UTMT's `DataType` enum.
 // They partially seem to originate from <https://tomat.dev/undertale/decompilation-corrected>.
 #[cold]
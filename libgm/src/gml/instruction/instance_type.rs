@@ -6,6 +6,11 @@ use crate::prelude::*;
 use crate::wad::elem::game_object::GameObject;
 use crate::wad::elem::room::InstanceID;
 
+/// The offset of room instance IDs in encoded [`InstanceType::RoomInstance`]s.
+///
+/// The first room instance ID is 100000, which is encoded as zero.
+pub const ROOM_INSTANCE_ID_OFFSET: i32 = 100_000;
+
 /// The scope/owner of a variable.
 ///
 /// The most notable ones (and less confusing ones) are:
@@ -38,13 +43,22 @@ pub enum InstanceType {
     /// this may limit the number of game objects to 32767.
     GameObject(GMRef<GameObject>),
 
-    /// Instance ID in the Room -100000; used when the
-    /// Variable Type is [`VariableType::Instance`].
+    /// A room instance ID; used when the Variable Type is
+    /// [`VariableType::Instance`].
     ///
     /// Both this and [`InstanceType::GameObject`] are represented using
-    /// positive integers. If the specified variable type was
+    /// positive 16-bit integers. If the specified variable type was
     /// [`VariableType::Instance`], the integer gets interpreted as a Room
     /// Instance ID instead of a game object reference.
+    ///
+    /// Room instance IDs start at 100000, so they are encoded as the
+    /// instance ID minus 100000 (see [`ROOM_INSTANCE_ID_OFFSET`]).
+    /// The contained instance ID is the actual ID (the same as in
+    /// [`RoomGameObject::instance_id`]), not the encoded value.
+    /// This limits the instance IDs that can be referenced this way
+    /// to 100000 - 132767.
+    ///
+    /// [`RoomGameObject::instance_id`]: crate::wad::elem::room::RoomGameObject::instance_id
     RoomInstance(InstanceID),
 
     /// Represents the current `self` instance.
@@ -138,7 +152,7 @@ impl InstanceType {
     pub fn from_i16(raw: i16, var_type: VariableType) -> Result<Self> {
         if raw >= 0 {
             return Ok(if var_type == VariableType::Instance {
-                Self::RoomInstance(InstanceID(raw as i32))
+                Self::RoomInstance(InstanceID(i32::from(raw) + ROOM_INSTANCE_ID_OFFSET))
             } else {
                 Self::GameObject(GMRef::new(raw as i32))
             });
@@ -167,13 +181,33 @@ impl InstanceType {
 
     /// Serializes this instance type into an i16.
     ///
-    /// If the game object reference is erroneously higher
-    /// than [`i16::MAX`], you will be boiled (truncated).
-    #[must_use]
-    pub const fn build(self) -> i16 {
-        match self {
-            Self::GameObject(game_object_ref) => game_object_ref.index as i16,
-            Self::RoomInstance(instance_id) => instance_id.0 as i16,
+    /// Returns an error if a game object reference or room instance ID
+    /// cannot be encoded in 16 bits, instead of silently truncating it.
+    /// For room instance IDs, see [`InstanceType::RoomInstance`].
+    pub fn build(self) -> Result<i16> {
+        Ok(match self {
+            Self::GameObject(game_object_ref) => {
+                i16::try_from(game_object_ref.index).map_err(|_| {
+                    err!(
+                        "Game object index {} does not fit in a 16-bit instance type",
+                        game_object_ref.index,
+                    )
+                })?
+            }
+            Self::RoomInstance(instance_id) => {
+                let encoded: i32 = instance_id.0 - ROOM_INSTANCE_ID_OFFSET;
+                i16::try_from(encoded)
+                    .ok()
+                    .filter(|&raw| raw >= 0)
+                    .ok_or_else(|| {
+                        err!(
+                            "Room instance ID {} is out of range for a 16-bit instance type \
+                             (valid range is {ROOM_INSTANCE_ID_OFFSET} - {})",
+                            instance_id.0,
+                            ROOM_INSTANCE_ID_OFFSET + i32::from(i16::MAX),
+                        )
+                    })?
+            }
             Self::Self_ => -1,
             Self::Other => -2,
             Self::All => -3,
@@ -184,7 +218,7 @@ impl InstanceType {
             Self::StackTop => -9,
             Self::Argument => -15,
             Self::Static => -16,
-        }
+        })
     }
 
     /// Converts an instance type to the "VARI version".
@@ -2,7 +2,8 @@
 use crate::gml::Instruction;
 use crate::gml::instruction::CodeVariable;
 use crate::gml::instruction::DataType;
-use crate::prelude::GMRef;
+use crate::prelude::*;
+use crate::wad::GMVersion;
 use crate::wad::elem::function::Function;
 
 /// A value to push to the stack.
@@ -40,12 +41,57 @@ impl PushValue {
         }
     }
 
+    /// The integer value, if this is an [`Int16`], [`Int32`] or [`Int64`].
+    ///
+    /// [`Int16`]: Self::Int16
+    /// [`Int32`]: Self::Int32
+    /// [`Int64`]: Self::Int64
+    #[must_use]
+    pub const fn as_i64(&self) -> Option<i64> {
+        match *self {
+            Self::Int16(int) => Some(int as i64),
+            Self::Int32(int) => Some(int as i64),
+            Self::Int64(int) => Some(int),
+            _ => None,
+        }
+    }
+
+    /// The numeric value, if this is a [`Double`] or an integer.
+    ///
+    /// 64-bit integers beyond 2^53 lose precision.
+    ///
+    /// [`Double`]: Self::Double
+    #[must_use]
+    pub const fn as_f64(&self) -> Option<f64> {
+        match *self {
+            Self::Int16(int) => Some(int as f64),
+            Self::Int32(int) => Some(int as f64),
+            #[expect(clippy::cast_precision_loss)]
+            Self::Int64(int) => Some(int as f64),
+            Self::Double(float) => Some(float),
+            _ => None,
+        }
+    }
+
+    /// The string reference, if this is a [`String`](Self::String).
+    #[must_use]
+    pub const fn as_string_ref(&self) -> Option<GMRef<String>> {
+        match *self {
+            Self::String(string) => Some(string),
+            _ => None,
+        }
+    }
+
     /// An approximate boolean representation for this [`PushValue`].
     ///
+    /// Numbers are considered true if they are greater than 0.5, like in
+    /// GameMaker. Strings, functions and variables have no constant boolean
+    /// representation.
+    ///
     /// Warning: This function does not fully conform with the GameMaker
     /// standards (yet).
     #[must_use]
-    pub(crate) const fn as_bool(&self) -> Option<bool> {
+    pub const fn as_bool(&self) -> Option<bool> {
         match self {
             &Self::Int16(int) => Some(int > 0),
             &Self::Int32(int) => Some(int > 0),
@@ -57,6 +103,50 @@ impl PushValue {
     }
 }
 
+/// Creates an [`Int16`](PushValue::Int16) value.
+impl From<i16> for PushValue {
+    fn from(value: i16) -> Self {
+        Self::Int16(value)
+    }
+}
+
+/// Creates an [`Int32`](PushValue::Int32) value.
+///
+/// For the smallest fitting integer type, see [`GMData::push_int_instruction`].
+impl From<i32> for PushValue {
+    fn from(value: i32) -> Self {
+        Self::Int32(value)
+    }
+}
+
+/// Creates an [`Int64`](PushValue::Int64) value.
+impl From<i64> for PushValue {
+    fn from(value: i64) -> Self {
+        Self::Int64(value)
+    }
+}
+
+/// Creates a [`Double`](PushValue::Double) value.
+impl From<f64> for PushValue {
+    fn from(value: f64) -> Self {
+        Self::Double(value)
+    }
+}
+
+/// Creates a [`Bool`](PushValue::Bool) value.
+impl From<bool> for PushValue {
+    fn from(value: bool) -> Self {
+        Self::Bool(value)
+    }
+}
+
+/// Creates a [`String`](PushValue::String) value.
+impl From<GMRef<String>> for PushValue {
+    fn from(value: GMRef<String>) -> Self {
+        Self::String(value)
+    }
+}
+
 impl Instruction {
     /// Attempts to extract a [`PushValue`] from this instruction.
     #[must_use]
@@ -71,3 +161,64 @@ impl Instruction {
         })
     }
 }
+
+impl GMData {
+    /// Creates an instruction pushing the specified string constant.
+    ///
+    /// The string is interned in [`GMData::strings`] first,
+    /// reusing an existing string if possible.
+    ///
+    /// ```
+    /// use libgm::gml::Instruction;
+    /// use libgm::gml::instruction::DataType;
+    /// use libgm::gml::instruction::PushValue;
+    /// use libgm::prelude::*;
+    ///
+    /// // show_debug_message("hi");
+    /// let mut data = GMData::default();
+    /// let function = data.functions.make("show_debug_message", &mut data.strings);
+    /// let instructions = vec![
+    ///     data.push_string_instruction("hi"),
+    ///     Instruction::Call { function, arg_count: 1 },
+    ///     Instruction::PopDiscard { data_type: DataType::Variable },
+    /// ];
+    /// data.make_code("gml_Script_scr_debug_print", instructions);
+    ///
+    /// let string = data.strings.find("hi")?;
+    /// assert_eq!(data.push_string_instruction("hi"), Instruction::Push {
+    ///     value: PushValue::String(string),
+    /// });
+    /// # Ok::<(), libgm::error::Error>(())
+    /// ```
+    pub fn push_string_instruction(&mut self, text: &str) -> Instruction {
+        let string: GMRef<String> = self.strings.make(text);
+        Instruction::Push { value: PushValue::String(string) }
+    }
+
+    /// Creates an instruction pushing the specified integer constant,
+    /// using the smallest fitting data type.
+    ///
+    /// In WAD 15+, 16-bit integers are pushed using [`Instruction::PushImmediate`].
+    #[must_use]
+    pub fn push_int_instruction(&self, value: i64) -> Instruction {
+        if let Ok(integer) = i16::try_from(value) {
+            if self.meta.version >= GMVersion::Wad15 {
+                return Instruction::PushImmediate { integer };
+            }
+            return Instruction::Push { value: PushValue::Int16(integer) };
+        }
+        let value = i32::try_from(value).map_or(PushValue::Int64(value), PushValue::Int32);
+        Instruction::Push { value }
+    }
+}
+
+/// Creates an instruction pushing the number constant as a double.
+///
+/// Whole numbers are pushed as doubles as well, since pushing them as
+/// integers would change their type at runtime (and lose `-0.0`).
+/// To push an integer, use [`GMData::push_int_instruction`].
+impl From<f64> for Instruction {
+    fn from(value: f64) -> Self {
+        Self::Push { value: PushValue::Double(value) }
+    }
+}
@@ -0,0 +1,181 @@
+// SPDX-License-Identifier: GPL-3.0-only
+use crate::gml::Code;
+use crate::gml::assembly::disassemble_instruction;
+use crate::gml::instruction::AssetReference;
+use crate::gml::instruction::CodeVariable;
+use crate::gml::instruction::InstanceType;
+use crate::gml::instruction::Instruction;
+use crate::gml::instruction::PushValue;
+use crate::prelude::*;
+use crate::wad::elem::GMNamedElement;
+use crate::wad::elem::string::Strings;
+
+/// An instruction with its references resolved to names.
+///
+/// This is a read-only view for displaying a single instruction,
+/// for example in a debugger or a disassembler UI.
+/// See [`GMData::resolved_instruction`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResolvedInstruction<'a> {
+    /// The instruction itself.
+    pub instruction: &'a Instruction,
+
+    /// The index of this instruction in the instructions of its code entry.
+    pub index: usize,
+
+    /// The position of this instruction in the bytecode of its code entry,
+    /// in bytes.
+    ///
+    /// This can be used to correlate crash addresses of the runner with
+    /// instructions.
+    pub offset: u32,
+
+    /// The assembly mnemonic of the instruction's opcode, e.g. `"push"`.
+    pub mnemonic: &'static str,
+
+    /// The referenced variable, function, string or asset, if any.
+    ///
+    /// Literal values (numbers and booleans), data types and branch offsets
+    /// are not resolved; they are available through
+    /// [`ResolvedInstruction::instruction`].
+    pub operand: Option<Operand<'a>>,
+
+    /// The disassembled instruction, as a single line of assembly.
+    pub assembly: String,
+}
+
+/// A reference of an instruction, resolved to a name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operand<'a> {
+    /// A variable with its name and instance type.
+    Variable {
+        name: &'a str,
+        instance_type: InstanceType,
+    },
+
+    /// A function, like in [`Instruction::Call`].
+    Function(&'a str),
+
+    /// A string literal.
+    String(&'a str),
+
+    /// An asset of [`Instruction::PushReference`] with its kind
+    /// (see [`AssetReference::kind`]) and name.
+    ///
+    /// Function references use the `Function` variant instead.
+    Asset { kind: &'static str, name: &'a str },
+
+    /// A room instance of [`Instruction::PushReference`].
+    /// Room instances do not have names, only IDs.
+    RoomInstance(i32),
+}
+
+impl GMData {
+    /// Resolves the instruction at the specified index of a code entry.
+    ///
+    /// Child code entries share the instructions of their parent
+    /// (see [`Code::parent`]). For these, the index and the resulting
+    /// [`ResolvedInstruction::offset`] refer to the instructions of the
+    /// parent code entry.
+    ///
+    /// Returns [`None`] if the index is out of range.
+    /// Returns an error if the code entry does not exist or if a reference of
+    /// the instruction is invalid.
+    #[doc(alias = "instruction_at")]
+    pub fn resolved_instruction(
+        &self,
+        code_ref: GMRef<Code>,
+        index: usize,
+    ) -> Result<Option<ResolvedInstruction<'_>>> {
+        let mut code: &Code = self.codes.by_ref(code_ref)?;
+        if !code.is_root() {
+            code = self
+                .codes
+                .by_ref(code.parent())
+                .ctx("resolving parent code entry")?;
+        }
+        let Some(instruction) = code.instructions.get(index) else {
+            return Ok(None);
+        };
+        let offset: u32 = code.instructions[..index]
+            .iter()
+            .map(Instruction::size)
+            .sum();
+
+        let ctx = || {
+            format!(
+                "resolving instruction #{index} of code entry {:?}",
+                code.name.display(&self.strings)
+            )
+        };
+        let operand: Option<Operand> = self.resolve_operand(instruction).ctx(ctx)?;
+        let assembly: String = disassemble_instruction(instruction, self).ctx(ctx)?;
+
+        Ok(Some(ResolvedInstruction {
+            instruction,
+            index,
+            offset,
+            mnemonic: instruction.mnemonic(),
+            operand,
+            assembly,
+        }))
+    }
+
+    fn resolve_operand(&self, instruction: &Instruction) -> Result<Option<Operand<'_>>> {
+        let operand = match *instruction {
+            Instruction::Pop { variable, .. }
+            | Instruction::PushLocal { variable }
+            | Instruction::PushGlobal { variable }
+            | Instruction::PushBuiltin { variable }
+            | Instruction::Push { value: PushValue::Variable(variable) } => {
+                self.resolve_variable(variable)?
+            }
+            Instruction::Push { value: PushValue::String(string) } => {
+                Operand::String(self.strings.by_ref(string)?)
+            }
+            Instruction::Push { value: PushValue::Function(function) }
+            | Instruction::Call { function, .. } => {
+                Operand::Function(self.functions.by_ref(function)?.name(&self.strings)?)
+            }
+            Instruction::PushReference { asset_reference } => {
+                self.resolve_asset_reference(asset_reference)?
+            }
+            _ => return Ok(None),
+        };
+        Ok(Some(operand))
+    }
+
+    fn resolve_variable(&self, code_variable: CodeVariable) -> Result<Operand<'_>> {
+        let variable = self.variables.by_ref(code_variable.variable)?;
+        Ok(Operand::Variable {
+            name: variable.name(&self.strings)?,
+            instance_type: code_variable.instance_type,
+        })
+    }
+
+    fn resolve_asset_reference(&self, asset_reference: AssetReference) -> Result<Operand<'_>> {
+        let s: &Strings = &self.strings;
+        let name: &str = match asset_reference {
+            AssetReference::Object(gm_ref) => self.game_objects.by_ref(gm_ref)?.name(s)?,
+            AssetReference::Sprite(gm_ref) => self.sprites.by_ref(gm_ref)?.name(s)?,
+            AssetReference::Sound(gm_ref) => self.sounds.by_ref(gm_ref)?.name(s)?,
+            AssetReference::Room(gm_ref) => self.rooms.by_ref(gm_ref)?.name(s)?,
+            AssetReference::Path(gm_ref) => self.paths.by_ref(gm_ref)?.name(s)?,
+            AssetReference::Script(gm_ref) => self.scripts.by_ref(gm_ref)?.name(s)?,
+            AssetReference::Font(gm_ref) => self.fonts.by_ref(gm_ref)?.name(s)?,
+            AssetReference::Timeline(gm_ref) => self.timelines.by_ref(gm_ref)?.name(s)?,
+            AssetReference::Shader(gm_ref) => self.shaders.by_ref(gm_ref)?.name(s)?,
+            AssetReference::Sequence(gm_ref) => self.sequences.by_ref(gm_ref)?.name(s)?,
+            AssetReference::AnimCurve(gm_ref) => self.animation_curves.by_ref(gm_ref)?.name(s)?,
+            AssetReference::ParticleSystem(gm_ref) => {
+                self.particle_systems.by_ref(gm_ref)?.name(s)?
+            }
+            AssetReference::Background(gm_ref) => self.tilesets.by_ref(gm_ref)?.name(s)?,
+            AssetReference::RoomInstance(id) => return Ok(Operand::RoomInstance(id)),
+            AssetReference::Function(gm_ref) => {
+                return Ok(Operand::Function(self.functions.by_ref(gm_ref)?.name(s)?));
+            }
+        };
+        Ok(Operand::Asset { kind: asset_reference.kind(), name })
+    }
+}
@@ -0,0 +1,134 @@
+// SPDX-License-Identifier: GPL-3.0-only
+use crate::gml::Instruction;
+use crate::gml::instruction::CodeVariable;
+use crate::gml::instruction::PushValue;
+use crate::gml::instruction::VariableType;
+
+/// How many values an instruction pops off of and pushes onto the VM stack.
+///
+/// Values are counted regardless of their [`DataType`], so a
+/// [`DataType::Variable`] counts as one value just like a [`DataType::Int32`].
+/// Pops happen before pushes.
+///
+/// [`DataType`]: crate::gml::instruction::DataType
+/// [`DataType::Variable`]: crate::gml::instruction::DataType::Variable
+/// [`DataType::Int32`]: crate::gml::instruction::DataType::Int32
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct StackEffect {
+    /// The number of values popped off of the stack.
+    pub pops: u32,
+
+    /// The number of values pushed onto the stack.
+    pub pushes: u32,
+}
+
+impl StackEffect {
+    const fn new(pops: u32, pushes: u32) -> Self {
+        Self { pops, pushes }
+    }
+
+    /// The change in stack depth (pushes minus pops).
+    #[must_use]
+    pub const fn delta(self) -> i64 {
+        self.pushes as i64 - self.pops as i64
+    }
+}
+
+/// The number of values popped to resolve a variable before accessing it.
+const fn variable_pops(variable: &CodeVariable) -> u32 {
+    match variable.variable_type {
+        VariableType::Normal | VariableType::Instance => 0,
+        // Instance
+        VariableType::StackTop => 1,
+        // Index and instance
+        VariableType::Array | VariableType::MultiPush | VariableType::MultiPop => 2,
+    }
+}
+
+impl Instruction {
+    /// Gets the number of values this instruction pops off of and pushes onto
+    /// the VM stack.
+    ///
+    /// This is the core primitive for stack balance checking and
+    /// decompilation. Notable cases:
+    /// * Variable accesses ([`Instruction::Pop`], [`Instruction::Push`] and
+    ///   its specializations) first pop the instance (for
+    ///   [`VariableType::StackTop`]) or the index and the instance (for
+    ///   [`VariableType::Array`], [`VariableType::MultiPush`] and
+    ///   [`VariableType::MultiPop`]).
+    /// * [`Instruction::Call`] pops `arg_count` arguments and pushes the return
+    ///   value.
+    /// * [`Instruction::CallVariable`] pops the function reference and the self
+    ///   instance in addition to its `arg_count` arguments, and pushes the
+    ///   return value.
+    /// * [`Instruction::PushWithContext`] pops the instance (or object) to
+    ///   iterate over.
+    /// * [`Instruction::IsNullishValue`] only peeks at the stack top.
+    /// * [`Instruction::Duplicate`] pushes `size + 1` values, assuming that
+    ///   all duplicated values have its data type.
+    /// * [`Instruction::PopSwap`] and [`Instruction::DuplicateSwap`] only
+    ///   reorder values, so they pop and push the same amount.
+    /// * [`Instruction::Unknown`] is assumed to have no effect on the stack.
+    #[must_use]
+    pub const fn stack_effect(&self) -> StackEffect {
+        let (pops, pushes) = match self {
+            Self::Convert { .. } | Self::Negate { .. } | Self::Not { .. } => (1, 1),
+
+            Self::Multiply { .. }
+            | Self::Divide { .. }
+            | Self::Remainder { .. }
+            | Self::Modulus { .. }
+            | Self::Add { .. }
+            | Self::Subtract { .. }
+            | Self::And { .. }
+            | Self::Or { .. }
+            | Self::Xor { .. }
+            | Self::ShiftLeft { .. }
+            | Self::ShiftRight { .. }
+            | Self::Compare { .. } => (2, 1),
+
+            Self::Pop { variable, .. } => (variable_pops(variable) + 1, 0),
+            Self::PopSwap { is_array: false } => (3, 3),
+            Self::PopSwap { is_array: true } => (4, 4),
+            Self::Duplicate { size, .. } => (0, *size as u32 + 1),
+            Self::DuplicateSwap { size1, size2, .. } => {
+                let count = *size1 as u32 + *size2 as u32;
+                (count, count)
+            }
+
+            Self::Push { value: PushValue::Variable(variable) }
+            | Self::PushLocal { variable }
+            | Self::PushGlobal { variable }
+            | Self::PushBuiltin { variable } => (variable_pops(variable), 1),
+            Self::Push { .. }
+            | Self::PushImmediate { .. }
+            | Self::PushReference { .. }
+            | Self::HasStaticInitialized
+            | Self::IsNullishValue
+            | Self::RestoreArrayReference => (0, 1),
+
+            Self::Return
+            | Self::PopDiscard { .. }
+            | Self::BranchIf { .. }
+            | Self::BranchUnless { .. }
+            | Self::PushWithContext { .. }
+            | Self::SetArrayOwner
+            | Self::SaveArrayReference => (1, 0),
+
+            Self::Exit
+            | Self::Branch { .. }
+            | Self::PopWithContext { .. }
+            | Self::PopWithContextExit
+            | Self::CheckArrayIndex
+            | Self::SetStaticInitialized
+            | Self::Unknown { .. } => (0, 0),
+
+            Self::Call { arg_count, .. } => (*arg_count as u32, 1),
+            Self::CallVariable { arg_count } => (*arg_count as u32 + 2, 1),
+
+            Self::PushArrayFinal | Self::PushArrayContainer => (2, 1),
+            Self::PopArrayFinal => (3, 0),
+        };
+        StackEffect::new(pops, pushes)
+    }
+}
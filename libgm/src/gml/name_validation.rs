@@ -10,11 +10,15 @@ impl GMNamedElement for Code {
     }
 
     fn validate_name(&self, gm_strings: &Strings) -> Result<()> {
-        let name: &str = self.name(gm_strings)?;
-        validate(name).ctx(|| format!("strictly validating code entry name {name:?}"))
+        validate_code_name(self.name(gm_strings)?)
     }
 }
 
+/// Strictly validates a code entry name.
+pub fn validate_code_name(name: &str) -> Result<()> {
+    validate(name).ctx(|| format!("strictly validating code entry name {name:?}"))
+}
+
 fn validate(mut name: &str) -> Result<()> {
     if let Some(rest) = name.strip_prefix("Timeline_") {
         return validate_identifier(rest);
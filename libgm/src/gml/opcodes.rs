@@ -89,6 +89,7 @@ impl Instruction {
             | Self::RestoreArrayReference
             | Self::IsNullishValue
             | Self::PushReference { .. } => EXTENDED,
+            Self::Unknown { opcode, .. } => *opcode,
         }
     }
 
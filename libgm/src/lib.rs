@@ -82,7 +82,7 @@
 //!         let name = gm_data.strings.by_ref(sound.name)?;
 //!         let audio: &Audio = gm_data.audios.by_ref(sound.audio)?;
 //!         let path = format!("exported_sounds/{name}.wav");
-//!         std::fs::write(path, &audio.data)?;
+//!         std::fs::write(path, &*audio.data)?;
 //!     }
 //! }
 //! # Ok(()) }
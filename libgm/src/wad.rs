@@ -8,14 +8,18 @@ mod version_detection;
 pub mod build;
 pub mod chunk;
 pub mod data;
+pub mod diff;
 pub mod elem;
 pub mod parse;
+pub mod scan;
+pub mod size;
 pub mod version;
 
 use std::any::type_name;
 use std::fmt;
 use std::ops::Deref;
 use std::ops::DerefMut;
+use std::sync::Arc;
 
 pub use self::build::build_bytes;
 pub use self::build::build_file;
@@ -23,13 +27,57 @@ pub use self::data::GMData;
 pub use self::parse::parse_bytes;
 pub use self::parse::parse_file;
 pub use self::reference::GMRef;
+pub use self::reference::visit_asset_refs;
+pub(crate) use self::reference::visit_string_refs;
 pub use self::version::GMVersion;
 
 /// A wrapper struct that holds a vector or array.
 ///
 /// This allows for Debug derives but does not print out the entire data as numbers.
+///
+/// The data is reference counted and copied on write: cloning a blob is cheap
+/// and shares the underlying buffer. Mutable access (through [`DerefMut`])
+/// copies the buffer first if it is currently shared with another blob.
+/// This way, cloning [`GMData`] does not duplicate audio, texture and other
+/// binary data until it is actually modified.
+///
+/// Blobs are created using [`Blob::new`] or [`From`].
 #[derive(Clone, PartialEq, Eq, Default)]
-pub struct Blob<T: BlobLike>(pub T);
+pub struct Blob<T: BlobLike>(Arc<T>);
+
+impl<T: BlobLike> Blob<T> {
+    /// Creates a new blob which does not share its data.
+    #[must_use]
+    pub fn new(data: T) -> Self {
+        Self(Arc::new(data))
+    }
+
+    /// Whether the underlying buffer is shared with another blob,
+    /// for example one in a cloned [`GMData`].
+    #[must_use]
+    pub fn is_shared(&self) -> bool {
+        Arc::strong_count(&self.0) > 1
+    }
+
+    /// A mutable reference to the underlying data, without copying it.
+    ///
+    /// Returns [`None`] if the data is shared with another blob.
+    pub fn get_mut(&mut self) -> Option<&mut T> {
+        Arc::get_mut(&mut self.0)
+    }
+
+    /// Unwraps the underlying data, copying it if it is shared.
+    #[must_use]
+    pub fn into_inner(self) -> T {
+        Arc::unwrap_or_clone(self.0)
+    }
+}
+
+impl<T: BlobLike> From<T> for Blob<T> {
+    fn from(data: T) -> Self {
+        Self::new(data)
+    }
+}
 
 impl<T: BlobLike> fmt::Debug for Blob<T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
@@ -47,11 +95,11 @@ impl<T: BlobLike> Deref for Blob<T> {
 
 impl<T: BlobLike> DerefMut for Blob<T> {
     fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut self.0
+        Arc::make_mut(&mut self.0)
     }
 }
 
-pub trait BlobLike: private::Sealed {
+pub trait BlobLike: private::Sealed + Clone {
     fn debugfmt(&self, f: &mut fmt::Formatter) -> fmt::Result;
 }
 
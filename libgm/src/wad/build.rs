@@ -19,9 +19,15 @@ use crate::prelude::*;
 use crate::util::bench::Stopwatch;
 use crate::util::unwind;
 use crate::wad::chunk::ChunkName;
+#[cfg(feature = "chunk-timings")]
+use crate::wad::data::ChunkTimings;
 use crate::wad::data::Endianness;
 use crate::wad::data::GMData;
+use crate::wad::elem::audio::Audio;
 use crate::wad::elem::data_file::DataFiles;
+use crate::wad::parse::ParsingOptions;
+use crate::wad::parse::chunk::ChunkBounds;
+use crate::wad::parse::parse_form;
 
 /// Builds a GameMaker data file and returns a byte buffer.
 ///
@@ -34,6 +40,20 @@ pub fn build_bytes(gm_data: &GMData) -> Result<Vec<u8>> {
     build(gm_data).ctx("building GameMaker data bytes")
 }
 
+/// Builds a GameMaker data file and returns a byte buffer, along with how long
+/// building each chunk took (in the order they were built).
+///
+/// Building only borrows the [`GMData`], so unlike the parse timings, these
+/// cannot be stored in [`Metadata::chunk_timings`].
+/// Requires the `chunk-timings` feature.
+///
+/// [`Metadata::chunk_timings`]: crate::wad::data::Metadata::chunk_timings
+#[cfg(feature = "chunk-timings")]
+pub fn build_bytes_timed(gm_data: &GMData) -> Result<(Vec<u8>, ChunkTimings)> {
+    let builder = build_with(gm_data, None).ctx("building GameMaker data bytes")?;
+    Ok((builder.raw_data, builder.chunk_timings))
+}
+
 /// Builds a GameMaker data file to the specified file path.
 ///
 /// If you want to build the data file to a buffer in memory, check out
@@ -46,21 +66,157 @@ pub fn build_file(gm_data: &GMData, path: impl AsRef<Path>) -> Result<()> {
         build(gm_data).ctx(|| format!("building GameMaker data file {}", path.display()))?;
 
     let stopwatch = Stopwatch::start();
+    write_included_files(gm_data, path)?;
+    std::fs::write(path, raw_data).ctx_any("writing data file")?;
+    log::trace!("Writing data file took {stopwatch}");
+    Ok(())
+}
+
+/// Builds a GameMaker data file to the specified file path, but only after
+/// verifying that the built data can be parsed again.
+///
+/// The built bytes are reparsed with the options the data was parsed with
+/// (see [`Metadata::parsing_options`]), assuming the data's format version.
+/// If that fails, an error is returned and nothing is written, so a broken
+/// data file never ends up on disk.
+/// This catches serialization bugs before they turn into runner crashes.
+///
+/// Since this parses the whole data file again, it takes roughly twice as
+/// long as [`build_file`]. If you also want to make sure that nothing changed
+/// structurally, build with [`build_bytes`] instead and compare the reparsed
+/// data using [`GMData::diff`].
+///
+/// [`Metadata::parsing_options`]: crate::wad::data::Metadata::parsing_options
+pub fn build_file_checked(gm_data: &GMData, path: impl AsRef<Path>) -> Result<()> {
+    let path: &Path = path.as_ref();
+    let ctx = || format!("building GameMaker data file {}", path.display());
+    let raw_data: Vec<u8> = build(gm_data).ctx(ctx)?;
+
+    let stopwatch = Stopwatch::start();
+    let options = ParsingOptions {
+        assumed_version: Some(gm_data.meta.version),
+        // Do not overwrite the trace of the original parse.
+        #[cfg(feature = "parse-trace")]
+        trace_file: None,
+        ..gm_data.meta.parsing_options.clone()
+    };
+    options
+        .parse_bytes(&raw_data)
+        .ctx("verifying built data by reparsing it")
+        .ctx(ctx)?;
+    log::trace!("Verifying built data took {stopwatch}");
+
+    let stopwatch = Stopwatch::start();
+    write_included_files(gm_data, path)?;
     std::fs::write(path, raw_data).ctx_any("writing data file")?;
     log::trace!("Writing data file took {stopwatch}");
     Ok(())
 }
 
+/// Writes the pending included file changes next to the data file at the specified path.
+///
+/// All changes are checked before anything is written, so that a conflicting
+/// included file does not leave a partially written output behind.
+/// This has to happen before writing the data file itself.
+fn write_included_files(gm_data: &GMData, path: &Path) -> Result<()> {
+    let directory: &Path = path.parent().unwrap_or_else(|| Path::new(""));
+    let changes = &gm_data.meta.included_file_changes;
+    for change in changes {
+        change.check(directory).ctx("checking included files")?;
+    }
+    for change in changes {
+        change.write(directory)?;
+    }
+    Ok(())
+}
+
+/// Builds a single chunk of a GameMaker data file and returns its contents.
+///
+/// The returned bytes exclude the chunk name, chunk length and padding.
+///
+/// This is **not** faster than building the whole data file: chunks contain
+/// absolute pointers to elements in other chunks (strings, texture page items,
+/// etc.), which can only be resolved once every chunk has been laid out.
+/// Because of this, the entire data file is built internally and only the
+/// specified chunk is extracted from it.
+///
+/// The extracted bytes can only be spliced into an existing data file if
+/// nothing outside of the chunk changed. Among other things, this is not the
+/// case when the chunk's size changed (which shifts all following chunks),
+/// when new strings were created (which changes `STRG`) or when code was
+/// changed (which changes the occurrence chains in `VARI` and `FUNC`).
+/// If in doubt, use [`build_bytes`] instead.
+pub fn build_chunk_bytes(gm_data: &GMData, chunk_name: ChunkName) -> Result<Vec<u8>> {
+    let ctx = || format!("building chunk {chunk_name}");
+    let raw_data: Vec<u8> = build(gm_data).ctx(ctx)?;
+    let bounds: ChunkBounds = chunk_bounds(&raw_data, chunk_name).ctx(ctx)?;
+    Ok(raw_data[bounds.start_pos as usize..bounds.end_pos as usize].to_vec())
+}
+
+/// Finds the position of the specified chunk's contents in a data file.
+pub(crate) fn chunk_bounds(raw_data: &[u8], chunk_name: ChunkName) -> Result<ChunkBounds> {
+    let reader = parse_form(raw_data).ctx("parsing FORM")?;
+    reader
+        .chunks
+        .get(chunk_name)
+        .ok_or_else(|| err!("Chunk {chunk_name} does not exist in data file"))
+}
+
+impl GMData {
+    /// Builds a single chunk of this data file and returns its contents.
+    ///
+    /// This still builds the entire data file internally.
+    /// This is a shorthand for [`build_chunk_bytes`].
+    pub fn rebuild_chunk(&self, chunk_name: ChunkName) -> Result<Vec<u8>> {
+        build_chunk_bytes(self, chunk_name)
+    }
+
+    /// Builds this data file and returns a byte buffer, consuming the data.
+    ///
+    /// This produces the same bytes as [`build_bytes`], but with a lower peak
+    /// memory usage. It is meant for one-shot pipelines which discard the
+    /// [`GMData`] after building anyway.
+    ///
+    /// The output is one contiguous buffer in which every blob is surrounded
+    /// by headers, pointers and padding, so blobs can never be moved into it;
+    /// they are always copied. Owning the data instead allows freeing blobs as
+    /// soon as they have been copied:
+    /// * Embedded audio (`AUDO`) is freed entry by entry while being written.
+    ///   This is where most of the savings come from, since `AUDO` is usually
+    ///   both the biggest and the last chunk. At that point, the whole rest of
+    ///   the file has already been written, so peak memory drops from
+    ///   "data + output" to roughly "output + data without audio".
+    /// * Texture pages (`TXTR`) are still kept until the end, since pointers to
+    ///   their images are only resolved after all chunks are written.
+    /// * If the `AUDO` chunk is stored verbatim (see
+    ///   [`Metadata::raw_chunks`](crate::wad::data::Metadata::raw_chunks)),
+    ///   nothing is saved.
+    #[doc(alias = "into_builder")]
+    pub fn into_bytes(mut self) -> Result<Vec<u8>> {
+        let audios: Vec<Audio> = std::mem::take(&mut self.audios.elems);
+        build_with(&self, Some(audios))
+            .map(DataBuilder::finish)
+            .ctx("building GameMaker data bytes")
+    }
+}
+
 #[inline]
 fn build(gm_data: &GMData) -> Result<Vec<u8>> {
+    build_with(gm_data, None).map(DataBuilder::finish)
+}
+
+/// Builds the data file; `owned_audios` replaces the (then empty) audio
+/// entries of `gm_data` so they can be freed while writing.
+#[inline]
+fn build_with(gm_data: &GMData, owned_audios: Option<Vec<Audio>>) -> Result<DataBuilder<'_>> {
     if cfg!(feature = "catch-panic") {
-        unwind::catch(|| build_impl(gm_data))
+        unwind::catch(|| build_impl(gm_data, owned_audios))
     } else {
-        build_impl(gm_data)
+        build_impl(gm_data, owned_audios)
     }
 }
 
-fn build_impl(data: &GMData) -> Result<Vec<u8>> {
+fn build_impl(data: &GMData, mut owned_audios: Option<Vec<Audio>>) -> Result<DataBuilder<'_>> {
     let stopwatch = Stopwatch::start();
     let mut builder = DataBuilder::new(data);
 
@@ -80,10 +236,17 @@ fn build_impl(data: &GMData) -> Result<Vec<u8>> {
     }
 
     for chunk_name in &data.meta.chunks {
+        if let Some(raw_chunk) = data.meta.raw_chunks.iter().find(|c| c.name == chunk_name) {
+            builder.build_raw_chunk(raw_chunk)?;
+            continue;
+        }
         match chunk_name {
             ChunkName::ACRV => builder.build_chunk(&data.animation_curves),
             ChunkName::AGRP => builder.build_chunk(&data.audio_groups),
-            ChunkName::AUDO => builder.build_chunk(&data.audios),
+            ChunkName::AUDO => match owned_audios.take() {
+                Some(audios) => builder.build_audio_chunk_consuming(audios),
+                None => builder.build_chunk(&data.audios),
+            },
             ChunkName::BGND => builder.build_chunk(&data.tilesets),
             ChunkName::CODE => builder.build_chunk(&data.codes),
             ChunkName::DAFL => builder.build_chunk(&DataFiles),
@@ -129,11 +292,9 @@ fn build_impl(data: &GMData) -> Result<Vec<u8>> {
 
     log::trace!("Building data file took {stopwatch}");
 
-    let raw_data: Vec<u8> = builder.finish();
-
-    if raw_data.len() >= i32::MAX as usize {
+    if builder.raw_data.len() >= i32::MAX as usize {
         bail!("Data file is bigger than 2,147,483,646 bytes which will lead to bugs in the runner")
     }
 
-    Ok(raw_data)
+    Ok(builder)
 }
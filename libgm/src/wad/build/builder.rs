@@ -5,6 +5,8 @@ use crate::gml::instruction::VariableType;
 use crate::prelude::*;
 use crate::wad::GMVersion;
 use crate::wad::build::pointers::Pointer;
+#[cfg(feature = "chunk-timings")]
+use crate::wad::data::ChunkTimings;
 use crate::wad::data::Endianness;
 use crate::wad::data::GMData;
 
@@ -48,6 +50,10 @@ pub struct DataBuilder<'a> {
     pub variable_occurrences: Vec<Vec<(u32, VariableType)>>,
 
     pub last_chunk: LastChunk,
+
+    /// How long building each chunk took, in the order they were built.
+    #[cfg(feature = "chunk-timings")]
+    pub chunk_timings: ChunkTimings,
 }
 
 impl<'a> DataBuilder<'a> {
@@ -62,6 +68,8 @@ impl<'a> DataBuilder<'a> {
             function_occurrences: vec![Vec::new(); gm_data.functions.len()],
             variable_occurrences: vec![Vec::new(); gm_data.variables.len()],
             last_chunk: LastChunk::default(),
+            #[cfg(feature = "chunk-timings")]
+            chunk_timings: Vec::new(),
         }
     }
 
@@ -86,9 +94,18 @@ impl<'a> DataBuilder<'a> {
     /// Pads the internal buffer with zero bytes until its length is aligned to
     /// `alignment`.
     ///
-    /// This adds zero bytes until `self.len()` is a multiple of `alignment`.
+    /// This adds zero bytes until `self.len()` is a multiple of `alignment`,
+    /// which is `(alignment - pos % alignment) % alignment` bytes (so nothing
+    /// if the position is already aligned). GameMaker always pads with zeros;
+    /// [`DataReader::align`] rejects any other padding byte.
+    ///
+    /// Alignment is relative to the start of the data file, not to the start
+    /// of the current chunk. `alignment` must not be zero.
+    ///
+    /// [`DataReader::align`]: crate::wad::parse::reader::DataReader::align
     #[inline]
     pub fn align(&mut self, alignment: u32) {
+        debug_assert_ne!(alignment, 0, "cannot align to zero bytes");
         while !self.pos().is_multiple_of(alignment) {
             self.write_u8(0);
         }
@@ -6,6 +6,8 @@ use crate::wad::build::builder::DataBuilder;
 use crate::wad::build::builder::LastChunk;
 use crate::wad::chunk::ChunkName;
 use crate::wad::data::Endianness;
+use crate::wad::data::RawChunk;
+use crate::wad::elem::audio::Audio;
 
 impl DataBuilder<'_> {
     /// Write a 4 character ASCII GameMaker chunk name.
@@ -27,8 +29,34 @@ impl DataBuilder<'_> {
     /// This padding has to then be manually cut off for the last chunk in the
     /// data file.
     pub fn build_chunk<T: GMChunk>(&mut self, element: &T) -> Result<()> {
-        let name: ChunkName = T::NAME;
+        self.build_chunk_with(T::NAME, |builder| element.serialize(builder))
+    }
+
+    /// Writes the `AUDO` chunk from owned audio entries.
+    ///
+    /// The data of every audio entry is freed right after it has been copied
+    /// into the output buffer. See [`GMData::into_bytes`].
+    pub fn build_audio_chunk_consuming(&mut self, audios: Vec<Audio>) -> Result<()> {
+        self.build_chunk_with(ChunkName::AUDO, |builder| {
+            builder.write_pointer_list_consuming(audios)
+        })
+    }
 
+    /// Writes a chunk that failed to parse, with its contents copied verbatim.
+    ///
+    /// See [`ParsingOptions::recover_chunks`](crate::wad::parse::ParsingOptions::recover_chunks).
+    pub fn build_raw_chunk(&mut self, chunk: &RawChunk) -> Result<()> {
+        self.build_chunk_with(chunk.name, |builder| {
+            builder.write_bytes(&chunk.data);
+            Ok(())
+        })
+    }
+
+    fn build_chunk_with(
+        &mut self,
+        name: ChunkName,
+        serialize: impl FnOnce(&mut Self) -> Result<()>,
+    ) -> Result<()> {
         let stopwatch = Stopwatch::start();
 
         self.write_chunk_name(name);
@@ -36,9 +64,7 @@ impl DataBuilder<'_> {
         let start_pos: u32 = self.pos();
         let length_pos = start_pos - 4;
 
-        element
-            .serialize(self)
-            .ctx(|| format!("serializing chunk {name}"))?;
+        serialize(self).ctx(|| format!("serializing chunk {name}"))?;
 
         // Write padding in these versions
         let padding_start_pos = self.pos();
@@ -55,6 +81,9 @@ impl DataBuilder<'_> {
         self.overwrite_u32(chunk_length, length_pos)
             .expect("Chunk length overwrite position out of bounds");
 
+        #[cfg(feature = "chunk-timings")]
+        self.chunk_timings.push((name, stopwatch.elapsed()));
+
         log::trace!("Building chunk {name} took {stopwatch}");
         Ok(())
     }
@@ -75,6 +75,41 @@ impl DataBuilder<'_> {
         Ok(())
     }
 
+    /// Writes a pointer list like [`DataBuilder::write_pointer_list`], but
+    /// takes ownership of the elements and drops every element as soon as it
+    /// has been written.
+    ///
+    /// This keeps peak memory usage down for lists of big elements.
+    /// The elements must not be the target of any pointer, since their memory
+    /// addresses do not stay valid until pointer placeholders are resolved.
+    pub fn write_pointer_list_consuming<T: GMElement>(&mut self, elements: Vec<T>) -> Result<()> {
+        let count: usize = elements.len();
+        let ctx = || {
+            format!(
+                "building consumed pointer list of {} with {} elements",
+                typename::<T>(),
+                count,
+            )
+        };
+
+        self.write_usize(count).ctx(ctx)?;
+        let pointer_list_pos: u32 = self.pos();
+        for _ in 0..count {
+            self.write_u32(0xDEAD_C0DE);
+        }
+
+        for (i, element) in elements.into_iter().enumerate() {
+            element.serialize_pre_padding(self).ctx(ctx)?;
+            self.overwrite_pointer_with_cur_pos(pointer_list_pos, i)
+                .ctx(ctx)?;
+            element.serialize(self).ctx(ctx)?;
+            element
+                .serialize_post_padding(self, i == count - 1)
+                .ctx(ctx)?;
+        }
+        Ok(())
+    }
+
     // TODO: clean up this code
     pub fn write_pointer_list_opt<T: GMElement>(&mut self, elements: &[Option<T>]) -> Result<()> {
         let count: usize = elements.len();
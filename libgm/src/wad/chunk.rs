@@ -289,6 +289,12 @@ impl ChunkOrder {
         }
     }
 
+    /// The chunks of this chunk order as a slice.
+    #[must_use]
+    pub const fn as_slice(&self) -> &[ChunkName] {
+        self.0.as_slice()
+    }
+
     pub fn iter(&'_ self) -> std::iter::Copied<std::slice::Iter<'_, ChunkName>> {
         self.0.iter().copied()
     }
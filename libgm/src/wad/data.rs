@@ -1,6 +1,8 @@
 // SPDX-License-Identifier: GPL-3.0-only
 //! The full GameMaker data struct, containing all information from a data file.
 use std::path::PathBuf;
+#[cfg(feature = "chunk-timings")]
+use std::time::Duration;
 
 use crate::prelude::*;
 use crate::util::bench::Stopwatch;
@@ -51,6 +53,9 @@ use crate::wad::elem::ui_node::UINodes;
 use crate::wad::elem::validate_names;
 use crate::wad::elem::variable::ModernHeader;
 use crate::wad::elem::variable::Variables;
+use crate::wad::parse::ParsingOptions;
+#[cfg(feature = "element-ranges")]
+use crate::wad::parse::ranges::ElementRanges;
 use crate::wad::version::IdeVersion;
 
 /// Byte order (endianness) for integers and chunk names in data files.
@@ -74,6 +79,10 @@ pub enum Endianness {
     Big,
 }
 
+/// How long parsing or building each chunk took, in chunk order.
+#[cfg(feature = "chunk-timings")]
+pub type ChunkTimings = Vec<(ChunkName, Duration)>;
+
 /// Some metadata about a [`GMData`] (GameMaker data file).
 #[derive(Debug, Clone)]
 #[non_exhaustive]
@@ -85,16 +94,15 @@ pub struct Metadata {
     /// Also determines which chunks exist.
     pub chunks: ChunkOrder,
 
-    /// The directory in which this data file is located.
+    /// The path of the data file this data was parsed from.
     ///
-    /// This can be used to find, read and edit the following:
+    /// Its parent directory can be used to find, read and edit the following:
     /// * Audio group files (e.g. `audiogroup1.dat`)
     /// * External sound files (e.g. `mus_st_him.ogg`)
     /// * JSON language files
     ///
     /// If you do not want these files to be available,
     /// you set this to `None` after parsing.
-    // NOTE: This is currently unused.
     pub location: Option<PathBuf>,
 
     /// Indicates the number of padding bytes (null bytes) between chunks.
@@ -118,6 +126,81 @@ pub struct Metadata {
     /// This is a micro optimization. This field's value
     /// can be initialized to zero without any problems.
     pub original_data_size: u32,
+
+    /// Whether to emulate GameMaker's padding bug when building compressed
+    /// tile data (GM 2024.2+).
+    ///
+    /// Due to a GMAC bug, GameMaker inserts a run of 2 blank tiles after a
+    /// tile layer's data if its last 2 tiles are different.
+    /// This is enabled by default for compatibility.
+    ///
+    /// Disabling this produces the correct encoding, but breaks byte equality
+    /// with data files built by GameMaker.
+    /// When parsing, this is detected from the tile layers; it stays enabled
+    /// if no tile layer would be padded.
+    pub emulate_gmac_tile_padding_bug: bool,
+
+    /// Whether to fix the room order stored in `GEN8` when building.
+    ///
+    /// If enabled, the built room order is made consistent with the rooms in
+    /// `ROOM` as described in [`GMData::fix_room_order`]; the room order in
+    /// this [`GMData`] itself is left untouched. Any fixes are logged as a
+    /// warning. This is disabled by default.
+    pub fix_room_order_on_build: bool,
+
+    /// Chunks that failed to parse and are kept as raw bytes.
+    ///
+    /// This is only populated when parsing with
+    /// [`ParsingOptions::recover_chunks`] enabled.
+    /// When building, these chunks are written verbatim instead of being
+    /// serialized from their (empty) element in [`GMData`].
+    ///
+    /// [`ParsingOptions::recover_chunks`]: crate::wad::parse::ParsingOptions::recover_chunks
+    pub raw_chunks: Vec<RawChunk>,
+
+    /// The options this data was parsed with.
+    ///
+    /// This is [`ParsingOptions::STRICT`] if the data was not parsed from a
+    /// data file. See [`build_file_checked`].
+    ///
+    /// [`build_file_checked`]: crate::wad::build::build_file_checked
+    pub parsing_options: ParsingOptions,
+
+    /// The absolute position of the instructions of every code entry
+    /// in the parsed data file, in the order of [`GMData::codes`].
+    ///
+    /// This is only populated when parsing a data file and is not updated
+    /// when code entries are modified.
+    /// See [`GMData::locate_bytecode_offset`].
+    pub code_positions: Vec<u32>,
+
+    /// Pending changes to included files, in the order they were made.
+    ///
+    /// These are written next to the output file when building with
+    /// [`build_file`] or [`build_file_checked`]; building to bytes ignores them.
+    /// See [`GMData::set_included_file`].
+    ///
+    /// [`build_file`]: crate::wad::build::build_file
+    /// [`build_file_checked`]: crate::wad::build::build_file_checked
+    pub included_file_changes: Vec<IncludedFileChange>,
+
+    /// How long parsing each chunk took, in the order they were parsed.
+    ///
+    /// This is only populated when parsing a data file and
+    /// requires the `chunk-timings` feature.
+    /// Build timings are returned by [`build_bytes_timed`] instead.
+    ///
+    /// [`build_bytes_timed`]: crate::wad::build::build_bytes_timed
+    #[cfg(feature = "chunk-timings")]
+    pub chunk_timings: ChunkTimings,
+
+    /// The byte ranges of the elements in the parsed data file.
+    ///
+    /// This is only populated when parsing a data file and
+    /// requires the `element-ranges` feature.
+    /// See [`GMData::element_bytes`].
+    #[cfg(feature = "element-ranges")]
+    pub element_ranges: ElementRanges,
 }
 
 impl Default for Metadata {
@@ -129,11 +212,54 @@ impl Default for Metadata {
             chunk_padding: 16,
             endianness: Endianness::Little,
             original_data_size: 0,
+            emulate_gmac_tile_padding_bug: true,
+            fix_room_order_on_build: false,
             chunks: ChunkOrder::new_empty(),
+            raw_chunks: Vec::new(),
+            parsing_options: ParsingOptions::STRICT,
+            code_positions: Vec::new(),
+            included_file_changes: Vec::new(),
+            #[cfg(feature = "chunk-timings")]
+            chunk_timings: Vec::new(),
+            #[cfg(feature = "element-ranges")]
+            element_ranges: ElementRanges::default(),
         }
     }
 }
 
+/// A chunk that could not be parsed and is kept as raw bytes.
+///
+/// See [`Metadata::raw_chunks`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RawChunk {
+    /// The name of the chunk.
+    pub name: ChunkName,
+
+    /// The contents of the chunk, excluding the chunk name and length.
+    pub data: Blob<Vec<u8>>,
+
+    /// The error chain of the parsing failure.
+    pub error: String,
+}
+
+/// A pending change to an included file.
+///
+/// Included files are not part of the data file, so changes to them are
+/// only written when building the data file to a file on disk.
+/// See [`GMData::set_included_file`] and [`GMData::add_included_file`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IncludedFileChange {
+    /// The name of the included file, relative to the data file's directory.
+    pub name: String,
+
+    /// The new contents of the included file.
+    pub bytes: Blob<Vec<u8>>,
+
+    /// Whether the included file is added instead of replaced.
+    /// New included files are never written over existing files with different contents.
+    pub is_new: bool,
+}
+
 /// The full GameMaker data struct, containing all information from a data file.
 #[derive(Clone, Debug)]
 pub struct GMData {
@@ -192,11 +318,21 @@ impl Default for GMData {
             chunk_padding: 16,
             endianness: Endianness::Little,
             original_data_size: 5000,
+            emulate_gmac_tile_padding_bug: true,
+            fix_room_order_on_build: false,
             chunks: ChunkOrder(vec![
                 GEN8, OPTN, LANG, EXTN, SOND, AGRP, SPRT, BGND, PATH, SCPT, GLOB, SHDR, FONT, TMLN,
                 OBJT, FEDS, ACRV, SEQN, TAGS, ROOM, UILR, DAFL, EMBI, PSEM, PSYS, TPAG, TGIN, CODE,
                 VARI, FUNC, FEAT, STRG, TXTR, AUDO,
             ]),
+            raw_chunks: Vec::new(),
+            parsing_options: ParsingOptions::STRICT,
+            code_positions: Vec::new(),
+            included_file_changes: Vec::new(),
+            #[cfg(feature = "chunk-timings")]
+            chunk_timings: Vec::new(),
+            #[cfg(feature = "element-ranges")]
+            element_ranges: ElementRanges::default(),
         };
 
         let animation_curves = AnimationCurves { elems: Vec::new() };
@@ -224,21 +360,23 @@ impl Default for GMData {
             last_object_id: 100_000,
             last_tile_id: 10_000_000,
             game_id: 1337,
-            directplay_guid: Blob([0u8; 16]),
+            directplay_guid: Blob::new([0u8; 16]),
             game_name: strings.make("LibGM"),
             ide_version: IdeVersion::new(2026, 0, 0, 0),
             window_width: 640,
             window_height: 480,
             flags: Flags::SCALE | Flags::SHOW_CURSOR,
             license_crc32: 1337,
-            license_md5: Blob(*b"GnuPublicLicense"),
+            license_md5: Blob::new(*b"GnuPublicLicense"),
             creation_timestamp: chrono::DateTime::default(), // set this urself if you want to lol
             display_name: strings.make("LibGM: The Game"),
+            active_targets: 0,
             function_classifications: FunctionClassifications::empty(),
             steam_appid: 0,
             debugger_port: 0,
             room_order: vec![GMRef::new(0)],
             gms2_data: Some(GMS2Data::default()),
+            unknown_trailing_data: Blob::default(),
         };
         let global_init_scripts = GlobalInitScripts::default();
         let language_info = LanguageInfo::default();
@@ -341,6 +479,30 @@ impl Default for GMData {
 }
 
 impl GMData {
+    /// The names of all chunks in the order they appeared in the parsed data
+    /// file.
+    ///
+    /// Chunks are written in this order when building, so rebuilt data files
+    /// keep their original chunk order.
+    /// To change the order, modify [`Metadata::chunks`].
+    #[doc(alias = "list_chunks_in_file_order")]
+    #[must_use]
+    pub const fn chunk_order(&self) -> &[ChunkName] {
+        self.meta.chunks.as_slice()
+    }
+
+    /// The names of all chunks that failed to parse and are kept as raw bytes.
+    ///
+    /// See [`Metadata::raw_chunks`].
+    #[must_use]
+    pub fn failed_chunks(&self) -> Vec<ChunkName> {
+        self.meta
+            .raw_chunks
+            .iter()
+            .map(|chunk| chunk.name)
+            .collect()
+    }
+
     /// Validates all names of all named root elements.
     /// This checks for duplicates as well as name charset.
     pub fn validate_names(&self) -> Result<()> {
@@ -368,6 +530,30 @@ impl GMData {
         Ok(())
     }
 
+    /// Validates the data for problems that would produce a malformed
+    /// data file.
+    ///
+    /// This currently runs [`GMData::validate_names`],
+    /// [`GMData::validate_room_layer_data`],
+    /// [`GMData::validate_child_code_offsets`],
+    /// [`GMData::validate_code_entry_order`] and
+    /// [`GMData::validate_asset_references`].
+    pub fn validate(&self) -> Result<()> {
+        self.validate_structure()?;
+        self.validate_asset_references()?;
+        Ok(())
+    }
+
+    /// Runs all checks of [`GMData::validate`] that real data files
+    /// are expected to pass.
+    fn validate_structure(&self) -> Result<()> {
+        self.validate_names()?;
+        self.validate_room_layer_data()?;
+        self.validate_child_code_offsets()?;
+        self.validate_code_entry_order()?;
+        Ok(())
+    }
+
     /// Deserializes all embedded texture pages, turning their underlying image
     /// data into [`DynamicImage`].
     ///
@@ -395,10 +581,48 @@ impl GMData {
         Ok(())
     }
 
+    /// Creates a copy of this data file for editing, without duplicating
+    /// large binary data.
+    ///
+    /// All structural data (strings, code, rooms, game objects, sprite and
+    /// texture metadata, etc.) is copied, so it can be changed freely.
+    /// The following is shared with `self` instead:
+    /// * Embedded audio data (`AUDO`)
+    /// * Embedded texture page images (`TXTR`), both encoded and decoded
+    /// * Sprite collision masks as well as SWF and Spine sprite data
+    /// * Compiled shader data
+    /// * The contents of [raw chunks](Metadata::raw_chunks)
+    ///
+    /// Shared data is stored in a [`Blob`] (or reference counted internally
+    /// by [`GMImage`]) and copied on write, so modifying it in one data file
+    /// never affects the other. Building either data file works as usual.
+    ///
+    /// This is equivalent to [`Clone::clone`], which is cheap for the reasons
+    /// above.
+    ///
+    /// [`GMImage`]: crate::wad::elem::texture_page::GMImage
+    #[must_use]
+    #[doc(alias = "clone_for_edit")]
+    pub fn clone_metadata_only(&self) -> Self {
+        self.clone()
+    }
+
     /// Runs some actions to fully verify integrity and
     /// to prepare the data file for editing.
+    ///
+    /// Only [`GMData::validate_names`] is required to pass.
+    /// Problems found by the other structural checks of [`GMData::validate`]
+    /// are logged as warnings, so that existing data files still load.
     pub fn post_deserialize(&mut self) -> Result<()> {
         self.validate_names()?;
+        let results = [
+            self.validate_room_layer_data(),
+            self.validate_child_code_offsets(),
+            self.validate_code_entry_order(),
+        ];
+        for error in results.into_iter().filter_map(Result::err) {
+            log::warn!("{}", error.chain());
+        }
         self.deserialize_all_textures()?;
         self.optimize_memory();
         Ok(())
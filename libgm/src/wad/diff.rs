@@ -0,0 +1,360 @@
+// SPDX-License-Identifier: GPL-3.0-only
+//! Structured comparison of two GameMaker data files.
+//!
+//! Assets are matched by name where possible and by index otherwise.
+//! Matched assets are compared using their [`PartialEq`] implementation.
+//! Note that references to other elements (like strings) are compared by
+//! index, so an asset also counts as modified if only an element it refers
+//! to moved.
+//!
+//! For more information, see [`GMData::diff`].
+
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::fmt::Formatter;
+
+use crate::gml::Code;
+use crate::gml::Instruction;
+use crate::prelude::*;
+use crate::wad::chunk::ChunkName;
+use crate::wad::elem::string::Strings;
+
+/// A summary of all changes between two data files.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DataDiff {
+    /// Chunks that only exist in the new data file.
+    pub added_chunks: Vec<ChunkName>,
+
+    /// Chunks that only exist in the old data file.
+    pub removed_chunks: Vec<ChunkName>,
+
+    /// Chunks without individually tracked assets that were modified.
+    ///
+    /// This includes `GEN8`, `OPTN` and `STRG`, for example.
+    pub modified_chunks: Vec<ChunkName>,
+
+    /// Asset changes of every chunk with individual assets.
+    ///
+    /// Chunks without any asset changes are not included.
+    pub assets: Vec<AssetDiff>,
+
+    /// Instruction level changes of all modified code entries.
+    pub codes: Vec<CodeDiff>,
+}
+
+/// The asset changes of a single chunk.
+///
+/// Assets without a name are identified by their index, like `#12`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AssetDiff {
+    /// The chunk containing these assets.
+    pub chunk: ChunkName,
+
+    /// Assets that only exist in the new data file.
+    pub added: Vec<String>,
+
+    /// Assets that only exist in the old data file.
+    pub removed: Vec<String>,
+
+    /// Assets that exist in both data files but are not equal.
+    pub modified: Vec<String>,
+}
+
+impl AssetDiff {
+    const fn new(chunk: ChunkName) -> Self {
+        Self {
+            chunk,
+            added: Vec::new(),
+            removed: Vec::new(),
+            modified: Vec::new(),
+        }
+    }
+
+    /// Whether there are no changes.
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.modified.is_empty()
+    }
+}
+
+/// The instruction level changes of a modified code entry.
+///
+/// The counts are computed by stripping the common instructions at the start
+/// and end of both instruction lists; the remaining instructions are counted
+/// as removed and added respectively. This is an approximation; a single
+/// changed instruction counts as one removed and one added instruction.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CodeDiff {
+    /// The name of the code entry.
+    pub name: String,
+
+    /// The number of removed instructions.
+    pub removed_instructions: usize,
+
+    /// The number of added instructions.
+    pub added_instructions: usize,
+}
+
+impl DataDiff {
+    /// Whether there are no changes at all.
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.added_chunks.is_empty()
+            && self.removed_chunks.is_empty()
+            && self.modified_chunks.is_empty()
+            && self.assets.is_empty()
+    }
+}
+
+impl Display for DataDiff {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.is_empty() {
+            return writeln!(f, "No changes");
+        }
+
+        for chunk in &self.added_chunks {
+            writeln!(f, "Added chunk {chunk}")?;
+        }
+        for chunk in &self.removed_chunks {
+            writeln!(f, "Removed chunk {chunk}")?;
+        }
+        for chunk in &self.modified_chunks {
+            writeln!(f, "Modified chunk {chunk}")?;
+        }
+
+        for assets in &self.assets {
+            writeln!(f, "===== {} =====", assets.chunk)?;
+            for name in &assets.added {
+                writeln!(f, "+ {name}")?;
+            }
+            for name in &assets.removed {
+                writeln!(f, "- {name}")?;
+            }
+            for name in &assets.modified {
+                write!(f, "~ {name}")?;
+                if assets.chunk == ChunkName::CODE
+                    && let Some(code) = self.codes.iter().find(|code| &code.name == name)
+                {
+                    write!(
+                        f,
+                        " (-{} +{} instructions)",
+                        code.removed_instructions, code.added_instructions,
+                    )?;
+                }
+                writeln!(f)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl GMData {
+    /// Compares this (old) data file to another (new) data file.
+    ///
+    /// This reports which chunks were added or removed and which assets were
+    /// added, removed or modified, per chunk. Modified code entries also get
+    /// instruction level change counts. For more information, see
+    /// [`crate::wad::diff`].
+    pub fn diff(&self, other: &Self) -> Result<DataDiff> {
+        diff(self, other).ctx("comparing data files")
+    }
+}
+
+fn diff(old: &GMData, new: &GMData) -> Result<DataDiff> {
+    let mut diff = DataDiff::default();
+
+    for chunk in &new.meta.chunks {
+        if !old.meta.chunks.has(chunk) {
+            diff.added_chunks.push(chunk);
+        }
+    }
+    for chunk in &old.meta.chunks {
+        if !new.meta.chunks.has(chunk) {
+            diff.removed_chunks.push(chunk);
+        }
+    }
+
+    macro_rules! modified {
+        ($($field:ident),*) => {
+            $(if old.$field != new.$field {
+                diff.modified_chunks.push(field_chunk_name(&old.$field));
+            })*
+        };
+    }
+    modified!(
+        general_info,
+        options,
+        strings,
+        variables,
+        global_init_scripts,
+        game_end_scripts,
+        feature_flags,
+        tags
+    );
+
+    macro_rules! named {
+        ($($field:ident),*) => {
+            $(diff.assets.push(
+                diff_named(&old.$field, &old.strings, &new.$field, &new.strings)
+                    .ctx(|| format!("comparing chunk {}", field_chunk_name(&old.$field)))?,
+            );)*
+        };
+    }
+    named!(
+        animation_curves,
+        audio_groups,
+        extensions,
+        filter_effects,
+        fonts,
+        functions,
+        game_objects,
+        particle_systems,
+        paths,
+        rooms,
+        scripts,
+        sequences,
+        shaders,
+        sounds,
+        sprites,
+        tilesets,
+        timelines
+    );
+
+    macro_rules! indexed {
+        ($($field:ident),*) => {
+            $(diff.assets.push(diff_indexed(&old.$field, &new.$field));)*
+        };
+    }
+    indexed!(
+        audios,
+        embedded_images,
+        language_info,
+        particle_emitters,
+        texture_group_infos,
+        texture_page_items,
+        texture_pages,
+        ui_nodes
+    );
+
+    let (code_assets, codes) = diff_codes(old, new).ctx("comparing chunk CODE")?;
+    diff.assets.push(code_assets);
+    diff.codes = codes;
+
+    diff.assets.retain(|assets| !assets.is_empty());
+    Ok(diff)
+}
+
+/// Helper to get the chunk name of a `GMData` field through type inference.
+const fn field_chunk_name<T: GMChunk>(_: &T) -> ChunkName {
+    T::NAME
+}
+
+fn resolve_names<'a, T: GMNamedElement + 'a>(
+    elements: impl Iterator<Item = &'a T>,
+    strings: &'a Strings,
+) -> Result<Vec<(&'a str, &'a T)>> {
+    elements
+        .map(|element| Ok((element.name(strings)?.as_str(), element)))
+        .collect()
+}
+
+fn diff_named<C>(
+    old: &C,
+    old_strings: &Strings,
+    new: &C,
+    new_strings: &Strings,
+) -> Result<AssetDiff>
+where
+    C: GMListChunk,
+    C::Element: GMNamedElement + PartialEq,
+{
+    let old_elements = resolve_names(old.elements(), old_strings)?;
+    let new_elements = resolve_names(new.elements(), new_strings)?;
+    Ok(diff_by_name(C::NAME, &old_elements, &new_elements))
+}
+
+fn diff_by_name<T: PartialEq>(
+    chunk: ChunkName,
+    old_elements: &[(&str, &T)],
+    new_elements: &[(&str, &T)],
+) -> AssetDiff {
+    let old_map: HashMap<&str, &T> = old_elements.iter().copied().collect();
+    let new_map: HashMap<&str, &T> = new_elements.iter().copied().collect();
+    let mut diff = AssetDiff::new(chunk);
+
+    for &(name, new_element) in new_elements {
+        match old_map.get(name) {
+            None => diff.added.push(name.to_owned()),
+            Some(&old_element) if old_element != new_element => {
+                diff.modified.push(name.to_owned());
+            }
+            Some(_) => {}
+        }
+    }
+    for &(name, _) in old_elements {
+        if !new_map.contains_key(name) {
+            diff.removed.push(name.to_owned());
+        }
+    }
+    diff
+}
+
+fn diff_indexed<C>(old: &C, new: &C) -> AssetDiff
+where
+    C: GMListChunk,
+    C::Element: PartialEq,
+{
+    let old_elements: Vec<&C::Element> = old.elements().collect();
+    let new_elements: Vec<&C::Element> = new.elements().collect();
+    let mut diff = AssetDiff::new(C::NAME);
+
+    for (i, new_element) in new_elements.iter().enumerate() {
+        match old_elements.get(i) {
+            None => diff.added.push(format!("#{i}")),
+            Some(old_element) if old_element != new_element => diff.modified.push(format!("#{i}")),
+            Some(_) => {}
+        }
+    }
+    for i in new_elements.len()..old_elements.len() {
+        diff.removed.push(format!("#{i}"));
+    }
+    diff
+}
+
+fn diff_codes(old: &GMData, new: &GMData) -> Result<(AssetDiff, Vec<CodeDiff>)> {
+    let old_names: Vec<&str> = old.codes.names(&old.strings)?;
+    let new_names: Vec<&str> = new.codes.names(&new.strings)?;
+    let old_elements: Vec<(&str, &Code)> = old_names.into_iter().zip(&old.codes.elems).collect();
+    let new_elements: Vec<(&str, &Code)> = new_names.into_iter().zip(&new.codes.elems).collect();
+    let assets: AssetDiff = diff_by_name(ChunkName::CODE, &old_elements, &new_elements);
+
+    let old_map: HashMap<&str, &Code> = old_elements.into_iter().collect();
+    let new_map: HashMap<&str, &Code> = new_elements.into_iter().collect();
+    let mut codes: Vec<CodeDiff> = Vec::new();
+    for name in &assets.modified {
+        let old_code: &Code = old_map[name.as_str()];
+        let new_code: &Code = new_map[name.as_str()];
+        let (removed_instructions, added_instructions) =
+            count_instruction_changes(&old_code.instructions, &new_code.instructions);
+        codes.push(CodeDiff {
+            name: name.clone(),
+            removed_instructions,
+            added_instructions,
+        });
+    }
+
+    Ok((assets, codes))
+}
+
+fn count_instruction_changes(old: &[Instruction], new: &[Instruction]) -> (usize, usize) {
+    let prefix: usize = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+    let old: &[Instruction] = &old[prefix..];
+    let new: &[Instruction] = &new[prefix..];
+    let suffix: usize = old
+        .iter()
+        .rev()
+        .zip(new.iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    (old.len() - suffix, new.len() - suffix)
+}
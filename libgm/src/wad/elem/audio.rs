@@ -34,12 +34,33 @@ pub struct Audio {
     pub data: Blob<Vec<u8>>,
 }
 
+impl Audio {
+    /// Detects the container format of the audio data by its magic bytes.
+    ///
+    /// Returns the file extension (without a leading dot) of the detected
+    /// container: `wav`, `ogg` or `mp3`.
+    /// Returns [`None`] if the format is not recognized.
+    #[must_use]
+    pub fn container_extension(&self) -> Option<&'static str> {
+        let data: &[u8] = &self.data;
+        if data.starts_with(b"RIFF") && data.get(8..12) == Some(b"WAVE") {
+            return Some("wav");
+        }
+        match data {
+            [b'O', b'g', b'g', b'S', ..] => Some("ogg"),
+            [b'I', b'D', b'3', ..] => Some("mp3"),
+            // MPEG audio frame sync (11 set bits)
+            [0xFF, second, ..] if second & 0xE0 == 0xE0 => Some("mp3"),
+            _ => None,
+        }
+    }
+}
 
 impl GMElement for Audio {
     fn deserialize(reader: &mut DataReader) -> Result<Self> {
         let size = reader.read_u32()?;
         let data: Vec<u8> = reader.read_bytes_dyn(size)?.to_vec();
-        Ok(Self { data: Blob(data) })
+        Ok(Self { data: Blob::new(data) })
     }
 
     fn serialize(&self, builder: &mut DataBuilder) -> Result<()> {
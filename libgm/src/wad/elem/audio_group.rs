@@ -5,6 +5,7 @@ use crate::wad::build::builder::DataBuilder;
 use crate::wad::chunk::gm_named_list_chunk;
 use crate::wad::elem::GMElement;
 use crate::wad::parse::reader::DataReader;
+use crate::wad::reference::GMRef;
 
 /// Audio Groups allow you to manage a set sound entries easier.
 /// You can use these for memory management, volume control and more.
@@ -61,3 +62,29 @@ impl GMElement for AudioGroup {
         Ok(())
     }
 }
+
+impl GMData {
+    /// Gets the file name of the audio group file containing
+    /// the audio data of the specified audio group.
+    ///
+    /// The file name is relative to the main data file.
+    /// This returns [`None`] for the default audio group,
+    /// since its audio data is stored in the main data file.
+    ///
+    /// In GameMaker 2024.14 and above, this is the audio group's [`AudioGroup::path`].
+    /// Prior to that, audio group files are named `audiogroup{index}.dat`.
+    pub fn audio_group_file(&self, audio_group: GMRef<AudioGroup>) -> Result<Option<String>> {
+        let index: usize = audio_group.index().ok_or("Audio group reference is none")?;
+        if index == 0 {
+            return Ok(None);
+        }
+
+        let group: &AudioGroup = self.audio_groups.by_ref(audio_group)?;
+        if self.meta.version >= GMVersion::GM2024_14 {
+            let path: &String = self.strings.by_ref(group.path)?;
+            return Ok(Some(path.clone()));
+        }
+
+        Ok(Some(format!("audiogroup{index}.dat")))
+    }
+}
@@ -15,6 +15,7 @@ use crate::gml::instruction::Instruction;
 use crate::gml::instruction::PushValue;
 use crate::gml::instruction::VariableType;
 use crate::gml::opcodes;
+use crate::gml::validate_code_name;
 use crate::prelude::*;
 use crate::util::init::vec_with_capacity;
 use crate::wad::GMVersion;
@@ -28,6 +29,18 @@ use crate::wad::elem::variable::Variable;
 use crate::wad::parse::reader::DataReader;
 use crate::wad::reference::GMRef;
 
+/// The code entries of the `CODE` chunk.
+///
+/// The order of code entries is preserved when parsing and building.
+/// It matters for child code entries (WAD 15+), which have to come after
+/// their parent code entry since they share its bytecode (see
+/// [`GMData::validate_code_entry_order`]).
+///
+/// Reordering code entries therefore requires rewriting the parent
+/// references of all child entries, as well as their execution offsets if
+/// the parent's instructions change (see [`GMData::set_instruction_offset`]).
+/// Code entry references elsewhere (such as in scripts or events) would
+/// also have to be updated.
 #[derive(Debug, Clone, Default, PartialEq)]
 pub struct Codes {
     pub elems: Vec<Code>,
@@ -47,13 +60,164 @@ impl GMNamedListChunk for Codes {
     }
 }
 
+impl Codes {
+    /// Resolves the names of all code entries, in order.
+    ///
+    /// This is useful for listing all scripts and events at once.
+    /// For [`GMData`], you can also use [`GMData::code_names`].
+    pub fn names<'a>(&self, strings: &'a Strings) -> Result<Vec<&'a str>> {
+        let mut names: Vec<&str> = Vec::with_capacity(self.elems.len());
+        for code in &self.elems {
+            let name: &String = code.name.resolve(&strings.elems)?;
+            names.push(name);
+        }
+        Ok(names)
+    }
+
+    /// Renames the code entry with the specified name.
+    ///
+    /// The new name is interned in the specified strings first, reusing an
+    /// existing string if possible.
+    ///
+    /// GameMaker derives meaning from code entry names, such as
+    /// `gml_Object_obj_player_Step_0` or `gml_Script_scr_move`.
+    /// If `strict` is set, the new name has to follow these conventions
+    /// (see [`GMNamedElement::validate_name`]); otherwise nothing is changed
+    /// and an error is returned.
+    /// Renaming to a name already used by another code entry is an error.
+    /// A warning is logged if the kind (`Object`, `Script`, etc.) encoded in
+    /// the name changes, or the object/room and event of a non-script code
+    /// entry. The name then no longer matches what the code entry is
+    /// actually linked to.
+    ///
+    /// This does not update the code entry's code locals or the function
+    /// with the same name; use [`GMData::rename_code`] for that.
+    pub fn rename(
+        &mut self,
+        old_name: &str,
+        new_name: &str,
+        strings: &mut Strings,
+        strict: bool,
+    ) -> Result<()> {
+        let ctx = || format!("renaming code entry {old_name:?} to {new_name:?}");
+        let code_ref: GMRef<Code> = self.ref_by_name(old_name, strings).ctx(ctx)?;
+        if old_name != new_name && self.ref_by_name(new_name, strings).is_ok() {
+            bail!(
+                "Cannot rename code entry {old_name:?} to {new_name:?}: the name is already in use"
+            );
+        }
+        if strict {
+            validate_code_name(new_name).ctx(ctx)?;
+        }
+        let new_name_ref: GMRef<String> = strings.make(new_name);
+        self.by_ref_mut(code_ref)?.name = new_name_ref;
+
+        if name_linkage(old_name) != name_linkage(new_name) {
+            log::warn!(
+                "Renaming code entry {old_name:?} to {new_name:?} breaks the object/event linkage \
+                 implied by its name"
+            );
+        }
+        Ok(())
+    }
+}
+
+/// How many local variables or arguments a code entry may have in addition to
+/// one per instruction word.
+///
+/// Unused parameters and locals do not appear in any instruction, so some
+/// slack is needed. Corrupt metadata usually results in far higher counts.
+const SLOT_COUNT_SLACK: u32 = 256;
+
+/// Checks that the locals and argument counts are plausible for a code entry
+/// with the specified instruction length (in bytes).
+fn check_slot_counts(
+    reader: &DataReader,
+    locals_count: u16,
+    argument_count: u16,
+    code_length: u32,
+) -> Result<()> {
+    if cfg!(not(feature = "check-integrity")) {
+        return Ok(());
+    }
+    let max_count: u32 = code_length / 4 + SLOT_COUNT_SLACK;
+    for (description, count) in [("locals", locals_count), ("argument", argument_count)] {
+        if u32::from(count) > max_count {
+            reader.handle_invalid_const(format!(
+                "Code entry {description} count {count} is implausibly high for {code_length} \
+                 bytes of instructions (expected at most {max_count})"
+            ))?;
+        }
+    }
+    Ok(())
+}
+
+/// The parts of a code entry name that link it to an asset and event.
+///
+/// For example, this is `("Object", "obj_player_Step_0")` for
+/// `gml_Object_obj_player_Step_0`. Scripts are only linked by their kind,
+/// since their names are not tied to events.
+fn name_linkage(name: &str) -> Option<(&str, &str)> {
+    let (kind, rest) = name.strip_prefix("gml_")?.split_once('_')?;
+    match kind {
+        "Script" | "GlobalScript" => Some((kind, "")),
+        _ => Some((kind, rest)),
+    }
+}
+
+impl GMData {
+    /// Renames the code entry with the specified name.
+    ///
+    /// Unlike [`Codes::rename`], this also renames the code entry's
+    /// [`CodeLocal`] and the function with the same name (which exists for
+    /// `gml_Script_*` code entries in GMS 2.3+), so that calls to it still
+    /// resolve. If another function already has the new name, it is left
+    /// alone and a warning is logged instead.
+    ///
+    /// For more information, see [`Codes::rename`].
+    ///
+    /// [`CodeLocal`]: crate::wad::elem::function::CodeLocal
+    pub fn rename_code(&mut self, old_name: &str, new_name: &str, strict: bool) -> Result<()> {
+        self.codes
+            .rename(old_name, new_name, &mut self.strings, strict)?;
+        if old_name == new_name {
+            return Ok(());
+        }
+        let new_name_ref: GMRef<String> = self.strings.make(new_name);
+
+        let strings = &self.strings.elems;
+        let code_local = self.functions.code_locals.iter_mut().find(|local| {
+            local
+                .name
+                .resolve(strings)
+                .is_ok_and(|name| name == old_name)
+        });
+        if let Some(code_local) = code_local {
+            code_local.name = new_name_ref;
+        }
+
+        let Ok(function) = self.functions.ref_by_name(old_name, &self.strings) else {
+            return Ok(());
+        };
+        if self.functions.ref_by_name(new_name, &self.strings).is_ok() {
+            log::warn!(
+                "Function {old_name:?} was not renamed since function {new_name:?} already \
+                 exists; calls to it no longer resolve to code entry {new_name:?}"
+            );
+            return Ok(());
+        }
+        self.functions.by_ref_mut(function)?.name = new_name_ref;
+        Ok(())
+    }
+}
+
 element_stub!(Code);
 
 impl GMElement for Codes {
     fn deserialize(reader: &mut DataReader) -> Result<Self> {
         // This can happen with YYC.
         if reader.chunk.is_empty() {
-            return Ok(Self { elems: vec![]});
+            return Ok(Self { elems: vec![] });
         }
 
         let pointers: Vec<u32> = reader.read_simple_list()?;
@@ -80,14 +244,20 @@ impl GMElement for Codes {
 
             if reader.version < GMVersion::Wad15 {
                 instructions_start_pos = reader.cur_pos; // Instructions are placed immediately after code metadata; how convenient!
-                reader.cur_pos += code_length; // Skip over them; they will get parsed in the next loops
-                instructions_end_pos = reader.cur_pos;
+                instructions_end_pos = instructions_start_pos
+                    .checked_add(code_length)
+                    .ok_or("Instruction end position overflowed")?;
+                reader.cur_pos = instructions_end_pos; // Skip over them; they will get parsed in the next loops
                 modern_data = None;
             } else {
                 let locals_count = reader.read_u16()?;
                 let arguments_count_raw = reader.read_u16()?;
-                let argument_count: u16 = arguments_count_raw & 0x7FFF;
+                // All 16 bits are in use (the count and the weird local flag),
+                // so only the count itself can be validated.
+                let argument_count: u16 = arguments_count_raw & ModernData::MAX_ARGUMENT_COUNT;
                 let weird_local_flag: bool = arguments_count_raw & 0x8000 != 0;
+                check_slot_counts(reader, locals_count, argument_count, code_length)
+                    .ctx(|| format!("validating metadata of Code entry at position {pointer}"))?;
 
                 let position = reader.cur_pos;
                 let instructions_start_offset = reader.read_i32()?;
@@ -111,9 +281,25 @@ impl GMElement for Codes {
                 modern_data = Some(data);
             }
 
+            // Reads are bounds checked anyway, but a bogus range inside the file
+            // would otherwise be parsed as garbage instructions.
+            let chunk = reader.chunk;
+            if instructions_start_pos < chunk.start_pos || instructions_end_pos > chunk.end_pos {
+                bail!(
+                    "Instructions of Code entry at position {} span {}..{} which is out of bounds \
+                     for chunk CODE ({}..{})",
+                    pointer,
+                    instructions_start_pos,
+                    instructions_end_pos,
+                    chunk.start_pos,
+                    chunk.end_pos,
+                );
+            }
+
             elems.push(Code { name, instructions: vec![], modern_data });
 
             instructions_ranges.push((instructions_start_pos, instructions_end_pos));
+            reader.code_positions.push(instructions_start_pos);
             last_code_entry_pos = reader.cur_pos;
         }
 
@@ -176,11 +362,7 @@ impl GMElement for Codes {
                 let start = builder.pos();
 
                 // In WAD < 15, instructions are written immediately
-                for (i, instruction) in code.instructions.iter().enumerate() {
-                    instruction
-                        .serialize(builder)
-                        .ctx(|| format!("serializing code #{i} with name {:?}", code.name))?;
-                }
+                serialize_instructions(code, i, builder)?;
 
                 let code_length = builder.pos() - start;
                 builder.overwrite_u32(code_length, length_placeholder_pos)?;
@@ -192,21 +374,35 @@ impl GMElement for Codes {
         let mut instructions_ranges: Vec<(u32, u32)> = Vec::with_capacity(self.elems.len());
 
         for (i, code) in self.elems.iter().enumerate() {
-            if code.modern_data.as_ref().unwrap().parent.is_some() {
-                // If this is a child code entry, don't write instructions; just repeat last pointer
-                let prev_range = instructions_ranges
-                    .last()
-                    .ok_or("First code entry is a child code entry")?;
-                instructions_ranges.push(*prev_range);
+            let data: &ModernData = code.modern_data.as_ref().ok_or_else(|| {
+                format!("Code WAD15+ data not set in version {}", builder.version())
+            })?;
+
+            if let Some(parent_index) = data.parent.index() {
+                // If this is a child code entry, don't write instructions; just repeat the parent's pointer.
+                // This requires the parent to be serialized before its children.
+                let parent_range: (u32, u32) =
+                    *instructions_ranges.get(parent_index).ok_or_else(|| {
+                        err!(
+                            "Child code entry #{i} with name {:?} comes before its parent code \
+                             entry #{parent_index}",
+                            code.name.display(&builder.gm_data.strings),
+                        )
+                    })?;
+                // Reparsing would attach this entry to the root entry instead.
+                if !self.elems[parent_index].is_root() {
+                    bail!(
+                        "Child code entry #{i} with name {:?} has parent code entry \
+                         #{parent_index}, which is a child code entry itself",
+                        code.name.display(&builder.gm_data.strings),
+                    );
+                }
+                instructions_ranges.push(parent_range);
                 continue;
             }
 
             let start: u32 = builder.pos();
-            for instruction in &code.instructions {
-                instruction
-                    .serialize(builder)
-                    .ctx(|| format!("serializing code #{i} with name {:?}", code.name))?;
-            }
+            serialize_instructions(code, i, builder)?;
             let end: u32 = builder.pos();
             instructions_ranges.push((start, end));
         }
@@ -222,7 +418,13 @@ impl GMElement for Codes {
             builder.write_gm_string(code.name)?;
             builder.write_u32(length);
             builder.write_u16(data.local_count);
-            builder.write_u16(data.argument_count | if data.weird_local_flag { 0x8000 } else { 0 });
+            let arguments_count_raw: u16 = data.arguments_count_raw().ctx(|| {
+                format!(
+                    "building Code entry {:?}",
+                    code.name.display(&builder.gm_data.strings),
+                )
+            })?;
+            builder.write_u16(arguments_count_raw);
             let instructions_start_offset: i32 = start as i32 - builder.pos() as i32;
             builder.write_i32(instructions_start_offset);
             builder.write_u32(data.execution_offset);
@@ -397,6 +599,10 @@ impl GMElement for Instruction {
             opcodes::EXTENDED => reader
                 .parse_extended(b)
                 .ctx("parsing Extended Instruction")?,
+            _ if reader.options.allow_unknown_instructions => {
+                log::warn!("Keeping instruction with unknown opcode 0x{opcode:02X} as raw word");
+                Self::Unknown { opcode, raw: word }
+            }
             _ => bail!("Invalid Instruction Opcode {opcode} (0x{opcode:02X})"),
         })
     }
@@ -505,6 +711,9 @@ impl GMElement for Instruction {
             Self::PushReference { asset_reference } => {
                 build_pushref(builder, asset_reference)?;
             }
+            Self::Unknown { raw, .. } => {
+                builder.write_u32(raw);
+            }
         }
         Ok(())
     }
@@ -733,6 +942,11 @@ impl DataReader<'_> {
                     .ctx("parsing PushReference Extended Instruction")?;
                 Instruction::PushReference { asset_reference }
             }
+            (Int16, _) if self.options.allow_unknown_instructions => {
+                log::warn!("Keeping extended instruction with unknown kind {kind} as raw word");
+                let raw = u32::from_le_bytes([b[0], b[1], b[2], opcodes::EXTENDED]);
+                Instruction::Unknown { opcode: opcodes::EXTENDED, raw }
+            }
             _ => bail!("Invalid Extended Instruction with data type {data_type:?} and kind {kind}"),
         };
 
@@ -778,7 +992,7 @@ fn build_pop(
     type2: DataType,
 ) -> Result<()> {
     let instr_pos: u32 = builder.pos();
-    builder.write_i16(build_instance_type(variable));
+    builder.write_i16(build_instance_type(variable)?);
     builder.write_u8(type1.as_u8() | type2.as_u8() << 4);
     builder.write_u8(opcode);
     write_variable_occurrence(
@@ -792,7 +1006,7 @@ fn build_pop(
 
 fn build_popswap(builder: &mut DataBuilder, opcode: u8, array: bool) {
     builder.write_i16(if array { 6 } else { 5 });
-    builder.write_u8(DataType::Int32.as_u8() | DataType::Variable.as_u8() << 4);
+    builder.write_u8(DataType::Int16.as_u8() | DataType::Variable.as_u8() << 4);
     builder.write_u8(opcode);
 }
 
@@ -833,7 +1047,7 @@ fn build_push(builder: &mut DataBuilder, opcode: u8, value: PushValue) -> Result
     let instr_pos: u32 = builder.pos();
     builder.write_i16(match value {
         PushValue::Int16(int16) => int16,
-        PushValue::Variable(variable) => build_instance_type(variable),
+        PushValue::Variable(variable) => build_instance_type(variable)?,
         _ => 0,
     });
 
@@ -866,7 +1080,7 @@ fn build_push(builder: &mut DataBuilder, opcode: u8, value: PushValue) -> Result
 
 fn build_pushvar(builder: &mut DataBuilder, opcode: u8, variable: CodeVariable) -> Result<()> {
     let instr_pos = builder.pos();
-    builder.write_i16(build_instance_type(variable));
+    builder.write_i16(build_instance_type(variable)?);
     builder.write_u8(DataType::Variable.as_u8());
     builder.write_u8(opcode);
 
@@ -936,7 +1150,8 @@ impl GMElement for AssetReference {
 
     fn serialize(&self, builder: &mut DataBuilder) -> Result<()> {
         if let Self::Function(func_ref) = *self {
-            write_function_occurrence(builder, func_ref, builder.pos())?;
+            // Occurrences are tracked by instruction position, which is right before this.
+            write_function_occurrence(builder, func_ref, builder.pos() - 4)?;
             return Ok(());
         }
 
@@ -981,7 +1196,7 @@ fn read_variable(reader: &mut DataReader, raw_instance_type: i16) -> Result<Code
     })
 }
 
-const fn build_instance_type(code_variable: CodeVariable) -> i16 {
+fn build_instance_type(code_variable: CodeVariable) -> Result<i16> {
     // utmt requires this for proper disassembly
     if matches!(
         code_variable.variable_type,
@@ -990,10 +1205,24 @@ const fn build_instance_type(code_variable: CodeVariable) -> i16 {
         code_variable.instance_type.build()
     } else {
         // if special access, make it "undefined"
-        0
+        Ok(0)
     }
 }
 
+/// Serializes the instructions of a code entry.
+///
+/// Errors are annotated with the instruction index and the code entry's name,
+/// since the errors of instructions themselves lack this context.
+fn serialize_instructions(code: &Code, code_index: usize, builder: &mut DataBuilder) -> Result<()> {
+    for (i, instruction) in code.instructions.iter().enumerate() {
+        instruction.serialize(builder).ctx(|| {
+            let name: &str = code.name.display(&builder.gm_data.strings);
+            format!("serializing instruction #{i} of code entry #{code_index} {name:?}")
+        })?;
+    }
+    Ok(())
+}
+
 fn write_variable_occurrence(
     builder: &mut DataBuilder,
     variable_ref: GMRef<Variable>,
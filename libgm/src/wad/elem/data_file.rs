@@ -1,13 +1,26 @@
 // SPDX-License-Identifier: GPL-3.0-only
+use std::io::Write;
+use std::path::Component;
+use std::path::Path;
+use std::path::PathBuf;
+
 use crate::prelude::*;
+use crate::wad::Blob;
 use crate::wad::build::builder::DataBuilder;
 use crate::wad::chunk::ChunkName;
+use crate::wad::data::IncludedFileChange;
 use crate::wad::elem::GMElement;
 use crate::wad::parse::reader::DataReader;
 
 /// This is the always-empty unused chunk `DAFL`.
 ///
 /// It is unrelated to the `GMData` struct.
+///
+/// Despite its name, included files ("datafiles") are not stored in this
+/// chunk (or anywhere else in the data file). GameMaker ships them as regular
+/// files next to the data file, with their size determined by the file system.
+/// Changes to them are written when building to a file.
+/// See [`GMData::set_included_file`] and [`GMData::add_included_file`].
 #[derive(Debug, Clone, Default, PartialEq, Eq)]
 pub struct DataFiles;
 
@@ -25,3 +38,145 @@ impl GMElement for DataFiles {
         Ok(())
     }
 }
+
+impl IncludedFileChange {
+    /// Checks that this change can be written to the specified directory
+    /// (the output data file's directory), without writing anything.
+    pub(crate) fn check(&self, directory: &Path) -> Result<()> {
+        let path: PathBuf = directory.join(validate_name(&self.name)?);
+        if self.is_new && path.exists() && !self.is_written(&path) {
+            bail!("Included file {} already exists", path.display());
+        }
+        Ok(())
+    }
+
+    /// Writes this change to the specified directory (the output data file's directory).
+    pub(crate) fn write(&self, directory: &Path) -> Result<()> {
+        let path: PathBuf = directory.join(validate_name(&self.name)?);
+        let ctx = || format!("writing included file {}", path.display());
+        if let Some(directory) = path.parent() {
+            std::fs::create_dir_all(directory)
+                .ctx_any(|| format!("creating directory {}", directory.display()))?;
+        }
+        if !self.is_new {
+            return std::fs::write(&path, &*self.bytes).ctx_any(ctx);
+        }
+        if self.is_written(&path) {
+            // Already written by an earlier build to this directory.
+            return Ok(());
+        }
+        // Creating the file fails if it exists, so nothing is ever overwritten.
+        let mut file = std::fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&path)
+            .ctx_any(ctx)?;
+        file.write_all(&self.bytes).ctx_any(ctx)
+    }
+
+    /// Whether the file at the specified path already has the new contents.
+    fn is_written(&self, path: &Path) -> bool {
+        std::fs::read(path).is_ok_and(|bytes| bytes == *self.bytes)
+    }
+}
+
+/// Checks that an included file name is a relative path which
+/// does not leave the data file's directory.
+fn validate_name(name: &str) -> Result<&Path> {
+    let relative = Path::new(name);
+    let is_valid = relative
+        .components()
+        .all(|component| matches!(component, Component::Normal(_)));
+    if name.is_empty() || !is_valid {
+        bail!("Invalid included file name {name:?}; expected a relative path");
+    }
+    Ok(relative)
+}
+
+impl GMData {
+    /// Gets the path of the included file with the specified name.
+    ///
+    /// Included files are stored next to the data file, so this requires the
+    /// [`Metadata::location`] to be known (which is the case for data parsed
+    /// from a file). The name is relative to the data file's directory and
+    /// may contain subdirectories, but may not leave the directory.
+    ///
+    /// [`Metadata::location`]: crate::wad::data::Metadata::location
+    pub fn included_file_path(&self, name: &str) -> Result<PathBuf> {
+        let Some(location) = &self.meta.location else {
+            bail!("Data file location is unknown; cannot locate included file {name:?}");
+        };
+        let relative: &Path = validate_name(name)?;
+        let directory: &Path = location.parent().unwrap_or_else(|| Path::new(""));
+        Ok(directory.join(relative))
+    }
+
+    /// Replaces the contents of an existing included file.
+    ///
+    /// Included files are regular files next to the data file (see
+    /// [`GMData::included_file_path`]), so the new contents may have any size;
+    /// there is no offset or length metadata to update.
+    ///
+    /// The original file is left untouched. Instead, the change is recorded in
+    /// [`Metadata::included_file_changes`] and written next to the output
+    /// file by [`build_file`] and [`build_file_checked`].
+    ///
+    /// Returns an error if the file does not exist (and has not been added using
+    /// [`GMData::add_included_file`]); use [`GMData::add_included_file`] to
+    /// create it instead.
+    ///
+    /// [`Metadata::included_file_changes`]: crate::wad::data::Metadata::included_file_changes
+    /// [`build_file`]: crate::wad::build::build_file
+    /// [`build_file_checked`]: crate::wad::build::build_file_checked
+    #[doc(alias = "replace_included_file")]
+    pub fn set_included_file(&mut self, name: &str, bytes: Vec<u8>) -> Result<()> {
+        validate_name(name)?;
+        if let Some(change) = self.included_file_change_mut(name) {
+            change.bytes = Blob::new(bytes);
+            return Ok(());
+        }
+        let path: PathBuf = self.included_file_path(name)?;
+        if !path.is_file() {
+            bail!("Included file {} does not exist", path.display());
+        }
+        self.meta.included_file_changes.push(IncludedFileChange {
+            name: name.to_owned(),
+            bytes: Blob::new(bytes),
+            is_new: false,
+        });
+        Ok(())
+    }
+
+    /// Adds a new included file next to the data file.
+    ///
+    /// Like [`GMData::set_included_file`], the file is only written when
+    /// building to a file. Missing parent directories are created then.
+    ///
+    /// Returns an error if the file already exists; use
+    /// [`GMData::set_included_file`] to replace it instead.
+    /// Building also fails (before writing anything) if a file with different
+    /// contents exists next to the output file.
+    pub fn add_included_file(&mut self, name: &str, bytes: Vec<u8>) -> Result<()> {
+        validate_name(name)?;
+        let exists: bool = self.included_file_change_mut(name).is_some()
+            || self
+                .included_file_path(name)
+                .is_ok_and(|path| path.exists());
+        if exists {
+            bail!("Included file {name:?} already exists");
+        }
+        self.meta.included_file_changes.push(IncludedFileChange {
+            name: name.to_owned(),
+            bytes: Blob::new(bytes),
+            is_new: true,
+        });
+        Ok(())
+    }
+
+    fn included_file_change_mut(&mut self, name: &str) -> Option<&mut IncludedFileChange> {
+        self.meta
+            .included_file_changes
+            .iter_mut()
+            .find(|change| change.name == name)
+    }
+}
@@ -1,10 +1,16 @@
 // SPDX-License-Identifier: GPL-3.0-only
 use crate::prelude::*;
 use crate::wad::build::builder::DataBuilder;
+use crate::wad::chunk::ChunkName;
 use crate::wad::chunk::gm_list_chunk;
 use crate::wad::elem::GMElement;
 use crate::wad::parse::reader::DataReader;
 
+/// The feature flags enabled for this game (GM 2022.8+).
+///
+/// Some runner behavior is gated on these flags.
+/// Use [`GMData::add_feature_flag`] and [`GMData::remove_feature_flag`]
+/// to toggle them by name.
 #[derive(Debug, Clone, Default, PartialEq)]
 pub struct FeatureFlags {
     pub elems: Vec<GMRef<String>>,
@@ -25,3 +31,55 @@ impl GMElement for FeatureFlags {
         Ok(())
     }
 }
+
+impl GMData {
+    /// The names of all enabled feature flags, in chunk order.
+    pub fn feature_flag_names(&self) -> Result<Vec<&str>> {
+        self.feature_flags
+            .elems
+            .iter()
+            .map(|&name| self.strings.by_ref(name).map(String::as_str))
+            .collect()
+    }
+
+    /// Whether the feature flag with the specified name is enabled.
+    #[must_use]
+    pub fn has_feature_flag(&self, name: &str) -> bool {
+        self.feature_flag_index(name).is_some()
+    }
+
+    /// Enables the feature flag with the specified name by appending it to
+    /// the `FEAT` chunk.
+    ///
+    /// Returns `false` if the flag was already enabled.
+    /// Returns an error if the data file has no `FEAT` chunk (before GM 2022.8).
+    pub fn add_feature_flag(&mut self, name: &str) -> Result<bool> {
+        if !self.meta.chunks.has(ChunkName::FEAT) {
+            bail!("Cannot add feature flag {name:?}: data file has no FEAT chunk");
+        }
+        if self.has_feature_flag(name) {
+            return Ok(false);
+        }
+        let name: GMRef<String> = self.strings.make(name);
+        self.feature_flags.push(name);
+        Ok(true)
+    }
+
+    /// Disables the feature flag with the specified name.
+    ///
+    /// Returns `false` if the flag was not enabled.
+    pub fn remove_feature_flag(&mut self, name: &str) -> bool {
+        let Some(index) = self.feature_flag_index(name) else {
+            return false;
+        };
+        self.feature_flags.elems.remove(index);
+        true
+    }
+
+    fn feature_flag_index(&self, name: &str) -> Option<usize> {
+        self.feature_flags
+            .elems
+            .iter()
+            .position(|&flag| self.strings.by_ref(flag).is_ok_and(|flag| flag == name))
+    }
+}
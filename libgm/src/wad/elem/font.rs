@@ -1,7 +1,9 @@
 // SPDX-License-Identifier: GPL-3.0-only
+mod atlas;
 mod glyph;
 mod kerning;
 
+pub use atlas::GlyphRect;
 pub use glyph::Glyph;
 pub use kerning::Kerning;
 
@@ -30,7 +32,7 @@ impl GMElement for Fonts {
             let bytes = reader
                 .read_bytes_const()
                 .ctx("Reading FONT padding bytes")?;
-            Blob(*bytes)
+            Blob::new(*bytes)
         } else {
             generate_padding()
         };
@@ -225,7 +227,7 @@ pub enum FontSize {
 
 /// Generates standard padding bytes for the end of the `FONT` chunk.
 #[must_use]
-pub const fn generate_padding() -> Blob<[u8; 512]> {
+pub fn generate_padding() -> Blob<[u8; 512]> {
     let mut padding = [0u8; 512];
     let mut i = 0;
 
@@ -239,5 +241,5 @@ pub const fn generate_padding() -> Blob<[u8; 512]> {
         i += 1;
     }
 
-    Blob(padding)
+    Blob::new(padding)
 }
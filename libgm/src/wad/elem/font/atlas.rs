@@ -0,0 +1,137 @@
+// SPDX-License-Identifier: GPL-3.0-only
+//! Replacing the texture atlas of a font along with its glyph layout.
+
+use std::collections::HashSet;
+
+use image::RgbaImage;
+
+use crate::prelude::*;
+use crate::wad::elem::font::Font;
+use crate::wad::elem::font::Glyph;
+use crate::wad::elem::texture_page_item::TexturePageItem;
+
+/// The region (x, y, width, height) of a glyph in a font atlas.
+pub type GlyphRect = (u16, u16, u16, u16);
+
+impl GMData {
+    /// Replaces the texture atlas of the specified font with the given RGBA
+    /// image and rewrites the glyph crops to match its layout.
+    ///
+    /// Every entry of `glyph_rects` maps a character to its region
+    /// (x, y, width, height) in the new atlas, or to [`None`] to explicitly drop
+    /// that glyph. Every existing glyph has to be listed; a glyph that is
+    /// neither given a region nor dropped would point to arbitrary pixels.
+    /// Characters that do not have a glyph yet are added with an advance of
+    /// their width and no kerning. The character range of the font is
+    /// extended to cover all glyphs.
+    ///
+    /// The image is placed like in [`GMData::set_sprite_frame_image`]: on the
+    /// old texture page if its region is big enough, otherwise on a new
+    /// texture page in the same texture group.
+    #[doc(alias = "replace_font_texture")]
+    pub fn set_font_atlas(
+        &mut self,
+        font_ref: GMRef<Font>,
+        width: u32,
+        height: u32,
+        rgba: &[u8],
+        glyph_rects: &[(char, Option<GlyphRect>)],
+    ) -> Result<()> {
+        let ctx = "setting font atlas";
+        if width == 0 || height == 0 || width > u32::from(u16::MAX) || height > u32::from(u16::MAX)
+        {
+            bail!("Invalid font atlas size {width}x{height}");
+        }
+        let expected_len = u64::from(width) * u64::from(height) * 4;
+        let image = RgbaImage::from_raw(width, height, rgba.to_vec()).ok_or_else(|| {
+            err!(
+                "Expected {expected_len} bytes of RGBA data for a {width}x{height} image; got {}",
+                rgba.len(),
+            )
+        })?;
+
+        let mut seen: HashSet<char> = HashSet::new();
+        for &(character, rect) in glyph_rects {
+            if u16::try_from(u32::from(character)).is_err() || character == '\0' {
+                bail!("Character {character:?} cannot be stored in a font glyph");
+            }
+            if !seen.insert(character) {
+                bail!("Character {character:?} is specified more than once");
+            }
+            if let Some((x, y, w, h)) = rect
+                && (u32::from(x) + u32::from(w) > width || u32::from(y) + u32::from(h) > height)
+            {
+                bail!(
+                    "Region ({x}, {y}, {w}, {h}) of character {character:?} is out of bounds of \
+                     the {width}x{height} font atlas"
+                );
+            }
+        }
+
+        let font: &Font = self.fonts.by_ref(font_ref)?;
+        for glyph in &font.glyphs {
+            let Some(character) = glyph.character else {
+                bail!("Font has a glyph without a character which cannot be specified");
+            };
+            if !seen.contains(&character) {
+                bail!("Glyph of character {character:?} is neither given a region nor dropped");
+            }
+        }
+        let texture: GMRef<TexturePageItem> = font.texture;
+
+        let item: TexturePageItem = self.place_texture_image(texture, image).ctx(ctx)?;
+        let texture: GMRef<TexturePageItem> = if texture.is_some() {
+            *self.texture_page_items.by_ref_mut(texture)? = item;
+            texture
+        } else {
+            self.texture_page_items.push(item)
+        };
+
+        let font: &mut Font = self.fonts.by_ref_mut(font_ref)?;
+        font.texture = texture;
+        for &(character, rect) in glyph_rects {
+            let index: Option<usize> = font
+                .glyphs
+                .iter()
+                .position(|glyph| glyph.character == Some(character));
+            let Some((x, y, width, height)) = rect else {
+                if let Some(index) = index {
+                    font.glyphs.remove(index);
+                }
+                continue;
+            };
+            if let Some(index) = index {
+                let glyph: &mut Glyph = &mut font.glyphs[index];
+                glyph.x = x;
+                glyph.y = y;
+                glyph.width = width;
+                glyph.height = height;
+            } else {
+                font.glyphs.push(Glyph {
+                    character: Some(character),
+                    x,
+                    y,
+                    width,
+                    height,
+                    shift_modifier: i16::try_from(width).unwrap_or(i16::MAX),
+                    offset: 0,
+                    kernings: Vec::new(),
+                });
+            }
+        }
+
+        font.glyphs.sort_by_key(|glyph| glyph.character);
+        let codepoints = font
+            .glyphs
+            .iter()
+            .filter_map(|glyph| glyph.character)
+            .map(u32::from);
+        if let (Some(first), Some(last)) = (codepoints.clone().min(), codepoints.max()) {
+            // Characters were checked to fit in 16 bits above
+            let first = u16::try_from(first).unwrap_or(u16::MAX);
+            font.range_start = font.range_start.min(first);
+            font.range_end = font.range_end.max(last);
+        }
+        Ok(())
+    }
+}
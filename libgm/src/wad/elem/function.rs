@@ -37,6 +37,88 @@ impl Functions {
     }
 }
 
+impl GMData {
+    /// Finds the function with the specified name.
+    ///
+    /// This is a shorthand for [`Functions::ref_by_name`].
+    pub fn function_by_name(&self, name: &str) -> Result<GMRef<Function>> {
+        self.functions.ref_by_name(name, &self.strings)
+    }
+
+    /// Whether the specified function is a built-in function provided by the
+    /// runner (like `array_length` or `show_debug_message`).
+    ///
+    /// Data files do not mark built-in functions, so this is a heuristic.
+    /// A function is considered user defined if its name
+    /// * starts with `gml_Script_` (script functions in GMS 2.3+),
+    /// * is the name of a code entry or a script (scripts before GMS 2.3), or
+    /// * is the name of an extension function.
+    ///
+    /// Every other function is considered built-in.
+    pub fn is_builtin_function(&self, function: GMRef<Function>) -> Result<bool> {
+        let name: &str = self.functions.by_ref(function)?.name(&self.strings)?;
+        if name.starts_with("gml_Script_") {
+            return Ok(false);
+        }
+        let is_named = |string: GMRef<String>| string.display(&self.strings) == name;
+        let user_defined: bool = self.codes.elements().any(|code| is_named(code.name))
+            || self.scripts.elements().any(|script| is_named(script.name))
+            || self
+                .extensions
+                .elements()
+                .flat_map(|extension| &extension.files)
+                .flat_map(|file| &file.functions)
+                .any(|ext_function| is_named(ext_function.name));
+        Ok(!user_defined)
+    }
+
+    /// Returns every function with its resolved name, in chunk order.
+    ///
+    /// This is useful for building symbol tables.
+    pub fn all_function_names(&self) -> Result<Vec<(GMRef<Function>, &str)>> {
+        self.functions
+            .element_refs()
+            .map(|(func_ref, func)| Ok((func_ref, func.name(&self.strings)?.as_str())))
+            .collect()
+    }
+
+    /// The number of times each function is referenced in code, indexed by
+    /// function index.
+    ///
+    /// These are the occurrence counts stored in `FUNC`. They are counted from
+    /// the code instructions (calls, function pushes and function asset
+    /// references), so they stay correct after modifying code.
+    /// References to functions that do not exist are ignored.
+    #[must_use]
+    pub fn function_use_counts(&self) -> Vec<usize> {
+        let mut counts: Vec<usize> = vec![0; self.functions.len()];
+        for code in &self.codes.elems {
+            for instruction in &code.instructions {
+                if let Some(index) = instruction.function().and_then(GMRef::index)
+                    && let Some(count) = counts.get_mut(index)
+                {
+                    *count += 1;
+                }
+            }
+        }
+        counts
+    }
+
+    /// The number of times the specified function is referenced in code.
+    ///
+    /// To get the counts of all functions at once, use
+    /// [`GMData::function_use_counts`].
+    #[must_use]
+    pub fn function_use_count(&self, function: GMRef<Function>) -> usize {
+        self.codes
+            .elems
+            .iter()
+            .flat_map(|code| &code.instructions)
+            .filter(|instruction| instruction.function() == Some(function))
+            .count()
+    }
+}
+
 impl GMElement for Functions {
     fn deserialize(reader: &mut DataReader) -> Result<Self> {
         let functions_count = if reader.version >= GMVersion::Wad15 {
@@ -1,9 +1,13 @@
 // SPDX-License-Identifier: GPL-3.0-only
 pub mod event;
 
+use self::event::Action;
 pub use self::event::Event;
+pub use self::event::EventGroup;
 pub use self::event::EventGroups;
+use self::event::subtype::Collision;
 use crate::gm_enum::gm_enum;
+use crate::gml::Code;
 use crate::prelude::*;
 use crate::util::init::vec_with_capacity;
 use crate::wad::GMVersion;
@@ -224,6 +228,119 @@ pub struct GameObject {
 }
 element_stub!(GameObject);
 
+impl GameObject {
+    /// Returns the code entries of all of this game object's events,
+    /// flattened into `(event group, event subtype, code)` tuples.
+    ///
+    /// Inherited events from parent objects are not included.
+    /// For more information, see [`EventGroups::codes`].
+    #[must_use]
+    pub fn event_code(&self) -> Vec<(EventGroup, i32, GMRef<Code>)> {
+        self.events.codes()
+    }
+
+    /// Returns the collision partners of this game object,
+    /// each together with the code entry handling the collision.
+    ///
+    /// The partner is the other game object, which is stored as the event
+    /// subtype (see [`Collision`]). If a collision event has multiple actions,
+    /// the partner is listed once per action. Actions without code are skipped.
+    ///
+    /// Inherited events from parent objects are not included.
+    #[must_use]
+    pub fn collision_events(&self) -> Vec<(GMRef<Self>, GMRef<Code>)> {
+        let mut events: Vec<(GMRef<Self>, GMRef<Code>)> = Vec::new();
+        for event in &self.events.collision {
+            for action in &event.actions {
+                if action.code.is_some() {
+                    events.push((event.subtype.object, action.code));
+                }
+            }
+        }
+        events
+    }
+
+    /// Adds a collision event with the specified game object, which executes
+    /// the specified code entry.
+    ///
+    /// If this game object already has a collision event with that game
+    /// object, the code is appended as another action to it.
+    /// Otherwise, a new collision event is created.
+    pub fn add_collision_event(&mut self, other: GMRef<Self>, code: GMRef<Code>) {
+        let action = Action::new(code);
+        let existing = self
+            .events
+            .collision
+            .iter_mut()
+            .find(|event| event.subtype.object == other);
+        if let Some(event) = existing {
+            event.actions.push(action);
+        } else {
+            self.events.collision.push(Event {
+                subtype: Collision::new(other),
+                actions: vec![action],
+            });
+        }
+    }
+}
+
+impl GMData {
+    /// Returns the code entries of all events of the game object
+    /// with the specified name.
+    ///
+    /// For more information, see [`GameObject::event_code`].
+    pub fn object_events_code(&self, name: &str) -> Result<Vec<(EventGroup, i32, GMRef<Code>)>> {
+        let game_object: &GameObject = self.game_objects.by_name(name, &self.strings)?;
+        Ok(game_object.event_code())
+    }
+
+    /// Returns the collision partners of the game object with the specified
+    /// name, each together with the code entry handling the collision.
+    ///
+    /// For more information, see [`GameObject::collision_events`].
+    pub fn object_collision_events(
+        &self,
+        name: &str,
+    ) -> Result<Vec<(GMRef<GameObject>, GMRef<Code>)>> {
+        let game_object: &GameObject = self.game_objects.by_name(name, &self.strings)?;
+        Ok(game_object.collision_events())
+    }
+
+    /// Resolves the sprite of the game object with the specified name.
+    ///
+    /// Returns [`None`] if the game object does not have a sprite.
+    /// Returns an error if the game object does not exist.
+    pub fn object_sprite(&self, object_name: &str) -> Result<Option<&Sprite>> {
+        let game_object: &GameObject = self.game_objects.by_name(object_name, &self.strings)?;
+        if game_object.sprite.is_none() {
+            return Ok(None);
+        }
+        let sprite: &Sprite = self
+            .sprites
+            .by_ref(game_object.sprite)
+            .ctx(|| format!("resolving sprite of game object {object_name:?}"))?;
+        Ok(Some(sprite))
+    }
+
+    /// Sets the sprite of the game object with the specified name to the
+    /// sprite with the specified name.
+    ///
+    /// Returns an error if the game object or the sprite does not exist.
+    ///
+    /// Only [`GameObject::sprite`] is changed. If the game object has a
+    /// separate [`GameObject::texture_mask`], collisions still use that mask
+    /// instead of the new sprite. Likewise, custom physics shape vertices
+    /// ([`GameObject::physics_shape_vertices`]) are in pixel coordinates of
+    /// the old sprite and may need to be adjusted if the sizes differ.
+    pub fn set_object_sprite(&mut self, object_name: &str, sprite_name: &str) -> Result<()> {
+        let sprite: GMRef<Sprite> = self.sprites.ref_by_name(sprite_name, &self.strings)?;
+        let game_object: &mut GameObject =
+            self.game_objects.by_name_mut(object_name, &self.strings)?;
+        game_object.sprite = sprite;
+        Ok(())
+    }
+}
+
 gm_enum!(CollisionShape {
     Circle = 0,
     Box = 1,
@@ -12,6 +12,7 @@ use self::subtype::Key;
 use self::subtype::Mouse;
 use self::subtype::Other;
 use self::subtype::Step;
+use crate::gml::Code;
 use crate::prelude::*;
 use crate::util::assert;
 use crate::util::fmt::typename;
@@ -121,6 +122,49 @@ pub struct EventGroups {
     pub pre_create: Vec<Event<()>>,
 }
 
+impl EventGroups {
+    /// Returns the code entries of all event actions, together with their
+    /// event group and raw event subtype.
+    ///
+    /// Events are ordered by event group (in the order of [`EventGroup`]),
+    /// then by their order in the data file.
+    /// Actions without code are skipped.
+    #[must_use]
+    pub fn codes(&self) -> Vec<(EventGroup, i32, GMRef<Code>)> {
+        let mut codes: Vec<(EventGroup, i32, GMRef<Code>)> = Vec::new();
+        macro_rules! add {
+            ($($field:ident => $group:ident),*) => {
+                $(for event in &self.$field {
+                    let subtype: i32 = event.subtype.build();
+                    for action in &event.actions {
+                        if action.code.is_some() {
+                            codes.push((EventGroup::$group, subtype, action.code));
+                        }
+                    }
+                })*
+            };
+        }
+        add!(
+            create => Create,
+            destroy => Destroy,
+            alarm => Alarm,
+            step => Step,
+            collision => Collision,
+            keyboard => Keyboard,
+            mouse => Mouse,
+            other => Other,
+            draw => Draw,
+            key_press => KeyPress,
+            key_release => KeyRelease,
+            trigger => Trigger,
+            cleanup => Cleanup,
+            gesture => Gesture,
+            pre_create => PreCreate
+        );
+        codes
+    }
+}
+
 impl GMElement for EventGroups {
     fn deserialize(reader: &mut DataReader) -> Result<Self> {
         let pointers: Vec<u32> = reader
@@ -19,14 +19,14 @@ use crate::wad::parse::reader::DataReader;
 
 #[derive(Clone, PartialEq)]
 pub struct Action {
-    lib_id: u32,         // usually 1
-    id: u32,             // usually 603, sometimes 601, sometimes other
-    kind: u32,           // usually 7
-    use_relative: bool,  // usually false
-    is_question: bool,   // usually false
-    use_apply_to: bool,  // usually true
-    exe_type: u32,       // usually 2
-    name: GMRef<String>, // Some("") or None
+    lib_id: u32,                    // usually 1
+    id: u32,                        // usually 603, sometimes 601, sometimes other
+    kind: u32,                      // usually 7
+    use_relative: bool,             // usually false
+    is_question: bool,              // usually false
+    use_apply_to: bool,             // usually true
+    exe_type: u32,                  // usually 2
+    pub(crate) name: GMRef<String>, // Some("") or None
 
     /// The code that will be executed when this action is ran.
     pub code: GMRef<Code>,
@@ -3,6 +3,9 @@ mod flags;
 mod function_classifications;
 mod gms2;
 
+use std::collections::HashSet;
+use std::fmt;
+
 use chrono::DateTime;
 use chrono::Utc;
 
@@ -15,6 +18,7 @@ use crate::wad::build::builder::DataBuilder;
 use crate::wad::chunk::ChunkName;
 use crate::wad::elem::GMElement;
 use crate::wad::elem::room::Room;
+use crate::wad::elem::room::layer;
 use crate::wad::parse::reader::DataReader;
 use crate::wad::reference::GMRef;
 use crate::wad::version::GMVersion;
@@ -96,6 +100,12 @@ pub struct GeneralInfo {
     /// The name that gets displayed in the window title.
     pub display_name: GMRef<String>,
 
+    /// The targets (platforms) the game was compiled for, as a bitmask.
+    ///
+    /// This is normally zero; other values are only kept when parsing
+    /// leniently.
+    pub active_targets: u64,
+
     /// The function classifications of this data file.
     pub function_classifications: FunctionClassifications,
 
@@ -113,6 +123,13 @@ pub struct GeneralInfo {
 
     /// Set in GameMaker 2+ data files.
     pub gms2_data: Option<GMS2Data>,
+
+    /// Unknown data at the end of the chunk, after all known fields.
+    ///
+    /// This is kept as raw bytes so that fields added in newer GameMaker
+    /// versions survive a round trip. Trailing zero bytes that fit into the
+    /// chunk alignment are considered chunk padding and are not included.
+    pub unknown_trailing_data: Blob<Vec<u8>>,
 }
 
 impl GMChunk for GeneralInfo {
@@ -174,21 +191,23 @@ impl GMElement for GeneralInfo {
             last_object_id,
             last_tile_id,
             game_id,
-            directplay_guid: Blob(directplay_guid),
+            directplay_guid: Blob::new(directplay_guid),
             game_name,
             ide_version,
             window_width,
             window_height,
             flags,
             license_crc32,
-            license_md5: Blob(license_md5),
+            license_md5: Blob::new(license_md5),
             creation_timestamp,
             display_name,
+            active_targets,
             function_classifications,
             steam_appid,
             debugger_port,
             room_order,
             gms2_data: None,
+            unknown_trailing_data: Blob::default(),
         };
 
         if ide_version.major >= 2 {
@@ -196,6 +215,32 @@ impl GMElement for GeneralInfo {
             general_info.gms2_data = Some(gms2);
         }
 
+        let remaining: u32 = reader.chunk.end_pos.saturating_sub(reader.cur_pos);
+        let trailing: &[u8] = reader.read_bytes_dyn(remaining)?;
+        let data_len: u32 = trailing
+            .iter()
+            .rposition(|&byte| byte != 0)
+            .map_or(0, |index| index as u32 + 1);
+
+        // Only the zero bytes that fit into the chunk alignment can be padding.
+        // Leave those for `read_chunk_padding` (and misalignment checks).
+        let alignment: u32 = reader.chunk_padding;
+        let padding_len: u32 = if reader.version >= GMVersion::Wad16Pad
+            && reader.last_chunk != Self::NAME
+            && reader.chunk.end_pos.is_multiple_of(alignment)
+        {
+            (remaining - data_len).min(alignment - 1)
+        } else {
+            0
+        };
+
+        let kept_len: u32 = remaining - padding_len;
+        if kept_len > 0 {
+            log::warn!("Keeping {kept_len} unknown trailing bytes in GEN8");
+            general_info.unknown_trailing_data = Blob::new(trailing[..kept_len as usize].to_vec());
+        }
+        reader.cur_pos -= padding_len;
+
         Ok(general_info)
     }
 
@@ -218,17 +263,27 @@ impl GMElement for GeneralInfo {
         builder.write_bytes(&*self.license_md5);
         builder.write_i64(self.creation_timestamp.timestamp());
         builder.write_gm_string(self.display_name)?;
-        builder.write_u64(0); // "Active targets"
+        builder.write_u64(self.active_targets);
         builder.write_u64(self.function_classifications.bits());
         builder.write_i32(self.steam_appid);
         if builder.version() >= GMVersion::Wad14 {
             builder.write_u32(self.debugger_port);
         }
-        builder.write_simple_list(&self.room_order)?;
+        let mut fixed_room_order: Option<Vec<GMRef<Room>>> = None;
+        if builder.gm_data.meta.fix_room_order_on_build {
+            let (room_order, fixes) = builder.gm_data.fixed_room_order();
+            if !fixes.is_empty() {
+                log::warn!("Fixed room order in GEN8 while building: {fixes}");
+                fixed_room_order = Some(room_order);
+            }
+        }
+        let room_order: &Vec<GMRef<Room>> = fixed_room_order.as_ref().unwrap_or(&self.room_order);
+        builder.write_simple_list(room_order)?;
 
         if builder.version() >= GMVersion::GMS2 {
-            self.write_gms2_data(builder)?;
+            self.write_gms2_data(builder, room_order.len())?;
         }
+        builder.write_bytes(&self.unknown_trailing_data);
         Ok(())
     }
 }
@@ -244,21 +299,251 @@ impl Default for GeneralInfo {
             last_object_id: 100_000,
             last_tile_id: 10_000_000,
             game_id: 1337,
-            directplay_guid: Blob([0u8; 16]),
+            directplay_guid: Blob::new([0u8; 16]),
             game_name: GMRef::none(),
             ide_version: IdeVersion::GMS2,
             window_width: 1337,
             window_height: 1337,
             flags: Flags::empty(),
             license_crc32: 69420,
-            license_md5: Blob([69; 16]),
+            license_md5: Blob::new([69; 16]),
             creation_timestamp: DateTime::default(),
             display_name: GMRef::none(),
+            active_targets: 0,
             function_classifications: FunctionClassifications::empty(),
             steam_appid: 0,
             debugger_port: 0,
             room_order: vec![],
             gms2_data: Some(GMS2Data::default()),
+            unknown_trailing_data: Blob::default(),
+        }
+    }
+}
+
+/// The changes made to the room order stored in `GEN8` by
+/// [`GMData::fix_room_order`].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RoomOrderFixes {
+    /// Room order entries that were removed because they referenced a
+    /// nonexistent room or a room that was already in the room order.
+    pub removed: Vec<GMRef<Room>>,
+
+    /// Rooms that were missing from the room order and have been appended
+    /// to it.
+    pub added: Vec<GMRef<Room>>,
+}
+
+impl RoomOrderFixes {
+    /// Whether the room order was already consistent.
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.removed.is_empty() && self.added.is_empty()
+    }
+}
+
+impl fmt::Display for RoomOrderFixes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let format_rooms = |rooms: &[GMRef<Room>]| {
+            let indices: Vec<String> = rooms
+                .iter()
+                .map(|room| format!("#{}", room.index))
+                .collect();
+            indices.join(", ")
+        };
+        write!(
+            f,
+            "removed {} entries [{}], added {} rooms [{}]",
+            self.removed.len(),
+            format_rooms(&self.removed),
+            self.added.len(),
+            format_rooms(&self.added),
+        )
+    }
+}
+
+impl GMData {
+    /// The name of the game, as stored in `GEN8`.
+    pub fn game_name(&self) -> Result<&str> {
+        let name: &String = self
+            .strings
+            .by_ref(self.general_info.game_name)
+            .ctx("resolving game name")?;
+        Ok(name)
+    }
+
+    /// Sets the name of the game stored in `GEN8`.
+    ///
+    /// The name string is reused if it already exists.
+    /// Note that this does not change the window title; for that,
+    /// see [`GeneralInfo::display_name`].
+    pub fn set_game_name(&mut self, name: &str) {
+        self.general_info.game_name = self.strings.make(name);
+    }
+
+    /// The game id stored in `GEN8`.
+    #[must_use]
+    pub const fn game_id(&self) -> u32 {
+        self.general_info.game_id
+    }
+
+    /// Sets the game id stored in `GEN8`.
+    ///
+    /// Returns an error if the id does not fit in the 32-bit unsigned field.
+    pub fn set_game_id(&mut self, id: i64) -> Result<()> {
+        self.general_info.game_id =
+            u32::try_from(id).map_err(|_| err!("Game id {id} does not fit in 32 bits"))?;
+        Ok(())
+    }
+
+    /// Checks whether the IDs and the room order stored in `GEN8` are
+    /// consistent with the other chunks.
+    ///
+    /// This checks that:
+    /// * The last object ID is at least as high as every room instance ID.
+    /// * The last tile ID is at least as high as every room tile ID.
+    /// * The room order only contains existing rooms, each exactly once.
+    /// * Every room is contained in the room order.
+    ///
+    /// Returns a description of every inconsistency found.
+    /// These are logged as warnings when parsing a data file.
+    /// To fix them, see [`GMData::recompute_gen8_counts`].
+    #[must_use]
+    pub fn check_gen8_counts(&self) -> Vec<String> {
+        let info: &GeneralInfo = &self.general_info;
+        let mut issues: Vec<String> = Vec::new();
+
+        if let Some(id) = self.highest_room_instance_id()
+            && id > info.last_object_id
+        {
+            issues.push(format!(
+                "Last object ID {} in GEN8 is lower than room instance ID {id}",
+                info.last_object_id,
+            ));
+        }
+
+        if let Some(id) = self.highest_room_tile_id()
+            && id > info.last_tile_id
+        {
+            issues.push(format!(
+                "Last tile ID {} in GEN8 is lower than room tile ID {id}",
+                info.last_tile_id,
+            ));
+        }
+
+        let mut seen: HashSet<GMRef<Room>> = HashSet::new();
+        for &room_ref in &info.room_order {
+            if self.rooms.by_ref(room_ref).is_err() {
+                issues.push(format!(
+                    "Room order in GEN8 contains nonexistent room #{}",
+                    room_ref.index,
+                ));
+            } else if !seen.insert(room_ref) {
+                issues.push(format!(
+                    "Room order in GEN8 contains room #{} multiple times",
+                    room_ref.index,
+                ));
+            }
+        }
+
+        let missing: usize = self
+            .rooms
+            .element_refs()
+            .filter(|(room_ref, _)| !seen.contains(room_ref))
+            .count();
+        if missing > 0 {
+            issues.push(format!(
+                "Room order in GEN8 has {} entries, but {missing} of {} rooms are missing from it",
+                info.room_order.len(),
+                self.rooms.elements().count(),
+            ));
+        }
+
+        issues
+    }
+
+    /// Updates the IDs and the room order stored in `GEN8` to be consistent
+    /// with the other chunks.
+    ///
+    /// This should be called after adding or removing rooms, room instances
+    /// or room tiles, so that the rebuilt data file passes runner validation:
+    /// * The last object ID and last tile ID are raised (never lowered) to the
+    ///   highest room instance ID and room tile ID respectively.
+    /// * Nonexistent and duplicate rooms are removed from the room order.
+    /// * Rooms missing from the room order are appended to it, in chunk order.
+    ///
+    /// For more information, see [`GMData::check_gen8_counts`].
+    pub fn recompute_gen8_counts(&mut self) {
+        if let Some(id) = self.highest_room_instance_id() {
+            self.general_info.last_object_id = self.general_info.last_object_id.max(id);
+        }
+        if let Some(id) = self.highest_room_tile_id() {
+            self.general_info.last_tile_id = self.general_info.last_tile_id.max(id);
+        }
+
+        self.fix_room_order();
+    }
+
+    /// Makes the room order stored in `GEN8` consistent with the rooms in
+    /// `ROOM`, and reports what was changed.
+    ///
+    /// Nonexistent and duplicate rooms are removed from the room order and
+    /// rooms missing from it are appended, in chunk order. The order of the
+    /// remaining entries is preserved.
+    ///
+    /// Rooms absent from the room order are unreachable in-engine, so this
+    /// should be called after adding or removing rooms. This is also done by
+    /// [`Self::recompute_gen8_counts`]. To apply this fix automatically
+    /// (without changing this [`GMData`]) every time the data is built, see
+    /// [`Metadata::fix_room_order_on_build`].
+    ///
+    /// [`Metadata::fix_room_order_on_build`]: crate::wad::data::Metadata::fix_room_order_on_build
+    #[doc(alias = "gen8_room_order_consistency")]
+    pub fn fix_room_order(&mut self) -> RoomOrderFixes {
+        let (room_order, fixes) = self.fixed_room_order();
+        self.general_info.room_order = room_order;
+        fixes
+    }
+
+    /// Computes the consistent room order for [`Self::fix_room_order`].
+    fn fixed_room_order(&self) -> (Vec<GMRef<Room>>, RoomOrderFixes) {
+        let mut fixes = RoomOrderFixes::default();
+        let mut seen: HashSet<GMRef<Room>> = HashSet::new();
+        let mut room_order: Vec<GMRef<Room>> = Vec::new();
+        for &room_ref in &self.general_info.room_order {
+            if self.rooms.by_ref(room_ref).is_ok() && seen.insert(room_ref) {
+                room_order.push(room_ref);
+            } else {
+                fixes.removed.push(room_ref);
+            }
+        }
+        for (room_ref, _) in self.rooms.element_refs() {
+            if !seen.contains(&room_ref) {
+                room_order.push(room_ref);
+                fixes.added.push(room_ref);
+            }
+        }
+        (room_order, fixes)
+    }
+
+    fn highest_room_instance_id(&self) -> Option<u32> {
+        self.rooms
+            .elements()
+            .flat_map(|room| &room.game_objects)
+            .map(|game_object| game_object.instance_id.0 as u32)
+            .max()
+    }
+
+    fn highest_room_tile_id(&self) -> Option<u32> {
+        let mut highest: Option<u32> = None;
+        for room in self.rooms.elements() {
+            let layer_tiles = room.layers.iter().filter_map(|layer| match &layer.data {
+                layer::Data::Assets(assets) => Some(&assets.legacy_tiles),
+                _ => None,
+            });
+            for tile in room.tiles.iter().chain(layer_tiles.flatten()) {
+                highest = highest.max(Some(tile.instance_id.0 as u32));
+            }
         }
+        highest
     }
 }
@@ -29,10 +29,10 @@ pub struct GMS2Data {
 impl Default for GMS2Data {
     fn default() -> Self {
         Self {
-            random_uid: Blob([69; 4]),
+            random_uid: Blob::new([69; 4]),
             fps: 60.0,
             allow_statistics: false,
-            game_guid: Blob([69; 16]),
+            game_guid: Blob::new([69; 16]),
             info_timestamp_offset: true,
         }
     }
@@ -52,7 +52,7 @@ impl GeneralInfo {
             bail!("Unexpected random UID #1: expected {first_expected}; got {first_actual}");
         }
 
-        let info_location: i32 = self.get_info_location(timestamp);
+        let info_location: i32 = self.get_info_location(timestamp, self.room_order.len());
         let mut random_uid = [0_i64; 4];
 
         for i in 0i32..4 {
@@ -93,15 +93,21 @@ impl GeneralInfo {
             .ctx("reading Game GUID")?;
 
         Ok(GMS2Data {
-            random_uid: Blob(random_uid),
+            random_uid: Blob::new(random_uid),
             fps,
             allow_statistics,
-            game_guid: Blob(game_guid),
+            game_guid: Blob::new(game_guid),
             info_timestamp_offset,
         })
     }
 
-    pub(super) fn write_gms2_data(&self, builder: &mut DataBuilder) -> Result<()> {
+    /// The room count has to be specified separately since the room order
+    /// may be fixed while building (see `Metadata::fix_room_order_on_build`).
+    pub(super) fn write_gms2_data(
+        &self,
+        builder: &mut DataBuilder,
+        room_count: usize,
+    ) -> Result<()> {
         let gms2_info: &GMS2Data = self
             .gms2_data
             .as_ref()
@@ -112,7 +118,7 @@ impl GeneralInfo {
         let mut rng = DotnetRng::new(seed);
         let first_random: i64 = (i64::from(rng.next()) << 32) | i64::from(rng.next());
         let info_number = self.get_info_number(first_random, gms2_info.info_timestamp_offset);
-        let info_location: i32 = self.get_info_location(timestamp);
+        let info_location: i32 = self.get_info_location(timestamp, room_count);
         builder.write_i64(first_random);
         for i in 0..4 {
             if i == info_location {
@@ -132,10 +138,10 @@ impl GeneralInfo {
     }
 
     // random bullshit go!
-    const fn get_info_location(&self, timestamp: i64) -> i32 {
+    const fn get_info_location(&self, timestamp: i64, room_count: usize) -> i32 {
         let t = (timestamp as u16 / 7) as i32;
         let g = self.game_id.wrapping_sub(self.window_width) as i32;
-        let r = self.room_order.len() as i32;
+        let r = room_count as i32;
         (t + g + r).abs() % 4
     }
 
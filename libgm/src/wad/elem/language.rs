@@ -3,15 +3,33 @@
 use crate::prelude::*;
 use crate::util::init::vec_with_capacity;
 use crate::wad::build::builder::DataBuilder;
+use crate::wad::chunk::ChunkName;
 use crate::wad::chunk::gm_list_chunk;
 use crate::wad::elem::GMElement;
 use crate::wad::elem::element_stub;
 use crate::wad::parse::reader::DataReader;
 
+/// The built-in localization table of the game (`LANG` chunk).
+///
+/// The table has one row per language ([`LanguageData`]) and one column per
+/// entry key ([`LanguageInfo::entry_ids`]). Every language stores the
+/// translated text for every key, in the same order as the keys.
+/// Games using GameMaker's built-in localization look up text here by key
+/// instead of embedding it in code.
+///
+/// Use [`GMData::language_text`] and [`GMData::set_language_text`] to read and
+/// edit translations, and [`GMData::add_language`] to add a language.
 #[derive(Debug, Clone, Default, PartialEq, Eq)]
 pub struct LanguageInfo {
+    /// DOCME: Currently unknown value; usually 1.
     pub unknown1: u32,
+
+    /// The languages of this table.
     pub elems: Vec<LanguageData>,
+
+    /// The keys of all entries.
+    ///
+    /// The entries of every language have to have the same length as this.
     pub entry_ids: Vec<GMRef<String>>,
 }
 
@@ -43,6 +61,15 @@ impl GMElement for LanguageInfo {
     }
 
     fn serialize(&self, builder: &mut DataBuilder) -> Result<()> {
+        for language in &self.elems {
+            if language.entries.len() != self.entry_ids.len() {
+                bail!(
+                    "Language has {} entries but there are {} entry keys",
+                    language.entries.len(),
+                    self.entry_ids.len(),
+                );
+            }
+        }
         builder.write_u32(self.unknown1);
         builder.write_usize(self.elems.len())?;
         builder.write_usize(self.entry_ids.len())?;
@@ -60,10 +87,129 @@ impl GMElement for LanguageInfo {
     }
 }
 
+/// A language in the [`LanguageInfo`] table.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct LanguageData {
+    /// The name of the language, for example `English`.
     pub name: GMRef<String>,
+
+    /// The region of the language, for example `US`.
     pub region: GMRef<String>,
+
+    /// The translated text of every entry, in the order of
+    /// [`LanguageInfo::entry_ids`].
     pub entries: Vec<GMRef<String>>,
 }
 element_stub!(LanguageData);
+
+impl GMData {
+    /// The names and regions of all languages in the `LANG` chunk, in chunk order.
+    pub fn languages(&self) -> Result<Vec<(&str, &str)>> {
+        self.language_info
+            .elems
+            .iter()
+            .map(|language| {
+                let name: &str = self.strings.by_ref(language.name)?;
+                let region: &str = self.strings.by_ref(language.region)?;
+                Ok((name, region))
+            })
+            .collect()
+    }
+
+    /// Gets the translated text of the entry with the specified key.
+    ///
+    /// Languages are identified by their name and region,
+    /// for example `English` and `US`.
+    /// Returns [`None`] if there is no entry with this key.
+    /// Returns an error if the language does not exist.
+    pub fn language_text(&self, language: &str, region: &str, key: &str) -> Result<Option<&str>> {
+        let language: &LanguageData =
+            &self.language_info.elems[self.language_index(language, region)?];
+        let Some(index) = self.language_entry_index(key) else {
+            return Ok(None);
+        };
+        let text: GMRef<String> = *language
+            .entries
+            .get(index)
+            .ok_or_else(|| err!("Language has no text for entry #{index}"))?;
+        Ok(Some(self.strings.by_ref(text)?))
+    }
+
+    /// Sets the translated text of the entry with the specified key.
+    ///
+    /// If there is no entry with this key yet, it is added, with the same
+    /// text in all other languages (to be translated later).
+    /// Returns an error if the language does not exist.
+    pub fn set_language_text(
+        &mut self,
+        language: &str,
+        region: &str,
+        key: &str,
+        text: &str,
+    ) -> Result<()> {
+        let language: usize = self.language_index(language, region)?;
+        let text: GMRef<String> = self.strings.make(text);
+        let index: usize = if let Some(index) = self.language_entry_index(key) {
+            index
+        } else {
+            let key: GMRef<String> = self.strings.make(key);
+            self.language_info.entry_ids.push(key);
+            for language in &mut self.language_info.elems {
+                language.entries.push(text);
+            }
+            self.language_info.entry_ids.len() - 1
+        };
+
+        let entries = &mut self.language_info.elems[language].entries;
+        let entry_count = entries.len();
+        let entry: &mut GMRef<String> = entries.get_mut(index).ok_or_else(|| {
+            err!("Language has only {entry_count} entries; cannot set entry #{index}")
+        })?;
+        *entry = text;
+        Ok(())
+    }
+
+    /// Adds a language to the `LANG` chunk and returns its index.
+    ///
+    /// The entries of the new language are copied from the first language
+    /// (or left empty if there are no entries yet), so they can be translated
+    /// one by one.
+    /// Returns an error if the language already exists in this region or if
+    /// the data file has no `LANG` chunk.
+    pub fn add_language(&mut self, name: &str, region: &str) -> Result<usize> {
+        if !self.meta.chunks.has(ChunkName::LANG) {
+            bail!("Cannot add language {name:?}: data file has no LANG chunk");
+        }
+        if self.language_index(name, region).is_ok() {
+            bail!("Language {name:?} ({region}) already exists");
+        }
+        let entries: Vec<GMRef<String>> = match self.language_info.elems.first() {
+            Some(language) => language.entries.clone(),
+            None if self.language_info.entry_ids.is_empty() => Vec::new(),
+            None => vec![self.strings.make(""); self.language_info.entry_ids.len()],
+        };
+        let name: GMRef<String> = self.strings.make(name);
+        let region: GMRef<String> = self.strings.make(region);
+        self.language_info
+            .push(LanguageData { name, region, entries });
+        Ok(self.language_info.elems.len() - 1)
+    }
+
+    fn language_index(&self, name: &str, region: &str) -> Result<usize> {
+        let matches = |string: GMRef<String>, expected: &str| {
+            self.strings.by_ref(string).is_ok_and(|s| s == expected)
+        };
+        self.language_info
+            .elems
+            .iter()
+            .position(|language| matches(language.name, name) && matches(language.region, region))
+            .ok_or_else(|| err!("Language {name:?} ({region}) does not exist"))
+    }
+
+    fn language_entry_index(&self, key: &str) -> Option<usize> {
+        self.language_info
+            .entry_ids
+            .iter()
+            .position(|&id| self.strings.by_ref(id).is_ok_and(|id| id == key))
+    }
+}
@@ -11,6 +11,7 @@ use crate::prelude::*;
 use crate::wad::build::builder::DataBuilder;
 use crate::wad::chunk::ChunkName;
 use crate::wad::elem::GMElement;
+use crate::wad::elem::general_info::Flags;
 use crate::wad::elem::texture_page_item::TexturePageItem;
 use crate::wad::parse::reader::DataReader;
 use crate::wad::reference::GMRef;
@@ -57,3 +58,96 @@ impl GMElement for Options {
         }
     }
 }
+
+/// The window options of a game, as named booleans.
+///
+/// These options are stored in the flags of both `GEN8`
+/// ([`GeneralInfo::flags`]) and `OPTN` ([`Options::flags`]).
+/// See [`GMData::window_options`] and [`GMData::set_window_options`].
+///
+/// [`GeneralInfo::flags`]: crate::wad::elem::general_info::GeneralInfo::flags
+#[doc(alias = "GMOptions")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct WindowOptions {
+    /// Whether the game starts in fullscreen.
+    ///
+    /// This is [`Flags::FULLSCREEN`] and [`OptionFlags::FULLSCREEN`].
+    pub fullscreen: bool,
+
+    /// Whether colors are interpolated between pixels when scaling.
+    ///
+    /// This is [`Flags::INTERPOLATE`] and
+    /// [`OptionFlags::INTERPOLATE_PIXELS`].
+    pub interpolate_pixels: bool,
+
+    /// Whether the aspect ratio is kept when scaling the game to the window
+    /// (instead of stretching it).
+    ///
+    /// This is [`Flags::SCALE`]. It has no equivalent in `OPTN`.
+    pub keep_aspect_ratio: bool,
+
+    /// Whether the mouse cursor is shown.
+    ///
+    /// This is [`Flags::SHOW_CURSOR`] and [`OptionFlags::SHOW_CURSOR`].
+    pub show_cursor: bool,
+
+    /// Whether the window is resizable.
+    ///
+    /// This is [`Flags::SIZEABLE`] and [`OptionFlags::SIZEABLE`].
+    pub sizeable: bool,
+
+    /// Whether the window is borderless.
+    ///
+    /// This is [`Flags::BORDERLESS_WINDOW`] and [`OptionFlags::NO_BORDER`].
+    pub borderless: bool,
+}
+
+impl GMData {
+    /// The window options of the game.
+    ///
+    /// These are read from the `GEN8` flags, which are the ones used by
+    /// GameMaker Studio runners.
+    #[must_use]
+    pub const fn window_options(&self) -> WindowOptions {
+        let flags: Flags = self.general_info.flags;
+        WindowOptions {
+            fullscreen: flags.contains(Flags::FULLSCREEN),
+            interpolate_pixels: flags.contains(Flags::INTERPOLATE),
+            keep_aspect_ratio: flags.contains(Flags::SCALE),
+            show_cursor: flags.contains(Flags::SHOW_CURSOR),
+            sizeable: flags.contains(Flags::SIZEABLE),
+            borderless: flags.contains(Flags::BORDERLESS_WINDOW),
+        }
+    }
+
+    /// Sets the window options of the game.
+    ///
+    /// This updates the corresponding flags in both `GEN8` and `OPTN`,
+    /// keeping all other flags as they are.
+    #[doc(alias = "set_option_flags")]
+    pub fn set_window_options(&mut self, window_options: WindowOptions) {
+        let WindowOptions {
+            fullscreen,
+            interpolate_pixels,
+            keep_aspect_ratio,
+            show_cursor,
+            sizeable,
+            borderless,
+        } = window_options;
+
+        let flags: &mut Flags = &mut self.general_info.flags;
+        flags.set(Flags::FULLSCREEN, fullscreen);
+        flags.set(Flags::INTERPOLATE, interpolate_pixels);
+        flags.set(Flags::SCALE, keep_aspect_ratio);
+        flags.set(Flags::SHOW_CURSOR, show_cursor);
+        flags.set(Flags::SIZEABLE, sizeable);
+        flags.set(Flags::BORDERLESS_WINDOW, borderless);
+
+        let flags: &mut OptionFlags = &mut self.options.flags;
+        flags.set(OptionFlags::FULLSCREEN, fullscreen);
+        flags.set(OptionFlags::INTERPOLATE_PIXELS, interpolate_pixels);
+        flags.set(OptionFlags::SHOW_CURSOR, show_cursor);
+        flags.set(OptionFlags::SIZEABLE, sizeable);
+        flags.set(OptionFlags::NO_BORDER, borderless);
+    }
+}
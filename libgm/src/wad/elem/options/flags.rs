@@ -28,33 +28,93 @@ bitflags::bitflags! {
         /// If the resolution can be changed.
         const CHANGE_RESOLUTION = 0x80;
 
+        /// If the window caption should not have any buttons.
         const NO_BUTTONS = 0x100;
+
+        /// If <kbd>F4</kbd> should switch between windowed and fullscreen mode.
         const SCREEN_KEY = 0x200;
+
+        /// If <kbd>F1</kbd> should show the game information.
         const HELP_KEY = 0x400;
+
+        /// If <kbd>Esc</kbd> should end the game.
         const QUIT_KEY = 0x800;
+
+        /// If <kbd>F5</kbd> and <kbd>F6</kbd> should save and load the game.
         const SAVE_KEY = 0x1000;
+
+        /// If <kbd>F9</kbd> should take a screenshot.
         const SCREENSHOT_KEY = 0x2000;
+
+        /// If the close button of the window should act like the quit key.
         const CLOSE_SEC = 0x4000;
+
+        /// If the game should pause while its window is not focused.
         const FREEZE = 0x8000;
+
+        /// If a progress bar should be shown while loading.
         const SHOW_PROGRESS = 0x10000;
+
+        /// If the loading image should be transparent.
         const LOAD_TRANSPARENT = 0x20000;
+
+        /// If the loading progress bar image should be scaled.
         const SCALE_PROGRESS = 0x40000;
+
+        /// If error messages should be displayed.
         const DISPLAY_ERRORS = 0x80000;
+
+        /// If error messages should be written to a log file.
         const WRITE_ERRORS = 0x10_0000;
+
+        /// If the game should abort on all error messages.
         const ABORT_ERRORS = 0x20_0000;
+
+        /// If reading uninitialized variables should be an error.
         const VARIABLE_ERRORS = 0x40_0000;
+
+        /// If the creation code of room instances should run after their
+        /// create event (rather than before).
+        /// The exact behavior of this legacy option is not fully known.
         const CREATION_EVENT_ORDER = 0x80_0000;
+
+        /// If the front touch pad should be used (PlayStation Vita).
         const USE_FRONT_TOUCH = 0x100_0000;
+
+        /// If the rear touch pad should be used (PlayStation Vita).
         const USE_REAR_TOUCH = 0x200_0000;
+
+        /// If the faster collision system should be used.
         const USE_FAST_COLLISION = 0x400_0000;
+
+        /// If the fast collision system should behave like the old one
+        /// where they differ.
         const FAST_COLLISION_COMPATIBILITY = 0x800_0000;
+
+        /// If the file system sandbox should be disabled, allowing access to
+        /// files outside of the game's directories.
         const DISABLE_SANDBOX = 0x1000_0000;
+
+        /// If arrays should be copied on write (the deprecated array
+        /// copy-on-write behavior).
         const ENABLE_COPY_ON_WRITE = 0x2000_0000;
+
+        /// If the legacy JSON parsing behavior should be used.
         const LEGACY_JSON_PARSING = 0x4000_0000;
+
+        /// If the legacy number to string conversion behavior should be used.
         const LEGACY_NUMBER_CONVERSION = 0x8000_0000;
+
+        /// If other legacy runner behaviors should be kept.
         const LEGACY_OTHER_BEHAVIOR = 0x1_0000_0000;
+
+        /// If the legacy audio error behavior should be used.
         const AUDIO_ERROR_BEHAVIOR = 0x2_0000_0000;
+
+        /// If the deprecated `instance_change` function should be allowed.
         const ALLOW_INSTANCE_CHANGE = 0x4_0000_0000;
+
+        /// If the legacy primitive drawing behavior should be used.
         const LEGACY_PRIMITIVE_DRAWING = 0x8_0000_0000;
     }
 }
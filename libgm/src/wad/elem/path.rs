@@ -60,6 +60,132 @@ impl GMElement for Path {
     }
 }
 
+impl Path {
+    /// The highest precision GameMaker allows for smooth paths.
+    pub const MAX_PRECISION: u32 = 8;
+
+    /// The point at the specified index.
+    pub fn point(&self, index: usize) -> Result<&Point> {
+        self.points.get(index).ok_or_else(|| {
+            err!(
+                "Point index {index} out of range for path with {} points",
+                self.points.len(),
+            )
+        })
+    }
+
+    /// The point at the specified index, mutably.
+    pub fn point_mut(&mut self, index: usize) -> Result<&mut Point> {
+        let count: usize = self.points.len();
+        self.points
+            .get_mut(index)
+            .ok_or_else(|| err!("Point index {index} out of range for path with {count} points"))
+    }
+
+    /// Inserts a point at the specified index, shifting all points after it.
+    ///
+    /// Returns an error if the index is greater than the number of points.
+    pub fn insert_point(&mut self, index: usize, point: Point) -> Result<()> {
+        if index > self.points.len() {
+            bail!(
+                "Cannot insert point at index {index} into path with {} points",
+                self.points.len(),
+            );
+        }
+        self.points.insert(index, point);
+        Ok(())
+    }
+
+    /// Removes and returns the point at the specified index.
+    pub fn remove_point(&mut self, index: usize) -> Result<Point> {
+        self.point(index)?;
+        Ok(self.points.remove(index))
+    }
+
+    /// The total length of this path in pixels.
+    ///
+    /// Closed paths include the segment from the last point back to the first
+    /// one. Smooth paths are approximated like GameMaker does: Every point
+    /// becomes the control point of a quadratic curve between the middles of
+    /// its adjacent segments, which is split into `2^precision` line segments.
+    #[must_use]
+    pub fn length(&self) -> f64 {
+        let points: Vec<(f64, f64)> = self
+            .points
+            .iter()
+            .map(|point| (f64::from(point.x), f64::from(point.y)))
+            .collect();
+        let count: usize = points.len();
+        if count < 2 {
+            return 0.0;
+        }
+
+        if !self.is_smooth {
+            return polyline_length(&points, self.is_closed);
+        }
+
+        let steps: u32 = 1 << self.precision.min(Self::MAX_PRECISION);
+        let mut samples: Vec<(f64, f64)> = Vec::new();
+        if self.is_closed {
+            for i in 0..count {
+                let previous = points[(i + count - 1) % count];
+                let next = points[(i + 1) % count];
+                sample_curve(&mut samples, previous, points[i], next, steps);
+            }
+        } else {
+            samples.push(points[0]);
+            for window in points.windows(3) {
+                sample_curve(&mut samples, window[0], window[1], window[2], steps);
+            }
+            samples.push(points[count - 1]);
+        }
+        polyline_length(&samples, self.is_closed)
+    }
+}
+
+fn distance(a: (f64, f64), b: (f64, f64)) -> f64 {
+    (b.0 - a.0).hypot(b.1 - a.1)
+}
+
+const fn midpoint(a: (f64, f64), b: (f64, f64)) -> (f64, f64) {
+    (f64::midpoint(a.0, b.0), f64::midpoint(a.1, b.1))
+}
+
+fn polyline_length(points: &[(f64, f64)], is_closed: bool) -> f64 {
+    let mut length: f64 = points
+        .windows(2)
+        .map(|pair| distance(pair[0], pair[1]))
+        .sum();
+    if is_closed && let (Some(&first), Some(&last)) = (points.first(), points.last()) {
+        length += distance(last, first);
+    }
+    length
+}
+
+/// Samples the quadratic curve around `point`, from the middle of the segment
+/// before it to the middle of the segment after it.
+fn sample_curve(
+    samples: &mut Vec<(f64, f64)>,
+    previous: (f64, f64),
+    point: (f64, f64),
+    next: (f64, f64),
+    steps: u32,
+) {
+    let start = midpoint(previous, point);
+    let end = midpoint(point, next);
+    for step in 0..=steps {
+        let t = f64::from(step) / f64::from(steps);
+        let u = 1.0 - t;
+        let weights = (u * u, 2.0 * u * t, t * t);
+        let interpolate =
+            |a: f64, b: f64, c: f64| weights.2.mul_add(c, weights.1.mul_add(b, weights.0 * a));
+        samples.push((
+            interpolate(start.0, point.0, end.0),
+            interpolate(start.1, point.1, end.1),
+        ));
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct Point {
     pub x: f32,
@@ -67,6 +193,17 @@ pub struct Point {
     pub speed: f32,
 }
 
+impl Point {
+    /// Creates a new path point.
+    ///
+    /// The speed is a percentage of the instance's path speed; the default
+    /// in GameMaker is 100.
+    #[must_use]
+    pub const fn new(x: f32, y: f32, speed: f32) -> Self {
+        Self { x, y, speed }
+    }
+}
+
 impl GMElement for Point {
     fn deserialize(reader: &mut DataReader) -> Result<Self> {
         let x = reader.read_f32()?;
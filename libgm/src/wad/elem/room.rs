@@ -3,22 +3,31 @@ mod background;
 mod flags;
 mod game_object;
 pub mod layer;
+#[cfg(feature = "room-render")]
+mod render;
 pub mod tile;
 mod view;
 
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::fmt;
+
 pub use self::background::RoomBackground;
 pub use self::flags::RoomFlags;
 pub use self::game_object::RoomGameObject;
 pub use self::layer::RoomLayer;
 pub use self::tile::RoomTile;
 pub use self::view::RoomView;
+pub use self::view::ViewFollow;
 use crate::gml::Code;
 use crate::prelude::*;
 use crate::wad::GMVersion;
 use crate::wad::build::builder::DataBuilder;
 use crate::wad::chunk::gm_named_list_chunk;
 use crate::wad::elem::GMElement;
+use crate::wad::elem::game_object::GameObject;
 use crate::wad::elem::sequence::Sequence;
+use crate::wad::elem::string::Strings;
 use crate::wad::parse::reader::DataReader;
 use crate::wad::reference::GMRef;
 
@@ -41,6 +50,26 @@ impl GMElement for Rooms {
     }
 }
 
+impl Rooms {
+    /// Iterates over all rooms, yielding each room's reference, name and size
+    /// (width, height) in pixels.
+    ///
+    /// Null rooms (removed by the asset compiler) are skipped.
+    /// Invalid names are displayed as `<invalid string ref>`.
+    pub fn iter_named<'a>(
+        &'a self,
+        strings: &'a Strings,
+    ) -> impl Iterator<Item = (GMRef<Room>, &'a str, (u32, u32))> {
+        self.element_refs().map(|(room_ref, room)| {
+            (
+                room_ref,
+                room.name.display(strings),
+                (room.width, room.height),
+            )
+        })
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 #[repr(C)] // Need explicit layout so memory addresses for gm pointers don't collide
 pub struct Room {
@@ -243,23 +272,6 @@ impl GMElement for Room {
 
 impl Default for Room {
     fn default() -> Self {
-        let view = RoomView {
-            enabled: false,
-            view_x: 0,
-            view_y: 0,
-            view_width: 640,
-            view_height: 480,
-            port_x: 0,
-            port_y: 0,
-            port_width: 640,
-            port_height: 480,
-            border_x: 32,
-            border_y: 32,
-            speed_x: -1,
-            speed_y: -1,
-            object: GMRef::none(),
-        };
-
         Self {
             name: GMRef::none(), // "room{idx}"
             caption: GMRef::none(),
@@ -275,7 +287,7 @@ impl Default for Room {
                 | RoomFlags::GM2
                 | RoomFlags::GM2_3,
             backgrounds: Vec::new(),
-            views: vec![view; 8],
+            views: vec![RoomView::default(); Self::MAX_VIEWS],
             game_objects: Vec::new(),
             tiles: Vec::new(),
             instance_creation_order: Vec::new(),
@@ -293,6 +305,542 @@ impl Default for Room {
     }
 }
 
+impl Room {
+    /// The number of views a room can have.
+    pub const MAX_VIEWS: usize = 8;
+
+    /// Summarizes this room's name, size, layer count and instance count
+    /// for display purposes.
+    #[must_use]
+    pub fn summary<'a>(&self, strings: &'a Strings) -> RoomSummary<'a> {
+        RoomSummary {
+            name: self.name.display(strings),
+            width: self.width,
+            height: self.height,
+            layer_count: self.layers.len(),
+            instance_count: self.game_objects.len(),
+        }
+    }
+
+    /// Finds the layer with the specified name in this room.
+    ///
+    /// To access the layer's data, use one of the typed accessors
+    /// like [`layer::Data::as_instances`].
+    pub fn layer_by_name(&self, name: &str, strings: &Strings) -> Result<&RoomLayer> {
+        for layer in &self.layers {
+            let layer_name: &String = strings.by_ref(layer.name)?;
+            if layer_name == name {
+                return Ok(layer);
+            }
+        }
+        bail!("Could not find room layer with name {name:?}");
+    }
+
+    /// Finds the layer with the specified name in this room, mutably.
+    ///
+    /// For more information, see [`Self::layer_by_name`].
+    pub fn layer_by_name_mut(&mut self, name: &str, strings: &Strings) -> Result<&mut RoomLayer> {
+        for layer in &mut self.layers {
+            let layer_name: &String = strings.by_ref(layer.name)?;
+            if layer_name == name {
+                return Ok(layer);
+            }
+        }
+        bail!("Could not find room layer with name {name:?}");
+    }
+
+    /// Places a new instance of the specified game object in this room.
+    ///
+    /// The instance is appended to the room's game objects and the instance
+    /// creation order. For GMS2+ rooms (rooms with layers), it is also added
+    /// to the first instances layer.
+    ///
+    /// Instance IDs have to be unique across all rooms, so the specified ID
+    /// should come from [`GMData::next_free_instance_id`]. Consider using
+    /// [`GMData::add_object_to_room`] instead, which allocates the ID for you.
+    pub fn add_instance(
+        &mut self,
+        game_object: GMRef<GameObject>,
+        x: i32,
+        y: i32,
+        instance_id: InstanceID,
+    ) -> Result<()> {
+        if !self.layers.is_empty() {
+            let layer: &mut layer::Instances = self
+                .layers
+                .iter_mut()
+                .find_map(|layer| layer.data.as_instances_mut().ok())
+                .ok_or("Room does not have an instances layer")?;
+            layer.instances.push(instance_id.0 as u32);
+        }
+
+        self.game_objects.push(RoomGameObject {
+            x,
+            y,
+            object_definition: game_object,
+            instance_id,
+            creation_code: GMRef::none(),
+            scale_x: 1.0,
+            scale_y: 1.0,
+            image_speed: Some(1.0),
+            image_index: Some(0),
+            color: 0xFFFF_FFFF,
+            rotation: 0.0,
+            pre_create_code: GMRef::none(),
+        });
+        self.instance_creation_order.push(instance_id);
+        Ok(())
+    }
+
+    /// Assigns new instance IDs to all room game objects whose ID is already
+    /// used by a previous game object in this room.
+    ///
+    /// The first game object with a given ID keeps it; every following one gets
+    /// a new ID from the specified allocator. Instance layers and the instance
+    /// creation order are updated accordingly: The n-th occurrence of a
+    /// duplicate ID is mapped to the n-th game object that had this ID.
+    ///
+    /// Duplicate instance IDs crash the runner, which can happen after cloning
+    /// or merging rooms. To get a starting point for the allocator, use
+    /// [`GMData::next_free_instance_id`].
+    ///
+    /// Returns the number of reassigned instance IDs.
+    pub fn reassign_duplicate_instance_ids(
+        &mut self,
+        mut allocator: impl FnMut() -> InstanceID,
+    ) -> usize {
+        // Maps the old instance ID to the IDs of all game objects that had it.
+        let mut assigned: HashMap<InstanceID, Vec<InstanceID>> = HashMap::new();
+        let mut reassigned_count: usize = 0;
+
+        for game_object in &mut self.game_objects {
+            let old_id: InstanceID = game_object.instance_id;
+            let ids: &mut Vec<InstanceID> = assigned.entry(old_id).or_default();
+            if !ids.is_empty() {
+                game_object.instance_id = allocator();
+                reassigned_count += 1;
+            }
+            ids.push(game_object.instance_id);
+        }
+
+        if reassigned_count == 0 {
+            return 0;
+        }
+
+        let remap = |occurrences: &mut HashMap<InstanceID, usize>, id: InstanceID| {
+            let Some(ids) = assigned.get(&id) else {
+                return id;
+            };
+            let occurrence: &mut usize = occurrences.entry(id).or_default();
+            let new_id: InstanceID = ids.get(*occurrence).copied().unwrap_or(id);
+            *occurrence += 1;
+            new_id
+        };
+
+        let mut occurrences: HashMap<InstanceID, usize> = HashMap::new();
+        for id in &mut self.instance_creation_order {
+            *id = remap(&mut occurrences, *id);
+        }
+
+        let mut occurrences: HashMap<InstanceID, usize> = HashMap::new();
+        for layer in &mut self.layers {
+            let layer::Data::Instances(instances) = &mut layer.data else {
+                continue;
+            };
+            for id in &mut instances.instances {
+                *id = remap(&mut occurrences, InstanceID(*id as i32)).0 as u32;
+            }
+        }
+
+        reassigned_count
+    }
+
+    /// Checks that the instance creation order of this room contains every
+    /// room game object exactly once, and nothing else.
+    ///
+    /// Returns a description of every discrepancy found.
+    /// To fix them, see [`Self::fix_instance_creation_order`].
+    #[must_use]
+    pub fn check_instance_creation_order(&self) -> Vec<String> {
+        let mut issues: Vec<String> = Vec::new();
+        let instances: HashSet<InstanceID> = self
+            .game_objects
+            .iter()
+            .map(|obj| obj.instance_id)
+            .collect();
+
+        let mut seen: HashSet<InstanceID> = HashSet::new();
+        for &id in &self.instance_creation_order {
+            if !instances.contains(&id) {
+                issues.push(format!(
+                    "Instance creation order contains nonexistent instance ID {}",
+                    id.0,
+                ));
+            } else if !seen.insert(id) {
+                issues.push(format!(
+                    "Instance creation order contains instance ID {} multiple times",
+                    id.0,
+                ));
+            }
+        }
+
+        for game_object in &self.game_objects {
+            let id: InstanceID = game_object.instance_id;
+            if seen.insert(id) {
+                issues.push(format!(
+                    "Instance ID {} is missing from the instance creation order",
+                    id.0,
+                ));
+            }
+        }
+        issues
+    }
+
+    /// Regenerates the instance creation order of this room from its
+    /// game objects.
+    ///
+    /// Nonexistent and duplicate instance IDs are removed and missing
+    /// instances are appended in game object order. The order of the remaining
+    /// entries is preserved.
+    ///
+    /// Returns whether the instance creation order was changed.
+    pub fn fix_instance_creation_order(&mut self) -> bool {
+        let instances: HashSet<InstanceID> = self
+            .game_objects
+            .iter()
+            .map(|obj| obj.instance_id)
+            .collect();
+        let mut seen: HashSet<InstanceID> = HashSet::new();
+        let mut order: Vec<InstanceID> = self
+            .instance_creation_order
+            .iter()
+            .copied()
+            .filter(|id| instances.contains(id) && seen.insert(*id))
+            .collect();
+        for game_object in &self.game_objects {
+            if seen.insert(game_object.instance_id) {
+                order.push(game_object.instance_id);
+            }
+        }
+
+        let changed: bool = order != self.instance_creation_order;
+        self.instance_creation_order = order;
+        changed
+    }
+
+    /// Configures the view (camera) with the specified index.
+    ///
+    /// The rectangles are specified as `(x, y, width, height)`: `view_rect`
+    /// is the area of the room the camera shows and `port_rect` is the area
+    /// of the window it is drawn to. If `follow` is [`None`], the view does
+    /// not follow any game object.
+    ///
+    /// If the room has fewer views than needed, it is padded with disabled
+    /// views. Enabling a view also sets the room's
+    /// [`RoomFlags::ENABLE_VIEWS`] flag.
+    pub fn set_view(
+        &mut self,
+        index: usize,
+        enabled: bool,
+        view_rect: (i32, i32, i32, i32),
+        port_rect: (i32, i32, i32, i32),
+        follow: Option<ViewFollow>,
+    ) -> Result<()> {
+        if index >= Self::MAX_VIEWS {
+            bail!(
+                "View index {index} is out of bounds; rooms can only have {} views",
+                Self::MAX_VIEWS,
+            );
+        }
+        if self.views.len() <= index {
+            self.views.resize(index + 1, RoomView::default());
+        }
+
+        let (view_x, view_y, view_width, view_height) = view_rect;
+        let (port_x, port_y, port_width, port_height) = port_rect;
+        let follow = follow.unwrap_or(ViewFollow::new(GMRef::none()));
+        self.views[index] = RoomView {
+            enabled,
+            view_x,
+            view_y,
+            view_width,
+            view_height,
+            port_x,
+            port_y,
+            port_width,
+            port_height,
+            border_x: follow.border.0,
+            border_y: follow.border.1,
+            speed_x: follow.speed.0,
+            speed_y: follow.speed.1,
+            object: follow.object,
+        };
+
+        if enabled {
+            self.flags |= RoomFlags::ENABLE_VIEWS;
+        }
+        Ok(())
+    }
+}
+
+/// A short human readable description of a room.
+///
+/// The [`fmt::Display`] output looks like
+/// `room_title (1280x720, 3 layers, 12 instances)`.
+/// See [`Room::summary`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RoomSummary<'a> {
+    /// The name of the room.
+    pub name: &'a str,
+
+    /// The width of the room in pixels.
+    pub width: u32,
+
+    /// The height of the room in pixels.
+    pub height: u32,
+
+    /// The number of layers (GMS2+).
+    pub layer_count: usize,
+
+    /// The number of game object instances placed in the room.
+    pub instance_count: usize,
+}
+
+impl fmt::Display for RoomSummary<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let plural = |count: usize| if count == 1 { "" } else { "s" };
+        write!(
+            f,
+            "{} ({}x{}, {} layer{}, {} instance{})",
+            self.name,
+            self.width,
+            self.height,
+            self.layer_count,
+            plural(self.layer_count),
+            self.instance_count,
+            plural(self.instance_count),
+        )
+    }
+}
+
+impl GMData {
+    /// Iterates over all rooms, yielding each room's reference, name and size.
+    ///
+    /// This is a shorthand for [`Rooms::iter_named`].
+    pub fn iter_rooms_with_names(&self) -> impl Iterator<Item = (GMRef<Room>, &str, (u32, u32))> {
+        self.rooms.iter_named(&self.strings)
+    }
+
+    /// Finds the layer with the specified name in the room with the specified name.
+    ///
+    /// For more information, see [`Room::layer_by_name`].
+    pub fn room_layer_by_name(&self, room_name: &str, layer_name: &str) -> Result<&RoomLayer> {
+        let room: &Room = self.rooms.by_name(room_name, &self.strings)?;
+        room.layer_by_name(layer_name, &self.strings)
+            .ctx(|| format!("finding layer in room {room_name:?}"))
+    }
+
+    /// Finds the layer with the specified name in the room with the specified name, mutably.
+    ///
+    /// For more information, see [`Room::layer_by_name`].
+    pub fn room_layer_by_name_mut(
+        &mut self,
+        room_name: &str,
+        layer_name: &str,
+    ) -> Result<&mut RoomLayer> {
+        let room: &mut Room = self.rooms.by_name_mut(room_name, &self.strings)?;
+        room.layer_by_name_mut(layer_name, &self.strings)
+            .ctx(|| format!("finding layer in room {room_name:?}"))
+    }
+
+    /// Places a new instance of the specified game object in the room with the
+    /// specified name.
+    ///
+    /// A new unique instance ID is allocated (see [`Self::next_free_instance_id`])
+    /// and the last object ID in `GEN8` is updated accordingly.
+    /// For more information, see [`Room::add_instance`].
+    ///
+    /// Returns the instance ID of the new instance.
+    pub fn add_object_to_room(
+        &mut self,
+        room_name: &str,
+        game_object: GMRef<GameObject>,
+        x: i32,
+        y: i32,
+    ) -> Result<InstanceID> {
+        self.game_objects
+            .by_ref(game_object)
+            .ctx("adding game object to room")?;
+        let instance_id: InstanceID = self.next_free_instance_id();
+        let room: &mut Room = self.rooms.by_name_mut(room_name, &self.strings)?;
+        room.add_instance(game_object, x, y, instance_id)
+            .ctx(|| format!("adding game object to room {room_name:?}"))?;
+        self.general_info.last_object_id = instance_id.0 as u32;
+        Ok(instance_id)
+    }
+
+    /// Finds the lowest instance ID that is higher than every instance ID
+    /// of any room game object, as well as the last object ID in `GEN8`.
+    ///
+    /// Instance IDs have to be unique across all rooms, so this should be used
+    /// when adding new game objects to rooms.
+    /// See also [`Room::reassign_duplicate_instance_ids`].
+    #[must_use]
+    pub fn next_free_instance_id(&self) -> InstanceID {
+        let mut highest: i32 = self.general_info.last_object_id as i32;
+        for room in self.rooms.elements() {
+            for game_object in &room.game_objects {
+                highest = highest.max(game_object.instance_id.0);
+            }
+        }
+        InstanceID(highest + 1)
+    }
+
+    /// Finds the room containing the room game object with the specified
+    /// instance ID.
+    ///
+    /// Instance IDs should be unique across all rooms. If they are not
+    /// (see [`Room::reassign_duplicate_instance_ids`]), the first room
+    /// containing the instance ID is returned.
+    #[doc(alias = "find_room_of_instance")]
+    #[must_use]
+    pub fn room_containing_instance(&self, instance_id: InstanceID) -> Option<GMRef<Room>> {
+        self.rooms
+            .element_refs()
+            .find(|(_, room)| {
+                room.game_objects
+                    .iter()
+                    .any(|obj| obj.instance_id == instance_id)
+            })
+            .map(|(room_ref, _)| room_ref)
+    }
+
+    /// The room that is loaded when the game starts.
+    ///
+    /// This is the first room in the room order (see [`GeneralInfo::room_order`]).
+    /// If the room order is empty, [`GMRef::none`] is returned.
+    ///
+    /// [`GeneralInfo::room_order`]: crate::wad::elem::general_info::GeneralInfo::room_order
+    #[must_use]
+    pub fn startup_room(&self) -> GMRef<Room> {
+        self.general_info
+            .room_order
+            .first()
+            .copied()
+            .unwrap_or_else(GMRef::none)
+    }
+
+    /// The order of rooms as shown in the room manager.
+    ///
+    /// This is stored in `GEN8` rather than in `ROOM`.
+    /// The first room is the startup room and functions like `room_goto_next`
+    /// progress through the rooms in this order.
+    ///
+    /// See also [`GMData::set_room_order`].
+    #[must_use]
+    pub fn room_order(&self) -> &[GMRef<Room>] {
+        &self.general_info.room_order
+    }
+
+    /// Replaces the room order, which corresponds to reordering rooms in the
+    /// room manager.
+    ///
+    /// Returns an error if the order contains a nonexistent room or a room
+    /// multiple times. In that case, the room order is not modified.
+    /// Rooms that are not part of the order can only be reached with
+    /// `room_goto`.
+    ///
+    /// For more information, see [`GMData::room_order`].
+    pub fn set_room_order(&mut self, room_order: Vec<GMRef<Room>>) -> Result<()> {
+        let mut seen: HashSet<GMRef<Room>> = HashSet::new();
+        for &room in &room_order {
+            self.rooms.by_ref(room).ctx("setting room order")?;
+            if !seen.insert(room) {
+                bail!("Room order contains {room:?} multiple times");
+            }
+        }
+        self.general_info.room_order = room_order;
+        Ok(())
+    }
+
+    /// Sets the room that is loaded when the game starts, by name.
+    ///
+    /// The room is moved to the front of the room order.
+    /// If it is not part of the room order yet, it is inserted at the front.
+    pub fn set_startup_room(&mut self, name: &str) -> Result<()> {
+        let room: GMRef<Room> = self
+            .rooms
+            .ref_by_name(name, &self.strings)
+            .ctx("setting startup room")?;
+
+        let room_order: &mut Vec<GMRef<Room>> = &mut self.general_info.room_order;
+        if let Some(index) = room_order.iter().position(|&r| r == room) {
+            room_order.remove(index);
+        }
+        room_order.insert(0, room);
+        Ok(())
+    }
+
+    /// Verifies that every room layer's data variant matches its layer type.
+    ///
+    /// For more information, see [`RoomLayer::validate_data`].
+    pub fn validate_room_layer_data(&self) -> Result<()> {
+        for room in self.rooms.elements() {
+            for layer in &room.layers {
+                layer.validate_data(self.meta.version).ctx(|| {
+                    format!(
+                        "validating layer {:?} of room {:?}",
+                        layer.name.display(&self.strings),
+                        room.name.display(&self.strings),
+                    )
+                })?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Verifies the instance creation order of every room.
+    ///
+    /// The instance creation order is only stored since GameMaker 2024.13;
+    /// before that, this always succeeds.
+    /// For more information, see [`Room::check_instance_creation_order`].
+    ///
+    /// The returned error lists every discrepancy found in any room.
+    pub fn validate_instance_creation_order(&self) -> Result<()> {
+        if self.meta.version < GMVersion::GM2024_13 {
+            return Ok(());
+        }
+        let mut issues: Vec<String> = Vec::new();
+        for room in self.rooms.elements() {
+            let room_name: &str = room.name.display(&self.strings);
+            for issue in room.check_instance_creation_order() {
+                issues.push(format!("{issue} in room {room_name:?}"));
+            }
+        }
+        if !issues.is_empty() {
+            bail!(
+                "Found {} instance creation order issues:\n* {}",
+                issues.len(),
+                issues.join("\n* "),
+            );
+        }
+        Ok(())
+    }
+
+    /// Regenerates the instance creation order of every room.
+    ///
+    /// For more information, see [`Room::fix_instance_creation_order`].
+    ///
+    /// Returns the number of rooms whose instance creation order was changed.
+    pub fn fix_instance_creation_order(&mut self) -> usize {
+        self.rooms
+            .elements_mut()
+            .map(Room::fix_instance_creation_order)
+            .filter(|&changed| changed)
+            .count()
+    }
+}
+
 #[derive(Debug, Clone, Copy, Hash, PartialEq, Eq, PartialOrd, Ord)]
 pub struct InstanceID(pub i32);
 
@@ -17,6 +17,36 @@ use crate::wad::build::builder::DataBuilder;
 use crate::wad::elem::GMElement;
 use crate::wad::parse::reader::DataReader;
 
+macro_rules! layer_data_fn {
+    ($method:ident, $method_mut:ident, $variant:ident) => {
+        #[doc = concat!("Gets the ", stringify!($variant), " data of this layer.")]
+        /// Returns an error if the layer has a different type.
+        pub fn $method(&self) -> Result<&$variant> {
+            match self {
+                Self::$variant(data) => Ok(data),
+                _ => bail!(
+                    "Expected {} layer data but found {} layer data",
+                    stringify!($variant),
+                    self.type_name(),
+                ),
+            }
+        }
+
+        #[doc = concat!("Gets the mutable ", stringify!($variant), " data of this layer.")]
+        /// Returns an error if the layer has a different type.
+        pub fn $method_mut(&mut self) -> Result<&mut $variant> {
+            let type_name: &str = self.type_name();
+            match self {
+                Self::$variant(data) => Ok(data),
+                _ => bail!(
+                    "Expected {} layer data but found {type_name} layer data",
+                    stringify!($variant),
+                ),
+            }
+        }
+    };
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct RoomLayer {
     pub name: GMRef<String>,
@@ -106,6 +136,66 @@ impl GMElement for RoomLayer {
     }
 }
 
+impl RoomLayer {
+    /// Gets the mutable assets data of this layer, for example to place
+    /// sprites or text items with [`Assets::add_sprite_instance`] and similar.
+    ///
+    /// Unlike [`Data::as_assets_mut`], this also checks that the layer's
+    /// [`Type`] is [`Type::Assets`].
+    pub fn assets_mut(&mut self) -> Result<&mut Assets> {
+        if self.ty != Type::Assets {
+            bail!(
+                "Expected an assets layer but found layer type {:?}",
+                self.ty
+            );
+        }
+        self.data.as_assets_mut()
+    }
+
+    /// Verifies that this layer's [`Data`] variant matches its [`Type`].
+    ///
+    /// Since GameMaker 2022.1, effect layers store their effect in
+    /// [`RoomLayer::effect_data_2022_1`] and the [`Data::Effect`] is not
+    /// serialized at all. In that case, both have to describe the same effect;
+    /// otherwise changes to the layer data would silently be lost.
+    ///
+    /// A mismatch produces a malformed data file that crashes the runner.
+    pub fn validate_data(&self, version: GMVersion) -> Result<()> {
+        let matches: bool = match self.ty {
+            Type::Path | Type::Path2 => matches!(self.data, Data::None),
+            Type::Background => matches!(self.data, Data::Background(_)),
+            Type::Instances => matches!(self.data, Data::Instances(_)),
+            Type::Assets => matches!(self.data, Data::Assets(_)),
+            Type::Tiles => matches!(self.data, Data::Tiles(_)),
+            Type::Effect => matches!(self.data, Data::Effect(_)),
+        };
+        if !matches {
+            bail!(
+                "Layer type {:?} does not match layer data {}",
+                self.ty,
+                self.data.type_name(),
+            );
+        }
+
+        if version < GMVersion::GM2022_1 {
+            return Ok(());
+        }
+        let Some(effect_data) = &self.effect_data_2022_1 else {
+            bail!("Effect data (2022.1) needs to be set since GameMaker version 2022.1");
+        };
+        if let Data::Effect(effect) = &self.data
+            && (effect.effect_type, &effect.properties)
+                != (effect_data.effect_type, &effect_data.effect_properties)
+        {
+            bail!(
+                "Effect layer data does not match effect data (2022.1); only the latter is \
+                 serialized since GameMaker version 2022.1"
+            );
+        }
+        Ok(())
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Data2022_1 {
     pub effect_enabled: bool,
@@ -164,3 +254,27 @@ pub enum Data {
     Assets(Assets),
     Effect(Effect),
 }
+
+impl Data {
+    layer_data_fn!(as_instances, as_instances_mut, Instances);
+
+    layer_data_fn!(as_tiles, as_tiles_mut, Tiles);
+
+    layer_data_fn!(as_background, as_background_mut, Background);
+
+    layer_data_fn!(as_assets, as_assets_mut, Assets);
+
+    layer_data_fn!(as_effect, as_effect_mut, Effect);
+
+    /// The name of this layer data variant, for use in error messages.
+    const fn type_name(&self) -> &'static str {
+        match self {
+            Self::None => "None",
+            Self::Instances(_) => "Instances",
+            Self::Tiles(_) => "Tiles",
+            Self::Background(_) => "Background",
+            Self::Assets(_) => "Assets",
+            Self::Effect(_) => "Effect",
+        }
+    }
+}
@@ -12,8 +12,12 @@ use crate::prelude::*;
 use crate::wad::GMVersion;
 use crate::wad::build::builder::DataBuilder;
 use crate::wad::elem::GMElement;
+use crate::wad::elem::font::Font;
+use crate::wad::elem::particle_system::ParticleSystem;
 use crate::wad::elem::room::tile::RoomTile;
+use crate::wad::elem::sprite::Sprite;
 use crate::wad::parse::reader::DataReader;
+use crate::wad::reference::GMRef;
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct Assets {
@@ -25,6 +29,58 @@ pub struct Assets {
     pub text_items: Vec<TextItemInstance>,
 }
 
+impl Assets {
+    /// Appends a new sprite instance to this assets layer.
+    ///
+    /// For the default values of the remaining fields, see [`SpriteInstance::new`].
+    /// Returns the new instance so that these can be adjusted.
+    pub fn add_sprite_instance(
+        &mut self,
+        name: GMRef<String>,
+        sprite: GMRef<Sprite>,
+        x: i32,
+        y: i32,
+    ) -> &mut SpriteInstance {
+        self.sprites.push(SpriteInstance::new(name, sprite, x, y));
+        self.sprites.last_mut().unwrap()
+    }
+
+    /// Appends a new particle system instance to this assets layer.
+    ///
+    /// Particle system instances are only serialized since GameMaker 2023.2.
+    /// For the default values of the remaining fields, see [`ParticleSystemInstance::new`].
+    /// Returns the new instance so that these can be adjusted.
+    pub fn add_particle_instance(
+        &mut self,
+        name: GMRef<String>,
+        particle_system: GMRef<ParticleSystem>,
+        x: i32,
+        y: i32,
+    ) -> &mut ParticleSystemInstance {
+        let instance = ParticleSystemInstance::new(name, particle_system, x, y);
+        self.particle_systems.push(instance);
+        self.particle_systems.last_mut().unwrap()
+    }
+
+    /// Appends a new text item to this assets layer.
+    ///
+    /// Text items are only serialized since GameMaker 2024.6.
+    /// For the default values of the remaining fields, see [`TextItemInstance::new`].
+    /// Returns the new text item so that these can be adjusted.
+    pub fn add_text_item(
+        &mut self,
+        name: GMRef<String>,
+        font: GMRef<Font>,
+        text: GMRef<String>,
+        x: i32,
+        y: i32,
+    ) -> &mut TextItemInstance {
+        self.text_items
+            .push(TextItemInstance::new(name, font, text, x, y));
+        self.text_items.last_mut().unwrap()
+    }
+}
+
 impl GMElement for Assets {
     fn deserialize(reader: &mut DataReader) -> Result<Self> {
         let legacy_tiles_pointer = reader.read_u32()?;
@@ -18,6 +18,30 @@ pub struct ParticleSystemInstance {
     pub rotation: f32,
 }
 
+impl ParticleSystemInstance {
+    /// Creates a new particle system instance at the specified position.
+    ///
+    /// The instance is unscaled, unrotated and untinted (white).
+    #[must_use]
+    pub const fn new(
+        name: GMRef<String>,
+        particle_system: GMRef<ParticleSystem>,
+        x: i32,
+        y: i32,
+    ) -> Self {
+        Self {
+            name,
+            particle_system,
+            x,
+            y,
+            scale_x: 1.0,
+            scale_y: 1.0,
+            color: 0xFFFF_FFFF,
+            rotation: 0.0,
+        }
+    }
+}
+
 impl GMElement for ParticleSystemInstance {
     fn deserialize(reader: &mut DataReader) -> Result<Self> {
         let name: GMRef<String> = reader.read_gm_string()?;
@@ -22,6 +22,29 @@ pub struct SpriteInstance {
     pub rotation: f32,
 }
 
+impl SpriteInstance {
+    /// Creates a new sprite instance at the specified position.
+    ///
+    /// The instance is unscaled, unrotated, untinted (white) and plays its
+    /// animation at one frame per game frame, starting at the first frame.
+    #[must_use]
+    pub const fn new(name: GMRef<String>, sprite: GMRef<Sprite>, x: i32, y: i32) -> Self {
+        Self {
+            name,
+            sprite,
+            x,
+            y,
+            scale_x: 1.0,
+            scale_y: 1.0,
+            color: 0xFFFF_FFFF,
+            animation_speed: 1.0,
+            animation_speed_type: SpeedType::FramesPerGameFrame,
+            frame_index: 0.0,
+            rotation: 0.0,
+        }
+    }
+}
+
 impl GMElement for SpriteInstance {
     fn deserialize(reader: &mut DataReader) -> Result<Self> {
         let name: GMRef<String> = reader.read_gm_string()?;
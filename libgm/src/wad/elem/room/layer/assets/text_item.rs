@@ -26,6 +26,42 @@ pub struct TextItemInstance {
     pub wrap: bool,
 }
 
+impl TextItemInstance {
+    /// Creates a new text item displaying the specified text at the specified
+    /// position.
+    ///
+    /// The text item is unscaled, unrotated, untinted (white), left aligned,
+    /// has no extra character or line spacing and does not wrap.
+    #[must_use]
+    pub const fn new(
+        name: GMRef<String>,
+        font: GMRef<Font>,
+        text: GMRef<String>,
+        x: i32,
+        y: i32,
+    ) -> Self {
+        Self {
+            name,
+            x,
+            y,
+            font,
+            scale_x: 1.0,
+            scale_y: 1.0,
+            rotation: 0.0,
+            color: 0xFFFF_FFFF,
+            origin_x: 0.0,
+            origin_y: 0.0,
+            text,
+            alignment: 0,
+            character_spacing: 0.0,
+            line_spacing: 0.0,
+            frame_width: 0.0,
+            frame_height: 0.0,
+            wrap: false,
+        }
+    }
+}
+
 impl GMElement for TextItemInstance {
     fn deserialize(reader: &mut DataReader) -> Result<Self> {
         let name: GMRef<String> = reader.read_gm_string()?;
@@ -61,15 +61,19 @@ impl Tiles {
             return Ok(());
         }
 
+        // Whether the last repeat run goes past the end of the tile data.
+        let mut overshot = false;
+
         'outer: loop {
             let length = reader.read_u8()?;
             if length >= 128 {
                 // Repeat run
                 let run_length: u8 = (length & 0x7F) + 1;
                 let tile = reader.read_u32()?;
-                for _ in 0..run_length {
+                for j in 0..run_length {
                     tile_data.push(tile);
                     if tile_data.len() >= total_size {
+                        overshot = j + 1 < run_length;
                         break 'outer;
                     }
                 }
@@ -96,21 +100,17 @@ impl Tiles {
         } else {
             false // no tiles => no padding (should never happen though?)
         };
-        if has_padding {
-            let length = reader.read_u8()?;
-            let tile = reader.read_u32()?;
-
-            // Sanity check: run of 2 empty tiles
-            if length != 0x81 {
-                reader.handle_invalid_align(format!(
-                    "Expected 0x81 for run length of compressed tile data padding; got \
-                     0x{length:02X}"
-                ))?;
+        if overshot {
+            // If the last tile is -1, the padding is merged into its repeat run.
+            // Only data files emulating the bug produce such runs.
+            reader.gmac_tile_padding_bug.get_or_insert(true);
+        } else if has_padding {
+            if reader.gmac_tile_padding_bug.is_none() {
+                // Data files built without emulating the bug have no padding.
+                reader.gmac_tile_padding_bug = Some(Self::peek_padding(reader));
             }
-            if tile as i32 != -1 {
-                reader.handle_invalid_align(format!(
-                    "Expected -1 for tile of compressed tile data padding; got 0x{length:02X}"
-                ))?;
+            if reader.gmac_tile_padding_bug == Some(true) {
+                Self::read_padding(reader)?;
             }
         }
 
@@ -120,6 +120,33 @@ impl Tiles {
         Ok(())
     }
 
+    /// Checks whether the GMAC bug padding follows, without advancing the reader.
+    fn peek_padding(reader: &mut DataReader) -> bool {
+        let position: u32 = reader.cur_pos;
+        let padded: bool = reader.read_u8().is_ok_and(|length| length == 0x81)
+            && reader.read_u32().is_ok_and(|tile| tile as i32 == -1);
+        reader.cur_pos = position;
+        padded
+    }
+
+    fn read_padding(reader: &mut DataReader) -> Result<()> {
+        let length = reader.read_u8()?;
+        let tile = reader.read_u32()?;
+
+        // Sanity check: run of 2 empty tiles
+        if length != 0x81 {
+            reader.handle_invalid_align(format!(
+                "Expected 0x81 for run length of compressed tile data padding; got 0x{length:02X}"
+            ))?;
+        }
+        if tile as i32 != -1 {
+            reader.handle_invalid_align(format!(
+                "Expected -1 for tile of compressed tile data padding; got 0x{length:02X}"
+            ))?;
+        }
+        Ok(())
+    }
+
     fn build_compressed_tile_data(&self, builder: &mut DataBuilder) {
         let tile_count: usize = self.tile_data.len();
         if tile_count == 0 {
@@ -176,6 +203,17 @@ impl Tiles {
                 verbatim_start += num_to_write;
             }
 
+            // Repeat runs going out of bounds contain the padding of the GMAC bug
+            // (see `read_compressed_tile_data`). If the last tile is -1, the padding
+            // is merged into its run; otherwise, the run consists only of padding.
+            if i > tile_count && !builder.gm_data.meta.emulate_gmac_tile_padding_bug {
+                let run_start: usize = i - usize::from(num_repeats);
+                if run_start >= tile_count {
+                    break;
+                }
+                num_repeats -= 1;
+            }
+
             // Serialize this repeat run, splitting into 128-length chunks.
             while num_repeats > 0 {
                 let num_to_write: u8 = min(num_repeats, 128);
@@ -0,0 +1,569 @@
+// SPDX-License-Identifier: GPL-3.0-only
+//! Flat previews of rooms.
+
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::path::Path;
+
+use image::DynamicImage;
+use image::Rgba;
+use image::RgbaImage;
+use image::imageops;
+use image::imageops::FilterType;
+
+use crate::prelude::*;
+use crate::wad::elem::game_object::GameObject;
+use crate::wad::elem::room::Room;
+use crate::wad::elem::room::RoomBackground;
+use crate::wad::elem::room::RoomGameObject;
+use crate::wad::elem::room::RoomLayer;
+use crate::wad::elem::room::RoomTile;
+use crate::wad::elem::room::layer;
+use crate::wad::elem::room::tile::Texture;
+use crate::wad::elem::sprite::Sprite;
+use crate::wad::elem::texture_page::GMImage;
+use crate::wad::elem::texture_page::TexturePage;
+use crate::wad::elem::texture_page_item::TexturePageItem;
+use crate::wad::elem::tileset::Tileset;
+
+/// Color with full opacity and no tint.
+const WHITE: u32 = 0xFFFF_FFFF;
+
+/// The bits of a tile layer cell containing the tile index.
+const TILE_INDEX_MASK: u32 = 0x0007_FFFF;
+const TILE_MIRROR: u32 = 0x1000_0000;
+const TILE_FLIP: u32 = 0x2000_0000;
+const TILE_ROTATE: u32 = 0x4000_0000;
+
+/// The maximum number of pixels of a rendered room (1 GiB of RGBA data).
+/// This prevents huge allocations for rooms with corrupted or absurd sizes.
+const MAX_ROOM_PIXELS: u64 = 1 << 28;
+
+impl GMData {
+    /// Renders a top-down preview of the specified room at room resolution.
+    ///
+    /// The following are composited from back to front (by depth):
+    /// * The room background color (if enabled)
+    /// * Background layers (or legacy backgrounds)
+    /// * Tile layers and legacy tiles
+    /// * Sprites placed on asset layers
+    /// * Game object instances, using their object's sprite
+    ///
+    /// This is a flat composite, not what the game actually shows:
+    /// * Blend modes, shaders and layer effects are ignored.
+    /// * Draw events of game objects are not executed; instances are drawn
+    ///   with their sprite, image index, scale and color only. Rotation is
+    ///   ignored.
+    /// * Animations are not played; every sprite shows its initial frame.
+    /// * Views, nine slices, sequences, particle systems and text items are
+    ///   not rendered.
+    ///
+    /// Returns an error if the room does not exist, if it is too large to
+    /// render or if a referenced asset or texture is invalid or stored externally.
+    #[doc(alias = "export_room_as_image")]
+    pub fn render_room(&self, room_ref: GMRef<Room>) -> Result<RgbaImage> {
+        let room: &Room = self.rooms.by_ref(room_ref)?;
+        let mut renderer = Renderer::new(self, room)?;
+
+        if room.draw_background_color {
+            let color = Rgba(room.background_color.to_le_bytes());
+            renderer
+                .canvas
+                .pixels_mut()
+                .for_each(|pixel| *pixel = color);
+        }
+
+        for background in room.backgrounds.iter().filter(|bg| !bg.foreground) {
+            renderer
+                .draw_legacy_background(background)
+                .ctx("drawing legacy background")?;
+        }
+
+        let mut items: Vec<(i32, Item)> = Vec::new();
+        for tile in &room.tiles {
+            items.push((tile.tile_depth, Item::LegacyTile(tile)));
+        }
+        if room.layers.is_empty() {
+            // Before GMS2, instances are drawn at their object's depth.
+            for instance in &room.game_objects {
+                let object: &GameObject = self.game_objects.by_ref(instance.object_definition)?;
+                items.push((object.depth, Item::Instance(instance)));
+            }
+        }
+        for layer in room.layers.iter().filter(|layer| layer.is_visible) {
+            items.push((layer.depth, Item::Layer(layer)));
+        }
+        // Higher depths are further away. The sort is stable, so the
+        // original order is kept for equal depths.
+        items.sort_by_key(|&(depth, _)| Reverse(depth));
+
+        for (_, item) in items {
+            match item {
+                Item::LegacyTile(tile) => renderer.draw_legacy_tile(tile, 0, 0),
+                Item::Instance(instance) => renderer.draw_instance(instance),
+                Item::Layer(layer) => renderer
+                    .draw_layer(layer)
+                    .ctx(|| format!("drawing layer {:?}", layer.name.display(&self.strings))),
+            }?;
+        }
+
+        for background in room.backgrounds.iter().filter(|bg| bg.foreground) {
+            renderer
+                .draw_legacy_background(background)
+                .ctx("drawing legacy foreground")?;
+        }
+
+        Ok(renderer.canvas)
+    }
+
+    /// Renders a preview of the specified room and writes it to a PNG file.
+    ///
+    /// This requires the `png-image` crate feature.
+    /// For more information, see [`GMData::render_room`].
+    pub fn export_room_image(&self, room_ref: GMRef<Room>, path: impl AsRef<Path>) -> Result<()> {
+        let path: &Path = path.as_ref();
+        let image: RgbaImage = self.render_room(room_ref)?;
+        let png: Vec<u8> = GMImage::from_dynamic_image(DynamicImage::ImageRgba8(image)).to_png()?;
+        std::fs::write(path, png).ctx_any(|| format!("writing room image to {}", path.display()))
+    }
+}
+
+/// Something drawn at a specific depth.
+enum Item<'a> {
+    LegacyTile(&'a RoomTile),
+    Instance(&'a RoomGameObject),
+    Layer(&'a RoomLayer),
+}
+
+struct Renderer<'a> {
+    data: &'a GMData,
+    room: &'a Room,
+    canvas: RgbaImage,
+    /// Decoded texture pages.
+    pages: HashMap<GMRef<TexturePage>, RgbaImage>,
+    /// Extracted texture page items (with their bounding size).
+    textures: HashMap<GMRef<TexturePageItem>, RgbaImage>,
+}
+
+impl<'a> Renderer<'a> {
+    fn new(data: &'a GMData, room: &'a Room) -> Result<Self> {
+        let pixels = u64::from(room.width) * u64::from(room.height);
+        if pixels > MAX_ROOM_PIXELS {
+            bail!(
+                "Room size {}x{} is too large to render (more than {MAX_ROOM_PIXELS} pixels)",
+                room.width,
+                room.height,
+            );
+        }
+        Ok(Self {
+            data,
+            room,
+            canvas: RgbaImage::new(room.width, room.height),
+            pages: HashMap::new(),
+            textures: HashMap::new(),
+        })
+    }
+
+    /// Extracts the image of a texture page item, decoding its page if needed.
+    fn texture(&mut self, item_ref: GMRef<TexturePageItem>) -> Result<&RgbaImage> {
+        if !self.textures.contains_key(&item_ref) {
+            let item: &TexturePageItem = self.data.texture_page_items.by_ref(item_ref)?;
+            let page_ref: GMRef<TexturePage> = item.texture_page;
+            if !self.pages.contains_key(&page_ref) {
+                let index = page_ref.index().ok_or("Texture page is null")?;
+                let page: RgbaImage = self.data.texture_page_image(index)?;
+                self.pages.insert(page_ref, page);
+            }
+            let image: RgbaImage = item.extract_from(&self.pages[&page_ref])?;
+            self.textures.insert(item_ref, image);
+        }
+        Ok(&self.textures[&item_ref])
+    }
+
+    /// Gets the image of a sprite frame (wrapping around the frame count).
+    ///
+    /// Returns [`None`] if the sprite has no frames.
+    fn sprite_frame(
+        &mut self,
+        sprite_ref: GMRef<Sprite>,
+        frame: usize,
+    ) -> Result<Option<(&'a Sprite, RgbaImage)>> {
+        let sprite: &'a Sprite = self.data.sprites.by_ref(sprite_ref)?;
+        if sprite.textures.is_empty() {
+            return Ok(None);
+        }
+        let texture = sprite.textures[frame % sprite.textures.len()];
+        if texture.is_none() {
+            return Ok(None);
+        }
+        let image: RgbaImage = self.texture(texture)?.clone();
+        Ok(Some((sprite, image)))
+    }
+
+    fn tileset_image(&mut self, tileset_ref: GMRef<Tileset>) -> Result<(&'a Tileset, RgbaImage)> {
+        let tileset: &'a Tileset = self.data.tilesets.by_ref(tileset_ref)?;
+        let image: RgbaImage = self.texture(tileset.texture)?.clone();
+        Ok((tileset, image))
+    }
+
+    /// Draws an image with its origin at the specified position, scaled by the
+    /// specified factors (negative factors mirror the image) and tinted with
+    /// the specified color.
+    ///
+    /// Only the part of the scaled image inside the canvas is sampled, so
+    /// huge scale factors do not cause huge allocations.
+    fn draw(
+        &mut self,
+        image: &RgbaImage,
+        (x, y): (i64, i64),
+        (origin_x, origin_y): (i32, i32),
+        (scale_x, scale_y): (f32, f32),
+        color: u32,
+    ) {
+        let (width, height) = image.dimensions();
+        let (canvas_width, canvas_height) = self.canvas.dimensions();
+        let Some(columns) = Span::new(x, origin_x, width, scale_x, canvas_width) else {
+            return;
+        };
+        let Some(rows) = Span::new(y, origin_y, height, scale_y, canvas_height) else {
+            return;
+        };
+
+        let mut clipped = RgbaImage::from_fn(columns.len(), rows.len(), |column, row| {
+            *image.get_pixel(columns.source(column), rows.source(row))
+        });
+        tint(&mut clipped, color);
+        imageops::overlay(&mut self.canvas, &clipped, columns.start, rows.start);
+    }
+
+    fn draw_sprite(
+        &mut self,
+        sprite_ref: GMRef<Sprite>,
+        frame: usize,
+        position: (i64, i64),
+        scale: (f32, f32),
+        color: u32,
+    ) -> Result<()> {
+        if let Some((sprite, image)) = self.sprite_frame(sprite_ref, frame)? {
+            let origin = (sprite.origin_x, sprite.origin_y);
+            self.draw(&image, position, origin, scale, color);
+        }
+        Ok(())
+    }
+
+    fn draw_instance(&mut self, instance: &RoomGameObject) -> Result<()> {
+        let object: &GameObject = self.data.game_objects.by_ref(instance.object_definition)?;
+        if !object.visible || object.sprite.is_none() {
+            return Ok(());
+        }
+        let frame = instance.image_index.unwrap_or(0) as usize;
+        let position = (i64::from(instance.x), i64::from(instance.y));
+        let scale = (instance.scale_x, instance.scale_y);
+        self.draw_sprite(object.sprite, frame, position, scale, instance.color)
+            .ctx(|| format!("drawing instance {}", instance.instance_id.0))
+    }
+
+    fn draw_legacy_background(&mut self, background: &RoomBackground) -> Result<()> {
+        if !background.enabled || background.background_definition.is_none() {
+            return Ok(());
+        }
+        let (_, image) = self.tileset_image(background.background_definition)?;
+        let tiled = (background.tile_x != 0, background.tile_y != 0);
+        let position = (i64::from(background.x), i64::from(background.y));
+        self.draw_background_image(&image, position, tiled, background.stretch, WHITE);
+        Ok(())
+    }
+
+    /// Draws a background image, optionally repeated along the axes or
+    /// stretched to the room size.
+    fn draw_background_image(
+        &mut self,
+        image: &RgbaImage,
+        (x, y): (i64, i64),
+        (tiled_x, tiled_y): (bool, bool),
+        stretch: bool,
+        color: u32,
+    ) {
+        let (room_width, room_height) = self.canvas.dimensions();
+        if stretch {
+            let image = imageops::resize(image, room_width, room_height, FilterType::Nearest);
+            self.draw(&image, (0, 0), (0, 0), (1.0, 1.0), color);
+            return;
+        }
+        let (width, height) = (i64::from(image.width()), i64::from(image.height()));
+        if width == 0 || height == 0 {
+            return;
+        }
+        let positions = |start: i64, size: i64, room_size: u32, tiled: bool| {
+            if tiled {
+                let first = start.rem_euclid(size) - size;
+                let step = usize::try_from(size).unwrap_or(usize::MAX);
+                (first..i64::from(room_size)).step_by(step).collect()
+            } else {
+                vec![start]
+            }
+        };
+        let xs: Vec<i64> = positions(x, width, room_width, tiled_x);
+        let ys: Vec<i64> = positions(y, height, room_height, tiled_y);
+        for &y in &ys {
+            for &x in &xs {
+                self.draw(image, (x, y), (0, 0), (1.0, 1.0), color);
+            }
+        }
+    }
+
+    fn draw_legacy_tile(&mut self, tile: &RoomTile, offset_x: i64, offset_y: i64) -> Result<()> {
+        let source: RgbaImage = match tile.texture {
+            Texture::Sprite(sprite) => match self.sprite_frame(sprite, 0)? {
+                Some((_, image)) => image,
+                None => return Ok(()),
+            },
+            Texture::Background(tileset) => self.tileset_image(tileset)?.1,
+        };
+        let region = imageops::crop_imm(
+            &source,
+            tile.source_x,
+            tile.source_y,
+            tile.width,
+            tile.height,
+        )
+        .to_image();
+        let position = (i64::from(tile.x) + offset_x, i64::from(tile.y) + offset_y);
+        self.draw(
+            &region,
+            position,
+            (0, 0),
+            (tile.scale_x, tile.scale_y),
+            tile.color,
+        );
+        Ok(())
+    }
+
+    fn draw_layer(&mut self, layer: &RoomLayer) -> Result<()> {
+        #[expect(clippy::cast_possible_truncation)]
+        let offset: (i64, i64) = (
+            i64::from(layer.x_offset as i32),
+            i64::from(layer.y_offset as i32),
+        );
+        match &layer.data {
+            layer::Data::Background(background) => self.draw_background_layer(background, offset),
+            layer::Data::Tiles(tiles) => self.draw_tiles(tiles, offset),
+            layer::Data::Instances(instances) => {
+                for &id in &instances.instances {
+                    let instance = self
+                        .room
+                        .game_objects
+                        .iter()
+                        .find(|instance| i64::from(instance.instance_id.0) == i64::from(id))
+                        .ok_or_else(|| err!("Instance ID {id} does not exist in the room"))?;
+                    self.draw_instance(instance)?;
+                }
+                Ok(())
+            }
+            layer::Data::Assets(assets) => {
+                for tile in &assets.legacy_tiles {
+                    self.draw_legacy_tile(tile, offset.0, offset.1)?;
+                }
+                for sprite in &assets.sprites {
+                    #[expect(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+                    let frame = sprite.frame_index.max(0.0) as usize;
+                    let position = (
+                        i64::from(sprite.x) + offset.0,
+                        i64::from(sprite.y) + offset.1,
+                    );
+                    let scale = (sprite.scale_x, sprite.scale_y);
+                    self.draw_sprite(sprite.sprite, frame, position, scale, sprite.color)?;
+                }
+                Ok(())
+            }
+            layer::Data::None | layer::Data::Effect(_) => Ok(()),
+        }
+    }
+
+    fn draw_background_layer(
+        &mut self,
+        background: &layer::Background,
+        position: (i64, i64),
+    ) -> Result<()> {
+        if !background.visible {
+            return Ok(());
+        }
+        if background.sprite.is_none() {
+            // Without a sprite, the layer is filled with its color.
+            let (width, height) = self.canvas.dimensions();
+            let fill = RgbaImage::from_pixel(width, height, Rgba(background.color.to_le_bytes()));
+            self.draw(&fill, (0, 0), (0, 0), (1.0, 1.0), WHITE);
+            return Ok(());
+        }
+        #[expect(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+        let frame = background.first_frame.max(0.0) as usize;
+        let Some((_, image)) = self.sprite_frame(background.sprite, frame)? else {
+            return Ok(());
+        };
+        let tiled = (background.tiled_horizontally, background.tiled_vertically);
+        self.draw_background_image(
+            &image,
+            position,
+            tiled,
+            background.stretch,
+            background.color,
+        );
+        Ok(())
+    }
+
+    fn draw_tiles(&mut self, tiles: &layer::Tiles, (x, y): (i64, i64)) -> Result<()> {
+        if tiles.tileset.is_none() {
+            return Ok(());
+        }
+        let (tileset, image) = self.tileset_image(tiles.tileset)?;
+        let data = tileset
+            .gms2_data
+            .as_ref()
+            .ok_or("Tileset of tile layer does not have GMS2 data")?;
+        let (tile_width, tile_height) = (data.tile_width, data.tile_height);
+        if data.tile_columns == 0 {
+            bail!("Tileset has zero tile columns");
+        }
+
+        let size = u64::from(tiles.width) * u64::from(tiles.height);
+        if tiles.tile_data.len() as u64 != size {
+            bail!(
+                "Tile layer of size {}x{} has {} tiles",
+                tiles.width,
+                tiles.height,
+                tiles.tile_data.len(),
+            );
+        }
+
+        for row in 0..tiles.height {
+            for column in 0..tiles.width {
+                let cell: u32 = tiles.get(column, row).unwrap_or(0);
+                let index: u32 = cell & TILE_INDEX_MASK;
+                if index == 0 {
+                    continue;
+                }
+                let source_x =
+                    tile_source(index % data.tile_columns, tile_width, data.output_border_x);
+                let source_y =
+                    tile_source(index / data.tile_columns, tile_height, data.output_border_y);
+                let (Some(source_x), Some(source_y)) = (source_x, source_y) else {
+                    bail!("Source position of tile {index} in tileset is out of bounds");
+                };
+                let mut tile =
+                    imageops::crop_imm(&image, source_x, source_y, tile_width, tile_height)
+                        .to_image();
+                if cell & TILE_ROTATE != 0 {
+                    tile = imageops::rotate90(&tile);
+                }
+                if cell & TILE_MIRROR != 0 {
+                    imageops::flip_horizontal_in_place(&mut tile);
+                }
+                if cell & TILE_FLIP != 0 {
+                    imageops::flip_vertical_in_place(&mut tile);
+                }
+                let position = (
+                    x.saturating_add(i64::from(column).saturating_mul(i64::from(tile_width))),
+                    y.saturating_add(i64::from(row).saturating_mul(i64::from(tile_height))),
+                );
+                self.draw(&tile, position, (0, 0), (1.0, 1.0), WHITE);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The visible part of a scaled image along one axis.
+struct Span {
+    /// The first canvas position covered by the image.
+    start: i64,
+    /// The canvas position after the last one covered by the image.
+    end: i64,
+    /// The canvas position of the left (or top) edge of the scaled image.
+    edge: i64,
+    /// The scaled length of the image, in pixels.
+    scaled_length: f64,
+    /// The length of the image, in pixels.
+    length: u32,
+    mirrored: bool,
+}
+
+impl Span {
+    /// Clips the image side to the canvas side.
+    ///
+    /// Returns [`None`] if nothing of the image is visible.
+    #[expect(clippy::cast_possible_truncation)]
+    fn new(
+        position: i64,
+        origin: i32,
+        length: u32,
+        scale: f32,
+        canvas_length: u32,
+    ) -> Option<Self> {
+        let scaled_length: f64 = (f64::from(length) * f64::from(scale.abs())).round();
+        if scaled_length.is_nan() || scaled_length < 1.0 {
+            return None;
+        }
+
+        // The offset of the left (or top) edge of the scaled image relative to its origin position.
+        let start_offset: f64 = -f64::from(origin) * f64::from(scale);
+        let end_offset: f64 = (f64::from(length) - f64::from(origin)) * f64::from(scale);
+        let edge: i64 = position.saturating_add(start_offset.min(end_offset).round() as i64);
+
+        let start: i64 = edge.max(0);
+        let end: i64 = edge
+            .saturating_add(scaled_length as i64)
+            .min(i64::from(canvas_length));
+        if start >= end {
+            return None;
+        }
+        Some(Self {
+            start,
+            end,
+            edge,
+            scaled_length,
+            length,
+            mirrored: scale < 0.0,
+        })
+    }
+
+    /// The number of visible pixels, which is at most the canvas length.
+    fn len(&self) -> u32 {
+        u32::try_from(self.end - self.start).unwrap_or(u32::MAX)
+    }
+
+    /// The source pixel of the specified visible pixel (nearest neighbor).
+    #[expect(
+        clippy::cast_possible_truncation,
+        clippy::cast_sign_loss,
+        clippy::cast_precision_loss
+    )]
+    fn source(&self, pixel: u32) -> u32 {
+        let mut scaled: f64 = (self.start - self.edge + i64::from(pixel)) as f64 + 0.5;
+        if self.mirrored {
+            scaled = self.scaled_length - scaled;
+        }
+        let source = (scaled * f64::from(self.length) / self.scaled_length) as u32;
+        source.min(self.length - 1)
+    }
+}
+
+/// The position of a tile in its tileset image along one axis,
+/// or [`None`] if it overflows.
+fn tile_source(index: u32, tile_size: u32, border: u32) -> Option<u32> {
+    let stride: u32 = tile_size.checked_add(border.checked_mul(2)?)?;
+    index.checked_mul(stride)?.checked_add(border)
+}
+
+/// Multiplies every pixel with the specified color (`0xAABBGGRR`).
+#[expect(clippy::cast_possible_truncation)]
+fn tint(image: &mut RgbaImage, color: u32) {
+    if color == WHITE {
+        return;
+    }
+    let factors: [u8; 4] = color.to_le_bytes();
+    for pixel in image.pixels_mut() {
+        for (channel, factor) in pixel.0.iter_mut().zip(factors) {
+            *channel = (u16::from(*channel) * u16::from(factor) / 255) as u8;
+        }
+    }
+}
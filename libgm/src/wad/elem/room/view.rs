@@ -54,6 +54,58 @@ pub struct RoomView {
     pub object: GMRef<GameObject>,
 }
 
+impl Default for RoomView {
+    /// A disabled 640x480 view which does not follow any object.
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            view_x: 0,
+            view_y: 0,
+            view_width: 640,
+            view_height: 480,
+            port_x: 0,
+            port_y: 0,
+            port_width: 640,
+            port_height: 480,
+            border_x: 32,
+            border_y: 32,
+            speed_x: -1,
+            speed_y: -1,
+            object: GMRef::none(),
+        }
+    }
+}
+
+/// The "Object Following" settings of a [`RoomView`].
+///
+/// See [`Room::set_view`](crate::wad::elem::room::Room::set_view).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ViewFollow {
+    /// The game object the camera follows.
+    pub object: GMRef<GameObject>,
+
+    /// The horizontal and vertical border (in pixels) the object has to stay
+    /// away from the view's edges.
+    pub border: (u32, u32),
+
+    /// The maximum horizontal and vertical speed the view moves at.
+    /// A value of -1 means instant movement.
+    pub speed: (i32, i32),
+}
+
+impl ViewFollow {
+    /// Follows the specified game object with GameMaker's default border
+    /// (32 pixels) and instant movement.
+    #[must_use]
+    pub const fn new(object: GMRef<GameObject>) -> Self {
+        Self {
+            object,
+            border: (32, 32),
+            speed: (-1, -1),
+        }
+    }
+}
+
 impl GMElement for RoomView {
     fn deserialize(reader: &mut DataReader) -> Result<Self> {
         let enabled = reader.read_bool32()?;
@@ -29,6 +29,15 @@ impl GMElement for Scripts {
     }
 }
 
+/// A script asset.
+///
+/// Before GMS 2.3, a script is a single code entry (`gml_Script_<name>`)
+/// which is executed as a whole when the script is called.
+///
+/// Since GMS 2.3, scripts are functions declared inside of a global script
+/// code entry (`gml_GlobalScript_<file>`). The referenced code entry is then
+/// a child code entry whose instructions are stored in its parent
+/// (the global script); see [`Code::parent`].
 #[derive(Debug, Clone, PartialEq)]
 pub struct Script {
     pub name: GMRef<String>,
@@ -36,6 +45,35 @@ pub struct Script {
     pub code: GMRef<Code>,
 }
 
+impl Script {
+    /// The code entry of this script.
+    ///
+    /// Before GMS 2.3, this is the script's entire code.
+    /// Since GMS 2.3, this is the child code entry of the script function;
+    /// to get the code entry actually containing the instructions, use
+    /// [`Self::root_code`].
+    ///
+    /// Returns [`None`] if the script has no code entry (which is the case for
+    /// some constructors) or if the code reference is invalid.
+    #[must_use]
+    pub fn code<'a>(&self, data: &'a GMData) -> Option<&'a Code> {
+        data.codes.by_ref(self.code).ok()
+    }
+
+    /// The code entry containing the instructions of this script.
+    ///
+    /// This is the parent code entry (the global script) of [`Self::code`] if it
+    /// has one; otherwise it is the same as [`Self::code`].
+    #[must_use]
+    pub fn root_code<'a>(&self, data: &'a GMData) -> Option<&'a Code> {
+        let code: &Code = self.code(data)?;
+        if code.is_root() {
+            return Some(code);
+        }
+        data.codes.by_ref(code.parent()).ok()
+    }
+}
+
 impl GMElement for Script {
     fn deserialize(reader: &mut DataReader) -> Result<Self> {
         let name: GMRef<String> = reader.read_gm_string()?;
@@ -65,6 +103,36 @@ impl GMElement for Script {
 }
 
 impl GMData {
+    /// Finds the script owning the specified code entry.
+    ///
+    /// This is the script referencing the code entry directly.
+    /// If there is no such script and the code entry is a GMS 2.3+ global
+    /// script, the first script declared in it is returned instead.
+    ///
+    /// Returns [`None`] if the code entry does not belong to any script
+    /// (for example, object event code).
+    #[must_use]
+    pub fn script_of_code(&self, code: GMRef<Code>) -> Option<GMRef<Script>> {
+        if code.is_none() {
+            return None;
+        }
+        let direct = self
+            .scripts
+            .element_refs()
+            .find(|(_, script)| script.code == code);
+        if let Some((script_ref, _)) = direct {
+            return Some(script_ref);
+        }
+        self.scripts
+            .element_refs()
+            .find(|(_, script)| {
+                self.codes
+                    .by_ref(script.code)
+                    .is_ok_and(|script_code| script_code.parent() == code)
+            })
+            .map(|(script_ref, _)| script_ref)
+    }
+
     pub fn make_script(&mut self, name: &str, instructions: Vec<Instruction>) -> GMRef<Script> {
         if let Ok(script) = self.scripts.ref_by_name(name, &self.strings) {
             return script;
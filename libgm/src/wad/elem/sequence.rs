@@ -15,6 +15,7 @@ use crate::wad::GMVersion;
 use crate::wad::build::builder::DataBuilder;
 use crate::wad::chunk::gm_named_list_chunk;
 use crate::wad::elem::GMElement;
+use crate::wad::elem::string::Strings;
 use crate::wad::parse::reader::DataReader;
 
 /// This struct belong to the chunk SEQN.
@@ -141,6 +142,61 @@ impl GMElement for Sequence {
     }
 }
 
+impl Sequence {
+    /// Returns all tracks of this sequence, including sub tracks (depth-first).
+    ///
+    /// For only the top-level tracks, use [`Self::tracks`] directly.
+    #[doc(alias = "tracks")]
+    #[must_use]
+    pub fn all_tracks(&self) -> Vec<&Track> {
+        fn collect<'a>(tracks: &'a [Track], out: &mut Vec<&'a Track>) {
+            for track in tracks {
+                out.push(track);
+                collect(&track.sub_tracks, out);
+            }
+        }
+        let mut tracks: Vec<&Track> = Vec::new();
+        collect(&self.tracks, &mut tracks);
+        tracks
+    }
+
+    /// Finds the track (or sub track) with the specified name.
+    ///
+    /// Property tracks of instance tracks are usually named after the
+    /// property, such as `position`, `rotation` or `blend_multiply`.
+    pub fn track_by_name(&self, name: &str, strings: &Strings) -> Result<&Track> {
+        for track in self.all_tracks() {
+            if strings.by_ref(track.name)? == name {
+                return Ok(track);
+            }
+        }
+        bail!("Could not find sequence track with name {name:?}");
+    }
+
+    /// Finds the track (or sub track) with the specified name, mutably.
+    ///
+    /// For more information, see [`Self::track_by_name`].
+    pub fn track_by_name_mut(&mut self, name: &str, strings: &Strings) -> Result<&mut Track> {
+        fn find<'a>(
+            tracks: &'a mut [Track],
+            name: &str,
+            strings: &Strings,
+        ) -> Result<Option<&'a mut Track>> {
+            for track in tracks {
+                if strings.by_ref(track.name)? == name {
+                    return Ok(Some(track));
+                }
+                if let Some(sub_track) = find(&mut track.sub_tracks, name, strings)? {
+                    return Ok(Some(sub_track));
+                }
+            }
+            Ok(None)
+        }
+        find(&mut self.tracks, name, strings)?
+            .ok_or_else(|| err!("Could not find sequence track with name {name:?}"))
+    }
+}
+
 gm_enum!(PlaybackType {
     /// holy shit oneshot reference
     OneShot = 0,
@@ -45,7 +45,29 @@ pub struct Track {
     pub owned_resources: Vec<AnimationCurve>,
 
     // "GMAnimCurve"
-    animcurve_string: GMRef<String>,
+    pub(crate) animcurve_string: GMRef<String>,
+}
+
+impl Track {
+    /// Creates a track without tags, sub tracks or owned resources.
+    ///
+    /// The model name (such as `GMRealTrack`) has to match the type of
+    /// the keyframes.
+    #[must_use]
+    pub const fn new(model_name: GMRef<String>, name: GMRef<String>, keyframes: Keyframes) -> Self {
+        Self {
+            model_name,
+            name,
+            builtin_name: BuiltinName::None,
+            flags: Flags::empty(),
+            is_creation_track: false,
+            tags: Vec::new(),
+            sub_tracks: Vec::new(),
+            keyframes,
+            owned_resources: Vec::new(),
+            animcurve_string: GMRef::none(),
+        }
+    }
 }
 
 impl GMElement for Track {
@@ -49,6 +49,94 @@ pub enum Keyframes {
     BroadcastMessage(Data<BroadcastMessage>),
 }
 
+/// Runs the same expression for the keyframe data of every variant.
+macro_rules! each_variant {
+    ($keyframes:expr, $data:ident => $body:expr) => {
+        match $keyframes {
+            Self::Audio($data) => $body,
+            Self::Instance($data) => $body,
+            Self::Graphic($data) => $body,
+            Self::Sequence($data) => $body,
+            Self::SpriteFrames($data) => $body,
+            Self::Bool($data) => $body,
+            Self::String($data) => $body,
+            Self::Color($data) | Self::Real($data) => $body,
+            Self::Text($data) => $body,
+            Self::Particle($data) => $body,
+            Self::BroadcastMessage($data) => $body,
+        }
+    };
+}
+
+impl Keyframes {
+    /// The number of keyframes in this track.
+    #[must_use]
+    pub const fn len(&self) -> usize {
+        each_variant!(self, data => data.keyframes.len())
+    }
+
+    /// Whether this track has no keyframes.
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The start times (keys) of all keyframes in this track, in order.
+    #[must_use]
+    pub fn times(&self) -> Vec<f32> {
+        each_variant!(self, data => data.keyframes.iter().map(|keyframe| keyframe.key).collect())
+    }
+
+    /// Removes the keyframe starting at the specified time.
+    ///
+    /// Returns whether a keyframe was removed.
+    pub fn remove_at(&mut self, key: f32) -> bool {
+        each_variant!(self, data => remove_at(&mut data.keyframes, key).is_some())
+    }
+
+    /// Gets the keyframes of this real (number) track,
+    /// such as the position, rotation or alpha of an instance.
+    /// Returns an error if the track has a different type.
+    pub fn as_real(&self) -> Result<&color::KeyframesData> {
+        match self {
+            Self::Real(data) => Ok(data),
+            _ => bail!(
+                "Expected real keyframes but found {} keyframes",
+                self.type_name()
+            ),
+        }
+    }
+
+    /// Gets the mutable keyframes of this real (number) track.
+    ///
+    /// For more information, see [`Self::as_real`].
+    pub fn as_real_mut(&mut self) -> Result<&mut color::KeyframesData> {
+        let type_name: &str = self.type_name();
+        match self {
+            Self::Real(data) => Ok(data),
+            _ => bail!("Expected real keyframes but found {type_name} keyframes"),
+        }
+    }
+
+    /// The name of this keyframes variant, for use in error messages.
+    const fn type_name(&self) -> &'static str {
+        match self {
+            Self::Audio(_) => "audio",
+            Self::Instance(_) => "instance",
+            Self::Graphic(_) => "graphic",
+            Self::Sequence(_) => "sequence",
+            Self::SpriteFrames(_) => "sprite frames",
+            Self::Bool(_) => "bool",
+            Self::String(_) => "string",
+            Self::Color(_) => "color",
+            Self::Real(_) => "real",
+            Self::Text(_) => "text",
+            Self::Particle(_) => "particle",
+            Self::BroadcastMessage(_) => "broadcast message",
+        }
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 #[expect(private_bounds)]
 pub struct Data<T: GMElement> {
@@ -73,6 +161,27 @@ impl<T: GMElement> GMElement for Data<T> {
     }
 }
 
+#[expect(private_bounds)]
+impl<T: GMElement> Data<T> {
+    /// Inserts a keyframe, keeping the keyframes sorted by time.
+    ///
+    /// Returns the index of the inserted keyframe.
+    pub fn insert(&mut self, keyframe: Keyframe<T>) -> usize {
+        insert_sorted(&mut self.keyframes, keyframe)
+    }
+
+    /// Removes the keyframe starting at the specified time.
+    pub fn remove_at(&mut self, key: f32) -> Option<Keyframe<T>> {
+        remove_at(&mut self.keyframes, key)
+    }
+
+    /// Finds the keyframe which is active at the specified time.
+    #[must_use]
+    pub fn at(&self, time: f32) -> Option<&Keyframe<T>> {
+        active_at(&self.keyframes, time)
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 #[expect(private_bounds)]
 pub struct Keyframe<T: GMElement> {
@@ -102,6 +211,43 @@ impl<T: GMElement> GMElement for Keyframe<T> {
     }
 }
 
+#[expect(private_bounds)]
+impl<T: GMElement> Keyframe<T> {
+    /// Creates an enabled keyframe starting at `key` and lasting `length`
+    /// frames, with one channel per value (numbered from 0).
+    ///
+    /// For example, position keyframes have two channels (x and y).
+    pub fn new(key: f32, length: f32, values: impl IntoIterator<Item = T>) -> Self {
+        let channels: Vec<Channel<T>> = values
+            .into_iter()
+            .zip(0..)
+            .map(|(value, id)| Channel { id, value })
+            .collect();
+        Self {
+            key,
+            length,
+            stretch: false,
+            disabled: false,
+            channels,
+        }
+    }
+
+    /// The time at which this keyframe ends.
+    #[must_use]
+    pub fn end(&self) -> f32 {
+        self.key + self.length
+    }
+
+    /// Gets the value of the channel with the specified ID.
+    #[must_use]
+    pub fn value(&self, channel_id: i32) -> Option<&T> {
+        self.channels
+            .iter()
+            .find(|channel| channel.id == channel_id)
+            .map(|channel| &channel.value)
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 #[expect(private_bounds)]
 pub struct Channel<T: GMElement> {
@@ -125,3 +271,23 @@ impl<T: GMElement> GMElement for Channel<T> {
         Ok(())
     }
 }
+
+fn insert_sorted<T: GMElement>(keyframes: &mut Vec<Keyframe<T>>, keyframe: Keyframe<T>) -> usize {
+    let index: usize = keyframes.partition_point(|other| other.key <= keyframe.key);
+    keyframes.insert(index, keyframe);
+    index
+}
+
+/// Keys are matched exactly since they are usually whole frame numbers.
+#[expect(clippy::float_cmp)]
+fn remove_at<T: GMElement>(keyframes: &mut Vec<Keyframe<T>>, key: f32) -> Option<Keyframe<T>> {
+    let index: usize = keyframes.iter().position(|keyframe| keyframe.key == key)?;
+    Some(keyframes.remove(index))
+}
+
+fn active_at<T: GMElement>(keyframes: &[Keyframe<T>], time: f32) -> Option<&Keyframe<T>> {
+    keyframes
+        .iter()
+        .rev()
+        .find(|keyframe| keyframe.key <= time && time < keyframe.end())
+}
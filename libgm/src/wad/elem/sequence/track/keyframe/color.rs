@@ -1,6 +1,9 @@
 // SPDX-License-Identifier: GPL-3.0-only
 
 use super::Keyframe;
+use super::active_at;
+use super::insert_sorted;
+use super::remove_at;
 use crate::gm_enum::gm_enum;
 use crate::prelude::*;
 use crate::wad::build::builder::DataBuilder;
@@ -29,6 +32,27 @@ impl GMElement for KeyframesData {
     }
 }
 
+impl KeyframesData {
+    /// Inserts a keyframe, keeping the keyframes sorted by time.
+    ///
+    /// Returns the index of the inserted keyframe.
+    pub fn insert(&mut self, keyframe: Keyframe<Color>) -> usize {
+        insert_sorted(&mut self.keyframes, keyframe)
+    }
+
+    /// Removes the keyframe starting at the specified time.
+    pub fn remove_at(&mut self, key: f32) -> Option<Keyframe<Color>> {
+        remove_at(&mut self.keyframes, key)
+    }
+
+    /// Finds the keyframe which is active at the specified time.
+    #[must_use]
+    pub fn at(&self, time: f32) -> Option<&Keyframe<Color>> {
+        active_at(&self.keyframes, time)
+    }
+}
+
+/// A single number value of a color or real keyframe channel.
 #[derive(Debug, Clone, PartialEq)]
 pub struct Color {
     pub value: f32,
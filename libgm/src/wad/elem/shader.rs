@@ -287,7 +287,7 @@ fn read_shader_data(
 
     if expected_length == 0 {
         let data: Vec<u8> = reader.read_bytes_dyn(actual_length)?.to_vec();
-        return Ok(Some(Blob(data)));
+        return Ok(Some(Blob::new(data)));
     }
 
     if expected_length > actual_length {
@@ -307,7 +307,7 @@ fn read_shader_data(
     }
 
     let data: Vec<u8> = reader.read_bytes_dyn(expected_length)?.to_vec();
-    Ok(Some(Blob(data)))
+    Ok(Some(Blob::new(data)))
 }
 
 fn write_shader_data(builder: &mut DataBuilder, pad: u32, shader_data: &ShaderData) -> Result<()> {
@@ -1,5 +1,7 @@
 // SPDX-License-Identifier: GPL-3.0-only
 
+use std::collections::HashMap;
+
 use crate::prelude::*;
 use crate::wad::build::builder::DataBuilder;
 use crate::wad::chunk::gm_named_list_chunk;
@@ -149,11 +151,180 @@ impl GMElement for Sound {
     }
 }
 
+impl Sound {
+    /// The audio group this sound belongs to.
+    ///
+    /// Unlike [`Self::audio_group`], this is never [`GMRef::none`].
+    /// Sounds without an explicit audio group (before WAD 14 or without
+    /// the [`Flags::REGULAR`] flag) belong to the default audio group,
+    /// which is always the first one.
+    #[must_use]
+    pub const fn effective_audio_group(&self) -> GMRef<AudioGroup> {
+        if self.audio_group.is_some() {
+            self.audio_group
+        } else {
+            GMRef::new(0)
+        }
+    }
+
+    /// Sets the volume ([`Self::volume`]) this sound is played at.
+    ///
+    /// Returns an error if the gain is not within `0.0` - `1.0`.
+    pub fn set_gain(&mut self, gain: f32) -> Result<()> {
+        if !(0.0..=1.0).contains(&gain) {
+            bail!("Sound gain {gain} is not within 0.0 - 1.0");
+        }
+        self.volume = gain;
+        Ok(())
+    }
+
+    /// Sets the pitch of this sound.
+    ///
+    /// Returns an error if the pitch is not a finite number.
+    pub fn set_pitch(&mut self, pitch: f32) -> Result<()> {
+        if !pitch.is_finite() {
+            bail!("Sound pitch {pitch} is not a finite number");
+        }
+        self.pitch = pitch;
+        Ok(())
+    }
+
+    /// Whether the audio data is stored in the data file (or audio group file).
+    #[must_use]
+    pub const fn is_embedded(&self) -> bool {
+        self.flags.contains(Flags::EMBEDDED)
+    }
+
+    /// Whether the audio data is compressed (Ogg Vorbis).
+    #[must_use]
+    pub const fn is_compressed(&self) -> bool {
+        self.flags.contains(Flags::COMPRESSED)
+    }
+
+    /// Whether the audio data is streamed from an external file on disk.
+    ///
+    /// See [`Self::file`] for the file name.
+    #[must_use]
+    pub const fn is_streamed(&self) -> bool {
+        matches!(self.attributes(), Attributes::CompressedStreamed)
+    }
+
+    /// The audio attributes of this sound, decoded from its flags.
+    #[must_use]
+    pub const fn attributes(&self) -> Attributes {
+        match (self.is_embedded(), self.is_compressed()) {
+            (true, false) => Attributes::Uncompressed,
+            (false, true) => Attributes::Compressed,
+            (true, true) => Attributes::DecompressOnLoad,
+            (false, false) => Attributes::CompressedStreamed,
+        }
+    }
+
+    /// Sets the audio attributes of this sound by updating its flags.
+    ///
+    /// This only changes the flags; other flags like [`Flags::REGULAR`] are kept.
+    /// You have to update the audio data ([`Self::audio`]) or the external
+    /// audio file ([`Self::file`]) accordingly yourself.
+    pub fn set_attributes(&mut self, attributes: Attributes) {
+        let (embedded, compressed) = match attributes {
+            Attributes::Uncompressed => (true, false),
+            Attributes::Compressed => (false, true),
+            Attributes::DecompressOnLoad => (true, true),
+            Attributes::CompressedStreamed => (false, false),
+        };
+        self.flags.set(Flags::EMBEDDED, embedded);
+        self.flags.set(Flags::COMPRESSED, compressed);
+    }
+}
+
+impl GMData {
+    /// Gets the embedded audio data of the specified sound.
+    ///
+    /// Returns [`None`] if the audio data is not stored in this data file:
+    /// * The sound is streamed from an external file (see [`Sound::is_streamed`]).
+    /// * The sound belongs to an audio group other than the default one,
+    ///   which means its audio is stored in a separate `audiogroupN.dat` file.
+    /// * The sound has no audio entry.
+    pub fn sound_audio(&self, sound: GMRef<Sound>) -> Result<Option<&Audio>> {
+        let sound = self.sounds.by_ref(sound)?;
+        if sound.is_streamed() || sound.effective_audio_group() != GMRef::new(0) {
+            return Ok(None);
+        }
+        if sound.audio.is_none() {
+            return Ok(None);
+        }
+        let audio = self
+            .audios
+            .by_ref(sound.audio)
+            .ctx("resolving audio of sound")?;
+        Ok(Some(audio))
+    }
+
+    /// Gets all sounds belonging to the specified audio group, in order.
+    ///
+    /// For more information, see [`Sound::effective_audio_group`].
+    #[must_use]
+    pub fn sounds_in_group(&self, audio_group: GMRef<AudioGroup>) -> Vec<GMRef<Sound>> {
+        self.sounds
+            .element_refs()
+            .filter(|(_, sound)| sound.effective_audio_group() == audio_group)
+            .map(|(sound_ref, _)| sound_ref)
+            .collect()
+    }
+
+    /// Groups all sounds by the audio group they belong to.
+    ///
+    /// Audio groups without any sounds are not included.
+    /// If you only need the sounds of a single audio group,
+    /// use [`Self::sounds_in_group`] instead.
+    #[must_use]
+    pub fn audio_group_sounds(&self) -> HashMap<GMRef<AudioGroup>, Vec<GMRef<Sound>>> {
+        let mut map: HashMap<GMRef<AudioGroup>, Vec<GMRef<Sound>>> = HashMap::new();
+        for (sound_ref, sound) in self.sounds.element_refs() {
+            map.entry(sound.effective_audio_group())
+                .or_default()
+                .push(sound_ref);
+        }
+        map
+    }
+}
+
 bitflags::bitflags! {
+    /// The flags of a sound.
+    ///
+    /// The combination of [`Flags::EMBEDDED`] and [`Flags::COMPRESSED`]
+    /// determines the sound's [`Attributes`].
     #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
     pub struct Flags: u32 {
+        /// The audio data is stored in an [`Audio`] entry instead of an external file.
+        /// If [`Flags::COMPRESSED`] is set too, the audio is decompressed on load.
         const EMBEDDED = 0x1;
+        /// The audio data is compressed (Ogg Vorbis).
+        /// If [`Flags::EMBEDDED`] is not set, the compressed audio is stored in an [`Audio`] entry
+        /// anyway; only sounds with neither flag are streamed.
         const COMPRESSED = 0x2;
+        /// The sound uses the regular audio system (as opposed to the legacy audio system).
+        /// Only regular sounds can belong to an [`AudioGroup`].
+        /// This seems to be always set in GameMaker: Studio and above.
         const REGULAR = 0x64;
     }
 }
+
+/// The audio attributes of a sound, as shown in the GameMaker IDE.
+///
+/// These are stored in the sound's [`Flags`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Attributes {
+    /// Uncompressed, not streamed (WAV).
+    /// Sets [`Flags::EMBEDDED`].
+    Uncompressed,
+    /// Compressed, not streamed (Ogg Vorbis).
+    /// Sets [`Flags::COMPRESSED`].
+    Compressed,
+    /// Compressed, but decompressed when loading the game.
+    /// Sets [`Flags::EMBEDDED`] and [`Flags::COMPRESSED`].
+    DecompressOnLoad,
+    /// Compressed and streamed from an external file on disk.
+    /// Sets neither [`Flags::EMBEDDED`] nor [`Flags::COMPRESSED`].
+    CompressedStreamed,
+}
@@ -1,4 +1,5 @@
 // SPDX-License-Identifier: GPL-3.0-only
+mod frames;
 pub mod nine_slice;
 pub mod spine;
 pub mod swf;
@@ -149,7 +150,7 @@ impl GMElement for Sprite {
                     SpecialData::Swf(swf::Data {
                         swf_version,
                         yyswf_version,
-                        jpeg_table: Blob(jpeg_table),
+                        jpeg_table: Blob::new(jpeg_table),
                         timeline,
                     })
                 }
@@ -191,7 +192,9 @@ impl GMElement for Sprite {
                             spine_textures.push(spine::TextureEntry {
                                 page_width,
                                 page_height,
-                                data: spine::texture_entry::Data::Pre2023_1(Blob(texture_blob)),
+                                data: spine::texture_entry::Data::Pre2023_1(Blob::new(
+                                    texture_blob,
+                                )),
                             });
                         }
                         2 | 3 => {
@@ -455,6 +458,108 @@ impl GMElement for Sprite {
 }
 
 impl Sprite {
+    /// The sequence of this sprite, if it has one.
+    ///
+    /// Only special sprites (GMS 2+) with special version 2 or higher can store a sequence.
+    #[must_use]
+    pub fn sequence(&self) -> Option<&Sequence> {
+        self.special_fields.as_ref()?.sequence.as_ref()
+    }
+
+    /// The mutable sequence of this sprite, if it has one.
+    ///
+    /// For more information, see [`Self::sequence`].
+    pub fn sequence_mut(&mut self) -> Option<&mut Sequence> {
+        self.special_fields.as_mut()?.sequence.as_mut()
+    }
+
+    /// Attaches a sequence to this sprite, replacing the existing one.
+    ///
+    /// This only works for normal special sprites (not SWF or Spine).
+    /// If the special version is lower than 2, it is raised to 2 so that the
+    /// sequence is actually serialized.
+    ///
+    /// Returns the previous sequence, if there was one.
+    pub fn set_sequence(&mut self, sequence: Sequence) -> Result<Option<Sequence>> {
+        let special: &mut Special = self
+            .special_fields
+            .as_mut()
+            .ok_or("Sprite is not a special sprite; sequences require GMS 2 special sprites")?;
+
+        if !matches!(special.data, SpecialData::Normal) {
+            bail!("Sequences can only be attached to normal special sprites, not SWF or Spine");
+        }
+
+        special.special_version = special.special_version.max(2);
+        Ok(special.sequence.replace(sequence))
+    }
+
+    /// Removes the sequence of this sprite, returning it (if it had one).
+    pub fn remove_sequence(&mut self) -> Option<Sequence> {
+        self.special_fields.as_mut()?.sequence.take()
+    }
+
+    /// The nine slice data of this sprite, if it has one.
+    ///
+    /// Only special sprites (GMS 2+) with special version 3 or higher can store
+    /// nine slice data.
+    #[must_use]
+    pub fn nine_slice(&self) -> Option<&NineSlice> {
+        self.special_fields.as_ref()?.nine_slice.as_ref()
+    }
+
+    /// The mutable nine slice data of this sprite, if it has one.
+    ///
+    /// For more information, see [`Self::nine_slice`].
+    pub fn nine_slice_mut(&mut self) -> Option<&mut NineSlice> {
+        self.special_fields.as_mut()?.nine_slice.as_mut()
+    }
+
+    /// Attaches nine slice data to this sprite, replacing the existing one.
+    ///
+    /// If the special version is lower than 3, it is raised to 3 so that the
+    /// nine slice data is actually serialized.
+    ///
+    /// Returns the previous nine slice data, if there was one.
+    pub fn set_nine_slice(&mut self, nine_slice: NineSlice) -> Result<Option<NineSlice>> {
+        let special: &mut Special = self
+            .special_fields
+            .as_mut()
+            .ok_or("Sprite is not a special sprite; nine slices require GMS 2 special sprites")?;
+
+        special.special_version = special.special_version.max(3);
+        Ok(special.nine_slice.replace(nine_slice))
+    }
+
+    /// Removes the nine slice data of this sprite, returning it (if it had one).
+    pub fn remove_nine_slice(&mut self) -> Option<NineSlice> {
+        self.special_fields.as_mut()?.nine_slice.take()
+    }
+
+    /// Whether nine slicing is enabled for this sprite.
+    #[must_use]
+    pub fn is_nine_slice_enabled(&self) -> bool {
+        self.nine_slice()
+            .is_some_and(|nine_slice| nine_slice.enabled)
+    }
+
+    /// Enables or disables nine slicing for this sprite.
+    ///
+    /// When enabling nine slicing on a sprite without nine slice data,
+    /// new data with zero margins is attached (see [`Self::set_nine_slice`]).
+    /// Disabling keeps the nine slice data.
+    ///
+    /// Nine slice data is only supported since GMS 2.3.2; use
+    /// [`GMData::set_sprite_nine_slice_enabled`] to check the version.
+    pub fn set_nine_slice_enabled(&mut self, enabled: bool) -> Result<()> {
+        if let Some(nine_slice) = self.nine_slice_mut() {
+            nine_slice.enabled = enabled;
+        } else if enabled {
+            self.set_nine_slice(NineSlice::new(0, 0, 0, 0))?;
+        }
+        Ok(())
+    }
+
     fn read_texture_list(reader: &mut DataReader) -> Result<Vec<GMRef<TexturePageItem>>> {
         let count = reader.read_count("Sprite texture")?;
         let ctx = || format!("reading {count} Sprite textures");
@@ -508,6 +613,36 @@ impl Sprite {
     }
 }
 
+impl GMData {
+    /// Gets the sequence of the sprite with the specified name, if it has one.
+    ///
+    /// For more information, see [`Sprite::sequence`].
+    pub fn sprite_sequence(&self, sprite_name: &str) -> Result<Option<&Sequence>> {
+        let sprite: &Sprite = self.sprites.by_name(sprite_name, &self.strings)?;
+        Ok(sprite.sequence())
+    }
+
+    /// Enables or disables nine slicing for the specified sprite.
+    ///
+    /// Sprites only store nine slice data since GMS 2.3.2, so this returns an
+    /// error for older versions.
+    ///
+    /// For more information, see [`Sprite::set_nine_slice_enabled`].
+    pub fn set_sprite_nine_slice_enabled(
+        &mut self,
+        sprite_ref: GMRef<Sprite>,
+        enabled: bool,
+    ) -> Result<()> {
+        let version = self.meta.version;
+        if version < GMVersion::GMS2_3_2 {
+            bail!("Sprites cannot store nine slice data before GMS 2.3.2 (version is {version})");
+        }
+        self.sprites
+            .by_ref_mut(sprite_ref)?
+            .set_nine_slice_enabled(enabled)
+    }
+}
+
 #[derive(Debug, Clone, PartialEq)]
 pub struct Special {
     /// Version of Special Thingy
@@ -557,7 +692,7 @@ fn read_mask_data(reader: &mut DataReader, width: u32, height: u32) -> Result<Ve
             .read_bytes_dyn(length)
             .ctx("reading Mask Data")?
             .to_vec();
-        collision_masks.push(MaskEntry { data: Blob(data), width, height });
+        collision_masks.push(MaskEntry { data: Blob::new(data), width, height });
     }
 
     reader.align(4)?;
@@ -0,0 +1,347 @@
+// SPDX-License-Identifier: GPL-3.0-only
+//! Decoding sprite frames, compositing them into spritesheets and replacing
+//! them.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use image::DynamicImage;
+use image::RgbaImage;
+use image::imageops;
+
+use crate::prelude::*;
+use crate::wad::Blob;
+use crate::wad::elem::sprite::MaskEntry;
+use crate::wad::elem::sprite::Sprite;
+use crate::wad::elem::texture_page::GMImage;
+use crate::wad::elem::texture_page::TexturePage;
+use crate::wad::elem::texture_page::make_page;
+use crate::wad::elem::texture_page_item::TexturePageItem;
+use crate::wad::version::GMVersion;
+
+impl GMData {
+    /// Decodes all frames of the specified sprite, in frame order.
+    ///
+    /// Every frame has the size of the sprite. Frames without a texture are
+    /// fully transparent.
+    ///
+    /// The frames may be spread across multiple texture pages; every page is
+    /// only decoded once.
+    /// Returns an error if a frame is stored in an external texture page.
+    pub fn sprite_frames(&self, sprite: GMRef<Sprite>) -> Result<Vec<RgbaImage>> {
+        let sprite: &Sprite = self.sprites.by_ref(sprite)?;
+        let mut pages: HashMap<GMRef<TexturePage>, RgbaImage> = HashMap::new();
+        let mut frames: Vec<RgbaImage> = Vec::with_capacity(sprite.textures.len());
+
+        for (i, &texture) in sprite.textures.iter().enumerate() {
+            let mut frame = RgbaImage::new(sprite.width, sprite.height);
+            if texture.is_some() {
+                let item: &TexturePageItem = self.texture_page_items.by_ref(texture)?;
+                let page: &RgbaImage = if let Some(page) = pages.get(&item.texture_page) {
+                    page
+                } else {
+                    let index = item.texture_page.index().ok_or("Texture page is null")?;
+                    let page: RgbaImage = self.texture_page_image(index)?;
+                    pages.entry(item.texture_page).or_insert(page)
+                };
+                let image: RgbaImage = item
+                    .extract_from(page)
+                    .ctx(|| format!("extracting frame #{i}"))?;
+                imageops::replace(&mut frame, &image, 0, 0);
+            }
+            frames.push(frame);
+        }
+
+        Ok(frames)
+    }
+
+    /// Composites all frames of the specified sprite into a horizontal
+    /// spritesheet, from left to right in frame order.
+    ///
+    /// Transparency is preserved.
+    /// Sprites without frames result in an empty image, which cannot be
+    /// encoded as PNG.
+    /// For more information, see [`GMData::sprite_frames`].
+    pub fn sprite_spritesheet(&self, sprite: GMRef<Sprite>) -> Result<RgbaImage> {
+        let frames: Vec<RgbaImage> = self.sprite_frames(sprite)?;
+        let sprite: &Sprite = self.sprites.by_ref(sprite)?;
+        let count = u32::try_from(frames.len()).map_err(|_| "Sprite has too many frames")?;
+        let width = sprite
+            .width
+            .checked_mul(count)
+            .ok_or("Spritesheet width overflowed")?;
+
+        let mut sheet = RgbaImage::new(width, sprite.height);
+        for (i, frame) in frames.iter().enumerate() {
+            let x = i64::from(sprite.width) * i as i64;
+            imageops::replace(&mut sheet, frame, x, 0);
+        }
+        Ok(sheet)
+    }
+
+    /// Writes the horizontal spritesheet of the specified sprite to a PNG
+    /// file.
+    ///
+    /// This requires the `png-image` crate feature.
+    /// For more information, see [`GMData::sprite_spritesheet`].
+    pub fn export_sprite_spritesheet(
+        &self,
+        sprite: GMRef<Sprite>,
+        path: impl AsRef<Path>,
+    ) -> Result<()> {
+        let path: &Path = path.as_ref();
+        let sheet: RgbaImage = self.sprite_spritesheet(sprite)?;
+        let png: Vec<u8> = GMImage::from_dynamic_image(DynamicImage::ImageRgba8(sheet)).to_png()?;
+        std::fs::write(path, png).ctx_any(|| format!("writing spritesheet to {}", path.display()))
+    }
+
+    /// Replaces a single frame of the specified sprite with an image.
+    ///
+    /// `rgba` contains the `width * height` pixels of the image row by row,
+    /// with four bytes (red, green, blue, alpha) per pixel.
+    ///
+    /// If the frame's current texture region is big enough, the image is drawn
+    /// onto the existing texture page (clearing the rest of the region).
+    /// Otherwise, a new texture page containing only this frame is created in
+    /// the same texture group. Either way, the texture page is re-encoded in its
+    /// previous format and the frame's texture page item is updated to cover
+    /// the new image exactly. Note that texture page items can be shared with
+    /// other assets, which will then show the new image as well.
+    ///
+    /// All frames of a sprite have the same size. If the sprite only has one
+    /// frame, its size is set to the image size (and its bounding box margins
+    /// are clamped to it). Its collision masks are then regenerated from the
+    /// opaque pixels of the image. Otherwise, the image has to have the
+    /// sprite's size.
+    pub fn set_sprite_frame_image(
+        &mut self,
+        sprite_ref: GMRef<Sprite>,
+        frame: usize,
+        width: u32,
+        height: u32,
+        rgba: &[u8],
+    ) -> Result<()> {
+        let ctx = || format!("setting image of sprite frame #{frame}");
+        let (width16, height16) = match (u16::try_from(width), u16::try_from(height)) {
+            (Ok(w), Ok(h)) if w > 0 && h > 0 => (w, h),
+            _ => bail!("Invalid sprite frame image size {width}x{height}"),
+        };
+        let expected_len = u64::from(width) * u64::from(height) * 4;
+        let image = RgbaImage::from_raw(width, height, rgba.to_vec()).ok_or_else(|| {
+            err!(
+                "Expected {expected_len} bytes of RGBA data for a {width}x{height} image; got {}",
+                rgba.len(),
+            )
+        })?;
+
+        let sprite: &Sprite = self.sprites.by_ref(sprite_ref)?;
+        let frame_count = sprite.textures.len();
+        let texture: GMRef<TexturePageItem> = *sprite.textures.get(frame).ok_or_else(|| {
+            err!("Sprite frame #{frame} does not exist; the sprite has {frame_count} frames")
+        })?;
+        if frame_count > 1 && (width, height) != (sprite.width, sprite.height) {
+            bail!(
+                "Image size {width}x{height} does not match size {}x{} of sprite with \
+                 {frame_count} frames",
+                sprite.width,
+                sprite.height,
+            );
+        }
+
+        // Single frame sprites are resized to the image. Their collision masks
+        // are regenerated, since masks of the old size would fail to build.
+        let resized: Option<(Margins, Vec<MaskEntry>)> =
+            ((sprite.width, sprite.height) != (width, height)).then(|| {
+                let max_x = i32::from(width16) - 1;
+                let max_y = i32::from(height16) - 1;
+                let margins = Margins {
+                    left: sprite.margin_left.clamp(0, max_x),
+                    right: sprite.margin_right.clamp(0, max_x),
+                    top: sprite.margin_top.clamp(0, max_y),
+                    bottom: sprite.margin_bottom.clamp(0, max_y),
+                };
+                let mask = generate_collision_mask(&image, &margins, self.meta.version);
+                (margins, vec![mask; sprite.collision_masks.len()])
+            });
+
+        let item: TexturePageItem = self.place_texture_image(texture, image).ctx(ctx)?;
+        if texture.is_some() {
+            *self.texture_page_items.by_ref_mut(texture)? = item;
+        } else {
+            let texture: GMRef<TexturePageItem> = self.texture_page_items.push(item);
+            self.sprites.by_ref_mut(sprite_ref)?.textures[frame] = texture;
+        }
+
+        if let Some((margins, collision_masks)) = resized {
+            let sprite: &mut Sprite = self.sprites.by_ref_mut(sprite_ref)?;
+            sprite.width = width;
+            sprite.height = height;
+            sprite.margin_left = margins.left;
+            sprite.margin_right = margins.right;
+            sprite.margin_top = margins.top;
+            sprite.margin_bottom = margins.bottom;
+            sprite.collision_masks = collision_masks;
+        }
+        Ok(())
+    }
+
+    /// Places the image on an embedded texture page and returns a texture page
+    /// item covering it exactly.
+    ///
+    /// If the region of the old texture page item is big enough, the image is
+    /// drawn onto its texture page (clearing the rest of the region).
+    /// Otherwise, a new texture page containing only this image is created in
+    /// the same texture group. Either way, the texture page is encoded in its
+    /// previous format. The old texture page item itself is not modified.
+    pub(crate) fn place_texture_image(
+        &mut self,
+        texture: GMRef<TexturePageItem>,
+        image: RgbaImage,
+    ) -> Result<TexturePageItem> {
+        let (Ok(width16), Ok(height16)) =
+            (u16::try_from(image.width()), u16::try_from(image.height()))
+        else {
+            bail!("Image size {}x{} is too big", image.width(), image.height());
+        };
+        let old_item: Option<&TexturePageItem> = if texture.is_some() {
+            Some(self.texture_page_items.by_ref(texture)?)
+        } else {
+            None
+        };
+        let old_page: Option<usize> =
+            old_item
+                .and_then(|item| item.texture_page.index())
+                .filter(|&page| {
+                    self.texture_pages
+                        .elems
+                        .get(page)
+                        .is_some_and(|p| p.image.is_some())
+                });
+        let fits: bool = old_item
+            .is_some_and(|item| item.source_width >= width16 && item.source_height >= height16);
+
+        let (texture_page, source_x, source_y) =
+            if let (Some(page), Some(item), true) = (old_page, old_item, fits) {
+                let region = (
+                    item.source_x,
+                    item.source_y,
+                    item.source_width,
+                    item.source_height,
+                );
+                self.draw_on_texture_page(page, region, &image)?;
+                (GMRef::from(page), region.0, region.1)
+            } else {
+                let template: usize = old_page
+                    .or_else(|| {
+                        self.texture_pages
+                            .elems
+                            .iter()
+                            .position(|p| p.image.is_some())
+                    })
+                    .ok_or("There is no embedded texture page to use as a template")?;
+                let page_ref = self.push_texture_page_like(template, image)?;
+                (page_ref, 0, 0)
+            };
+
+        let item = TexturePageItem {
+            source_x,
+            source_y,
+            source_width: width16,
+            source_height: height16,
+            target_x: 0,
+            target_y: 0,
+            target_width: width16,
+            target_height: height16,
+            bounding_width: width16,
+            bounding_height: height16,
+            texture_page,
+        };
+        Ok(item)
+    }
+
+    /// Clears the specified region (x, y, width, height) of an embedded
+    /// texture page and draws the image at its top left corner.
+    fn draw_on_texture_page(
+        &mut self,
+        page: usize,
+        (x, y, width, height): (u16, u16, u16, u16),
+        image: &RgbaImage,
+    ) -> Result<()> {
+        let clear = RgbaImage::new(u32::from(width), u32::from(height));
+        let mut canvas: RgbaImage = self.texture_page_image(page)?;
+        imageops::replace(&mut canvas, &clear, i64::from(x), i64::from(y));
+        imageops::replace(&mut canvas, image, i64::from(x), i64::from(y));
+
+        let texture_page: &mut TexturePage = &mut self.texture_pages.elems[page];
+        let format = texture_page.image.as_ref().map(GMImage::format);
+        let mut gm_image = GMImage::from_dynamic_image(DynamicImage::ImageRgba8(canvas));
+        if let Some(format) = format {
+            gm_image
+                .change_format(format)
+                .ctx(|| format!("encoding texture page #{page}"))?;
+        }
+        texture_page.image = Some(gm_image);
+        Ok(())
+    }
+
+    /// Adds a new texture page with the specified image, using the metadata
+    /// and texture group of the template page.
+    fn push_texture_page_like(
+        &mut self,
+        template: usize,
+        image: RgbaImage,
+    ) -> Result<GMRef<TexturePage>> {
+        let group = self
+            .texture_group_infos
+            .group_of_texture_page(GMRef::from(template));
+        let index_in_group: usize = match group {
+            Some(group) => self.texture_group_infos.by_ref(group)?.texture_pages.len(),
+            None => 0,
+        };
+        let new_page: TexturePage =
+            make_page(&self.texture_pages.elems[template], image, index_in_group)?;
+        let page_ref: GMRef<TexturePage> = self.texture_pages.push(new_page);
+        if let Some(group) = group {
+            self.texture_group_infos
+                .by_ref_mut(group)?
+                .texture_pages
+                .push(page_ref);
+        }
+        Ok(page_ref)
+    }
+}
+
+/// The bounding box of a sprite, in pixels (inclusive).
+struct Margins {
+    left: i32,
+    right: i32,
+    top: i32,
+    bottom: i32,
+}
+
+/// Generates a collision mask from the opaque pixels of an image.
+///
+/// Since GM 2024.6, masks only cover the bounding box of the sprite.
+fn generate_collision_mask(image: &RgbaImage, margins: &Margins, version: GMVersion) -> MaskEntry {
+    let (left, top, width, height) = if version >= GMVersion::GM2024_6 {
+        (
+            margins.left as u32,
+            margins.top as u32,
+            (margins.right - margins.left + 1) as u32,
+            (margins.bottom - margins.top + 1) as u32,
+        )
+    } else {
+        (0, 0, image.width(), image.height())
+    };
+
+    let stride = width.div_ceil(8) as usize;
+    let mut data: Vec<u8> = vec![0; stride * height as usize];
+    for y in 0..height {
+        for x in 0..width {
+            if image.get_pixel(left + x, top + y).0[3] != 0 {
+                data[y as usize * stride + x as usize / 8] |= 0x80 >> (x % 8);
+            }
+        }
+    }
+    MaskEntry { data: Blob::new(data), width, height }
+}
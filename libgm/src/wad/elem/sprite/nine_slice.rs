@@ -7,16 +7,87 @@ use crate::wad::build::builder::DataBuilder;
 use crate::wad::elem::GMElement;
 use crate::wad::parse::reader::DataReader;
 
+/// Nine slice data of a sprite (GMS 2.3.2+).
+///
+/// The margins split the sprite into nine slices: four corners, which are
+/// drawn unscaled, four edges and the center, which are drawn according to
+/// their [`TileMode`] when the sprite is stretched.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct NineSlice {
+    /// The left margin, in pixels.
     pub left: i32,
+    /// The top margin, in pixels.
     pub top: i32,
+    /// The right margin, in pixels.
     pub right: i32,
+    /// The bottom margin, in pixels.
     pub bottom: i32,
+    /// Whether nine slicing is enabled for this sprite.
     pub enabled: bool,
+    /// The tile modes of the left, top, right and bottom edge and the center,
+    /// in that order. Use [`Self::tile_mode`] for typed access.
     pub tile_modes: [TileMode; 5],
 }
 
+impl NineSlice {
+    /// Creates enabled nine slice data with the specified margins
+    /// (left, top, right, bottom) which stretches all slices.
+    #[must_use]
+    pub const fn new(left: i32, top: i32, right: i32, bottom: i32) -> Self {
+        Self {
+            left,
+            top,
+            right,
+            bottom,
+            enabled: true,
+            tile_modes: [TileMode::Stretch; 5],
+        }
+    }
+
+    /// The margins (left, top, right, bottom), in pixels.
+    #[must_use]
+    pub const fn margins(&self) -> (i32, i32, i32, i32) {
+        (self.left, self.top, self.right, self.bottom)
+    }
+
+    /// Sets the margins (left, top, right, bottom), in pixels.
+    ///
+    /// Returns an error if any margin is negative.
+    pub fn set_margins(&mut self, left: i32, top: i32, right: i32, bottom: i32) -> Result<()> {
+        if left < 0 || top < 0 || right < 0 || bottom < 0 {
+            bail!("Nine slice margins ({left}, {top}, {right}, {bottom}) must not be negative");
+        }
+        self.left = left;
+        self.top = top;
+        self.right = right;
+        self.bottom = bottom;
+        Ok(())
+    }
+
+    /// The tile mode of the specified slice.
+    #[must_use]
+    pub const fn tile_mode(&self, slice: Slice) -> TileMode {
+        self.tile_modes[slice as usize]
+    }
+
+    /// Sets the tile mode of the specified slice.
+    pub const fn set_tile_mode(&mut self, slice: Slice, tile_mode: TileMode) {
+        self.tile_modes[slice as usize] = tile_mode;
+    }
+}
+
+/// A slice of a [`NineSlice`] sprite that has a [`TileMode`].
+///
+/// Corners are always drawn unscaled, so they do not have a tile mode.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Slice {
+    Left = 0,
+    Top = 1,
+    Right = 2,
+    Bottom = 3,
+    Center = 4,
+}
+
 impl GMElement for NineSlice {
     fn deserialize(reader: &mut DataReader) -> Result<Self> {
         let left = reader.read_i32()?;
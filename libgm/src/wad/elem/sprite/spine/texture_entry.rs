@@ -24,7 +24,7 @@ impl GMElement for TextureEntry {
         } else {
             let size = reader.read_u32()?;
             let texture_blob: Vec<u8> = reader.read_bytes_dyn(size)?.to_vec();
-            Data::Pre2023_1(Blob(texture_blob))
+            Data::Pre2023_1(Blob::new(texture_blob))
         };
         Ok(Self { page_width, page_height, data })
     }
@@ -19,7 +19,7 @@ impl GMElement for CollisionMask {
             .ctx("reading RLE Data of Timeline")?
             .to_vec();
         reader.align(4)?; // [From UndertaleModTool] "why it's not aligned before the data is beyond my brain"
-        Ok(Self { rle_data: Blob(rle_data) })
+        Ok(Self { rle_data: Blob::new(rle_data) })
     }
 
     fn serialize(&self, builder: &mut DataBuilder) -> Result<()> {
@@ -56,9 +56,9 @@ impl GMElement for Data {
 
             reader.align(4)?;
             VersionData::Pre2022_1(VersionDataPre2022_1 {
-                image_data: Blob(image_data),
-                alpha_data: Blob(alpha_data),
-                color_palette_data: Blob(color_palette_data),
+                image_data: Blob::new(image_data),
+                alpha_data: Blob::new(alpha_data),
+                color_palette_data: Blob::new(color_palette_data),
             })
         };
 
@@ -1,10 +1,13 @@
 // SPDX-License-Identifier: GPL-3.0-only
+use std::collections::HashMap;
+
 use crate::prelude::*;
 use crate::util::assert;
 use crate::wad::build::builder::DataBuilder;
 use crate::wad::chunk::gm_list_chunk;
 use crate::wad::elem::GMElement;
 use crate::wad::parse::reader::DataReader;
+use crate::wad::visit_string_refs;
 
 const ALIGNMENT: u32 = 4;
 
@@ -96,3 +99,138 @@ impl Strings {
         GMRef::from(self.len() - 1)
     }
 }
+
+/// Describes how identical strings are merged.
+///
+/// See [`GMData::dedupe_strings`].
+#[derive(Debug, Clone)]
+struct StringMerge {
+    /// For every string, the index of the first string with the same text.
+    canonical: Vec<usize>,
+
+    /// For every string, the index of its surviving entry after merging.
+    merged_ids: Vec<usize>,
+}
+
+impl StringMerge {
+    fn new(strings: &[String]) -> Self {
+        let mut first_occurrences: HashMap<&str, usize> = HashMap::new();
+        let mut canonical: Vec<usize> = Vec::with_capacity(strings.len());
+        let mut merged_ids: Vec<usize> = Vec::with_capacity(strings.len());
+        let mut survivor_count: usize = 0;
+        for (index, string) in strings.iter().enumerate() {
+            let first: usize = *first_occurrences.entry(string).or_insert(index);
+            canonical.push(first);
+            if first == index {
+                merged_ids.push(survivor_count);
+                survivor_count += 1;
+            } else {
+                merged_ids.push(merged_ids[first]);
+            }
+        }
+        Self { canonical, merged_ids }
+    }
+
+    /// The number of strings that are merged into an earlier identical string.
+    fn merged_count(&self) -> usize {
+        self.canonical
+            .iter()
+            .enumerate()
+            .filter(|&(index, &first)| index != first)
+            .count()
+    }
+
+    /// Whether the string at this index is kept (not merged into an earlier one).
+    fn is_survivor(&self, index: usize) -> bool {
+        self.canonical[index] == index
+    }
+
+    /// The reference to the surviving string after merging.
+    /// Invalid references are returned unchanged.
+    fn merged_id(&self, string_ref: GMRef<String>) -> GMRef<String> {
+        match string_ref.index().and_then(|i| self.merged_ids.get(i)) {
+            Some(&id) => GMRef::from(id),
+            None => string_ref,
+        }
+    }
+}
+
+impl GMData {
+    /// Merges byte-identical strings into their first occurrence and
+    /// returns the number of merged (removed) strings.
+    ///
+    /// All string references are remapped to the surviving entry, which
+    /// shrinks the `STRG` chunk. This is safe because strings are values;
+    /// GameMaker never compares string entries by identity.
+    /// Note that the indices of all strings after the first merged one change.
+    ///
+    /// Returns an error if the data contains raw chunks (see
+    /// [`Metadata::raw_chunks`](crate::wad::data::Metadata::raw_chunks)),
+    /// since their string pointers cannot be updated.
+    #[doc(alias = "deduplicate_strings")]
+    pub fn dedupe_strings(&mut self) -> Result<usize> {
+        let merge = StringMerge::new(&self.strings.elems);
+        let merged_count: usize = merge.merged_count();
+        if merged_count == 0 {
+            return Ok(0);
+        }
+        if !self.meta.raw_chunks.is_empty() {
+            bail!("Cannot merge strings in data with raw (unparsed) chunks");
+        }
+
+        visit_string_refs(self, &mut |string_ref| {
+            *string_ref = merge.merged_id(*string_ref);
+        });
+        let mut index: usize = 0;
+        self.strings.elems.retain(|_| {
+            let keep: bool = merge.is_survivor(index);
+            index += 1;
+            keep
+        });
+        Ok(merged_count)
+    }
+
+    /// Imports translated strings from another build of the game.
+    ///
+    /// `original` and `translated` must be the same data file before and
+    /// after translating it, with strings at the same indices (which is how
+    /// translation patches usually work). Every string in this data whose text
+    /// matches a string in `original` is replaced with the string at the same
+    /// index in `translated`.
+    ///
+    /// This is useful for porting a translation to a newer version of a game.
+    /// If a text occurs multiple times in `original` with different
+    /// translations, the first one is used.
+    ///
+    /// Returns the number of replaced strings.
+    #[doc(alias = "import_matching_strings")]
+    pub fn merge_strings_from(&mut self, original: &Self, translated: &Self) -> usize {
+        let original: &[String] = &original.strings.elems;
+        let translated: &[String] = &translated.strings.elems;
+        if original.len() != translated.len() {
+            log::warn!(
+                "Original data has {} strings but translated data has {}; only matching the first \
+                 {}",
+                original.len(),
+                translated.len(),
+                original.len().min(translated.len()),
+            );
+        }
+
+        let mut translations: HashMap<&str, &str> = HashMap::new();
+        for (source, translation) in original.iter().zip(translated) {
+            if source != translation {
+                translations.entry(source).or_insert(translation);
+            }
+        }
+
+        let mut replaced_count: usize = 0;
+        for string in &mut self.strings.elems {
+            if let Some(&translation) = translations.get(string.as_str()) {
+                translation.clone_into(string);
+                replaced_count += 1;
+            }
+        }
+        replaced_count
+    }
+}
@@ -8,6 +8,8 @@ use crate::wad::chunk::gm_list_chunk;
 use crate::wad::elem::GMElement;
 use crate::wad::elem::GMNamedElement;
 use crate::wad::elem::font::Font;
+use crate::wad::elem::sprite::Special;
+use crate::wad::elem::sprite::SpecialData;
 use crate::wad::elem::sprite::Sprite;
 use crate::wad::elem::string::Strings;
 use crate::wad::elem::texture_page::TexturePage;
@@ -65,17 +67,155 @@ impl GMElement for TextureGroupInfos {
     }
 }
 
+macro_rules! group_of_fn {
+    ($method:ident, $element:ty, $($field:ident),+) => {
+        /// Finds the texture group containing the specified element.
+        ///
+        /// If the element is contained in multiple texture groups,
+        /// the first one is returned.
+        #[must_use]
+        pub fn $method(&self, element: GMRef<$element>) -> Option<GMRef<TextureGroupInfo>> {
+            self.element_refs()
+                .find(|(_, group)| $(group.$field.contains(&element))||+)
+                .map(|(group_ref, _)| group_ref)
+        }
+    };
+}
+
+impl TextureGroupInfos {
+    group_of_fn!(group_of_texture_page, TexturePage, texture_pages);
+
+    group_of_fn!(group_of_sprite, Sprite, sprites, spine_sprites);
+
+    group_of_fn!(group_of_font, Font, fonts);
+
+    group_of_fn!(group_of_tileset, Tileset, tilesets);
+}
+
+/// A texture group, which determines which assets
+/// are stored on which texture pages.
 #[derive(Debug, Clone, PartialEq)]
 pub struct TextureGroupInfo {
+    /// The name of the texture group.
     pub name: GMRef<String>,
+
+    /// The texture pages of this group.
     pub texture_pages: Vec<GMRef<TexturePage>>,
+
+    /// The (non-spine) sprites of this group.
     pub sprites: Vec<GMRef<Sprite>>,
+
+    /// The spine sprites of this group.
+    ///
+    /// This list only exists before 2023.1.
+    /// In later versions, spine sprites are contained in [`Self::sprites`]
+    /// and this list is not serialized.
     pub spine_sprites: Vec<GMRef<Sprite>>,
+
+    /// The fonts of this group.
     pub fonts: Vec<GMRef<Font>>,
+
+    /// The tilesets of this group.
     pub tilesets: Vec<GMRef<Tileset>>,
+
+    /// Set in 2022.9 and above.
     pub data_2022_9: Option<Data2022_9>,
 }
 
+impl TextureGroupInfo {
+    /// Creates an empty texture group with the specified name.
+    ///
+    /// In 2022.9 and above, the texture group is stored in the data file
+    /// (see [`LoadType::InFile`]) with an empty directory and extension.
+    pub fn new(name: &str, strings: &mut Strings, version: GMVersion) -> Self {
+        let data_2022_9: Option<Data2022_9> = (version >= GMVersion::GM2022_9).then(|| {
+            let directory: GMRef<String> = strings.make("");
+            let extension: GMRef<String> = strings.make("");
+            Data2022_9 {
+                directory,
+                extension,
+                load_type: LoadType::InFile,
+            }
+        });
+        Self {
+            name: strings.make(name),
+            texture_pages: Vec::new(),
+            sprites: Vec::new(),
+            spine_sprites: Vec::new(),
+            fonts: Vec::new(),
+            tilesets: Vec::new(),
+            data_2022_9,
+        }
+    }
+
+    /// Whether this texture group has no members.
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        self.texture_pages.is_empty()
+            && self.sprites.is_empty()
+            && self.spine_sprites.is_empty()
+            && self.fonts.is_empty()
+            && self.tilesets.is_empty()
+    }
+}
+
+impl GMData {
+    /// Returns the texture group with the specified name if it exists,
+    /// otherwise creates a new empty one.
+    ///
+    /// For more information, see [`TextureGroupInfo::new`].
+    pub fn make_texture_group(&mut self, name: &str) -> GMRef<TextureGroupInfo> {
+        if let Ok(group_ref) = self.texture_group_infos.ref_by_name(name, &self.strings) {
+            return group_ref;
+        }
+        let group = TextureGroupInfo::new(name, &mut self.strings, self.meta.version);
+        self.texture_group_infos.push(group)
+    }
+
+    /// Moves the specified sprite to the texture group with the specified name.
+    ///
+    /// The sprite is removed from all other texture groups.
+    /// Before 2023.1, spine sprites are stored in
+    /// [`TextureGroupInfo::spine_sprites`] instead of
+    /// [`TextureGroupInfo::sprites`].
+    ///
+    /// Only the group membership is changed; the texture page items of the
+    /// sprite stay on their current texture pages.
+    ///
+    /// Returns the previous texture group of the sprite (see
+    /// [`TextureGroupInfos::group_of_sprite`]), so that this can be undone.
+    /// Returns an error if the sprite or the texture group does not exist.
+    #[doc(alias = "set_texture_group_for_sprite")]
+    pub fn assign_sprite_to_group(
+        &mut self,
+        sprite_ref: GMRef<Sprite>,
+        group_name: &str,
+    ) -> Result<Option<GMRef<TextureGroupInfo>>> {
+        let sprite: &Sprite = self.sprites.by_ref(sprite_ref)?;
+        let is_spine: bool = matches!(
+            &sprite.special_fields,
+            Some(Special { data: SpecialData::Spine(_), .. })
+        );
+        let group_ref: GMRef<TextureGroupInfo> = self
+            .texture_group_infos
+            .ref_by_name(group_name, &self.strings)?;
+
+        let previous = self.texture_group_infos.group_of_sprite(sprite_ref);
+        for group in self.texture_group_infos.elements_mut() {
+            group.sprites.retain(|&sprite| sprite != sprite_ref);
+            group.spine_sprites.retain(|&sprite| sprite != sprite_ref);
+        }
+
+        let group: &mut TextureGroupInfo = self.texture_group_infos.by_ref_mut(group_ref)?;
+        if is_spine && self.meta.version < GMVersion::GM2023_1 {
+            group.spine_sprites.push(sprite_ref);
+        } else {
+            group.sprites.push(sprite_ref);
+        }
+        Ok(previous)
+    }
+}
+
 impl GMElement for TextureGroupInfo {
     fn deserialize(reader: &mut DataReader) -> Result<Self> {
         let name: GMRef<String> = reader.read_gm_string()?;
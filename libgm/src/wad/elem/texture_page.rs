@@ -1,10 +1,17 @@
 // SPDX-License-Identifier: GPL-3.0-only
+mod free_space;
 mod img;
+mod repack;
 
 use std::cmp::max;
+use std::path::Path;
 
+pub use free_space::Rect;
+use image::DynamicImage;
+use image::RgbaImage;
 pub use img::Format;
 pub use img::GMImage;
+pub use repack::RepackReport;
 
 use crate::prelude::*;
 use crate::util::fmt::hexdump;
@@ -167,6 +174,80 @@ pub struct TexturePage {
 }
 element_stub!(TexturePage);
 
+impl GMData {
+    /// Decodes the embedded texture page with the specified index to RGBA.
+    ///
+    /// Returns an error if the texture page does not exist or is external
+    /// (not stored in the data file).
+    pub fn texture_page_image(&self, index: usize) -> Result<RgbaImage> {
+        self.texture_page_gm_image(index)?
+            .to_dynamic_image()
+            .map(|image| image.to_rgba8())
+            .ctx(|| format!("decoding texture page #{index}"))
+    }
+
+    /// Decodes the embedded texture page with the specified index and writes
+    /// it to a PNG file.
+    ///
+    /// This requires the `png-image` crate feature.
+    /// For more information, see [`GMData::texture_page_image`].
+    pub fn export_texture_page(&self, index: usize, path: impl AsRef<Path>) -> Result<()> {
+        let path: &Path = path.as_ref();
+        let png: Vec<u8> = self
+            .texture_page_gm_image(index)?
+            .to_png()
+            .ctx(|| format!("encoding texture page #{index}"))?;
+        std::fs::write(path, png)
+            .ctx_any(|| format!("writing texture page #{index} to {}", path.display()))
+    }
+
+    fn texture_page_gm_image(&self, index: usize) -> Result<&GMImage> {
+        let texture_page: &TexturePage = self.texture_pages.elems.get(index).ok_or_else(|| {
+            format!(
+                "Texture page #{index} does not exist; there are only {} texture pages",
+                self.texture_pages.len(),
+            )
+        })?;
+        texture_page
+            .image
+            .as_ref()
+            .ok_or_else(|| err!("Texture page #{index} is external"))
+    }
+}
+
+/// Creates a new texture page with the metadata of the template page.
+///
+/// The image is encoded in the format of the template page's image.
+/// `index` is the index of the new page within its texture group.
+pub(crate) fn make_page(
+    template: &TexturePage,
+    canvas: RgbaImage,
+    index: usize,
+) -> Result<TexturePage> {
+    let (width, height) = canvas.dimensions();
+    let mut image = GMImage::from_dynamic_image(DynamicImage::ImageRgba8(canvas));
+    if let Some(template_image) = &template.image {
+        image
+            .change_format(template_image.format())
+            .ctx("encoding texture page")?;
+    }
+
+    let data_2022_9 = template.data_2022_9.as_ref().map(|_| Data2022_9 {
+        texture_width: width,
+        texture_height: height,
+        index_in_group: index as u32,
+    });
+
+    Ok(TexturePage {
+        scaled: template.scaled,
+        generated_mips: template.generated_mips,
+        // This is overwritten with the actual size when building.
+        texture_block_size: template.texture_block_size.map(|_| 0),
+        data_2022_9,
+        image: Some(image),
+    })
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Data2022_9 {
     /// Width of the texture.
@@ -0,0 +1,106 @@
+// SPDX-License-Identifier: GPL-3.0-only
+use std::collections::HashMap;
+
+use crate::prelude::*;
+use crate::wad::elem::texture_page::TexturePage;
+
+/// A rectangle on a texture page: `(x, y, width, height)` in pixels.
+pub type Rect = (u32, u32, u32, u32);
+
+impl GMData {
+    /// Computes the regions of an embedded texture page which are not covered
+    /// by any texture page item.
+    ///
+    /// The free space is split into horizontal bands at every top and bottom
+    /// edge of an item. Within each band, the uncovered spans become
+    /// rectangles; spans with the same horizontal extent in consecutive bands
+    /// are merged. The returned rectangles do not overlap and are ordered by
+    /// their top edge, then by their left edge.
+    ///
+    /// This is a conservative approximation of packable space: an image might
+    /// still fit across the border of two adjacent rectangles. Padding between
+    /// items (to prevent texture bleeding) is not accounted for either.
+    ///
+    /// Returns an error if the texture page does not exist or is external.
+    #[doc(alias = "page_free_rects")]
+    pub fn texture_page_free_space(&self, index: usize) -> Result<Vec<Rect>> {
+        let (width, height) = self
+            .texture_page_size(index)
+            .ctx(|| format!("determining size of texture page #{index}"))?;
+
+        let page_ref: GMRef<TexturePage> = GMRef::from(index);
+        let occupied: Vec<Rect> = self
+            .texture_page_items
+            .elems
+            .iter()
+            .filter(|item| item.texture_page == page_ref)
+            .filter_map(|item| {
+                let x = u32::from(item.source_x).min(width);
+                let y = u32::from(item.source_y).min(height);
+                let right = (x + u32::from(item.source_width)).min(width);
+                let bottom = (y + u32::from(item.source_height)).min(height);
+                (right > x && bottom > y).then_some((x, y, right - x, bottom - y))
+            })
+            .collect();
+
+        let mut edges: Vec<u32> = vec![0, height];
+        for &(_, y, _, h) in &occupied {
+            edges.push(y);
+            edges.push(y + h);
+        }
+        edges.sort_unstable();
+        edges.dedup();
+
+        let mut free: Vec<Rect> = Vec::new();
+        // Free rectangles ending at the current band, by their horizontal extent.
+        let mut open: HashMap<(u32, u32), usize> = HashMap::new();
+        for band in edges.windows(2) {
+            let (top, bottom) = (band[0], band[1]);
+            let mut spans: Vec<(u32, u32)> = occupied
+                .iter()
+                .filter(|&&(_, y, _, h)| y < bottom && y + h > top)
+                .map(|&(x, _, w, _)| (x, x + w))
+                .collect();
+            spans.sort_unstable();
+
+            let mut still_open: HashMap<(u32, u32), usize> = HashMap::new();
+            let mut add_free = |left: u32, right: u32| {
+                if right <= left {
+                    return;
+                }
+                let index = if let Some(&index) = open.get(&(left, right)) {
+                    free[index].3 += bottom - top;
+                    index
+                } else {
+                    free.push((left, top, right - left, bottom - top));
+                    free.len() - 1
+                };
+                still_open.insert((left, right), index);
+            };
+
+            let mut cursor: u32 = 0;
+            for (start, end) in spans {
+                add_free(cursor, start);
+                cursor = cursor.max(end);
+            }
+            add_free(cursor, width);
+            open = still_open;
+        }
+
+        free.sort_unstable_by_key(|&(x, y, _, _)| (y, x));
+        Ok(free)
+    }
+
+    /// The size of an embedded texture page in pixels.
+    ///
+    /// This avoids decoding the image if the size is stored in the data file.
+    fn texture_page_size(&self, index: usize) -> Result<(u32, u32)> {
+        let image = self.texture_page_gm_image(index)?;
+        let texture_page: &TexturePage = &self.texture_pages.elems[index];
+        if let Some(data) = &texture_page.data_2022_9 {
+            return Ok((data.texture_width, data.texture_height));
+        }
+        let image = image.to_dynamic_image()?;
+        Ok((image.width(), image.height()))
+    }
+}
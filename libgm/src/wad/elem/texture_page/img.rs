@@ -5,6 +5,7 @@ mod qoi;
 
 use std::borrow::Cow;
 use std::fmt;
+use std::sync::Arc;
 
 pub use bz2::BZip2QoiHeader;
 use image::DynamicImage;
@@ -75,23 +76,23 @@ impl GMImage {
     /// Creates a new [`GMImage`] from the specified [`DynamicImage`], consuming
     /// it.
     #[must_use]
-    pub const fn from_dynamic_image(dynamic_image: DynamicImage) -> Self {
-        Self(Img::Dyn(dynamic_image))
+    pub fn from_dynamic_image(dynamic_image: DynamicImage) -> Self {
+        Self(Img::Dyn(Arc::new(dynamic_image)))
     }
 
     #[must_use]
-    pub(super) const fn from_png(raw_png_data: Vec<u8>) -> Self {
-        Self(Img::Png(Blob(raw_png_data)))
+    pub(super) fn from_png(raw_png_data: Vec<u8>) -> Self {
+        Self(Img::Png(Blob::new(raw_png_data)))
     }
 
     #[must_use]
-    pub(super) const fn from_qoi(raw_qoi_data: Vec<u8>) -> Self {
-        Self(Img::Qoi(Blob(raw_qoi_data)))
+    pub(super) fn from_qoi(raw_qoi_data: Vec<u8>) -> Self {
+        Self(Img::Qoi(Blob::new(raw_qoi_data)))
     }
 
     #[must_use]
-    pub(super) const fn from_bz2_qoi(raw_bz2_qoi_data: Vec<u8>, header: BZip2QoiHeader) -> Self {
-        Self(Img::Bz2Qoi(Blob(raw_bz2_qoi_data), header))
+    pub(super) fn from_bz2_qoi(raw_bz2_qoi_data: Vec<u8>, header: BZip2QoiHeader) -> Self {
+        Self(Img::Bz2Qoi(Blob::new(raw_bz2_qoi_data), header))
     }
 
     /// The Image [`Format`] of the underlying stored image data.
@@ -121,7 +122,7 @@ impl GMImage {
     /// [`Self::change_format`].
     pub fn to_dynamic_image(&'_ self) -> Result<Cow<'_, DynamicImage>> {
         let image: DynamicImage = match &self.0 {
-            Img::Dyn(dyn_img) => return Ok(Cow::Borrowed(dyn_img)),
+            Img::Dyn(dyn_img) => return Ok(Cow::Borrowed(dyn_img.as_ref())),
             Img::Png(raw) => png::decode(raw).ctx("converting PNG image to DynamicImage")?,
             Img::Qoi(raw) => qoi::decode(raw).ctx("converting QOI image to DynamicImage")?,
             Img::Bz2Qoi(raw, _) => {
@@ -131,13 +132,21 @@ impl GMImage {
         Ok(Cow::Owned(image))
     }
 
+    /// Decodes this image to RGBA and encodes it as a PNG file.
+    ///
+    /// This requires the `png-image` crate feature.
+    pub fn to_png(&self) -> Result<Vec<u8>> {
+        let image = DynamicImage::ImageRgba8(self.to_dynamic_image()?.to_rgba8());
+        png::encode(&image)
+    }
+
     /// An immutable reference to the [`DynamicImage`] stored internally.
     ///
     /// This will only return [`Some`] if the stored image data is actually a
     /// `DynamicImage` (you can check this by using [`Self::format`] or
     /// [`Self::is_dynamic_image`]).
     #[must_use]
-    pub const fn dynamic_image_ref(&self) -> Option<&DynamicImage> {
+    pub fn dynamic_image_ref(&self) -> Option<&DynamicImage> {
         match &self.0 {
             Img::Dyn(dynamic_image) => Some(dynamic_image),
             _ => None,
@@ -149,10 +158,13 @@ impl GMImage {
     /// This will only return [`Some`] if the stored image data is actually a
     /// `DynamicImage` (you can check this by using [`Self::format`] or
     /// [`Self::is_dynamic_image`]).
+    ///
+    /// If the image is shared with a clone (see [`GMData::clone_metadata_only`]),
+    /// it is copied first.
     #[must_use]
-    pub const fn dynamic_image_mut(&mut self) -> Option<&mut DynamicImage> {
+    pub fn dynamic_image_mut(&mut self) -> Option<&mut DynamicImage> {
         match &mut self.0 {
-            Img::Dyn(dynamic_image) => Some(dynamic_image),
+            Img::Dyn(dynamic_image) => Some(Arc::make_mut(dynamic_image)),
             _ => None,
         }
     }
@@ -219,12 +231,12 @@ impl GMImage {
                 let size = Some(raw_data.len() as u32);
                 let bz2_header = BZip2QoiHeader::new(qoi_header.width, qoi_header.height, size);
                 let data: Vec<u8> = bz2::compress(raw_data)?;
-                self.0 = Img::Bz2Qoi(Blob(data), bz2_header);
+                self.0 = Img::Bz2Qoi(Blob::new(data), bz2_header);
                 return Ok(());
             }
             (Img::Bz2Qoi(raw_data, _), Format::Qoi) => {
                 let data: Vec<u8> = bz2::decompress(raw_data)?;
-                self.0 = Img::Qoi(Blob(data));
+                self.0 = Img::Qoi(Blob::new(data));
                 return Ok(());
             }
             _ => {}
@@ -238,12 +250,12 @@ impl GMImage {
             // This `into_owned()` never actually clones:
             // to_dynamic_image returns Cow::Owned only if it was not a DynamicImage before
             // which would be impossible (since Dyn -> Dyn is skipped out by change_format)
-            Format::Dyn => Img::Dyn(dyn_img.into_owned()),
-            Format::Png => Img::Png(Blob(png::encode(&dyn_img)?)),
-            Format::Qoi => Img::Qoi(Blob(qoi::encode(&dyn_img)?)),
+            Format::Dyn => Img::Dyn(Arc::new(dyn_img.into_owned())),
+            Format::Png => Img::Png(Blob::new(png::encode(&dyn_img)?)),
+            Format::Qoi => Img::Qoi(Blob::new(qoi::encode(&dyn_img)?)),
             Format::Bz2Qoi => {
                 let (data, header) = bz2::encode_image(&dyn_img)?;
-                Img::Bz2Qoi(Blob(data), header)
+                Img::Bz2Qoi(Blob::new(data), header)
             }
         };
 
@@ -263,7 +275,11 @@ impl GMImage {
     /// [`GMData::optimize_memory`].
     pub(crate) fn optimize_memory(&mut self) -> usize {
         // not public for now. make gh issue if u want this
-        fn shrink(buffer: &mut Vec<u8>) -> usize {
+        fn shrink(buffer: &mut Blob<Vec<u8>>) -> usize {
+            // Shrinking a shared buffer would copy it.
+            let Some(buffer) = buffer.get_mut() else {
+                return 0;
+            };
             let before = buffer.capacity();
             buffer.shrink_to_fit();
             let after = buffer.capacity();
@@ -276,6 +292,33 @@ impl GMImage {
         }
     }
 
+    /// The number of bytes this image takes up when serialized for the
+    /// specified GameMaker version.
+    ///
+    /// This is exact, but images stored as a [`DynamicImage`] have to be
+    /// encoded (as QOI since GM 2022.1, otherwise as PNG) to determine their
+    /// size, which is slow.
+    pub fn serialized_size(&self, version: GMVersion) -> Result<usize> {
+        let size = match &self.0 {
+            Img::Dyn(dyn_img) if version >= GMVersion::GM2022_1 => qoi::encode(dyn_img)
+                .ctx("encoding DynamicImage as QOI")?
+                .len(),
+            Img::Dyn(dyn_img) => png::encode(dyn_img)
+                .ctx("encoding DynamicImage as PNG")?
+                .len(),
+            Img::Png(raw_data) | Img::Qoi(raw_data) => raw_data.len(),
+            Img::Bz2Qoi(raw_data, _) => {
+                let header_size = if version >= GMVersion::GM2022_5 {
+                    12
+                } else {
+                    8
+                };
+                header_size + raw_data.len()
+            }
+        };
+        Ok(size)
+    }
+
     pub(super) fn serialize(&self, builder: &mut DataBuilder) -> Result<()> {
         let is_qoi = matches!(self.0, Img::Qoi(_) | Img::Bz2Qoi(_, _));
         let is_qoi_eligible = builder.version() >= GMVersion::GM2022_1;
@@ -320,7 +363,7 @@ impl PartialEq for GMImage {
 
 #[derive(Debug, Clone)]
 enum Img {
-    Dyn(DynamicImage),
+    Dyn(Arc<DynamicImage>),
     Png(Blob<Vec<u8>>),
     Qoi(Blob<Vec<u8>>),
     Bz2Qoi(Blob<Vec<u8>>, BZip2QoiHeader),
@@ -0,0 +1,421 @@
+// SPDX-License-Identifier: GPL-3.0-only
+//! Repacking texture page items onto fewer, larger texture pages.
+
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::ops::Range;
+
+use image::GenericImage;
+use image::GenericImageView;
+use image::RgbaImage;
+
+use crate::prelude::*;
+use crate::wad::elem::texture_page::TexturePage;
+use crate::wad::elem::texture_page::make_page;
+
+/// The number of transparent pixels between packed regions.
+///
+/// This prevents texture filtering from bleeding neighbouring regions into
+/// each other.
+const PADDING: u32 = 2;
+
+/// Statistics about a texture repacking operation.
+///
+/// See [`GMData::repack_textures`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RepackReport {
+    /// The number of embedded texture pages before repacking.
+    pub old_page_count: usize,
+
+    /// The number of embedded texture pages after repacking.
+    pub new_page_count: usize,
+
+    /// The number of distinct texture regions that were packed.
+    ///
+    /// Texture page items sharing the exact same region are only packed once.
+    pub region_count: usize,
+
+    /// The total pixel area of all embedded texture pages before repacking.
+    pub old_area: u64,
+
+    /// The total pixel area of all embedded texture pages after repacking.
+    pub new_area: u64,
+}
+
+/// A rectangle on an old texture page.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct Region {
+    page: usize,
+    x: u32,
+    y: u32,
+    width: u32,
+    height: u32,
+}
+
+/// The position of a packed region on one of its group's new texture pages.
+#[derive(Debug, Clone, Copy)]
+struct Placement {
+    page: usize,
+    x: u32,
+    y: u32,
+}
+
+/// A row of regions with the height of its first (highest) region.
+#[derive(Debug)]
+struct Shelf {
+    y: u32,
+    height: u32,
+    next_x: u32,
+}
+
+/// The layout of a new texture page.
+#[derive(Debug, Default)]
+struct PageLayout {
+    shelves: Vec<Shelf>,
+    /// The width actually covered by regions.
+    width: u32,
+    /// The height actually covered by regions.
+    height: u32,
+}
+
+impl PageLayout {
+    /// Tries to find a spot for a region of the given size.
+    fn allocate(&mut self, width: u32, height: u32, max_size: u32) -> Option<(u32, u32)> {
+        let existing = self
+            .shelves
+            .iter_mut()
+            .find(|row| height <= row.height && row.next_x + width <= max_size);
+
+        let row: &mut Shelf = if let Some(row) = existing {
+            row
+        } else {
+            let y: u32 = self
+                .shelves
+                .last()
+                .map_or(0, |last| last.y + last.height + PADDING);
+            if y + height > max_size {
+                return None;
+            }
+            self.shelves.push(Shelf { y, height, next_x: 0 });
+            self.shelves.last_mut()?
+        };
+
+        let position = (row.next_x, row.y);
+        row.next_x += width + PADDING;
+        self.width = self.width.max(position.0 + width);
+        self.height = self.height.max(position.1 + height);
+        Some(position)
+    }
+
+    /// The smallest power of two page size fitting all regions.
+    fn page_size(&self, max_size: u32) -> (u32, u32) {
+        let round = |size: u32| size.max(1).next_power_of_two().min(max_size);
+        (round(self.width), round(self.height))
+    }
+}
+
+/// Lays out the regions on as few pages as possible using a shelf packer.
+///
+/// Regions are placed from highest to lowest, which keeps shelves dense.
+fn pack(regions: &[Region], max_size: u32) -> Result<(Vec<Placement>, Vec<PageLayout>)> {
+    let mut order: Vec<usize> = (0..regions.len()).collect();
+    order.sort_by_key(|&i| {
+        (
+            std::cmp::Reverse(regions[i].height),
+            std::cmp::Reverse(regions[i].width),
+        )
+    });
+
+    let mut placements: Vec<Option<Placement>> = vec![None; regions.len()];
+    let mut pages: Vec<PageLayout> = Vec::new();
+
+    for i in order {
+        let Region { width, height, .. } = regions[i];
+        if width > max_size || height > max_size {
+            bail!(
+                "Texture region with size {width}x{height} does not fit on a texture page with \
+                 size {max_size}x{max_size}"
+            );
+        }
+
+        let mut placement: Option<Placement> = None;
+        for (page, layout) in pages.iter_mut().enumerate() {
+            if let Some((x, y)) = layout.allocate(width, height, max_size) {
+                placement = Some(Placement { page, x, y });
+                break;
+            }
+        }
+
+        if placement.is_none() {
+            let mut layout = PageLayout::default();
+            let (x, y) = layout
+                .allocate(width, height, max_size)
+                .ok_or("Texture region does not fit on an empty texture page")?;
+            pages.push(layout);
+            placement = Some(Placement { page: pages.len() - 1, x, y });
+        }
+        placements[i] = placement;
+    }
+
+    let placements: Vec<Placement> = placements.into_iter().flatten().collect();
+    Ok((placements, pages))
+}
+
+/// The group index and region of a texture page item.
+///
+/// This is [`None`] for items located on external texture pages.
+type ItemRegion = Option<(usize, Region)>;
+
+/// The texture page items of one texture group (or of no texture group),
+/// which are packed together.
+#[derive(Debug)]
+struct PackGroup {
+    /// The index of the texture group, if any.
+    texture_group: Option<usize>,
+    /// The distinct regions of the group's texture page items.
+    regions: Vec<Region>,
+    indices: HashMap<Region, usize>,
+    /// Where every region is placed; parallel to `regions`.
+    placements: Vec<Placement>,
+    /// The new texture pages of this group.
+    pages: Vec<TexturePage>,
+}
+
+impl PackGroup {
+    fn new(texture_group: Option<usize>) -> Self {
+        Self {
+            texture_group,
+            regions: Vec::new(),
+            indices: HashMap::new(),
+            placements: Vec::new(),
+            pages: Vec::new(),
+        }
+    }
+
+    fn add_region(&mut self, region: Region) {
+        if !self.indices.contains_key(&region) {
+            self.indices.insert(region, self.regions.len());
+            self.regions.push(region);
+        }
+    }
+
+    fn placement(&self, region: &Region) -> Placement {
+        self.placements[self.indices[region]]
+    }
+
+    /// Lays out the regions and renders the new texture pages.
+    ///
+    /// Returns the total area of the new pages.
+    fn render(
+        &mut self,
+        template: &TexturePage,
+        old_images: &[Option<RgbaImage>],
+        max_page_size: u32,
+    ) -> Result<u64> {
+        let (placements, layouts) = pack(&self.regions, max_page_size)?;
+        let mut area: u64 = 0;
+        for (i, layout) in layouts.iter().enumerate() {
+            let (width, height) = layout.page_size(max_page_size);
+            area += u64::from(width) * u64::from(height);
+            let mut canvas = RgbaImage::new(width, height);
+            for (region, placement) in self.regions.iter().zip(&placements) {
+                if placement.page != i {
+                    continue;
+                }
+                let Some(source) = &old_images[region.page] else {
+                    continue;
+                };
+                let view = source.view(region.x, region.y, region.width, region.height);
+                canvas
+                    .copy_from(&*view, placement.x, placement.y)
+                    .ctx_any("copying texture region")?;
+            }
+            self.pages.push(make_page(template, canvas, i)?);
+        }
+        self.placements = placements;
+        Ok(area)
+    }
+}
+
+impl GMData {
+    /// Repacks all embedded texture page items onto as few texture pages as
+    /// possible.
+    ///
+    /// The regions of all texture page items are laid out on square pages of
+    /// at most `max_page_size` pixels using a simple shelf packer. New pages
+    /// are shrunk to the smallest power of two fitting their contents.
+    /// Every texture group is packed separately, so that the texture group
+    /// of every page stays intact. Items whose page is not in any texture
+    /// group are packed together.
+    ///
+    /// The new pages are encoded in the image format of the first old page
+    /// of their group. Texture page items are updated in place, so all
+    /// sprites, backgrounds and fonts referencing them stay valid.
+    /// External texture pages (without an embedded image) are left untouched;
+    /// embedded pages no texture page item is located on are removed.
+    ///
+    /// Returns an error if a texture region does not fit on a page of the
+    /// given size or if a texture page could not be decoded or encoded.
+    /// In that case, nothing is modified.
+    pub fn repack_textures(&mut self, max_page_size: u32) -> Result<RepackReport> {
+        if max_page_size == 0 || max_page_size > u32::from(u16::MAX) {
+            bail!("Invalid maximum texture page size {max_page_size}");
+        }
+
+        let old_images: Vec<Option<RgbaImage>> = self
+            .texture_pages
+            .elems
+            .iter()
+            .enumerate()
+            .map(|(i, page)| {
+                page.image
+                    .as_ref()
+                    .map(|image| image.to_dynamic_image().map(|image| image.to_rgba8()))
+                    .transpose()
+                    .ctx(|| format!("decoding texture page {i}"))
+            })
+            .collect::<Result<_>>()?;
+
+        let (mut groups, item_regions) = self.collect_texture_regions(&old_images)?;
+        let mut new_area: u64 = 0;
+        for group in &mut groups {
+            let template: &TexturePage = &self.texture_pages.elems[group.regions[0].page];
+            new_area += group
+                .render(template, &old_images, max_page_size)
+                .ctx(|| format!("repacking texture group {:?}", group.texture_group))?;
+        }
+
+        // Everything succeeded; apply the changes.
+        let report = RepackReport {
+            old_page_count: old_images.iter().flatten().count(),
+            new_page_count: groups.iter().map(|group| group.pages.len()).sum(),
+            region_count: groups.iter().map(|group| group.regions.len()).sum(),
+            old_area: old_images
+                .iter()
+                .flatten()
+                .map(|image| u64::from(image.width()) * u64::from(image.height()))
+                .sum(),
+            new_area,
+        };
+        self.apply_repacked_textures(groups, &item_regions);
+        Ok(report)
+    }
+
+    /// Collects the distinct regions of all embedded texture page items,
+    /// grouped by texture group.
+    ///
+    /// Also returns the group index and region of every texture page item
+    /// (or [`None`] for items on external texture pages).
+    fn collect_texture_regions(
+        &self,
+        old_images: &[Option<RgbaImage>],
+    ) -> Result<(Vec<PackGroup>, Vec<ItemRegion>)> {
+        let mut groups: Vec<PackGroup> = Vec::new();
+        let mut item_regions: Vec<ItemRegion> = Vec::new();
+
+        for (i, item) in self.texture_page_items.elems.iter().enumerate() {
+            let page: usize = item
+                .texture_page
+                .index()
+                .filter(|&page| page < old_images.len())
+                .ok_or_else(|| {
+                    err!(
+                        "Texture page item {i} has invalid texture page {:?}",
+                        item.texture_page,
+                    )
+                })?;
+            let Some(old_image) = &old_images[page] else {
+                item_regions.push(None);
+                continue;
+            };
+
+            let region = Region {
+                page,
+                x: u32::from(item.source_x),
+                y: u32::from(item.source_y),
+                width: u32::from(item.source_width),
+                height: u32::from(item.source_height),
+            };
+            if region.x + region.width > old_image.width()
+                || region.y + region.height > old_image.height()
+            {
+                bail!(
+                    "Texture page item {i} exceeds the bounds of texture page {page} with size \
+                     {}x{}",
+                    old_image.width(),
+                    old_image.height(),
+                );
+            }
+
+            let group: Option<usize> = self
+                .texture_group_infos
+                .group_of_texture_page(item.texture_page)
+                .and_then(GMRef::index);
+            let existing: Option<usize> = groups.iter().position(|g| g.texture_group == group);
+            let group_index: usize = existing.unwrap_or_else(|| {
+                groups.push(PackGroup::new(group));
+                groups.len() - 1
+            });
+            groups[group_index].add_region(region);
+            item_regions.push(Some((group_index, region)));
+        }
+
+        Ok((groups, item_regions))
+    }
+
+    /// Replaces the embedded texture pages with the repacked ones and updates
+    /// all references to texture pages.
+    fn apply_repacked_textures(&mut self, mut groups: Vec<PackGroup>, item_regions: &[ItemRegion]) {
+        // External texture pages are kept, but may move.
+        let mut pages: Vec<TexturePage> = Vec::new();
+        let mut external_map: Vec<Option<usize>> = Vec::new();
+        for page in std::mem::take(&mut self.texture_pages.elems) {
+            if page.image.is_none() {
+                external_map.push(Some(pages.len()));
+                pages.push(page);
+            } else {
+                external_map.push(None);
+            }
+        }
+
+        let mut group_pages: Vec<Range<usize>> = Vec::with_capacity(groups.len());
+        for group in &mut groups {
+            let start: usize = pages.len();
+            pages.append(&mut group.pages);
+            group_pages.push(start..pages.len());
+        }
+        self.texture_pages.elems = pages;
+
+        for (item, region) in self.texture_page_items.elems.iter_mut().zip(item_regions) {
+            if let Some((group_index, region)) = region {
+                let placement: Placement = groups[*group_index].placement(region);
+                item.texture_page = GMRef::from(group_pages[*group_index].start + placement.page);
+                // The page size is at most `u16::MAX`, so this does not truncate.
+                item.source_x = placement.x as u16;
+                item.source_y = placement.y as u16;
+            } else if let Some(index) = item.texture_page.index()
+                && let Some(new_index) = external_map[index]
+            {
+                item.texture_page = GMRef::from(new_index);
+            }
+        }
+
+        for (group_ref, texture_group) in self.texture_group_infos.element_refs_mut() {
+            let mut texture_pages: Vec<GMRef<TexturePage>> = texture_group
+                .texture_pages
+                .iter()
+                .filter_map(|page| external_map.get(page.index()?).copied()?)
+                .map(GMRef::from)
+                .collect();
+            if let Some(group_index) = groups
+                .iter()
+                .position(|g| g.texture_group == group_ref.index())
+            {
+                texture_pages.extend(group_pages[group_index].clone().map(GMRef::from));
+            }
+            let mut seen: HashSet<GMRef<TexturePage>> = HashSet::new();
+            texture_pages.retain(|page| seen.insert(*page));
+            texture_group.texture_pages = texture_pages;
+        }
+    }
+}
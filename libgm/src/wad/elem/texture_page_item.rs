@@ -1,5 +1,10 @@
 // SPDX-License-Identifier: GPL-3.0-only
 
+use image::GenericImageView;
+use image::RgbaImage;
+use image::imageops;
+use image::imageops::FilterType;
+
 use crate::prelude::*;
 use crate::wad::build::builder::DataBuilder;
 use crate::wad::chunk::gm_list_chunk;
@@ -54,6 +59,60 @@ pub struct TexturePageItem {
     pub texture_page: GMRef<TexturePage>,
 }
 
+impl TexturePageItem {
+    /// Extracts the image of this item from its decoded texture page.
+    ///
+    /// The returned image has the bounding size of this item.
+    /// The source region is placed at the target position (and scaled to the
+    /// target size, if needed). Everything else is transparent.
+    ///
+    /// Returns an error if the source region is out of bounds for the page.
+    pub fn extract_from(&self, page: &RgbaImage) -> Result<RgbaImage> {
+        let x = u32::from(self.source_x);
+        let y = u32::from(self.source_y);
+        let width = u32::from(self.source_width);
+        let height = u32::from(self.source_height);
+        if x + width > page.width() || y + height > page.height() {
+            bail!(
+                "Source region {width}x{height} at ({x}, {y}) is out of bounds for texture page \
+                 with size {}x{}",
+                page.width(),
+                page.height(),
+            );
+        }
+
+        let mut source: RgbaImage = page.view(x, y, width, height).to_image();
+        let target_width = u32::from(self.target_width);
+        let target_height = u32::from(self.target_height);
+        if (target_width, target_height) != (width, height) {
+            source = imageops::resize(&source, target_width, target_height, FilterType::Nearest);
+        }
+
+        let mut image = RgbaImage::new(
+            u32::from(self.bounding_width),
+            u32::from(self.bounding_height),
+        );
+        imageops::replace(
+            &mut image,
+            &source,
+            i64::from(self.target_x),
+            i64::from(self.target_y),
+        );
+        Ok(image)
+    }
+}
+
+impl GMData {
+    /// Iterates over all texture page items, in chunk order.
+    ///
+    /// Each item contains its source and target rectangles as well as its
+    /// texture page. Texture page images are not decoded or even accessed,
+    /// which keeps metadata-only passes (such as building an atlas map) fast.
+    pub fn texture_items(&self) -> impl Iterator<Item = &TexturePageItem> {
+        self.texture_page_items.elems.iter()
+    }
+}
+
 impl GMElement for TexturePageItem {
     fn deserialize(reader: &mut DataReader) -> Result<Self> {
         let source_x = reader.read_u16()?;
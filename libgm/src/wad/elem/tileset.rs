@@ -102,6 +102,31 @@ pub struct Tileset {
     pub gms2_data: Option<GMS2Data>,
 }
 
+impl GMData {
+    /// The [`TexturePageItem`] containing the image of the specified
+    /// background or tileset.
+    ///
+    /// Its source rectangle is the region of the texture page containing
+    /// the background image (or tileset sheet).
+    /// Returns [`None`] if the background does not have a texture.
+    /// Returns an error if the background does not exist or if its texture
+    /// page item is out of bounds.
+    pub fn texture_item_for_background(
+        &self,
+        tileset_ref: GMRef<Tileset>,
+    ) -> Result<Option<&TexturePageItem>> {
+        let tileset: &Tileset = self.tilesets.by_ref(tileset_ref)?;
+        if tileset.texture.is_none() {
+            return Ok(None);
+        }
+        let item = self
+            .texture_page_items
+            .by_ref(tileset.texture)
+            .ctx("resolving texture of background")?;
+        Ok(Some(item))
+    }
+}
+
 impl GMElement for Tileset {
     fn deserialize(reader: &mut DataReader) -> Result<Self> {
         let name: GMRef<String> = reader.read_gm_string()?;
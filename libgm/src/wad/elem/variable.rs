@@ -1,5 +1,7 @@
 // SPDX-License-Identifier: GPL-3.0-only
 
+use std::collections::HashMap;
+
 use crate::gml::instruction::InstanceType;
 use crate::prelude::*;
 use crate::util::init::vec_with_capacity;
@@ -23,7 +25,6 @@ pub struct Variables {
 
     /// Set in WAD 15 and above.
     pub modern_header: Option<ModernHeader>,
-
 }
 
 gm_list_chunk!(VARI, Variables, Variable, direct);
@@ -112,6 +113,122 @@ impl Variables {
     }
 }
 
+/// A variable with its resolved name, as returned by
+/// [`GMData::all_variable_names`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VariableName<'a> {
+    pub variable: GMRef<Variable>,
+    pub name: &'a str,
+
+    /// The instance type (scope) of this variable, if known.
+    ///
+    /// Instance types used by code references to this variable take
+    /// precedence, since the `VARI` chunk declares built-in variables as
+    /// [`InstanceType::Self_`]. Otherwise, the instance type declared in the
+    /// `VARI` chunk (WAD 15+) is used.
+    pub instance_type: Option<InstanceType>,
+}
+
+impl VariableName<'_> {
+    /// Whether this variable is known to be a global variable.
+    #[must_use]
+    pub fn is_global(&self) -> bool {
+        self.instance_type == Some(InstanceType::Global)
+    }
+
+    /// Whether this variable is known to be a local variable.
+    #[must_use]
+    pub fn is_local(&self) -> bool {
+        self.instance_type == Some(InstanceType::Local)
+    }
+
+    /// Whether this variable is known to be a built-in variable.
+    #[must_use]
+    pub fn is_builtin(&self) -> bool {
+        self.instance_type == Some(InstanceType::Builtin)
+    }
+}
+
+impl GMData {
+    /// Returns every variable with its resolved name and instance type,
+    /// in chunk order.
+    ///
+    /// Variables with the same name may occur multiple times (for example,
+    /// once per local scope). This is useful for building symbol tables.
+    pub fn all_variable_names(&self) -> Result<Vec<VariableName<'_>>> {
+        // Collect the scopes that are only visible in code references.
+        let mut code_instance_types: HashMap<GMRef<Variable>, InstanceType> = HashMap::new();
+        for code in &self.codes.elems {
+            for instruction in &code.instructions {
+                let Some(code_variable) = instruction.variable() else {
+                    continue;
+                };
+                if matches!(
+                    code_variable.instance_type,
+                    InstanceType::Global | InstanceType::Local | InstanceType::Builtin
+                ) {
+                    code_instance_types
+                        .entry(code_variable.variable)
+                        .or_insert(code_variable.instance_type);
+                }
+            }
+        }
+
+        self.variables
+            .element_refs()
+            .map(|(variable_ref, variable)| {
+                let declared: Option<InstanceType> =
+                    variable.modern_data.as_ref().map(|data| data.instance_type);
+                Ok(VariableName {
+                    variable: variable_ref,
+                    name: variable.name(&self.strings)?,
+                    instance_type: code_instance_types.get(&variable_ref).copied().or(declared),
+                })
+            })
+            .collect()
+    }
+
+    /// The number of times each variable is referenced in code, indexed by
+    /// variable index.
+    ///
+    /// These are the occurrence counts stored in `VARI`. They are counted from
+    /// the code instructions (which the occurrence chains are built from
+    /// anyway), so they stay correct after modifying code.
+    /// References to variables that do not exist are ignored.
+    #[must_use]
+    pub fn variable_use_counts(&self) -> Vec<usize> {
+        let mut counts: Vec<usize> = vec![0; self.variables.len()];
+        for code in &self.codes.elems {
+            for instruction in &code.instructions {
+                if let Some(index) = instruction.variable().and_then(|var| var.variable.index())
+                    && let Some(count) = counts.get_mut(index)
+                {
+                    *count += 1;
+                }
+            }
+        }
+        counts
+    }
+
+    /// The number of times the specified variable is referenced in code.
+    ///
+    /// To get the counts of all variables at once, use
+    /// [`GMData::variable_use_counts`].
+    #[must_use]
+    pub fn variable_use_count(&self, variable: GMRef<Variable>) -> usize {
+        self.codes
+            .elems
+            .iter()
+            .flat_map(|code| &code.instructions)
+            .filter(|instruction| {
+                instruction
+                    .variable()
+                    .is_some_and(|var| var.variable == variable)
+            })
+            .count()
+    }
+}
+
 impl GMElement for Variables {
     fn deserialize(reader: &mut DataReader) -> Result<Self> {
         let modern_header: Option<ModernHeader> =
@@ -251,7 +368,7 @@ impl GMElement for ModernData {
     }
 
     fn serialize(&self, builder: &mut DataBuilder) -> Result<()> {
-        builder.write_i32(i32::from(self.instance_type.build()));
+        builder.write_i32(i32::from(self.instance_type.build()?));
         builder.write_i32(self.variable_id);
         Ok(())
     }
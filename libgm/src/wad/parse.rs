@@ -3,18 +3,25 @@
 //!
 //! Some of these functions are also re-exported at the crate root.
 
-pub(super) mod chunk;
+pub(crate) mod chunk;
 pub mod integrity;
 mod lists;
 mod numbers;
+#[cfg(feature = "element-ranges")]
+pub mod ranges;
 pub(crate) mod reader;
 pub(super) mod resources;
+#[cfg(feature = "parse-trace")]
+mod trace;
 
 use std::path::Path;
+#[cfg(feature = "parse-trace")]
+use std::path::PathBuf;
 
 use crate::prelude::*;
 use crate::util::bench::Stopwatch;
 use crate::util::fmt::hexdump;
+use crate::wad::GMVersion;
 use crate::wad::chunk::ChunkName;
 use crate::wad::data::Endianness;
 use crate::wad::data::GMData;
@@ -58,6 +65,9 @@ use crate::wad::elem::variable::Variables;
 use crate::wad::parse::chunk::ChunkBounds;
 use crate::wad::parse::chunk::ChunkMap;
 use crate::wad::parse::reader::DataReader;
+#[cfg(feature = "parse-trace")]
+use crate::wad::parse::trace::ParseTrace;
+use crate::wad::version::version_floor_by;
 use crate::wad::version_detection::detect_format_version;
 
 const ERR_TOO_BIG: &str =
@@ -74,6 +84,19 @@ pub struct ParsingOptions {
     /// See [`ParsingOptions::allow_unknown_chunks`].
     // TODO: this option is useless rn due to the ChunkName refactor
     pub allow_unknown_chunks: bool,
+
+    /// See [`ParsingOptions::assumed_version`].
+    pub assumed_version: Option<GMVersion>,
+
+    /// See [`ParsingOptions::recover_chunks`].
+    pub recover_chunks: bool,
+
+    /// See [`ParsingOptions::allow_unknown_instructions`].
+    pub allow_unknown_instructions: bool,
+
+    /// See [`ParsingOptions::trace_file`].
+    #[cfg(feature = "parse-trace")]
+    pub trace_file: Option<PathBuf>,
 }
 
 impl Default for ParsingOptions {
@@ -87,11 +110,21 @@ impl ParsingOptions {
         verify_alignment: false,
         verify_constants: false,
         allow_unknown_chunks: true,
+        assumed_version: None,
+        recover_chunks: false,
+        allow_unknown_instructions: true,
+        #[cfg(feature = "parse-trace")]
+        trace_file: None,
     };
     pub const STRICT: Self = Self {
         verify_alignment: true,
         verify_constants: true,
         allow_unknown_chunks: false,
+        assumed_version: None,
+        recover_chunks: false,
+        allow_unknown_instructions: false,
+        #[cfg(feature = "parse-trace")]
+        trace_file: None,
     };
 
     /// Creates a new [`ParsingOptions`] with default settings
@@ -150,6 +183,96 @@ impl ParsingOptions {
         self
     }
 
+    /// When set, format version detection is skipped entirely and the
+    /// specified version is used for parsing instead.
+    ///
+    /// Version detection performs many speculative reads across chunks,
+    /// which can take a noticeable amount of time for big data files.
+    /// If you process lots of data files whose version is already known,
+    /// this can speed up parsing.
+    ///
+    /// **Be careful**: If the assumed version does not match the actual
+    /// format version of the data file, parsing will most likely fail with
+    /// confusing errors. Even worse, it may "succeed" with garbage data or
+    /// produce a data file that crashes the runner when rebuilt.
+    ///
+    /// > Default: **None**
+    #[inline]
+    #[must_use]
+    pub const fn assumed_version(mut self, version: Option<GMVersion>) -> Self {
+        self.assumed_version = version;
+        self
+    }
+
+    /// When enabled, chunks that fail to parse are kept as raw bytes
+    /// instead of aborting the whole parse.
+    ///
+    /// A warning is logged for every such chunk. Its element in [`GMData`]
+    /// is left empty (as if the chunk did not exist) and its raw contents
+    /// are stored in [`Metadata::raw_chunks`], so that they are written back
+    /// verbatim when building. Use [`GMData::failed_chunks`] to find out
+    /// which chunks failed.
+    ///
+    /// This lets you get partial data out of data files that LibGM cannot
+    /// fully handle yet. Keep in mind that other chunks referencing the
+    /// failed chunk's contents may fail to parse too, and that raw chunks
+    /// contain absolute pointers which are not updated when building.
+    /// Only modify such data if you know what you are doing.
+    ///
+    /// > Default: **false**
+    ///
+    /// [`Metadata::raw_chunks`]: crate::wad::data::Metadata::raw_chunks
+    #[inline]
+    #[must_use]
+    pub const fn recover_chunks(mut self, enabled: bool) -> Self {
+        self.recover_chunks = enabled;
+        self
+    }
+
+    /// When enabled, code instructions with an unknown opcode are kept as
+    /// [`Instruction::Unknown`] instead of aborting the whole parse.
+    ///
+    /// This is meant for data files using bytecode from GameMaker versions
+    /// newer than LibGM supports. Such instructions are written back verbatim
+    /// when building, but their meaning is unknown, so decompiling or
+    /// analyzing the affected code entries may give wrong results.
+    ///
+    /// Note that instructions which are followed by extra operand data (like
+    /// `push`) cannot be detected as such; parsing will most likely fail
+    /// anyway for those.
+    ///
+    /// > Default: **false**
+    ///
+    /// [`Instruction::Unknown`]: crate::gml::Instruction::Unknown
+    #[inline]
+    #[must_use]
+    pub const fn allow_unknown_instructions(mut self, enabled: bool) -> Self {
+        self.allow_unknown_instructions = enabled;
+        self
+    }
+
+    /// When set, a human-readable trace of the parsing process is written to
+    /// the specified file.
+    ///
+    /// Every parsed chunk and every element of a list gets its own line,
+    /// containing the start position, the chunk name, the element type and
+    /// the element's index within its list.
+    /// Lines are written as parsing proceeds, so if parsing fails, the last
+    /// few lines show which element caused the failure.
+    ///
+    /// Speculative reads made during format version detection are not traced.
+    ///
+    /// Only available with the `parse-trace` feature.
+    ///
+    /// > Default: **None**
+    #[cfg(feature = "parse-trace")]
+    #[inline]
+    #[must_use]
+    pub fn trace_file(mut self, path: Option<PathBuf>) -> Self {
+        self.trace_file = path;
+        self
+    }
+
     /// Parses a GameMaker data file (stored in memory) with the specified
     /// options.
     ///
@@ -225,7 +348,7 @@ pub fn parse_file(data_file_path: impl AsRef<Path>) -> Result<GMData> {
 // ================ Actual logic here ================
 
 /// This can later be reused for audiogroup files.
-fn parse_form(raw_data: &'_ [u8]) -> Result<DataReader<'_>> {
+pub(crate) fn parse_form(raw_data: &'_ [u8]) -> Result<DataReader<'_>> {
     // Length assertion
     if raw_data.len() >= i32::MAX as usize {
         bail!("{ERR_TOO_BIG}");
@@ -285,7 +408,7 @@ fn parse_form(raw_data: &'_ [u8]) -> Result<DataReader<'_>> {
         let end_pos = reader.cur_pos;
         reader.last_chunk = name;
 
-        let chunk_bounds = ChunkBounds { start_pos, end_pos };
+        let chunk_bounds = ChunkBounds { start_pos, end_pos, name: Some(name) };
         reader.chunks.push(name, chunk_bounds)?;
         reader.chunk_order.push(name);
     }
@@ -331,9 +454,30 @@ fn parse(raw_data: &[u8], options: &ParsingOptions) -> Result<GMData> {
     }
 
     reader.options = options.clone();
-    let stopwatch2 = Stopwatch::start();
-    reader.version = detect_format_version(reader.clone()).ctx("detecting format version")?;
-    log::debug!("Detecting format version took {:.2?}", stopwatch2.elapsed());
+    if let Some(version) = options.assumed_version {
+        log::debug!("Skipping format version detection; assuming version {version}");
+        if let Some((chunk_name, floor)) = version_floor_by(|name| reader.chunks.contains(name))
+            && version < floor
+        {
+            log::warn!(
+                "Assumed version {version} is older than version {floor} implied by chunk \
+                 {chunk_name}"
+            );
+        }
+        reader.version = version;
+    } else {
+        let stopwatch2 = Stopwatch::start();
+        reader.version = detect_format_version(reader.clone()).ctx("detecting format version")?;
+        log::debug!("Detecting format version took {:.2?}", stopwatch2.elapsed());
+    }
+
+    #[cfg(feature = "parse-trace")]
+    if let Some(path) = &options.trace_file {
+        reader.trace = ParseTrace::create(path)?;
+    }
+
+    #[cfg(feature = "element-ranges")]
+    reader.element_ranges.set_raw_data(raw_data);
 
     // The following chunk read order is required:
     // Required: STRG --> most others
@@ -402,7 +546,17 @@ fn parse(raw_data: &[u8], options: &ParsingOptions) -> Result<GMData> {
         chunk_padding: reader.chunk_padding,
         endianness: reader.endianness,
         original_data_size: reader.size(),
+        emulate_gmac_tile_padding_bug: reader.gmac_tile_padding_bug.unwrap_or(true),
+        fix_room_order_on_build: false,
         chunks: reader.chunk_order,
+        raw_chunks: reader.raw_chunks,
+        parsing_options: reader.options,
+        code_positions: reader.code_positions,
+        included_file_changes: Vec::new(),
+        #[cfg(feature = "chunk-timings")]
+        chunk_timings: reader.chunk_timings,
+        #[cfg(feature = "element-ranges")]
+        element_ranges: reader.element_ranges,
     };
 
     let data = GMData {
@@ -443,6 +597,12 @@ fn parse(raw_data: &[u8], options: &ParsingOptions) -> Result<GMData> {
         variables,
     };
 
+    if cfg!(feature = "check-integrity") {
+        for issue in data.check_gen8_counts() {
+            log::warn!("{issue}");
+        }
+    }
+
     log::trace!("Parsing data took {stopwatch}");
     Ok(data)
 }
@@ -1,8 +1,10 @@
 // SPDX-License-Identifier: GPL-3.0-only
 use crate::prelude::*;
 use crate::util::bench::Stopwatch;
+use crate::wad::Blob;
 use crate::wad::chunk::ChunkName;
 use crate::wad::data::Endianness;
+use crate::wad::data::RawChunk;
 use crate::wad::parse::reader::DataReader;
 use crate::wad::version::GMVersion;
 
@@ -10,6 +12,10 @@ use crate::wad::version::GMVersion;
 pub struct ChunkBounds {
     pub start_pos: u32,
     pub end_pos: u32,
+
+    /// The name of the chunk, if these are the bounds of a chunk.
+    /// Used for error messages and element ranges.
+    pub name: Option<ChunkName>,
 }
 
 impl ChunkBounds {
@@ -120,7 +126,16 @@ impl DataReader<'_> {
         self.cur_pos = chunk.start_pos;
         self.chunk = chunk;
 
-        let element = T::deserialize(self).ctx(ctx)?;
+        #[cfg(feature = "parse-trace")]
+        self.trace.chunk(T::NAME, chunk.start_pos);
+
+        let element = match T::deserialize(self).ctx(ctx) {
+            Ok(element) => element,
+            Err(error) if self.options.recover_chunks => {
+                return self.recover_chunk(T::NAME, &error);
+            }
+            Err(error) => return Err(error),
+        };
 
         // Last chunk does not get padding
         if T::NAME != self.last_chunk {
@@ -131,10 +146,32 @@ impl DataReader<'_> {
             self.handle_misaligned_chunk(T::NAME)?;
         }
 
+        #[cfg(feature = "chunk-timings")]
+        self.chunk_timings.push((T::NAME, stopwatch.elapsed()));
+
         log::trace!("Parsing chunk {} took {stopwatch}", T::NAME);
         Ok(element)
     }
 
+    /// Keeps the contents of the current chunk as raw bytes after it failed to parse.
+    /// Returns the `default` stub for the chunk element.
+    fn recover_chunk<T: GMChunk>(&mut self, chunk_name: ChunkName, error: &Error) -> Result<T> {
+        let error: String = error.chain();
+        log::warn!("Could not parse chunk {chunk_name}; keeping it as raw bytes: {error}");
+
+        #[cfg(feature = "element-ranges")]
+        self.element_ranges.remove_chunk(chunk_name);
+
+        self.cur_pos = self.chunk.start_pos;
+        let data: Vec<u8> = self.read_bytes_dyn(self.chunk.length())?.to_vec();
+        self.raw_chunks.push(RawChunk {
+            name: chunk_name,
+            data: Blob::new(data),
+            error,
+        });
+        Ok(T::default())
+    }
+
     fn handle_misaligned_chunk(&mut self, chunk_name: ChunkName) -> Result<()> {
         let pos = self.cur_pos;
         let end = self.chunk.end_pos;
@@ -20,13 +20,22 @@ impl DataReader<'_> {
             return Ok(());
         }
 
+        // Only build the (expensive) context on the error path.
+        let chunk: String = self
+            .chunk
+            .name
+            .map_or_else(String::new, |name| format!(" in chunk {name}"));
         self.handle_invalid_align(format!(
-            "{} pointer is misaligned: expected position {} but reader is actually at {} (diff: \
-             {})",
+            "{} pointer is misaligned{}: expected position {} ({:#X}) but reader is actually at \
+             {} ({:#X}) (diff: {}); bytes around reader position: {}",
             pointer_name,
+            chunk,
             position,
+            position,
+            self.cur_pos,
             self.cur_pos,
             i64::from(position) - i64::from(self.cur_pos),
+            self.hex_context(self.cur_pos, 16),
         ))
     }
 
@@ -19,6 +19,10 @@ impl DataReader<'_> {
             .ctx(|| format!("reading simple list of {}", typename::<T>()))?;
 
         for _ in 0..count {
+            #[cfg(feature = "parse-trace")]
+            self.trace
+                .element::<T>(elements.len(), count as usize, self.cur_pos);
+
             let element = T::deserialize(self).ctx(|| {
                 format!(
                     "deserializing element {}/{} of {} simple list",
@@ -46,6 +50,10 @@ impl DataReader<'_> {
             .ctx(|| format!("reading short simple list of {}", typename::<T>()))?;
 
         for _ in 0..count {
+            #[cfg(feature = "parse-trace")]
+            self.trace
+                .element::<T>(elements.len(), count as usize, self.cur_pos);
+
             let element = T::deserialize(self).ctx(|| {
                 format!(
                     "deserializing element {}/{} of {} short simple list",
@@ -66,8 +74,11 @@ impl DataReader<'_> {
 
         let mut elements: Vec<T> = Vec::with_capacity(count);
         for (i, pointer) in pointers.into_iter().enumerate() {
+            #[cfg(feature = "parse-trace")]
+            self.trace.element::<T>(i, count, pointer);
+
             let element_opt: Option<T> =
-                self.read_pointer_element(pointer, i == count - 1).ctx(|| {
+                self.read_pointer_element(pointer, i, count).ctx(|| {
                     format!(
                         "deserializing element {}/{} of {} pointer list",
                         i,
@@ -95,8 +106,11 @@ impl DataReader<'_> {
 
         let mut elements: Vec<Option<T>> = Vec::with_capacity(count);
         for (i, pointer) in pointers.into_iter().enumerate() {
+            #[cfg(feature = "parse-trace")]
+            self.trace.element::<T>(i, count, pointer);
+
             let element_opt: Option<T> =
-                self.read_pointer_element(pointer, i == count - 1).ctx(|| {
+                self.read_pointer_element(pointer, i, count).ctx(|| {
                     format!(
                         "deserializing element {}/{} of nullable {} pointer list",
                         i,
@@ -112,7 +126,8 @@ impl DataReader<'_> {
     fn read_pointer_element<T: GMElement>(
         &mut self,
         pointer: u32,
-        is_last: bool,
+        index: usize,
+        count: usize,
     ) -> Result<Option<T>> {
         T::deserialize_pre_padding(self).ctx("reading pre-padding")?;
 
@@ -132,9 +147,34 @@ impl DataReader<'_> {
             ))?;
         }
 
+        #[cfg(feature = "element-ranges")]
+        let element = self.deserialize_recording_range(pointer, index)?;
+        #[cfg(not(feature = "element-ranges"))]
         let element = T::deserialize(self)?;
 
+        let is_last: bool = index == count - 1;
         T::deserialize_post_padding(self, is_last).ctx("reading post-padding")?;
         Ok(Some(element))
     }
+
+    /// Deserializes a pointer list element and records its byte range if it is
+    /// a top-level element of the current chunk.
+    #[cfg(feature = "element-ranges")]
+    fn deserialize_recording_range<T: GMElement>(
+        &mut self,
+        pointer: u32,
+        index: usize,
+    ) -> Result<T> {
+        let is_top_level: bool = self.element_depth == 0;
+        self.element_depth += 1;
+        let result = T::deserialize(self);
+        self.element_depth -= 1;
+        let element: T = result?;
+
+        if is_top_level && let Some(chunk_name) = self.chunk.name {
+            self.element_ranges
+                .record(chunk_name, index, pointer..self.cur_pos);
+        }
+        Ok(element)
+    }
 }
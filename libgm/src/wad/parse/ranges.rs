@@ -0,0 +1,85 @@
+// SPDX-License-Identifier: GPL-3.0-only
+//! The byte ranges elements were parsed from.
+//!
+//! Only available with the `element-ranges` feature.
+//! See [`GMData::element_bytes`].
+
+use std::collections::HashMap;
+use std::ops::Range;
+
+use crate::prelude::*;
+use crate::wad::Blob;
+use crate::wad::chunk::ChunkName;
+
+/// The byte ranges of all top-level chunk elements in the parsed data file,
+/// along with a copy of the data file itself.
+///
+/// The copy is shared between clones (see [`Blob`]), so cloning [`GMData`]
+/// does not copy the data file again.
+///
+/// Only elements of pointer lists are recorded; this includes assets like
+/// sprites, rooms or code entries but not strings, variables or functions.
+///
+/// See [`Metadata::element_ranges`](crate::wad::data::Metadata::element_ranges).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ElementRanges {
+    /// The raw bytes of the parsed data file.
+    raw_data: Blob<Vec<u8>>,
+
+    /// Maps a chunk name and element index to the element's byte range.
+    ranges: HashMap<(ChunkName, usize), Range<u32>>,
+}
+
+impl ElementRanges {
+    pub(crate) fn record(&mut self, chunk: ChunkName, index: usize, range: Range<u32>) {
+        self.ranges.insert((chunk, index), range);
+    }
+
+    /// Forgets the ranges of all elements of the specified chunk.
+    pub(crate) fn remove_chunk(&mut self, chunk: ChunkName) {
+        self.ranges.retain(|&(name, _), _| name != chunk);
+    }
+
+    pub(crate) fn set_raw_data(&mut self, raw_data: &[u8]) {
+        self.raw_data = Blob::new(raw_data.to_vec());
+    }
+
+    /// The byte range (absolute data file position) the specified element was
+    /// parsed from.
+    ///
+    /// The range starts at the element's pointer and ends where the reader
+    /// stopped after deserializing it, which includes nested data such as
+    /// lists stored directly after the element.
+    #[must_use]
+    pub fn range(&self, chunk: ChunkName, index: usize) -> Option<Range<u32>> {
+        self.ranges.get(&(chunk, index)).cloned()
+    }
+
+    /// The raw bytes the specified element was parsed from.
+    ///
+    /// For more information, see [`Self::range`].
+    #[must_use]
+    pub fn bytes(&self, chunk: ChunkName, index: usize) -> Option<&[u8]> {
+        let range = self.range(chunk, index)?;
+        self.raw_data.get(range.start as usize..range.end as usize)
+    }
+}
+
+impl GMData {
+    /// The raw bytes of the specified element in the original data file.
+    ///
+    /// Elements are specified by their chunk (such as [`ChunkName::SPRT`])
+    /// and their index within that chunk.
+    /// Returns [`None`] if the element was not parsed from a data file
+    /// (or is not part of a pointer list chunk).
+    ///
+    /// This is useful for hashing the on-disk representation of an asset.
+    /// Note that the bytes do not reflect any changes made after parsing.
+    ///
+    /// Requires the `element-ranges` feature.
+    #[doc(alias = "as_bytes_region")]
+    #[must_use]
+    pub fn element_bytes(&self, chunk: ChunkName, index: usize) -> Option<&[u8]> {
+        self.meta.element_ranges.bytes(chunk, index)
+    }
+}
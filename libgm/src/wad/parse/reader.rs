@@ -1,12 +1,16 @@
 // SPDX-License-Identifier: GPL-3.0-only
 use std::collections::HashMap;
+#[cfg(feature = "chunk-timings")]
+use std::time::Duration;
 
 use crate::gml::instruction::InstanceType;
 use crate::prelude::*;
 use crate::util::assert;
+use crate::util::fmt::hexdump;
 use crate::wad::chunk::ChunkName;
 use crate::wad::chunk::ChunkOrder;
 use crate::wad::data::Endianness;
+use crate::wad::data::RawChunk;
 use crate::wad::elem::GMElement;
 use crate::wad::elem::function::Function;
 use crate::wad::elem::texture_page_item::TexturePageItem;
@@ -14,6 +18,10 @@ use crate::wad::elem::variable::Variable;
 use crate::wad::parse::ParsingOptions;
 use crate::wad::parse::chunk::ChunkBounds;
 use crate::wad::parse::chunk::ChunkMap;
+#[cfg(feature = "element-ranges")]
+use crate::wad::parse::ranges::ElementRanges;
+#[cfg(feature = "parse-trace")]
+use crate::wad::parse::trace::ParseTrace;
 use crate::wad::reference::GMRef;
 use crate::wad::version::GMVersion;
 
@@ -86,6 +94,40 @@ pub struct DataReader<'a> {
     /// Should only be set by [`crate::wad::elem::function`].
     /// This means that `FUNC` has to be parsed before `CODE`.
     pub function_occurrences: HashMap<u32, GMRef<Function>>,
+
+    /// Whether compressed tile data contains the padding of GameMaker's GMAC bug.
+    /// Detected by the first tile layer that would be padded; [`None`] before that.
+    /// See [`Metadata::emulate_gmac_tile_padding_bug`](crate::wad::data::Metadata::emulate_gmac_tile_padding_bug).
+    pub gmac_tile_padding_bug: Option<bool>,
+
+    /// The absolute position of the instructions of every code entry.
+    /// Set by [`crate::wad::elem::code`].
+    pub code_positions: Vec<u32>,
+
+    /// Chunks that failed to parse, if chunk recovery is enabled.
+    /// Set by [`DataReader::read_chunk`].
+    pub raw_chunks: Vec<RawChunk>,
+
+    /// How long parsing each chunk took, in the order they were parsed.
+    /// Set by [`DataReader::read_chunk`].
+    #[cfg(feature = "chunk-timings")]
+    pub chunk_timings: Vec<(ChunkName, Duration)>,
+
+    /// The byte ranges of all top-level chunk elements.
+    /// Set by [`DataReader::read_pointer_list`] and
+    /// [`DataReader::read_pointer_list_opt`].
+    #[cfg(feature = "element-ranges")]
+    pub element_ranges: ElementRanges,
+
+    /// How many pointer list elements are currently being deserialized.
+    /// Used to only record the ranges of top-level chunk elements.
+    #[cfg(feature = "element-ranges")]
+    pub element_depth: u32,
+
+    /// Log of every parsed chunk and list element.
+    /// Set up by [`crate::wad::parse`] if a trace file was specified.
+    #[cfg(feature = "parse-trace")]
+    pub trace: ParseTrace,
 }
 
 impl<'a> DataReader<'a> {
@@ -108,7 +150,7 @@ impl<'a> DataReader<'a> {
             chunk_padding: 16,
             // Assume little endian; big endian is an edge case.
             endianness: Endianness::Little,
-            chunk: ChunkBounds { start_pos: 0, end_pos },
+            chunk: ChunkBounds { start_pos: 0, end_pos, name: None },
             chunks: ChunkMap::new(),
             chunk_order: ChunkOrder::new_empty(),
             last_chunk: ChunkName::DAFL,          // stub
@@ -118,6 +160,17 @@ impl<'a> DataReader<'a> {
             texture_page_item_occurrences: HashMap::new(),
             variable_occurrences: HashMap::new(),
             function_occurrences: HashMap::new(),
+            gmac_tile_padding_bug: None,
+            code_positions: Vec::new(),
+            raw_chunks: Vec::new(),
+            #[cfg(feature = "chunk-timings")]
+            chunk_timings: Vec::new(),
+            #[cfg(feature = "element-ranges")]
+            element_ranges: ElementRanges::default(),
+            #[cfg(feature = "element-ranges")]
+            element_depth: 0,
+            #[cfg(feature = "parse-trace")]
+            trace: ParseTrace::default(),
         }
     }
 
@@ -126,6 +179,25 @@ impl<'a> DataReader<'a> {
         self.data.len() as u32
     }
 
+    /// Formats the bytes within `radius` bytes around the specified position
+    /// as a hex dump, for error messages.
+    ///
+    /// The window is clamped to the data file (not the current chunk).
+    /// The byte at `position` is marked with brackets.
+    pub fn hex_context(&self, position: u32, radius: u32) -> String {
+        let position = (position as usize).min(self.data.len());
+        let radius = radius as usize;
+        let start: usize = position.saturating_sub(radius);
+        let end: usize = (position + radius).min(self.data.len());
+
+        let before: String = hexdump(&self.data[start..position]);
+        let Some((byte, after)) = self.data[position..end].split_first() else {
+            return format!("{before} [EOF]");
+        };
+        let after: String = hexdump(after);
+        format!("{before} [{byte:02X}] {after}").trim().to_owned()
+    }
+
     /// Reads the specified number of bytes from the data file while advancing
     /// the data position. Returns an error when trying to read out of chunk
     /// bounds.
@@ -255,6 +327,51 @@ impl<'a> DataReader<'a> {
         Ok(())
     }
 
+    /// Sets the reader position to the specified absolute position.
+    ///
+    /// Returns an error if the position is outside of the current chunk.
+    /// The chunk end position itself is allowed, even though nothing can be
+    /// read there.
+    pub fn seek(&mut self, position: u32) -> Result<()> {
+        let start = self.chunk.start_pos;
+        let end = self.chunk.end_pos;
+        if position < start || position > end {
+            bail!("Cannot seek to position {position} outside of chunk bounds {start}..{end}");
+        }
+        self.cur_pos = position;
+        Ok(())
+    }
+
+    /// Advances the reader position by the specified number of bytes without
+    /// reading them.
+    ///
+    /// Returns an error if this would move past the end of the current chunk.
+    pub fn skip(&mut self, count: u32) -> Result<()> {
+        let position: u32 = self.cur_pos.checked_add(count).ok_or_else(|| {
+            format!(
+                "Skipping {count} bytes would overflow from position {}",
+                self.cur_pos
+            )
+        })?;
+        self.seek(position)
+            .ctx(|| format!("skipping {count} bytes"))
+    }
+
+    /// The number of bytes left until the end of the current chunk.
+    ///
+    /// This is zero if the reader position is at or past the chunk end.
+    pub const fn remaining(&self) -> u32 {
+        self.chunk.end_pos.saturating_sub(self.cur_pos)
+    }
+
+    /// Reads a 32-bit unsigned integer without advancing the reader position.
+    pub fn peek_u32(&mut self) -> Result<u32> {
+        let position: u32 = self.cur_pos;
+        let number: Result<u32> = self.read_u32();
+        self.cur_pos = position;
+        number
+    }
+
     /// Deserializes an element if the GameMaker version meets the requirement
     /// (`>=`).
     ///
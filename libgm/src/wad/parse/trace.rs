@@ -0,0 +1,68 @@
+// SPDX-License-Identifier: GPL-3.0-only
+//! A human-readable log of every element parsed, written to a file.
+//!
+//! Only available with the `parse-trace` feature.
+//! See [`ParsingOptions::trace_file`](crate::wad::parse::ParsingOptions::trace_file).
+
+use std::cell::RefCell;
+use std::fs::File;
+use std::io::BufWriter;
+use std::io::Write;
+use std::path::Path;
+use std::rc::Rc;
+
+use crate::prelude::*;
+use crate::util::fmt::typename;
+use crate::wad::chunk::ChunkName;
+
+/// Writes one line per parsed chunk and list element.
+///
+/// Lines are written as parsing proceeds, so when parsing fails, the last
+/// lines of the file point to the element that caused the failure.
+///
+/// The writer is shared between clones of the reader.
+#[derive(Debug, Clone, Default)]
+pub struct ParseTrace {
+    writer: Option<Rc<RefCell<BufWriter<File>>>>,
+
+    /// The chunk currently being parsed.
+    chunk: Option<ChunkName>,
+}
+
+impl ParseTrace {
+    /// Creates (or truncates) the trace file at the given path.
+    pub fn create(path: &Path) -> Result<Self> {
+        let file = File::create(path)
+            .ctx_any(|| format!("creating parse trace file {}", path.display()))?;
+        let writer = Some(Rc::new(RefCell::new(BufWriter::new(file))));
+        Ok(Self { writer, chunk: None })
+    }
+
+    /// Records the start of a chunk.
+    pub fn chunk(&mut self, chunk: ChunkName, start_pos: u32) {
+        self.chunk = Some(chunk);
+        self.write(format_args!("{start_pos:#010X}  {chunk}"));
+    }
+
+    /// Records the start of element `index` (out of `count`) of a list.
+    pub fn element<T>(&mut self, index: usize, count: usize, start_pos: u32) {
+        let chunk = self
+            .chunk
+            .map_or_else(String::new, |chunk| chunk.to_string());
+        let name = typename::<T>();
+        self.write(format_args!(
+            "{start_pos:#010X}  {chunk}  {name} {index}/{count}"
+        ));
+    }
+
+    fn write(&mut self, line: std::fmt::Arguments) {
+        let Some(writer) = &self.writer else {
+            return;
+        };
+        let result = writeln!(writer.borrow_mut(), "{line}");
+        if let Err(error) = result {
+            log::warn!("Could not write parse trace, disabling it: {error}");
+            self.writer = None;
+        }
+    }
+}
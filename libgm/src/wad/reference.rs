@@ -1,12 +1,16 @@
 // SPDX-License-Identifier: GPL-3.0-only
 //! Contains the `GMRef` type which is used to refer to other GameMaker elements.
 
+mod visit;
+
 use std::fmt;
 use std::hash::Hash;
 use std::hash::Hasher;
 use std::hint::cold_path;
 use std::marker::PhantomData;
 
+pub use self::visit::visit_asset_refs;
+pub use self::visit::visit_string_refs;
 use crate::prelude::*;
 use crate::util::fmt::typename;
 use crate::wad::elem::string::Strings;
@@ -122,11 +126,53 @@ impl<T> GMRef<T> {
     ///
     /// If the specified index is negative, then this reference is
     /// counted as null and will not point to anything.
+    ///
+    /// The index is not validated in any way; this function trusts the caller
+    /// that it is in range for the list it refers to.
+    /// An out of range reference will only cause an error once it is resolved,
+    /// which may be as late as when building the data file.
+    /// If the index comes from user input, consider using
+    /// [`GMRef::checked`] instead.
     #[must_use]
     pub const fn new(index: i32) -> Self {
         Self { index, _marker: PhantomData }
     }
 
+    /// Creates a new GameMaker reference with the specified index,
+    /// verifying that it is in range for the given element list.
+    ///
+    /// # Errors
+    /// Returns an error if `index` is out of bounds for `elements`.
+    pub fn checked(index: usize, elements: &[T]) -> Result<Self> {
+        Self::checked_len(index, elements.len())
+    }
+
+    /// Creates a new GameMaker reference with the specified index,
+    /// verifying that it is in range for the given nullable element list.
+    ///
+    /// # Errors
+    /// Returns an error if `index` is out of bounds for `elements`
+    /// or if the specified element is [`None`].
+    pub fn opt_checked(index: usize, elements: &[Option<T>]) -> Result<Self> {
+        let gm_ref = Self::checked_len(index, elements.len())?;
+        if elements[index].is_none() {
+            bail!("The reference {gm_ref:?} points to a null element (removed by asset compiler)");
+        }
+        Ok(gm_ref)
+    }
+
+    fn checked_len(index: usize, len: usize) -> Result<Self> {
+        if index >= len {
+            bail!(
+                "Index {index} is out of bounds for {} elements vector with length {len}",
+                typename::<T>(),
+            );
+        }
+        let index = i32::try_from(index)
+            .map_err(|_| err!("Index {index} is too large for a GameMaker reference"))?;
+        Ok(Self::new(index))
+    }
+
     /// Creates a null GameMaker reference which does not point to anything.
     #[must_use]
     pub const fn none() -> Self {
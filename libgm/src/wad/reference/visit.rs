@@ -0,0 +1,582 @@
+// SPDX-License-Identifier: GPL-3.0-only
+//! Walks all asset and string references stored in a [`GMData`].
+
+use std::any::Any;
+
+use crate::gml::Instruction;
+use crate::gml::instruction::AssetReference;
+use crate::gml::instruction::InstanceType;
+use crate::gml::instruction::PushValue;
+use crate::prelude::*;
+use crate::wad::elem::GMElement;
+use crate::wad::elem::game_object::event::Action;
+use crate::wad::elem::game_object::event::EventGroups;
+use crate::wad::elem::room::layer;
+use crate::wad::elem::room::layer::effect;
+use crate::wad::elem::room::tile::RoomTile;
+use crate::wad::elem::room::tile::Texture;
+use crate::wad::elem::sequence::Sequence;
+use crate::wad::elem::sequence::Track;
+use crate::wad::elem::sequence::track::Keyframes;
+use crate::wad::elem::sequence::track::keyframe;
+use crate::wad::elem::ui_node::NodeData;
+use crate::wad::elem::ui_node::UINode;
+
+/// A type erased callback receiving a `&mut GMRef<T>`.
+///
+/// Use [`Any::downcast_mut`] to filter for the reference type of interest.
+pub type Visitor<'a> = dyn FnMut(&mut dyn Any) + 'a;
+
+/// A callback receiving a `&mut GMRef<String>`.
+pub type StringVisitor<'a> = dyn FnMut(&mut GMRef<String>) + 'a;
+
+/// Calls the visitor with every asset reference in the data.
+///
+/// Asset references are references to sprites, sounds, game objects, rooms,
+/// paths, scripts, fonts, timelines, shaders, sequences, animation curves,
+/// particle systems, particle emitters and backgrounds (tilesets).
+/// References to strings, code entries, functions, variables and texture page
+/// items are not visited.
+///
+/// Null references are visited as well.
+pub fn visit_asset_refs(data: &mut GMData, visit: &mut Visitor) {
+    for room in &mut data.general_info.room_order {
+        visit(room);
+    }
+
+    for game_object in data.game_objects.elems.iter_mut().flatten() {
+        visit(&mut game_object.sprite);
+        visit(&mut game_object.parent);
+        visit(&mut game_object.texture_mask);
+        for event in &mut game_object.events.collision {
+            visit(&mut event.subtype.object);
+        }
+    }
+
+    for room in data.rooms.elems.iter_mut().flatten() {
+        for background in &mut room.backgrounds {
+            visit(&mut background.background_definition);
+        }
+        for view in &mut room.views {
+            visit(&mut view.object);
+        }
+        for instance in &mut room.game_objects {
+            visit(&mut instance.object_definition);
+        }
+        visit_tiles(&mut room.tiles, visit);
+        for layer in &mut room.layers {
+            visit_layer_data(&mut layer.data, visit);
+        }
+        for sequence in &mut room.sequences {
+            visit_sequence(sequence, visit);
+        }
+    }
+
+    for sequence in data.sequences.elems.iter_mut().flatten() {
+        visit_sequence(sequence, visit);
+    }
+
+    for sprite in data.sprites.elems.iter_mut().flatten() {
+        if let Some(sequence) = sprite
+            .special_fields
+            .as_mut()
+            .and_then(|special| special.sequence.as_mut())
+        {
+            visit_sequence(sequence, visit);
+        }
+    }
+
+    for particle_system in data.particle_systems.elems.iter_mut().flatten() {
+        for emitter in &mut particle_system.emitters {
+            visit(emitter);
+        }
+    }
+
+    for emitter in &mut data.particle_emitters.elems {
+        visit(&mut emitter.sprite);
+        visit(&mut emitter.spawn_on_death);
+        visit(&mut emitter.spawn_on_update);
+    }
+
+    for group in &mut data.texture_group_infos.elems {
+        for sprite in group.sprites.iter_mut().chain(&mut group.spine_sprites) {
+            visit(sprite);
+        }
+        for font in &mut group.fonts {
+            visit(font);
+        }
+        for tileset in &mut group.tilesets {
+            visit(tileset);
+        }
+    }
+
+    for node in &mut data.ui_nodes.elems {
+        visit_ui_node(node, visit);
+    }
+
+    for code in &mut data.codes.elems {
+        for instruction in &mut code.instructions {
+            if let Some(variable) = instruction.variable_mut()
+                && let InstanceType::GameObject(game_object) = &mut variable.instance_type
+            {
+                visit(game_object);
+            }
+            if let Instruction::PushReference { asset_reference } = instruction {
+                visit_asset_reference(asset_reference, visit);
+            }
+        }
+    }
+}
+
+fn visit_asset_reference(asset_reference: &mut AssetReference, visit: &mut Visitor) {
+    match asset_reference {
+        AssetReference::Object(gm_ref) => visit(gm_ref),
+        AssetReference::Sprite(gm_ref) => visit(gm_ref),
+        AssetReference::Sound(gm_ref) => visit(gm_ref),
+        AssetReference::Room(gm_ref) => visit(gm_ref),
+        AssetReference::Path(gm_ref) => visit(gm_ref),
+        AssetReference::Script(gm_ref) => visit(gm_ref),
+        AssetReference::Font(gm_ref) => visit(gm_ref),
+        AssetReference::Timeline(gm_ref) => visit(gm_ref),
+        AssetReference::Shader(gm_ref) => visit(gm_ref),
+        AssetReference::Sequence(gm_ref) => visit(gm_ref),
+        AssetReference::AnimCurve(gm_ref) => visit(gm_ref),
+        AssetReference::ParticleSystem(gm_ref) => visit(gm_ref),
+        AssetReference::Background(gm_ref) => visit(gm_ref),
+        AssetReference::RoomInstance(_) | AssetReference::Function(_) => {}
+    }
+}
+
+fn visit_tiles(tiles: &mut [RoomTile], visit: &mut Visitor) {
+    for tile in tiles {
+        match &mut tile.texture {
+            Texture::Sprite(sprite) => visit(sprite),
+            Texture::Background(background) => visit(background),
+        }
+    }
+}
+
+fn visit_layer_data(data: &mut layer::Data, visit: &mut Visitor) {
+    match data {
+        layer::Data::Tiles(tiles) => visit(&mut tiles.tileset),
+        layer::Data::Background(background) => visit(&mut background.sprite),
+        layer::Data::Assets(assets) => {
+            visit_tiles(&mut assets.legacy_tiles, visit);
+            for instance in assets.sprites.iter_mut().chain(&mut assets.nine_slices) {
+                visit(&mut instance.sprite);
+            }
+            for instance in &mut assets.sequences {
+                visit(&mut instance.sequence);
+            }
+            for instance in &mut assets.particle_systems {
+                visit(&mut instance.particle_system);
+            }
+            for instance in &mut assets.text_items {
+                visit(&mut instance.font);
+            }
+        }
+        layer::Data::None | layer::Data::Instances(_) | layer::Data::Effect(_) => {}
+    }
+}
+
+fn visit_sequence(sequence: &mut Sequence, visit: &mut Visitor) {
+    for track in &mut sequence.tracks {
+        visit_track(track, visit);
+    }
+}
+
+fn visit_track(track: &mut Track, visit: &mut Visitor) {
+    match &mut track.keyframes {
+        Keyframes::Audio(data) => {
+            for value in channel_values(data) {
+                visit(&mut value.sound);
+            }
+        }
+        Keyframes::Instance(data) => {
+            for value in channel_values(data) {
+                visit(&mut value.game_object);
+            }
+        }
+        Keyframes::Graphic(data) => {
+            for value in channel_values(data) {
+                visit(&mut value.sprite);
+            }
+        }
+        Keyframes::Sequence(data) => {
+            for value in channel_values(data) {
+                visit(&mut value.sequence);
+            }
+        }
+        Keyframes::Particle(data) => {
+            for value in channel_values(data) {
+                visit(&mut value.particle);
+            }
+        }
+        _ => {}
+    }
+    for sub_track in &mut track.sub_tracks {
+        visit_track(sub_track, visit);
+    }
+}
+
+fn channel_values<T: GMElement>(data: &mut keyframe::Data<T>) -> impl Iterator<Item = &mut T> {
+    data.keyframes
+        .iter_mut()
+        .flat_map(|keyframe| &mut keyframe.channels)
+        .map(|channel| &mut channel.value)
+}
+
+fn visit_ui_node(node: &mut UINode, visit: &mut Visitor) {
+    match &mut node.node {
+        NodeData::GameObject(game_object) => {
+            visit(&mut game_object.room_game_object.object_definition);
+        }
+        NodeData::SequenceInstance(instance) => visit(&mut instance.sequence_instance.sequence),
+        NodeData::SpriteInstance(instance) => visit(&mut instance.sprite_instance.sprite),
+        NodeData::TextItemInstance(instance) => visit(&mut instance.text_item_instance.font),
+        NodeData::Layer(_) | NodeData::FlexPanel(_) | NodeData::EffectLayer(_) => {}
+    }
+    for child in &mut node.children {
+        visit_ui_node(child, visit);
+    }
+}
+
+/// Calls the visitor with every string reference in the data.
+///
+/// This includes element names, code strings, shader sources and all other
+/// fields stored in the `STRG` chunk.
+/// String references in raw (unparsed) chunks are not visited.
+///
+/// Null references are visited as well.
+#[expect(clippy::too_many_lines)]
+pub fn visit_string_refs(data: &mut GMData, visit: &mut StringVisitor) {
+    let general_info = &mut data.general_info;
+    visit(&mut general_info.game_file_name);
+    visit(&mut general_info.config);
+    visit(&mut general_info.game_name);
+    visit(&mut general_info.display_name);
+
+    for constant in &mut data.options.constants {
+        visit(&mut constant.name);
+        visit(&mut constant.value);
+    }
+
+    let language_info = &mut data.language_info;
+    language_info.entry_ids.iter_mut().for_each(&mut *visit);
+    for language in &mut language_info.elems {
+        visit(&mut language.name);
+        visit(&mut language.region);
+        language.entries.iter_mut().for_each(&mut *visit);
+    }
+
+    for extension in &mut data.extensions.elems {
+        visit(&mut extension.folder_name);
+        visit(&mut extension.name);
+        visit(&mut extension.version);
+        visit(&mut extension.class_name);
+        for file in &mut extension.files {
+            visit(&mut file.filename);
+            visit(&mut file.cleanup_script);
+            visit(&mut file.init_script);
+            for function in &mut file.functions {
+                visit(&mut function.name);
+                visit(&mut function.ext_name);
+            }
+        }
+        for option in &mut extension.options {
+            visit(&mut option.name);
+            visit(&mut option.value);
+        }
+    }
+
+    for sound in data.sounds.elems.iter_mut().flatten() {
+        visit(&mut sound.name);
+        visit(&mut sound.audio_type);
+        visit(&mut sound.file);
+    }
+
+    for audio_group in data.audio_groups.elems.iter_mut().flatten() {
+        visit(&mut audio_group.name);
+        visit(&mut audio_group.path);
+    }
+
+    for sprite in data.sprites.elems.iter_mut().flatten() {
+        visit(&mut sprite.name);
+        if let Some(sequence) = sprite
+            .special_fields
+            .as_mut()
+            .and_then(|special| special.sequence.as_mut())
+        {
+            visit_sequence_strings(sequence, visit);
+        }
+    }
+
+    for tileset in data.tilesets.elems.iter_mut().flatten() {
+        visit(&mut tileset.name);
+    }
+
+    for path in data.paths.elems.iter_mut().flatten() {
+        visit(&mut path.name);
+    }
+
+    for script in &mut data.scripts.elems {
+        visit(&mut script.name);
+    }
+
+    for font in data.fonts.elems.iter_mut().flatten() {
+        visit(&mut font.name);
+        visit(&mut font.display_name);
+    }
+
+    for timeline in data.timelines.elems.iter_mut().flatten() {
+        visit(&mut timeline.name);
+        for moment in &mut timeline.moments {
+            visit_action_strings(&mut moment.actions, visit);
+        }
+    }
+
+    for game_object in data.game_objects.elems.iter_mut().flatten() {
+        visit(&mut game_object.name);
+        visit_event_strings(&mut game_object.events, visit);
+    }
+
+    for room in data.rooms.elems.iter_mut().flatten() {
+        visit(&mut room.name);
+        visit(&mut room.caption);
+        for layer in &mut room.layers {
+            visit(&mut layer.name);
+            if let Some(effect_data) = &mut layer.effect_data_2022_1 {
+                visit(&mut effect_data.effect_type);
+                visit_effect_properties(&mut effect_data.effect_properties, visit);
+            }
+            visit_layer_strings(&mut layer.data, visit);
+        }
+        for sequence in &mut room.sequences {
+            visit_sequence_strings(sequence, visit);
+        }
+    }
+
+    for shader in data.shaders.elems.iter_mut().flatten() {
+        visit(&mut shader.name);
+        visit(&mut shader.glsl_es_vertex);
+        visit(&mut shader.glsl_es_fragment);
+        visit(&mut shader.glsl_vertex);
+        visit(&mut shader.glsl_fragment);
+        visit(&mut shader.hlsl9_vertex);
+        visit(&mut shader.hlsl9_fragment);
+        shader
+            .vertex_shader_attributes
+            .iter_mut()
+            .for_each(&mut *visit);
+    }
+
+    for image in &mut data.embedded_images.elems {
+        visit(&mut image.name);
+    }
+
+    for group in &mut data.texture_group_infos.elems {
+        visit(&mut group.name);
+        if let Some(data_2022_9) = &mut group.data_2022_9 {
+            visit(&mut data_2022_9.directory);
+            visit(&mut data_2022_9.extension);
+        }
+    }
+
+    for code in &mut data.codes.elems {
+        visit(&mut code.name);
+        for instruction in &mut code.instructions {
+            if let Instruction::Push { value: PushValue::String(string) } = instruction {
+                visit(string);
+            }
+        }
+    }
+
+    for function in &mut data.functions.elems {
+        visit(&mut function.name);
+    }
+    for code_local in &mut data.functions.code_locals {
+        visit(&mut code_local.name);
+        for variable in &mut code_local.variables {
+            visit(&mut variable.name);
+        }
+    }
+
+    for variable in &mut data.variables.elems {
+        visit(&mut variable.name);
+    }
+
+    for curve in data.animation_curves.elems.iter_mut().flatten() {
+        visit(&mut curve.name);
+        for channel in &mut curve.channels {
+            visit(&mut channel.name);
+        }
+    }
+
+    for sequence in data.sequences.elems.iter_mut().flatten() {
+        visit_sequence_strings(sequence, visit);
+    }
+
+    for particle_system in data.particle_systems.elems.iter_mut().flatten() {
+        visit(&mut particle_system.name);
+    }
+
+    for emitter in &mut data.particle_emitters.elems {
+        visit(&mut emitter.name);
+    }
+
+    data.tags.elems.iter_mut().for_each(&mut *visit);
+    for asset_tags in &mut data.tags.asset_tags {
+        asset_tags.tags.iter_mut().for_each(&mut *visit);
+    }
+
+    data.feature_flags.elems.iter_mut().for_each(&mut *visit);
+
+    for filter_effect in &mut data.filter_effects.elems {
+        visit(&mut filter_effect.name);
+        visit(&mut filter_effect.value);
+    }
+
+    for node in &mut data.ui_nodes.elems {
+        visit_ui_node_strings(node, visit);
+    }
+}
+
+fn visit_action_strings(actions: &mut [Action], visit: &mut StringVisitor) {
+    for action in actions {
+        visit(&mut action.name);
+    }
+}
+
+fn visit_event_strings(events: &mut EventGroups, visit: &mut StringVisitor) {
+    macro_rules! visit_groups {
+        ($($field:ident),*) => {
+            $(for event in &mut events.$field {
+                visit_action_strings(&mut event.actions, visit);
+            })*
+        };
+    }
+    visit_groups!(
+        create,
+        destroy,
+        alarm,
+        step,
+        collision,
+        keyboard,
+        mouse,
+        other,
+        draw,
+        key_press,
+        key_release,
+        trigger,
+        cleanup,
+        gesture,
+        pre_create
+    );
+}
+
+fn visit_effect_properties(properties: &mut [effect::Property], visit: &mut StringVisitor) {
+    for property in properties {
+        visit(&mut property.name);
+        visit(&mut property.value);
+    }
+}
+
+fn visit_layer_strings(data: &mut layer::Data, visit: &mut StringVisitor) {
+    match data {
+        layer::Data::Assets(assets) => {
+            for instance in assets.sprites.iter_mut().chain(&mut assets.nine_slices) {
+                visit(&mut instance.name);
+            }
+            for instance in &mut assets.sequences {
+                visit(&mut instance.name);
+            }
+            for instance in &mut assets.particle_systems {
+                visit(&mut instance.name);
+            }
+            for instance in &mut assets.text_items {
+                visit(&mut instance.name);
+                visit(&mut instance.text);
+            }
+        }
+        layer::Data::Effect(effect) => {
+            visit(&mut effect.effect_type);
+            visit_effect_properties(&mut effect.properties, visit);
+        }
+        layer::Data::None
+        | layer::Data::Instances(_)
+        | layer::Data::Tiles(_)
+        | layer::Data::Background(_) => {}
+    }
+}
+
+fn visit_sequence_strings(sequence: &mut Sequence, visit: &mut StringVisitor) {
+    visit(&mut sequence.name);
+    for data in &mut sequence.broadcast_messages {
+        for value in channel_values(data) {
+            value.messages.iter_mut().for_each(&mut *visit);
+        }
+    }
+    for track in &mut sequence.tracks {
+        visit_track_strings(track, visit);
+    }
+    sequence.function_ids.values_mut().for_each(&mut *visit);
+    for data in &mut sequence.moments {
+        for value in channel_values(data) {
+            visit(&mut value.event);
+        }
+    }
+}
+
+fn visit_track_strings(track: &mut Track, visit: &mut StringVisitor) {
+    visit(&mut track.model_name);
+    visit(&mut track.name);
+    visit(&mut track.animcurve_string);
+    match &mut track.keyframes {
+        Keyframes::String(data) => {
+            for value in channel_values(data) {
+                visit(&mut value.string);
+            }
+        }
+        Keyframes::Text(data) => {
+            for value in channel_values(data) {
+                visit(&mut value.text);
+            }
+        }
+        Keyframes::BroadcastMessage(data) => {
+            for value in channel_values(data) {
+                value.messages.iter_mut().for_each(&mut *visit);
+            }
+        }
+        _ => {}
+    }
+    for curve in &mut track.owned_resources {
+        visit(&mut curve.name);
+        for channel in &mut curve.channels {
+            visit(&mut channel.name);
+        }
+    }
+    for sub_track in &mut track.sub_tracks {
+        visit_track_strings(sub_track, visit);
+    }
+}
+
+fn visit_ui_node_strings(node: &mut UINode, visit: &mut StringVisitor) {
+    match &mut node.node {
+        NodeData::Layer(layer) => visit(&mut layer.name),
+        NodeData::FlexPanel(panel) => visit(&mut panel.name),
+        NodeData::EffectLayer(effect_layer) => {
+            visit(&mut effect_layer.effect_type);
+            visit_effect_properties(&mut effect_layer.properties, visit);
+        }
+        NodeData::SequenceInstance(instance) => visit(&mut instance.sequence_instance.name),
+        NodeData::SpriteInstance(instance) => visit(&mut instance.sprite_instance.name),
+        NodeData::TextItemInstance(instance) => {
+            visit(&mut instance.text_item_instance.name);
+            visit(&mut instance.text_item_instance.text);
+        }
+        NodeData::GameObject(_) => {}
+    }
+    for child in &mut node.children {
+        visit_ui_node_strings(child, visit);
+    }
+}
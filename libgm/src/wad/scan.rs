@@ -0,0 +1,81 @@
+// SPDX-License-Identifier: GPL-3.0-only
+//! Scanning raw data files for embedded resources by their magic bytes.
+//!
+//! This is a read-only diagnostic which complements the structured
+//! accessors: it finds blobs regardless of which chunk (if any) claims them.
+
+use crate::prelude::*;
+use crate::wad::elem::texture_page::BZ2_QOI_HEADER;
+use crate::wad::elem::texture_page::PNG_HEADER;
+use crate::wad::elem::texture_page::QOI_HEADER;
+
+/// The kind of resource identified by its magic bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MagicKind {
+    /// A PNG image (`\x89PNG\r\n\x1A\n`).
+    Png,
+    /// An Ogg container, usually Ogg Vorbis audio (`OggS`).
+    Ogg,
+    /// A RIFF container, usually WAV audio (`RIFF`).
+    Riff,
+    /// A GameMaker QOI image (`fioq`).
+    Qoi,
+    /// A GameMaker QOI image compressed with bzip2 (`2zoq`).
+    Bz2Qoi,
+}
+
+impl MagicKind {
+    /// All magic kinds, in the order they are checked.
+    pub const ALL: [Self; 5] = [Self::Png, Self::Ogg, Self::Riff, Self::Qoi, Self::Bz2Qoi];
+
+    /// The magic bytes identifying this kind of resource.
+    #[must_use]
+    pub const fn magic(self) -> &'static [u8] {
+        match self {
+            Self::Png => &PNG_HEADER,
+            Self::Ogg => b"OggS",
+            Self::Riff => b"RIFF",
+            Self::Qoi => QOI_HEADER,
+            Self::Bz2Qoi => BZ2_QOI_HEADER,
+        }
+    }
+}
+
+/// Finds all occurrences of known magic bytes in a raw data file.
+///
+/// Returns the absolute byte offset and kind of every match, in order.
+///
+/// Note that magic bytes may also occur by chance, for example inside
+/// compressed data. Ogg files consist of many pages which each start with
+/// `OggS`, so every page is reported.
+#[must_use]
+pub fn scan_for_magic(raw_data: &[u8]) -> Vec<(usize, MagicKind)> {
+    let mut matches: Vec<(usize, MagicKind)> = Vec::new();
+    for pos in 0..raw_data.len() {
+        let rest: &[u8] = &raw_data[pos..];
+        for kind in MagicKind::ALL {
+            if rest.starts_with(kind.magic()) {
+                matches.push((pos, kind));
+            }
+        }
+    }
+    matches
+}
+
+impl GMData {
+    /// Finds all occurrences of known magic bytes in the original data file.
+    ///
+    /// The data file is read again from [`Metadata::location`], so this only
+    /// works for data parsed from a file. Offsets are absolute positions in
+    /// that file. For data in memory, use [`scan_for_magic`] directly.
+    ///
+    /// [`Metadata::location`]: crate::wad::data::Metadata::location
+    pub fn scan_for_magic(&self) -> Result<Vec<(usize, MagicKind)>> {
+        let Some(path) = &self.meta.location else {
+            bail!("Data file location is unknown; scan the raw data with `scan_for_magic` instead");
+        };
+        let raw_data: Vec<u8> =
+            std::fs::read(path).ctx_any(|| format!("reading data file {}", path.display()))?;
+        Ok(scan_for_magic(&raw_data))
+    }
+}
@@ -0,0 +1,120 @@
+// SPDX-License-Identifier: GPL-3.0-only
+//! Estimates of how many bytes each category of data takes up in a data file.
+//!
+//! These are computed from the elements directly, without building the data.
+//! Texture and audio sizes are exact; string and code sizes are
+//! approximations (see the individual functions).
+
+use std::fmt;
+
+use crate::prelude::*;
+use crate::util::fmt::format_bytes;
+
+/// The serialized byte footprint of a [`GMData`], split into categories.
+///
+/// See [`GMData::size_breakdown`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SizeBreakdown {
+    /// See [`GMData::strings_total_bytes`].
+    pub strings: usize,
+    /// See [`GMData::code_total_bytes`].
+    pub code: usize,
+    /// See [`GMData::textures_total_bytes`].
+    pub textures: usize,
+    /// See [`GMData::audio_total_bytes`].
+    pub audio: usize,
+}
+
+impl SizeBreakdown {
+    /// The sum of all categories.
+    #[must_use]
+    pub const fn total(&self) -> usize {
+        self.strings + self.code + self.textures + self.audio
+    }
+}
+
+impl fmt::Display for SizeBreakdown {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "textures: {}", format_bytes(self.textures))?;
+        writeln!(f, "audio: {}", format_bytes(self.audio))?;
+        writeln!(f, "code: {}", format_bytes(self.code))?;
+        write!(f, "strings: {}", format_bytes(self.strings))
+    }
+}
+
+impl GMData {
+    /// Computes the serialized byte footprint of strings, code, textures and
+    /// embedded audio without building the data.
+    ///
+    /// This encodes all textures stored as a
+    /// [`DynamicImage`](image::DynamicImage), which is slow.
+    pub fn size_breakdown(&self) -> Result<SizeBreakdown> {
+        Ok(SizeBreakdown {
+            strings: self.strings_total_bytes(),
+            code: self.code_total_bytes(),
+            textures: self.textures_total_bytes()?,
+            audio: self.audio_total_bytes(),
+        })
+    }
+
+    /// The number of bytes all strings take up in the `STRG` chunk.
+    ///
+    /// Each string consists of a pointer, a length, the UTF-8 bytes and a
+    /// null terminator. This is an approximation since alignment padding
+    /// is not included.
+    #[must_use]
+    pub fn strings_total_bytes(&self) -> usize {
+        self.strings
+            .elems
+            .iter()
+            .map(|string| 4 + 4 + string.len() + 1)
+            .sum()
+    }
+
+    /// The number of bytes all code instructions take up.
+    ///
+    /// This is an approximation since it only includes the bytecode itself,
+    /// not the code entry headers or the `VARI`/`FUNC` occurrence data.
+    #[must_use]
+    pub fn code_total_bytes(&self) -> usize {
+        self.codes
+            .elems
+            .iter()
+            .flat_map(|code| &code.instructions)
+            .map(|instruction| instruction.size() as usize)
+            .sum()
+    }
+
+    /// The number of bytes all embedded texture page images take up.
+    ///
+    /// This is exact, excluding alignment padding between images.
+    /// External textures are not counted.
+    ///
+    /// For more information, see [`GMImage::serialized_size`](crate::wad::elem::texture_page::GMImage::serialized_size).
+    pub fn textures_total_bytes(&self) -> Result<usize> {
+        let mut total: usize = 0;
+        for (i, texture_page) in self.texture_pages.elems.iter().enumerate() {
+            let Some(image) = &texture_page.image else {
+                continue;
+            };
+            total += image
+                .serialized_size(self.meta.version)
+                .ctx(|| format!("computing size of texture page #{i}"))?;
+        }
+        Ok(total)
+    }
+
+    /// The number of bytes all embedded audio entries take up in the `AUDO`
+    /// chunk.
+    ///
+    /// This is exact, excluding alignment padding between entries.
+    /// Audio stored in external audio group files is not counted.
+    #[must_use]
+    pub fn audio_total_bytes(&self) -> usize {
+        self.audios
+            .elems
+            .iter()
+            .map(|audio| 4 + audio.data.len())
+            .sum()
+    }
+}
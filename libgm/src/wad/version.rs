@@ -6,6 +6,7 @@ use std::fmt::Formatter;
 
 use crate::prelude::*;
 use crate::wad::build::builder::DataBuilder;
+use crate::wad::chunk::ChunkName;
 use crate::wad::elem::GMElement;
 use crate::wad::parse::reader::DataReader;
 
@@ -304,6 +305,56 @@ impl Display for GMVersion {
     }
 }
 
+impl GMVersion {
+    /// Whether this version is at least the version implied by the
+    /// existence of the given chunks.
+    ///
+    /// This is useful for checking whether a manually specified version
+    /// can possibly be correct for a data file.
+    /// See [`version_floor_from_chunks`] for more information.
+    #[must_use]
+    pub fn satisfied_by_chunks(self, chunk_names: &[&str]) -> bool {
+        version_floor_from_chunks(chunk_names).is_none_or(|floor| self >= floor)
+    }
+}
+
+/// Chunks whose existence implies a minimum format version, newest first.
+///
+/// This table is also used by the format version detection.
+pub const CHUNK_VERSION_FLOORS: [(ChunkName, GMVersion); 6] = [
+    (ChunkName::UILR, GMVersion::GM2024_13),
+    (ChunkName::PSEM, GMVersion::GM2023_2),
+    (ChunkName::FEAT, GMVersion::GM2022_8),
+    (ChunkName::FEDS, GMVersion::GMS2_3_6),
+    (ChunkName::SEQN, GMVersion::GMS2_3),
+    (ChunkName::TGIN, GMVersion::GMS2_2_1),
+];
+
+/// Gets the minimum format version implied purely by the existence of the
+/// given chunks (such as `"TGIN"` or `"SEQN"`).
+///
+/// Unknown chunk names are ignored.
+/// Returns [`None`] if none of the chunks imply a version.
+#[must_use]
+pub fn version_floor_from_chunks(chunk_names: &[&str]) -> Option<GMVersion> {
+    let floor = version_floor_by(|chunk| {
+        chunk_names
+            .iter()
+            .any(|name| name.as_bytes() == chunk.as_bytes())
+    });
+    floor.map(|(_, version)| version)
+}
+
+/// Gets the chunk implying the highest minimum format version,
+/// along with that version.
+pub(crate) fn version_floor_by(
+    contains: impl Fn(ChunkName) -> bool,
+) -> Option<(ChunkName, GMVersion)> {
+    CHUNK_VERSION_FLOORS
+        .into_iter()
+        .find(|&(chunk_name, _)| contains(chunk_name))
+}
+
 // TODO: better detection whether it's 1.1 - 1.4
 const fn gms1_minor_by_build(build: u32) -> Option<u8> {
     if build < 1000 || build > 9999 {
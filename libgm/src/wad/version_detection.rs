@@ -17,12 +17,16 @@ mod txtr;
 use crate::prelude::*;
 use crate::wad::GMVersion;
 use crate::wad::GMVersion::*;
+use crate::wad::build_bytes;
 use crate::wad::chunk::ChunkName;
 use crate::wad::chunk::ChunkName::*;
 use crate::wad::elem::GMElement;
+use crate::wad::parse::ParsingOptions;
 use crate::wad::parse::chunk::ChunkMap;
+use crate::wad::parse::parse_form;
 use crate::wad::parse::reader::DataReader;
 use crate::wad::version::IdeVersion;
+use crate::wad::version::version_floor_by;
 
 type CheckerFn = fn(&mut DataReader) -> Result<Option<GMVersion>>;
 
@@ -54,23 +58,9 @@ impl VersionCheck {
 }
 
 fn init_by_chunk_existence(chunks: &ChunkMap) -> Option<GMVersion> {
-    const UPGRADES: [(ChunkName, GMVersion); 6] = [
-        (UILR, GM2024_13),
-        (PSEM, GM2023_2),
-        (FEAT, GM2022_8),
-        (FEDS, GMS2_3_6),
-        (SEQN, GMS2_3),
-        (TGIN, GMS2_2_1),
-    ];
-
-    for (chunk_name, version) in UPGRADES {
-        if chunks.contains(chunk_name) {
-            log::debug!("Existence of chunk {chunk_name} implies a Version of at least {version}");
-            return Some(version);
-        }
-    }
-
-    None
+    let (chunk_name, version) = version_floor_by(|chunk_name| chunks.contains(chunk_name))?;
+    log::debug!("Existence of chunk {chunk_name} implies a Version of at least {version}");
+    Some(version)
 }
 
 /// HACK: The `Into` trait is still not const unfortunately.
@@ -106,9 +96,10 @@ fn create_version_checks() -> [VersionCheck; 24] {
 fn init_by_gen8(reader: &mut DataReader) -> Result<GMVersion> {
     reader.chunk = reader.chunks.get(GEN8).ok_or("Chunk GEN8 does not exist")?;
 
-    reader.cur_pos = reader.chunk.start_pos + 1; // Skip to WAD version
+    reader.seek(reader.chunk.start_pos)?;
+    reader.skip(1)?; // Skip to WAD version
     let wad_version = reader.read_u8().ctx("reading WAD version")?;
-    reader.cur_pos += 42; // Skip to IDE version
+    reader.skip(42)?; // Skip to IDE version
     let ide_version = IdeVersion::deserialize(reader).ctx("reading IDE version")?;
 
     log::debug!("GEN8 specifies IDE Version {ide_version} and WAD Version {wad_version}");
@@ -181,7 +172,7 @@ pub fn detect_format_version(mut reader: DataReader) -> Result<GMVersion> {
             };
 
             reader.chunk = chunk;
-            reader.cur_pos = reader.chunk.start_pos;
+            reader.seek(chunk.start_pos)?;
 
             let detected_version_opt: Option<GMVersion> =
                 (check.checker_fn)(&mut reader).ctx(|| {
@@ -218,6 +209,40 @@ pub fn detect_format_version(mut reader: DataReader) -> Result<GMVersion> {
     Ok(reader.version)
 }
 
+impl GMData {
+    /// Re-runs format version detection on the current (edited) data.
+    ///
+    /// Edits such as adding chunks or using newer features may imply a newer
+    /// format version than the one the data was parsed with. For example,
+    /// adding the `PSEM` chunk (particle systems) implies at least 2023.2.
+    ///
+    /// The data is built in memory and the detection logic is run on the
+    /// built bytes. If the detected version is newer than
+    /// [`Metadata::version`](crate::wad::data::Metadata::version), it is
+    /// updated and the new version is returned.
+    /// Otherwise, nothing is changed and [`None`] is returned.
+    ///
+    /// Note that the detection can only see what the current version
+    /// serializes; see [`detect_format_version`] for its limitations.
+    pub fn redetect_version(&mut self) -> Result<Option<GMVersion>> {
+        let raw_data: Vec<u8> = build_bytes(self)?;
+        let mut reader: DataReader = parse_form(&raw_data).ctx("parsing FORM")?;
+        // Only the version is of interest here, not the validity of constants.
+        reader.options = ParsingOptions::LENIENT;
+        let detected: GMVersion = detect_format_version(reader).ctx("detecting format version")?;
+
+        if detected <= self.meta.version {
+            return Ok(None);
+        }
+        log::info!(
+            "Edits imply format version {detected}; upgrading from {}",
+            self.meta.version,
+        );
+        self.meta.version = detected;
+        Ok(Some(detected))
+    }
+}
+
 macro_rules! target_version {
     ($ident:ident) => {
         Ok(Some($crate::wad::version::GMVersion::$ident))
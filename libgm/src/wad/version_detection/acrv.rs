@@ -13,7 +13,9 @@ pub fn check_2_3_1(reader: &mut DataReader) -> Result<Option<GMVersion>> {
     }
 
     // Go to the first "point"
-    reader.cur_pos = reader.read_u32()? + 8;
+    let point_pointer = reader.read_u32()?;
+    reader.seek(point_pointer)?;
+    reader.skip(8)?;
 
     // In 2.3 an int with the value of 0 would be set here, it cannot be version 2.3
     // if this value isn't 0.
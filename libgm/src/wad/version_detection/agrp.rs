@@ -36,11 +36,12 @@ pub fn check_2024_14(reader: &mut DataReader) -> Result<Option<GMVersion>> {
         // Only one group
         // Look for non-null bytes in the 4 bytes after the audio group name (and within
         // bounds of the chunk)
-        reader.cur_pos = position1 + 4;
-        if reader.cur_pos + 4 > reader.chunk.end_pos {
+        reader.seek(position1)?;
+        if reader.remaining() < 8 {
             return Ok(None); // New field can't fit in remaining space
         }
-        let path_pointer = reader.read_u32()?;
+        reader.skip(4)?;
+        let path_pointer = reader.peek_u32()?;
         if path_pointer == 0 {
             return Ok(None); // If the field data is zero, it's not 2024.14
         }
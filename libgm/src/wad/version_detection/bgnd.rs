@@ -29,7 +29,8 @@ pub fn check_2024_14_1(reader: &mut DataReader) -> Result<Option<GMVersion>> {
 
         // Skip all the way to "GMS2ItemsPerTileCount" (at its pre-2024.14.1 location),
         // which is what we actually care about.
-        reader.cur_pos = bg_ptr + 44;
+        reader.seek(bg_ptr)?;
+        reader.skip(44)?;
         let items_per_tile_count = reader.read_u32()?;
         let tile_count = reader.read_u32()?;
 
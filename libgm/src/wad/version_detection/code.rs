@@ -1,5 +1,7 @@
 // SPDX-License-Identifier: GPL-3.0-only
 use super::target_version;
+use crate::gml::instruction::AssetCounts;
+use crate::gml::instruction::AssetReference;
 use crate::gml::instruction::DataType;
 use crate::gml::opcodes;
 use crate::prelude::*;
@@ -17,9 +19,12 @@ pub fn check_2023_8_and_2024_4(reader: &mut DataReader) -> Result<Option<GMVersi
         let Some(chunk) = reader.chunks.get(chunk_name) else {
             return Ok(0);
         };
+        if chunk.is_empty() {
+            return Ok(0);
+        }
 
         reader.chunk = chunk;
-        reader.cur_pos = chunk.start_pos;
+        reader.seek(chunk.start_pos)?;
         if gms2 {
             reader.align(4)?;
             reader.read_u32()?; // GMS2 chunk version (always 1)
@@ -29,35 +34,33 @@ pub fn check_2023_8_and_2024_4(reader: &mut DataReader) -> Result<Option<GMVersi
     }
 
     let chunk_code = reader.chunk;
-    let background_count = get_chunk_elem_count(reader, ChunkName::BGND, false)?;
-    let path_count = get_chunk_elem_count(reader, ChunkName::PATH, false)?;
-    let script_count = get_chunk_elem_count(reader, ChunkName::SCPT, false)?;
-    let font_count = get_chunk_elem_count(reader, ChunkName::FONT, false)?;
-    let timeline_count = get_chunk_elem_count(reader, ChunkName::TMLN, false)?;
-    let shader_count = get_chunk_elem_count(reader, ChunkName::SHDR, false)?;
-    let sequence_count = get_chunk_elem_count(reader, ChunkName::SEQN, true)?;
-    let particle_system_count = get_chunk_elem_count(reader, ChunkName::SEQN, true)?;
+    // Count the assets of every type whose asset type number changed in 2024.4.
+    let counts = AssetCounts {
+        backgrounds: Some(get_chunk_elem_count(reader, ChunkName::BGND, false)?),
+        paths: Some(get_chunk_elem_count(reader, ChunkName::PATH, false)?),
+        scripts: Some(get_chunk_elem_count(reader, ChunkName::SCPT, false)?),
+        fonts: Some(get_chunk_elem_count(reader, ChunkName::FONT, false)?),
+        timelines: Some(get_chunk_elem_count(reader, ChunkName::TMLN, false)?),
+        shaders: Some(get_chunk_elem_count(reader, ChunkName::SHDR, false)?),
+        sequences: Some(get_chunk_elem_count(reader, ChunkName::SEQN, true)?),
+        particle_systems: Some(get_chunk_elem_count(reader, ChunkName::PSYS, true)?),
+        ..AssetCounts::default()
+    };
 
     let is_asset_type_2024_4 = |int_argument: u32| -> bool {
-        let resource_id = int_argument & 0xFF_FFFF;
         let resource_type = (int_argument >> 24) as u8;
         match resource_type {
-            4 => resource_id >= background_count,
-            5 => resource_id >= path_count,
-            6 => resource_id >= script_count,
-            7 => resource_id >= font_count,
-            8 => resource_id >= timeline_count,
             9 => true, // Used to be unused, now are sequences
-            10 => resource_id >= shader_count,
-            11 => resource_id >= sequence_count,
             // case 12 used to be animcurves, but now is unused (so would actually mean earlier than 2024.4)
-            13 => resource_id >= particle_system_count,
-            _ => false,
+            12 => false,
+            // Assets out of bounds for their old asset type have to use the new asset types.
+            _ => AssetReference::parse_old(int_argument)
+                .is_ok_and(|asset_reference| counts.contains(asset_reference) == Some(false)),
         }
     };
 
-    reader.cur_pos = chunk_code.start_pos;
     reader.chunk = chunk_code;
+    reader.seek(chunk_code.start_pos)?;
 
     let code_count = reader.read_u32()?;
     let mut code_pointers = vec_with_capacity(code_count)?;
@@ -70,13 +73,17 @@ pub fn check_2023_8_and_2024_4(reader: &mut DataReader) -> Result<Option<GMVersi
     let mut detected_2023_8: bool = false;
 
     for code_ptr in code_pointers {
-        reader.cur_pos = code_ptr + 4; // Skip name
+        reader.seek(code_ptr)?;
+        reader.skip(4)?; // Skip name
         let instructions_length = reader.read_u32()?;
-        reader.cur_pos += 4; // Skip locals and arguments count
+        reader.skip(4)?; // Skip locals and arguments count
+        let relative_base = reader.cur_pos;
         let instructions_start_relative = reader.read_i32()?;
-        let instructions_start = (reader.cur_pos as i32 - 4 + instructions_start_relative) as u32;
-        let instructions_end = instructions_start + instructions_length;
-        reader.cur_pos = instructions_start;
+        let instructions_start = relative_base
+            .checked_add_signed(instructions_start_relative)
+            .ok_or("Instructions start position out of bounds")?;
+        let instructions_end = instructions_start.saturating_add(instructions_length);
+        reader.seek(instructions_start)?;
 
         while reader.cur_pos < instructions_end {
             let word = reader.read_u32()?;
@@ -84,13 +91,13 @@ pub fn check_2023_8_and_2024_4(reader: &mut DataReader) -> Result<Option<GMVersi
             let type1 = ((word & 0x00FF_0000) >> 16) as u8;
 
             if matches!(opcode, opcodes::POP | opcodes::CALL) {
-                reader.cur_pos += 4;
+                reader.skip(4)?;
             }
 
             if matches!(opcode, 0xC0..0xC4) {
                 // Push variants; account for int16
                 if type1 != DataType::Int16.as_u8() {
-                    reader.cur_pos += 4;
+                    reader.skip(4)?;
                 }
                 continue;
             }
@@ -15,7 +15,8 @@ pub fn check_2022_6(reader: &mut DataReader) -> Result<Option<GMVersion>> {
     } else {
         reader.chunk.end_pos
     };
-    reader.cur_pos = first_ext_ptr + 12;
+    reader.seek(first_ext_ptr)?;
+    reader.skip(12)?;
     let new_pointer1 = reader.read_u32()?;
     let new_pointer2 = reader.read_u32()?;
     if new_pointer1 != reader.cur_pos {
@@ -25,26 +26,25 @@ pub fn check_2022_6(reader: &mut DataReader) -> Result<Option<GMVersion>> {
         return Ok(None); // Second pointer out of bounds
     }
     // Check ending position
-    reader.cur_pos = new_pointer2;
+    reader.seek(new_pointer2)?;
     let option_count = reader.read_u32()?;
     if option_count > 0 {
-        let new_offset_check = reader.cur_pos + 4 * (option_count - 1); // MAYBE overflow issues on 32bit arch????
-        if new_offset_check >= reader.chunk.end_pos {
+        let options_size = (option_count - 1).saturating_mul(4);
+        if options_size >= reader.remaining() {
             return Ok(None); // Option count would place us out of bounds
         }
-        reader.cur_pos += 4 * (option_count - 1);
-        let new_offset_check = reader.read_u32()? + 12; // Jump past last option
-        if new_offset_check >= reader.chunk.end_pos {
+        reader.skip(options_size)?;
+        let mut end_pos = reader.read_u32()?.saturating_add(12); // Jump past last option
+        if end_pos >= reader.chunk.end_pos {
             return Ok(None); // Pointer list element would place us out of bounds
         }
-        reader.cur_pos = new_offset_check;
         if ext_count == 1 {
-            reader.cur_pos += 16; // skip GUID date (only one of them)
-            if reader.cur_pos & 16 != 0 {
-                reader.cur_pos += 16 - reader.cur_pos % 16; // Align to chunk end
+            end_pos += 16; // skip GUID date (only one of them)
+            if end_pos & 16 != 0 {
+                end_pos += 16 - end_pos % 16; // Align to chunk end
             }
         }
-        if reader.cur_pos != first_ext_end_ptr {
+        if end_pos != first_ext_end_ptr {
             return Ok(None);
         }
     }
@@ -58,7 +58,9 @@ pub fn check_2023_4(reader: &mut DataReader) -> Result<Option<GMVersion>> {
         return Ok(None);
     }
     // Go to first extension and skip the minimal amount of strings
-    reader.cur_pos = reader.read_u32()? + 4 * 3;
+    let first_ext_ptr = reader.read_u32()?;
+    reader.seek(first_ext_ptr)?;
+    reader.skip(4 * 3)?;
     let files_pointer = reader.read_u32()?;
     let options_pointer = reader.read_u32()?;
     // The file list pointer should be less than the option list pointer.
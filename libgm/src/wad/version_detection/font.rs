@@ -26,7 +26,8 @@ pub fn check_2022_2(reader: &mut DataReader) -> Result<Option<GMVersion>> {
         return Ok(None);
     }
 
-    reader.cur_pos = first_font_pointer + 48;
+    reader.seek(first_font_pointer)?;
+    reader.skip(48)?;
     let glyph_count = reader.read_u32()?;
     if glyph_count * 4 > reader.chunk.length() {
         return Ok(None);
@@ -48,9 +49,9 @@ pub fn check_2022_2(reader: &mut DataReader) -> Result<Option<GMVersion>> {
         if reader.cur_pos != pointer {
             return Ok(None);
         }
-        reader.cur_pos += 14;
+        reader.skip(14)?;
         let kerning_length = reader.read_u16()?;
-        reader.cur_pos += u32::from(kerning_length) * 4;
+        reader.skip(u32::from(kerning_length) * 4)?;
         // From utmt: "combining read/write would apparently break" ???
     }
 
@@ -101,10 +102,11 @@ pub fn check_2023_6_and_2024_11(reader: &mut DataReader) -> Result<Option<GMVers
         first_two_pointers.push(reader.chunk.end_pos - 512);
     }
 
-    reader.cur_pos = first_two_pointers[0] + 52; // Also the LineHeight value. 48 + 4 = 52
+    reader.seek(first_two_pointers[0])?;
+    reader.skip(52)?; // Also the LineHeight value. 48 + 4 = 52
     if ver >= GMVersion::GM2023_2 {
         // SDFSpread is present from 2023.2 non-LTS onward
-        reader.cur_pos += 4; // (already detected by PSEM/PSYS chunk existence)
+        reader.skip(4)?; // (already detected by PSEM/PSYS chunk existence)
     }
 
     let glyph_count = reader.read_u32()?;
@@ -125,7 +127,7 @@ pub fn check_2023_6_and_2024_11(reader: &mut DataReader) -> Result<Option<GMVers
         if reader.cur_pos != *glyph_pointer {
             return Ok(None);
         }
-        reader.cur_pos += 14;
+        reader.skip(14)?;
         let kerning_count = reader.read_u16()?;
 
         // Hopefully the last thing in a UTFont is the glyph list
@@ -172,20 +174,23 @@ pub fn check_2024_14(reader: &mut DataReader) -> Result<Option<GMVersion>> {
     // If we have a last font, advance to the end of its data (ignoring the new
     // alignment added in 2024.14)
     if last_font_position != 0 {
-        reader.cur_pos = last_font_position + 56;
+        reader.seek(last_font_position)?;
+        reader.skip(56)?;
 
         // Advance to last glyph in pointer list
         let glyph_count = reader.read_u32()?;
-        reader.cur_pos += (glyph_count - 1) * 4;
-        reader.cur_pos = reader.read_u32()? + 16;
+        reader.skip(glyph_count.saturating_sub(1).saturating_mul(4))?;
+        let last_glyph_position = reader.read_u32()?;
+        reader.seek(last_glyph_position)?;
+        reader.skip(16)?;
 
         // Advance past kerning
         let kerning_count = reader.read_u16()?;
-        reader.cur_pos += u32::from(kerning_count) * 4;
+        reader.skip(u32::from(kerning_count) * 4)?;
     }
 
     // Check for the final chunk padding being missing
-    if reader.cur_pos + 512 > reader.chunk.end_pos {
+    if reader.remaining() < 512 {
         // No padding can fit, so this is 2024.14
         return target_version!(GM2024_14);
     }
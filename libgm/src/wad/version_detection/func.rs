@@ -16,9 +16,13 @@ pub fn check_2024_8(reader: &mut DataReader) -> Result<Option<GMVersion>> {
     let function_count = reader.read_u32()?;
     // Skip over the (Simple)List
     // (3*4 is the size of a GMFunction object)
-    reader.cur_pos += function_count * 3 * 4;
+    let functions_size = function_count.saturating_mul(3 * 4);
+    if functions_size > reader.remaining() {
+        return Ok(None); // Function list would exceed chunk boundaries
+    }
+    reader.skip(functions_size)?;
 
-    if reader.cur_pos == reader.chunk.end_pos {
+    if reader.remaining() == 0 {
         // Directly reached the end of the chunk after the function list, so code locals
         // are definitely missing
         return ver;
@@ -28,7 +32,7 @@ pub fn check_2024_8(reader: &mut DataReader) -> Result<Option<GMVersion>> {
     let mut padding_bytes_read: u32 = 0;
 
     while reader.cur_pos & (reader.chunk_padding - 1) != 0 {
-        if reader.cur_pos >= reader.chunk.end_pos || reader.read_u8()? != 0 {
+        if reader.remaining() == 0 || reader.read_u8()? != 0 {
             return Ok(None); // If we hit a non-zero byte (or exceed chunk boundaries), it can't be padding
         }
         padding_bytes_read += 1;
@@ -36,7 +40,7 @@ pub fn check_2024_8(reader: &mut DataReader) -> Result<Option<GMVersion>> {
 
     // If we're at the end of the chunk after aligning padding, code locals are
     // either empty or do not exist altogether.
-    if reader.cur_pos != reader.chunk.end_pos {
+    if reader.remaining() != 0 {
         return Ok(None);
     }
 
@@ -50,7 +54,7 @@ pub fn check_2024_8(reader: &mut DataReader) -> Result<Option<GMVersion>> {
         return Ok(None);
     };
     reader.chunk = chunk_code;
-    reader.cur_pos = chunk_code.start_pos;
+    reader.seek(chunk_code.start_pos)?;
     let code_count = reader.read_u32()?;
     if code_count < 1 {
         return Ok(None);
@@ -11,20 +11,18 @@ pub fn check_2022_5(reader: &mut DataReader) -> Result<Option<GMVersion>> {
         return Ok(None); // No objects; nothing to detect
     }
     let first_object_pointer = reader.read_u32()?;
-    reader.cur_pos = first_object_pointer + 64;
+    reader.seek(first_object_pointer)?;
+    reader.skip(64)?;
     let vertex_count = reader.read_u32()?;
 
     // i hate integer safety
-    let Some(position) = vertex_count
-        .checked_mul(8)
-        .and_then(|x| x.checked_add(reader.cur_pos + 12))
-    else {
+    let Some(vertices_size) = vertex_count.checked_mul(8).and_then(|x| x.checked_add(12)) else {
         return Ok(None);
     };
-    if position >= reader.chunk.end_pos {
+    if vertices_size >= reader.remaining() {
         return target_ver; // Bounds check on vertex data "failed" => 2022.5
     }
-    reader.cur_pos = position;
+    reader.skip(vertices_size)?;
 
     if reader.read_u32()? == 15 {
         // !! 15 has to equal variant count of GMGameObjectEventType enum !!
@@ -15,21 +15,21 @@ pub fn check_2022_1(reader: &mut DataReader) -> Result<Option<GMVersion>> {
         // Advance to room data we're interested in (and grab pointer for next room)
         reader.set_rel_cur_pos(4 * room_index + 4)?;
         let room_pointer = reader.read_u32()?;
-        reader.cur_pos = room_pointer + 22 * 4;
+        reader.seek(room_pointer)?;
+        reader.skip(22 * 4)?;
 
         // Get the pointer for this room's layer list, as well as pointer to sequence
         // list
         let layer_list_pointer = reader.read_u32()?;
         let sequence_pointer = reader.read_u32()?;
-        reader.cur_pos = layer_list_pointer;
+        reader.seek(layer_list_pointer)?;
         let layer_count = reader.read_i32()?;
         if layer_count < 1 {
             continue; // No layers to detect; go to next room
         }
 
-        // Get pointer into the individual layer data (plus 8 bytes) for the first layer
-        // in the room
-        let jump_pointer = reader.read_u32()? + 8;
+        // Get pointer into the individual layer data for the first layer in the room
+        let layer_pointer = reader.read_u32()?;
 
         // Find the offset for the end of this layer
         let next_pointer = if layer_count == 1 {
@@ -39,7 +39,8 @@ pub fn check_2022_1(reader: &mut DataReader) -> Result<Option<GMVersion>> {
         };
 
         // Actually perform the length checks, depending on layer data
-        reader.cur_pos = jump_pointer;
+        reader.seek(layer_pointer)?;
+        reader.skip(8)?;
         let layer_type = reader.read_i32()?;
         let Some(layer_type) = Type::try_from_i32(layer_type) else {
             continue;
@@ -53,21 +54,21 @@ pub fn check_2022_1(reader: &mut DataReader) -> Result<Option<GMVersion>> {
                 }
             }
             Type::Instances => {
-                reader.cur_pos += 6 * 4;
+                reader.skip(6 * 4)?;
                 let instance_count = reader.read_u32()?;
                 if next_pointer - reader.cur_pos != instance_count * 4 {
                     return target_ver;
                 }
             }
             Type::Assets => {
-                reader.cur_pos += 6 * 4;
+                reader.skip(6 * 4)?;
                 let tile_pointer = reader.read_u32()?;
                 if tile_pointer != reader.cur_pos + 8 && tile_pointer != reader.cur_pos + 12 {
                     return target_ver;
                 }
             }
             Type::Tiles => {
-                reader.cur_pos += 6 * 4;
+                reader.skip(6 * 4)?;
                 let tile_map_width = reader.read_u32()?;
                 let tile_map_height = reader.read_u32()?;
                 if next_pointer - reader.cur_pos != (tile_map_width * tile_map_height * 4) {
@@ -75,7 +76,7 @@ pub fn check_2022_1(reader: &mut DataReader) -> Result<Option<GMVersion>> {
                 }
             }
             Type::Effect => {
-                reader.cur_pos += 7 * 4;
+                reader.skip(7 * 4)?;
                 let property_count = reader.read_u32()?;
                 if next_pointer - reader.cur_pos != (property_count * 3 * 4) {
                     return target_ver;
@@ -96,12 +97,13 @@ pub fn check_2_2_2_302(reader: &mut DataReader) -> Result<Option<GMVersion>> {
         // Advance to room data we're interested in (and grab pointer for next room)
         reader.set_rel_cur_pos(4 * room_index + 4)?;
         let room_pointer = reader.read_u32()?;
-        reader.cur_pos = room_pointer + 12 * 4;
+        reader.seek(room_pointer)?;
+        reader.skip(12 * 4)?;
 
         // Get the pointer for this room's object list, as well as pointer to tile list
         let object_list_pointer = reader.read_u32()?;
         let tile_list_pointer = reader.read_u32()?;
-        reader.cur_pos = object_list_pointer;
+        reader.seek(object_list_pointer)?;
         let object_count = reader.read_u32()?;
         if object_count < 1 {
             continue; // No objects => nothing to detect; go to next room
@@ -130,13 +132,14 @@ pub fn check_2024_2_and_2024_4(reader: &mut DataReader) -> Result<Option<GMVersi
         // Advance to room data we're interested in (and grab pointer for next room)
         reader.set_rel_cur_pos(4 * room_index + 4)?;
         let room_pointer = reader.read_u32()?;
-        reader.cur_pos = room_pointer + 22 * 4;
+        reader.seek(room_pointer)?;
+        reader.skip(22 * 4)?;
 
         // Get the pointer for this room's layer list, as well as pointer to sequence
         // list
         let layer_list_ptr = reader.read_u32()?;
         let sequence_ptr = reader.read_u32()?;
-        reader.cur_pos = layer_list_ptr;
+        reader.seek(layer_list_ptr)?;
         let layer_count = reader.read_u32()?;
         if layer_count < 1 {
             continue; // No layers to detect; go to next room
@@ -149,7 +152,8 @@ pub fn check_2024_2_and_2024_4(reader: &mut DataReader) -> Result<Option<GMVersi
                 any_layers_misaligned = true;
             }
 
-            reader.cur_pos = layer_ptr + 4;
+            reader.seek(layer_ptr)?;
+            reader.skip(4)?;
             // Get pointer into the individual layer data
             let layer_data_ptr = reader.read_u32()?;
 
@@ -161,16 +165,17 @@ pub fn check_2024_2_and_2024_4(reader: &mut DataReader) -> Result<Option<GMVersi
             };
 
             // Actually perform the length checks
-            reader.cur_pos = layer_data_ptr + 8;
+            reader.seek(layer_data_ptr)?;
+            reader.skip(8)?;
             let layer_type: Type = reader.read_enum()?;
             if layer_type != Type::Tiles {
                 check_next_layer_offset = false;
                 continue;
             }
             check_next_layer_offset = true;
-            reader.cur_pos += 32;
+            reader.skip(32)?;
             let effect_count = reader.read_u32()?;
-            reader.cur_pos += 12 * effect_count + 4;
+            reader.skip(effect_count.saturating_mul(12).saturating_add(4))?;
 
             let tile_map_width = reader.read_u32()?;
             let tile_map_height = reader.read_u32()?;
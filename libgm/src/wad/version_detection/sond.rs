@@ -32,7 +32,7 @@ pub fn check_2024_6(reader: &mut DataReader) -> Result<Option<GMVersion>> {
             if abs_pos % 16 != 4 {
                 bail!("Expected to be on specific alignment at this point");
             }
-            reader.cur_pos = abs_pos;
+            reader.seek(abs_pos)?;
             if reader.read_u32()? != 0 {
                 return target_ver;
             }
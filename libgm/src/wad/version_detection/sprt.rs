@@ -10,7 +10,8 @@ pub fn check_2_3_2(reader: &mut DataReader) -> Result<Option<GMVersion>> {
         if pointer == 0 {
             continue;
         }
-        reader.cur_pos = pointer + 14 * 4;
+        reader.seek(pointer)?;
+        reader.skip(14 * 4)?;
         if reader.read_i32()? != -1 {
             continue; // Sprite is not special type
         }
@@ -26,7 +27,7 @@ pub fn check_2024_6(reader: &mut DataReader) -> Result<Option<GMVersion>> {
     let ver = target_version!(GM2024_6);
     let sprite_count = reader.read_u32()?;
     for i in 0..sprite_count {
-        reader.cur_pos = reader.chunk.start_pos + i * 4 + 4;
+        reader.set_rel_cur_pos(i * 4 + 4)?;
         let sprite_pointer = reader.read_u32()?;
         if sprite_pointer == 0 {
             continue;
@@ -41,7 +42,7 @@ pub fn check_2024_6(reader: &mut DataReader) -> Result<Option<GMVersion>> {
             }
         }
 
-        reader.cur_pos += 4; // Skip past "Name"
+        reader.skip(4)?; // Skip past "Name"
         // Check if bbox size differs from width/height
         let width = reader.read_u32()?;
         let height = reader.read_u32()?;
@@ -54,7 +55,7 @@ pub fn check_2024_6(reader: &mut DataReader) -> Result<Option<GMVersion>> {
         if bbox_width == width && bbox_height == height {
             continue; // We can't determine anything from this sprite
         }
-        reader.cur_pos += 28;
+        reader.skip(28)?;
         if reader.read_i32()? != -1 {
             // Not special type
             continue;
@@ -71,7 +72,7 @@ pub fn check_2024_6(reader: &mut DataReader) -> Result<Option<GMVersion>> {
         let sequence_offset = reader.read_u32()?;
         let nine_slice_offset = reader.read_u32()?;
         let texture_count = reader.read_u32()?;
-        reader.cur_pos += texture_count * 4; // Skip past texture pointers
+        reader.skip(texture_count.saturating_mul(4))?; // Skip past texture pointers
         let mask_count = reader.read_u32()?;
         if mask_count == 0 {
             continue; // We can't determine anything from this sprite
@@ -85,8 +86,8 @@ pub fn check_2024_6(reader: &mut DataReader) -> Result<Option<GMVersion>> {
             bbox_length += 4 - bbox_length % 4; // Idk
         }
 
-        let full_end_pos = reader.cur_pos + full_length;
-        let bbox_end_pos = reader.cur_pos + bbox_length;
+        let full_end_pos = reader.cur_pos.saturating_add(full_length);
+        let bbox_end_pos = reader.cur_pos.saturating_add(bbox_length);
         let expected_end_offset;
         if sequence_offset != 0 {
             expected_end_offset = sequence_offset;
@@ -16,7 +16,8 @@ pub fn check_2022_9(reader: &mut DataReader) -> Result<Option<GMVersion>> {
     } else {
         reader.chunk.end_pos
     };
-    reader.cur_pos = pointer1 + 4;
+    reader.seek(pointer1)?;
+    reader.skip(4)?;
 
     // Check to see if the pointer located at this address points within this object
     // If not, then we know we're using a new format!
@@ -38,7 +39,8 @@ pub fn check_2023_1(reader: &mut DataReader) -> Result<Option<GMVersion>> {
 
     // Go to the 4th list pointer of the first TGIN entry.
     // (either to "Fonts" or "SpineTextures" depending on the version)
-    reader.cur_pos = pointer1 + 16 + 4 * 3;
+    reader.seek(pointer1)?;
+    reader.skip(16 + 4 * 3)?;
     let pointer4 = reader.read_u32()?;
 
     // If there's a "TexturePages" count instead of the 5th list pointer.
@@ -12,7 +12,7 @@ pub fn check_2022_3(reader: &mut DataReader) -> Result<Option<GMVersion>> {
         return Ok(None); // Can't detect if there are no texture pages
     }
     if texture_count == 1 {
-        reader.cur_pos += 16; // Jump to either padding or length, depending on version
+        reader.skip(16)?; // Jump to either padding or length, depending on version
         if reader.read_u32()? > 0 {
             // Check whether it's padding or length
             return ver;
@@ -34,18 +34,21 @@ pub fn check_2022_5(reader: &mut DataReader) -> Result<Option<GMVersion>> {
     for i in 0..texture_count {
         // Go to each texture, and then to each texture's data
         reader.set_rel_cur_pos(4 * i + 4)?;
-        reader.cur_pos = reader.read_u32()? + 12; // Go to texture; at an offset
-        reader.cur_pos = reader.read_u32()?; // Go to texture data
+        let texture_pointer = reader.read_u32()?;
+        reader.seek(texture_pointer)?; // Go to texture
+        reader.skip(12)?;
+        let data_pointer = reader.read_u32()?;
+        reader.seek(data_pointer)?; // Go to texture data
         let header: &[u8; 4] = reader.read_bytes_const()?;
         if header != BZ2_QOI_HEADER {
             continue; // Nothing useful, check the next texture
         }
-        reader.cur_pos += 4; // Skip width/height
+        reader.skip(4)?; // Skip width/height
         // Now check actual bz2 headers
         if reader.read_bytes_const::<3>()? != b"BZh" {
             return target_ver;
         }
-        reader.cur_pos += 1;
+        reader.skip(1)?;
         if *reader.read_bytes_const::<6>()? != [0x31, 0x41, 0x59, 0x26, 0x53, 0x59] {
             // Digits of pi (block header)
             return target_ver;
@@ -63,7 +66,9 @@ pub fn check_2_0_6(reader: &mut DataReader) -> Result<Option<GMVersion>> {
     }
     if texture_count == 1 {
         // Go to the first texture pointer (+ minimal texture entry size)
-        reader.cur_pos = reader.read_u32()? + 8;
+        let texture_pointer = reader.read_u32()?;
+        reader.seek(texture_pointer)?;
+        reader.skip(8)?;
         if reader.read_u32()? == 0 {
             return Ok(None); // If there is a zero instead of texture data pointer; it's not 2.0.6
         }
@@ -0,0 +1,356 @@
+// SPDX-License-Identifier: GPL-3.0-only
+//! Tests for other assets and chunks.
+mod common;
+
+use std::collections::HashMap;
+
+use common::make_game_object;
+use common::reparse;
+use libgm::gml::Code;
+use libgm::prelude::*;
+use libgm::wad::Blob;
+use libgm::wad::build::build_file;
+use libgm::wad::build_bytes;
+use libgm::wad::chunk::ChunkName;
+use libgm::wad::elem::audio::Audio;
+use libgm::wad::elem::game_object::GameObject;
+use libgm::wad::elem::path::Path;
+use libgm::wad::elem::path::Point;
+use libgm::wad::elem::room::Room;
+use libgm::wad::elem::sequence::PlaybackType;
+use libgm::wad::elem::sequence::Sequence;
+use libgm::wad::elem::sequence::SpeedType;
+use libgm::wad::elem::sequence::Track;
+use libgm::wad::elem::sequence::track::Keyframe;
+use libgm::wad::elem::sequence::track::Keyframes;
+use libgm::wad::elem::sequence::track::keyframe;
+use libgm::wad::elem::sequence::track::keyframe::color::Color;
+use libgm::wad::elem::sequence::track::keyframe::color::InterpolationMode;
+use libgm::wad::elem::sequence::track::keyframe::color::KeyframesData;
+use libgm::wad::elem::sound;
+use libgm::wad::elem::sound::Sound;
+use libgm::wad::parse::ParsingOptions;
+
+#[test]
+fn edit_closed_smooth_path() -> Result<()> {
+    let mut data = GMData::default();
+    let mut path = Path {
+        name: data.strings.make("path_test"),
+        is_smooth: true,
+        is_closed: false,
+        precision: 4,
+        points: vec![
+            Point::new(0.0, 0.0, 100.0),
+            Point::new(100.0, 0.0, 100.0),
+            Point::new(100.0, 100.0, 100.0),
+        ],
+    };
+    path.is_closed = true;
+    path.points.push(Point::new(0.0, 100.0, 50.0));
+    path.insert_point(1, Point::new(50.0, -25.0, 100.0))?;
+    assert_eq!(path.remove_point(1)?, Point::new(50.0, -25.0, 100.0));
+    assert!(path.insert_point(5, Point::new(0.0, 0.0, 100.0)).is_err());
+    assert!(path.remove_point(4).is_err());
+
+    // The smoothed square cuts its corners
+    let length = path.length();
+    path.is_smooth = false;
+    assert!((path.length() - 400.0).abs() < 1e-9);
+    path.is_smooth = true;
+    assert!(length > 300.0 && length < 400.0);
+
+    let path_ref = data.paths.push(path);
+    let reparsed = reparse(&data)?;
+    let path = reparsed.paths.by_ref(path_ref)?;
+    assert_eq!(path, data.paths.by_ref(path_ref)?);
+    assert_eq!(path.points.len(), 4);
+    assert!((path.length() - length).abs() < 1e-9);
+    Ok(())
+}
+
+#[test]
+fn checked_ref() -> Result<()> {
+    let data = GMData::default();
+    let room: GMRef<Room> = GMRef::opt_checked(0, &data.rooms.elems)?;
+    assert_eq!(room, GMRef::new(0));
+    assert!(GMRef::<Room>::opt_checked(1, &data.rooms.elems).is_err());
+    let string: GMRef<String> = GMRef::checked(0, &data.strings.elems)?;
+    assert_eq!(string, GMRef::new(0));
+    assert!(GMRef::<String>::checked(data.strings.len(), &data.strings.elems).is_err());
+    Ok(())
+}
+
+#[test]
+fn collision_events() -> Result<()> {
+    let mut data = GMData::default();
+    let player: GMRef<GameObject> = make_game_object("obj_player", &mut data);
+    let enemy: GMRef<GameObject> = make_game_object("obj_enemy", &mut data);
+    let wall: GMRef<GameObject> = make_game_object("obj_wall", &mut data);
+    let hurt: GMRef<Code> = data.make_code("gml_Object_obj_player_Collision_obj_enemy", vec![]);
+    let knockback: GMRef<Code> = data.make_code("gml_Script_knockback", vec![]);
+    let stop: GMRef<Code> = data.make_code("gml_Object_obj_player_Collision_obj_wall", vec![]);
+
+    let game_object: &mut GameObject = data.game_objects.by_ref_mut(player)?;
+    game_object.add_collision_event(enemy, hurt);
+    game_object.add_collision_event(wall, stop);
+    game_object.add_collision_event(enemy, knockback);
+    assert_eq!(game_object.events.collision.len(), 2);
+
+    let data = reparse(&data)?;
+    assert_eq!(data.object_collision_events("obj_player")?, [
+        (enemy, hurt),
+        (enemy, knockback),
+        (wall, stop)
+    ]);
+    assert!(data.object_collision_events("obj_enemy")?.is_empty());
+    assert!(data.object_collision_events("obj_missing").is_err());
+    Ok(())
+}
+
+#[test]
+fn sequence_keyframes() -> Result<()> {
+    let mut data = GMData::default();
+    let position = Keyframes::Real(KeyframesData {
+        interpolation: InterpolationMode::Linear,
+        keyframes: vec![Keyframe::new(0.0, 1.0, [Color { value: 0.0 }, Color {
+            value: 0.0,
+        }])],
+    });
+    let mut instance_track = Track::new(
+        data.strings.make("GMInstanceTrack"),
+        data.strings.make("obj_player"),
+        Keyframes::Instance(keyframe::Data { keyframes: Vec::new() }),
+    );
+    instance_track.sub_tracks.push(Track::new(
+        data.strings.make("GMRealTrack"),
+        data.strings.make("position"),
+        position,
+    ));
+    data.sequences.push(Sequence {
+        name: data.strings.make("seq_intro"),
+        playback: PlaybackType::OneShot,
+        playback_speed: 60.0,
+        playback_speed_type: SpeedType::FramesPerSecond,
+        length: 60.0,
+        origin_x: 0,
+        origin_y: 0,
+        volume: 1.0,
+        width: Some(640.0),
+        height: Some(480.0),
+        broadcast_messages: Vec::new(),
+        tracks: vec![instance_track],
+        function_ids: HashMap::new(),
+        moments: Vec::new(),
+    });
+    let mut data = reparse(&data)?;
+
+    let sequence: &mut Sequence = data.sequences.by_ref_mut(GMRef::new(0))?;
+    assert_eq!(sequence.all_tracks().len(), 2);
+    let track: &mut Track = sequence.track_by_name_mut("position", &data.strings)?;
+    let keyframes: &mut KeyframesData = track.keyframes.as_real_mut()?;
+    let keyframe = Keyframe::new(30.0, 1.0, [Color { value: 100.0 }, Color { value: 50.0 }]);
+    assert_eq!(keyframes.insert(keyframe), 1);
+    keyframes.insert(Keyframe::new(10.0, 1.0, [Color { value: 5.0 }, Color {
+        value: 5.0,
+    }]));
+    assert!(keyframes.remove_at(10.0).is_some());
+    assert!(keyframes.remove_at(10.0).is_none());
+    let data = reparse(&data)?;
+
+    let sequence: &Sequence = data.sequences.by_ref(GMRef::new(0))?;
+    let track: &Track = sequence.track_by_name("position", &data.strings)?;
+    assert_eq!(track.keyframes.times(), [0.0, 30.0]);
+    assert!(track.keyframes.as_real()?.at(15.0).is_none());
+    let keyframe = track.keyframes.as_real()?.at(30.5).unwrap();
+    assert_eq!(keyframe.value(0), Some(&Color { value: 100.0 }));
+    assert_eq!(keyframe.value(1), Some(&Color { value: 50.0 }));
+    assert!(sequence.track_by_name("rotation", &data.strings).is_err());
+    Ok(())
+}
+
+#[test]
+fn extract_sound_audio() -> Result<()> {
+    let mut data = GMData::default();
+    let mut wav: Vec<u8> = b"RIFF\0\0\0\0WAVEfmt ".to_vec();
+    wav.resize(64, 0);
+    data.audios.push(Audio { data: Blob::new(wav) });
+
+    let mut make_sound = |name: &str, flags: sound::Flags, audio: GMRef<Audio>| Sound {
+        name: data.strings.make(name),
+        flags,
+        audio_type: data.strings.make(".wav"),
+        file: data.strings.make(&format!("{name}.wav")),
+        effects: 0,
+        volume: 1.0,
+        pitch: 0.0,
+        audio_group: GMRef::none(),
+        audio,
+        audio_length: Some(0.0),
+    };
+    let embedded = make_sound("snd_embedded", sound::Flags::EMBEDDED, GMRef::new(0));
+    let streamed = make_sound("snd_streamed", sound::Flags::empty(), GMRef::none());
+    data.sounds.push(embedded);
+    data.sounds.push(streamed);
+
+    let data = reparse(&data)?;
+    let audio = data.sound_audio(GMRef::new(0))?.expect("embedded audio");
+    assert_eq!(audio.container_extension(), Some("wav"));
+    assert!(data.sound_audio(GMRef::new(1))?.is_none());
+
+    let ogg = Audio { data: Blob::new(b"OggS\0\x02".to_vec()) };
+    assert_eq!(ogg.container_extension(), Some("ogg"));
+    let unknown = Audio { data: Blob::new(vec![0; 4]) };
+    assert_eq!(unknown.container_extension(), None);
+    Ok(())
+}
+
+#[test]
+fn feature_flags() -> Result<()> {
+    let mut data = GMData::default();
+    assert!(data.add_feature_flag("use_new_audio")?);
+    assert!(data.add_feature_flag("legacy_collision")?);
+    assert!(!data.add_feature_flag("use_new_audio")?);
+    assert!(data.remove_feature_flag("legacy_collision"));
+    assert!(!data.remove_feature_flag("legacy_collision"));
+    assert!(data.add_feature_flag("texture_groups_v2")?);
+
+    let raw_data: Vec<u8> = build_bytes(&data)?;
+    let reparsed: GMData = reparse(&data)?;
+    assert_eq!(reparsed.feature_flag_names()?, [
+        "use_new_audio",
+        "texture_groups_v2"
+    ]);
+    assert!(reparsed.has_feature_flag("texture_groups_v2"));
+    assert!(!reparsed.has_feature_flag("legacy_collision"));
+    assert_eq!(build_bytes(&reparsed)?, raw_data);
+
+    let mut old = GMData::default();
+    old.meta.chunks.remove(ChunkName::FEAT);
+    assert!(old.add_feature_flag("use_new_audio").is_err());
+    Ok(())
+}
+
+#[test]
+fn language_table() -> Result<()> {
+    let mut data = GMData::default();
+    data.language_info.unknown1 = 1;
+    assert_eq!(data.add_language("English", "US")?, 0);
+    data.set_language_text("English", "US", "greeting", "Hello")?;
+    data.set_language_text("English", "US", "farewell", "Bye")?;
+    assert_eq!(data.add_language("German", "DE")?, 1);
+    assert!(data.add_language("German", "DE").is_err());
+    assert_eq!(data.add_language("English", "UK")?, 2);
+    data.set_language_text("English", "UK", "greeting", "Hello there")?;
+    // New languages start out with the text of the first language
+    assert_eq!(
+        data.language_text("German", "DE", "greeting")?,
+        Some("Hello")
+    );
+    data.set_language_text("German", "DE", "greeting", "Hallo")?;
+    data.set_language_text("German", "DE", "title", "Titel")?;
+    assert!(
+        data.set_language_text("French", "FR", "greeting", "Salut")
+            .is_err()
+    );
+
+    // The chunk has to round-trip exactly
+    let raw_data: Vec<u8> = build_bytes(&data)?;
+    let data: GMData = ParsingOptions::LENIENT.parse_bytes(&raw_data)?;
+    assert_eq!(build_bytes(&data)?, raw_data);
+
+    assert_eq!(data.languages()?, [
+        ("English", "US"),
+        ("German", "DE"),
+        ("English", "UK")
+    ]);
+    assert_eq!(
+        data.language_text("English", "US", "greeting")?,
+        Some("Hello")
+    );
+    assert_eq!(
+        data.language_text("German", "DE", "greeting")?,
+        Some("Hallo")
+    );
+    assert_eq!(data.language_text("German", "DE", "farewell")?, Some("Bye"));
+    assert_eq!(data.language_text("English", "US", "title")?, Some("Titel"));
+    assert_eq!(data.language_text("English", "US", "missing")?, None);
+    assert_eq!(
+        data.language_text("English", "UK", "greeting")?,
+        Some("Hello there")
+    );
+    assert!(data.language_text("German", "AT", "greeting").is_err());
+    assert_eq!(data.language_info.unknown1, 1);
+
+    let mut data = data;
+    data.language_info.elems[1].entries.pop();
+    let err = build_bytes(&data).unwrap_err();
+    assert!(err.chain().contains("entry keys"), "{}", err.chain());
+    Ok(())
+}
+
+#[test]
+fn included_files() -> Result<()> {
+    let mut data = GMData::default();
+    assert!(data.set_included_file("options.ini", vec![]).is_err());
+
+    let dir = std::env::temp_dir().join(format!("libgm-included-{}", std::process::id()));
+    let source_dir = dir.join("source");
+    let output_dir = dir.join("output");
+    std::fs::create_dir_all(&source_dir).unwrap();
+    std::fs::write(source_dir.join("existing.ini"), b"old").unwrap();
+    data.meta.location = Some(source_dir.join("data.win"));
+    let raw_data: Vec<u8> = build_bytes(&data)?;
+
+    let result = (|| -> Result<()> {
+        data.add_included_file("config/options.ini", b"[a]\nb=1\n".to_vec())?;
+        assert!(
+            data.add_included_file("config/options.ini", vec![])
+                .is_err()
+        );
+        assert!(data.add_included_file("existing.ini", vec![]).is_err());
+        assert!(data.set_included_file("missing.ini", vec![]).is_err());
+        assert!(data.set_included_file("../outside.ini", vec![]).is_err());
+
+        let bigger: Vec<u8> = b"[a]\nb=2\n".repeat(100);
+        data.set_included_file("config/options.ini", bigger.clone())?;
+        data.set_included_file("existing.ini", b"new".to_vec())?;
+        let path = data.included_file_path("config/options.ini")?;
+        assert_eq!(path, source_dir.join("config").join("options.ini"));
+
+        // The original files are left untouched until building to a file.
+        assert!(!path.exists());
+        assert_eq!(
+            std::fs::read(source_dir.join("existing.ini")).unwrap(),
+            b"old"
+        );
+
+        std::fs::create_dir_all(&output_dir).unwrap();
+        build_file(&data, output_dir.join("data.win"))?;
+        let read = |name: &str| std::fs::read(output_dir.join(name)).unwrap();
+        assert_eq!(read("config/options.ini"), bigger);
+        assert_eq!(read("existing.ini"), b"new");
+        assert_eq!(
+            std::fs::read(source_dir.join("existing.ini")).unwrap(),
+            b"old"
+        );
+
+        // Building to the same directory again rewrites the same files.
+        build_file(&data, output_dir.join("data.win"))?;
+        assert_eq!(read("config/options.ini"), bigger);
+
+        // Added files never overwrite other existing files,
+        // and the data file is not written in that case either.
+        std::fs::write(output_dir.join("config/options.ini"), b"user").unwrap();
+        std::fs::remove_file(output_dir.join("data.win")).unwrap();
+        assert!(build_file(&data, output_dir.join("data.win")).is_err());
+        assert_eq!(read("config/options.ini"), b"user");
+        assert!(!output_dir.join("data.win").exists());
+        Ok(())
+    })();
+    std::fs::remove_dir_all(&dir).unwrap();
+    result?;
+
+    // Included files live outside of the data file
+    assert_eq!(build_bytes(&data)?, raw_data);
+    Ok(())
+}
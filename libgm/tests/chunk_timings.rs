@@ -0,0 +1,23 @@
+// SPDX-License-Identifier: GPL-3.0-only
+//! Tests for the chunk timings recorded with the `chunk-timings` feature.
+#![cfg(feature = "chunk-timings")]
+
+use libgm::prelude::*;
+use libgm::wad::build::build_bytes_timed;
+use libgm::wad::chunk::ChunkName;
+use libgm::wad::parse::ParsingOptions;
+
+#[test]
+fn chunk_timings_cover_every_chunk() -> Result<()> {
+    let data = GMData::default();
+    let (raw_data, build_timings) = build_bytes_timed(&data)?;
+    let parsed: GMData = ParsingOptions::LENIENT
+        .assumed_version(Some(data.meta.version))
+        .parse_bytes(raw_data)?;
+
+    let chunks: Vec<ChunkName> = data.meta.chunks.iter().collect();
+    let built: Vec<ChunkName> = build_timings.iter().map(|(name, _)| *name).collect();
+    assert_eq!(built, chunks);
+    assert_eq!(parsed.meta.chunk_timings.len(), chunks.len());
+    Ok(())
+}
@@ -0,0 +1,749 @@
+// SPDX-License-Identifier: GPL-3.0-only
+//! Tests for editing code entries.
+mod common;
+
+use common::code_by_name;
+use common::find_chunk;
+use common::make_child_code;
+use common::make_code_variable;
+use common::make_game_object;
+use common::reparse;
+use libgm::gml::Code;
+use libgm::gml::Instruction;
+use libgm::gml::ModernData;
+use libgm::gml::SetBytecodeVersionError;
+use libgm::gml::build_with_statement;
+use libgm::gml::instruction::AssetReference;
+use libgm::gml::instruction::CodeVariable;
+use libgm::gml::instruction::DataType;
+use libgm::gml::instruction::InstanceType;
+use libgm::gml::instruction::PushValue;
+use libgm::gml::instruction::VariableType;
+use libgm::prelude::*;
+use libgm::wad::GMVersion;
+use libgm::wad::build_bytes;
+use libgm::wad::elem::game_object::GameObject;
+use libgm::wad::elem::room::InstanceID;
+use libgm::wad::elem::script::Script;
+use libgm::wad::parse::ParsingOptions;
+
+#[test]
+fn replace_code() -> Result<()> {
+    let mut data = GMData::default();
+    data.make_code("gml_Script_scr_test", vec![Instruction::Exit]);
+
+    let instructions = vec![
+        Instruction::PushImmediate { integer: 41 },
+        Instruction::PopDiscard { data_type: DataType::Int32 },
+        Instruction::Exit,
+    ];
+    data.replace_code("gml_Script_scr_test", instructions.clone())?;
+
+    let data = reparse(&data)?;
+    let code = code_by_name(&data, "gml_Script_scr_test")?;
+    assert_eq!(code.instructions, instructions);
+    Ok(())
+}
+
+#[test]
+fn replace_code_invalid_branch() {
+    let mut data = GMData::default();
+    data.make_code("gml_Script_scr_test", vec![Instruction::Exit]);
+
+    let instructions = vec![Instruction::Branch { jump_offset: 5 }, Instruction::Exit];
+    let result = data.replace_code("gml_Script_scr_test", instructions);
+    assert!(result.is_err());
+}
+
+#[test]
+fn push_value_conversions() -> Result<()> {
+    let mut data = GMData::default();
+    let string: GMRef<String> = data.strings.make("constant");
+    let values: Vec<(PushValue, DataType)> = vec![
+        (PushValue::from(-3_i16), DataType::Int16),
+        (PushValue::from(70_000_i32), DataType::Int32),
+        (PushValue::from(1_i64 << 40), DataType::Int64),
+        (PushValue::from(0.25), DataType::Double),
+        (PushValue::from(true), DataType::Bool),
+        (PushValue::from(string), DataType::String),
+    ];
+    let mut instructions = Vec::new();
+    for &(value, data_type) in &values {
+        assert_eq!(value.data_type(), data_type);
+        instructions.push(Instruction::Push { value });
+        instructions.push(Instruction::PopDiscard { data_type });
+    }
+    instructions.push(Instruction::Exit);
+    data.make_code("gml_Script_scr_constants", instructions);
+
+    let data = reparse(&data)?;
+    let code = code_by_name(&data, "gml_Script_scr_constants")?;
+    let pushed: Vec<PushValue> = code
+        .instructions
+        .iter()
+        .filter_map(Instruction::push_value)
+        .collect();
+    assert_eq!(pushed.len(), values.len());
+    assert_eq!(pushed[0].as_i64(), Some(-3));
+    assert_eq!(pushed[1].as_i64(), Some(70_000));
+    assert_eq!(pushed[2].as_f64(), Some((1_i64 << 40) as f64));
+    assert_eq!(pushed[3].as_f64(), Some(0.25));
+    assert_eq!(pushed[3].as_i64(), None);
+    assert_eq!(pushed[4].as_bool(), Some(true));
+    assert_eq!(pushed[5].as_string_ref(), Some(string));
+    assert_eq!(pushed[5].as_f64(), None);
+    Ok(())
+}
+
+#[test]
+fn find_duplicate_code() -> Result<()> {
+    let mut data = GMData::default();
+    let instructions = vec![
+        Instruction::PushImmediate { integer: 7 },
+        Instruction::PopDiscard { data_type: DataType::Int32 },
+        Instruction::Exit,
+    ];
+    data.make_code("gml_Script_scr_first", instructions.clone());
+    data.make_code("gml_Script_scr_other", vec![Instruction::Exit]);
+    data.make_code("gml_Script_scr_second", instructions);
+    let parent: GMRef<Code> = data.make_code("gml_Script_scr_parent", vec![Instruction::Exit]);
+    make_child_code("gml_Script_scr_child", parent, 0, &mut data);
+
+    let mut data = reparse(&data)?;
+    // Differing local counts should not matter.
+    let second: GMRef<Code> = data
+        .codes
+        .ref_by_name("gml_Script_scr_second", &data.strings)?;
+    data.codes
+        .by_ref_mut(second)?
+        .modern_data
+        .as_mut()
+        .unwrap()
+        .local_count = 3;
+
+    let names = data.code_names()?;
+    let groups: Vec<Vec<&str>> = data
+        .find_duplicate_code()
+        .iter()
+        .map(|group| {
+            group
+                .iter()
+                .map(|code| names[code.index().unwrap()])
+                .collect()
+        })
+        .collect();
+    assert_eq!(groups, [
+        vec!["gml_Script_scr_first", "gml_Script_scr_second"],
+        vec!["gml_Script_scr_other", "gml_Script_scr_parent"],
+    ]);
+    Ok(())
+}
+
+#[test]
+fn rename_code() -> Result<()> {
+    let mut data = GMData::default();
+    data.make_code("gml_Script_scr_old", vec![Instruction::Exit]);
+    data.make_code("gml_Object_obj_a_Step_0", vec![Instruction::Exit]);
+
+    assert!(
+        data.rename_code("gml_Script_scr_old", "gml_Object_obj_a_Nonsense_9", true)
+            .is_err()
+    );
+    assert!(
+        data.rename_code("gml_Script_scr_missing", "gml_Script_scr_new", true)
+            .is_err()
+    );
+    let string_count: usize = data.strings.len();
+    assert!(
+        data.rename_code("gml_Script_scr_old", "gml_Object_obj_a_Step_0", false)
+            .is_err()
+    );
+    assert_eq!(data.strings.len(), string_count);
+    data.rename_code("gml_Script_scr_old", "gml_Script_scr_old", true)?;
+    data.rename_code("gml_Script_scr_old", "gml_Script_scr_new", true)?;
+    data.rename_code("gml_Object_obj_a_Step_0", "custom name", false)?;
+
+    let data = reparse(&data)?;
+    let names = data.code_names()?;
+    assert!(names.contains(&"gml_Script_scr_new"));
+    assert!(names.contains(&"custom name"));
+    assert!(!names.contains(&"gml_Script_scr_old"));
+    Ok(())
+}
+
+#[test]
+fn rename_code_locals_and_function() -> Result<()> {
+    let mut data = GMData::default();
+    data.make_code("gml_Script_scr_old", vec![Instruction::Exit]);
+    data.functions.make("gml_Script_scr_old", &mut data.strings);
+    data.make_code("gml_Script_scr_taken", vec![Instruction::Exit]);
+    data.functions
+        .make("gml_Script_scr_taken", &mut data.strings);
+    data.functions
+        .make("gml_Script_scr_new_taken", &mut data.strings);
+
+    data.rename_code("gml_Script_scr_old", "gml_Script_scr_new", true)?;
+    // The function with the new name already exists, so it is kept as is.
+    data.rename_code("gml_Script_scr_taken", "gml_Script_scr_new_taken", true)?;
+
+    let local_names: Vec<&str> = data
+        .functions
+        .code_locals
+        .iter()
+        .map(|local| local.name.resolve(&data.strings.elems).map(String::as_str))
+        .collect::<Result<_>>()?;
+    assert_eq!(local_names, [
+        "gml_Script_scr_new",
+        "gml_Script_scr_new_taken"
+    ]);
+    assert!(data.function_by_name("gml_Script_scr_new").is_ok());
+    assert!(data.function_by_name("gml_Script_scr_old").is_err());
+    assert!(data.function_by_name("gml_Script_scr_taken").is_ok());
+    assert!(data.function_by_name("gml_Script_scr_new_taken").is_ok());
+    Ok(())
+}
+
+#[test]
+fn set_code_arguments_count() -> Result<()> {
+    let mut data = GMData::default();
+    let script = data.make_code("gml_Script_scr_add", vec![Instruction::Exit]);
+    let global = data.make_code("gml_GlobalScript_scr_add", vec![Instruction::Exit]);
+    data.set_code_arguments_count(script, 2, false)?;
+    data.set_code_arguments_count(global, 0x7FFF, true)?;
+    assert!(
+        data.set_code_arguments_count(script, 0x8000, false)
+            .is_err()
+    );
+    assert!(
+        data.set_code_arguments_count(GMRef::new(1000), 1, false)
+            .is_err()
+    );
+
+    let data = reparse(&data)?;
+    let modern_data = |name: &str| code_by_name(&data, name).map(|code| code.modern_data.clone());
+    let script_data: ModernData = modern_data("gml_Script_scr_add")?.unwrap();
+    assert_eq!(script_data.argument_count, 2);
+    assert!(!script_data.weird_local_flag);
+    assert_eq!(script_data.arguments_count_raw()?, 2);
+    let global_data: ModernData = modern_data("gml_GlobalScript_scr_add")?.unwrap();
+    assert_eq!(global_data.argument_count, 0x7FFF);
+    assert!(global_data.weird_local_flag);
+    assert_eq!(global_data.arguments_count_raw()?, 0xFFFF);
+
+    // Counts that would overwrite the flag are not truncated
+    let mut data = data;
+    let code: &mut Code = data
+        .codes
+        .by_name_mut("gml_Script_scr_add", &data.strings)?;
+    code.modern_data.as_mut().unwrap().argument_count = 0x8000;
+    let error = build_bytes(&data).unwrap_err().chain();
+    assert!(error.contains("Argument count 32768 exceeds"), "{error}");
+    Ok(())
+}
+
+#[test]
+fn implausible_code_arguments_count() -> Result<()> {
+    let mut data = GMData::default();
+    let code = data.make_code("gml_Script_scr_corrupt", vec![Instruction::Exit]);
+    data.set_code_arguments_count(code, 1000, false)?;
+    let raw_data: Vec<u8> = build_bytes(&data)?;
+
+    let strict = ParsingOptions::STRICT.assumed_version(Some(data.meta.version));
+    let error = strict.parse_bytes(&raw_data).unwrap_err().chain();
+    assert!(
+        error.contains("Code entry argument count 1000 is implausibly high"),
+        "{error}"
+    );
+
+    let data = reparse(&data)?;
+    let modern_data = code_by_name(&data, "gml_Script_scr_corrupt")?
+        .modern_data
+        .as_ref()
+        .unwrap();
+    assert_eq!(modern_data.argument_count, 1000);
+    Ok(())
+}
+
+#[test]
+fn child_code_after_parent() -> Result<()> {
+    let mut data = GMData::default();
+    let instructions = vec![
+        Instruction::PushImmediate { integer: 1 },
+        Instruction::PopDiscard { data_type: DataType::Int32 },
+        Instruction::Exit,
+    ];
+    let parent: GMRef<Code> = data.make_code("gml_Script_scr_parent", instructions.clone());
+    make_child_code("gml_Script_scr_child", parent, 8, &mut data);
+
+    let data = reparse(&data)?;
+    let parent_code = code_by_name(&data, "gml_Script_scr_parent")?;
+    let child_code = code_by_name(&data, "gml_Script_scr_child")?;
+    assert_eq!(parent_code.instructions, instructions);
+    assert_eq!(child_code.parent(), parent);
+    assert_eq!(child_code.execution_offset(), 8);
+    assert_eq!(child_code.length(), 0);
+    Ok(())
+}
+
+#[test]
+fn code_entry_order_round_trip() -> Result<()> {
+    let mut data = GMData::default();
+    let instructions = vec![
+        Instruction::PushImmediate { integer: 1 },
+        Instruction::PopDiscard { data_type: DataType::Int32 },
+        Instruction::Exit,
+    ];
+    let first: GMRef<Code> = data.make_code("gml_Script_scr_first", instructions.clone());
+    data.make_code("gml_Script_scr_plain", vec![Instruction::Exit]);
+    make_child_code("gml_Script_scr_first_a", first, 4, &mut data);
+    let second: GMRef<Code> = data.make_code("gml_Script_scr_second", instructions);
+    make_child_code("gml_Script_scr_second_a", second, 8, &mut data);
+    make_child_code("gml_Script_scr_first_b", first, 8, &mut data);
+    data.validate_code_entry_order()?;
+
+    let raw_data: Vec<u8> = build_bytes(&data)?;
+    let reparsed = reparse(&data)?;
+    assert_eq!(reparsed.code_names()?, data.code_names()?);
+    let parents = |data: &GMData| -> Vec<(GMRef<Code>, u32)> {
+        data.codes
+            .elements()
+            .map(|code| (code.parent(), code.execution_offset()))
+            .collect()
+    };
+    assert_eq!(parents(&reparsed), parents(&data));
+
+    // Child code entries point to the same instructions as their parent
+    let read_u32 = |pos: usize| u32::from_le_bytes(raw_data[pos..pos + 4].try_into().unwrap());
+    let chunk: usize = find_chunk(&raw_data, b"CODE");
+    let instructions_pos = |index: usize| -> i64 {
+        let entry = read_u32(chunk + 4 + index * 4) as usize;
+        (entry + 12) as i64 + i64::from(read_u32(entry + 12) as i32)
+    };
+    assert_eq!(read_u32(chunk) as usize, data.codes.len());
+    for (i, code) in data.codes.elements().enumerate() {
+        if let Some(parent) = code.parent().index() {
+            assert_eq!(instructions_pos(i), instructions_pos(parent));
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn asset_reference_validation() -> Result<()> {
+    let mut data = GMData::default();
+    let object: GMRef<GameObject> = make_game_object("obj_player", &mut data);
+    let function = data.functions.make("scr_move", &mut data.strings);
+    let push = |asset_reference| Instruction::PushReference { asset_reference };
+    data.make_code("gml_Script_scr_test", vec![
+        push(AssetReference::Object(object)),
+        push(AssetReference::Function(function)),
+        Instruction::Exit,
+    ]);
+    data.validate_asset_references()?;
+
+    let invalid = [
+        AssetReference::Object(GMRef::new(1)),
+        AssetReference::Sprite(GMRef::new(0)),
+        AssetReference::Function(GMRef::new(3)),
+        AssetReference::RoomInstance(100_000),
+    ];
+    for asset_reference in invalid {
+        let mut data = data.clone();
+        data.codes.by_ref_mut(GMRef::new(0))?.instructions[0] = push(asset_reference);
+        let error = data.validate_asset_references().unwrap_err().chain();
+        assert!(error.contains("instruction #0"), "{error}");
+        assert!(data.validate().is_err());
+        // Dangling asset references occur in real games.
+        data.post_deserialize()?;
+    }
+    Ok(())
+}
+
+#[test]
+fn code_entry_order_validation() {
+    let mut data = GMData::default();
+    make_child_code("gml_Script_scr_child", GMRef::new(1), 4, &mut data);
+    data.make_code("gml_Script_scr_parent", vec![Instruction::Exit]);
+    assert!(data.validate_code_entry_order().is_err());
+    assert!(data.validate().is_err());
+    // Only a warning when loading, so that existing data files still load
+    assert!(data.post_deserialize().is_ok());
+
+    // Nested child code entries
+    let mut data = GMData::default();
+    let parent: GMRef<Code> = data.make_code("gml_Script_scr_parent", vec![Instruction::Exit]);
+    make_child_code("gml_Script_scr_child", parent, 0, &mut data);
+    let child: GMRef<Code> = data
+        .codes
+        .ref_by_name("gml_Script_scr_child", &data.strings)
+        .unwrap();
+    make_child_code("gml_Script_scr_grandchild", child, 0, &mut data);
+    assert!(data.validate_code_entry_order().is_err());
+    let error = build_bytes(&data).unwrap_err();
+    assert!(
+        error.chain().contains("is a child code entry itself"),
+        "{}",
+        error.chain()
+    );
+    assert!(
+        error.chain().contains("\"gml_Script_scr_grandchild\""),
+        "{}",
+        error.chain()
+    );
+}
+
+#[test]
+fn child_code_before_parent() {
+    let mut data = GMData::default();
+    make_child_code("gml_Script_scr_child", GMRef::new(1), 4, &mut data);
+    data.make_code("gml_Script_scr_parent", vec![Instruction::Exit]);
+
+    let error = build_bytes(&data).unwrap_err();
+    let message: String = error.to_string();
+    assert!(message.contains("comes before its parent"), "{message}");
+    assert!(message.contains("\"gml_Script_scr_child\""), "{message}");
+}
+
+#[test]
+fn locate_bytecode_offset() -> Result<()> {
+    let mut data = GMData::default();
+    data.make_code("gml_Script_scr_first", vec![Instruction::Exit]);
+    let code: GMRef<Code> = data.make_code("gml_Script_scr_second", vec![
+        Instruction::PushImmediate { integer: 1 },
+        Instruction::Push { value: PushValue::Int32(0x1234_5678) },
+        Instruction::PopDiscard { data_type: DataType::Int32 },
+        Instruction::Exit,
+    ]);
+
+    // Find the pushed integer, which comes right after the push instruction itself.
+    let raw_data: Vec<u8> = build_bytes(&data)?;
+    let needle: [u8; 4] = 0x1234_5678_i32.to_le_bytes();
+    let value_pos = raw_data
+        .windows(4)
+        .position(|window| window == needle)
+        .unwrap() as u32;
+
+    assert!(data.locate_bytecode_offset(value_pos).is_err());
+    let data: GMData = ParsingOptions::LENIENT.parse_bytes(&raw_data)?;
+
+    assert_eq!(data.locate_bytecode_offset(value_pos)?, Some((code, 1)));
+    assert_eq!(data.locate_bytecode_offset(value_pos - 4)?, Some((code, 1)));
+    assert_eq!(data.locate_bytecode_offset(value_pos - 5)?, Some((code, 0)));
+    assert_eq!(data.locate_bytecode_offset(value_pos + 4)?, Some((code, 2)));
+    assert_eq!(data.locate_bytecode_offset(0)?, None);
+    Ok(())
+}
+
+/// Creates code accessing a variable of the given room instance, like `inst_100123.x = 1`.
+fn make_room_instance_code(instance_id: InstanceID, data: &mut GMData) -> Result<Vec<Instruction>> {
+    let name: GMRef<String> = data.strings.make("room_instance_variable");
+    let version = data.meta.version;
+    let variable = CodeVariable {
+        variable: data.variables.make(name, InstanceType::Self_, version)?,
+        variable_type: VariableType::Instance,
+        instance_type: InstanceType::RoomInstance(instance_id),
+        is_int32: false,
+    };
+    let instructions = vec![
+        Instruction::PushImmediate { integer: 1 },
+        Instruction::Pop {
+            variable,
+            type1: DataType::Variable,
+            type2: DataType::Int32,
+        },
+        Instruction::Push { value: PushValue::Variable(variable) },
+        Instruction::PopDiscard { data_type: DataType::Variable },
+        Instruction::Exit,
+    ];
+    data.make_code("gml_Script_scr_room_instance", instructions.clone());
+    Ok(instructions)
+}
+
+#[test]
+fn room_instance_large_id() -> Result<()> {
+    let mut data = GMData::default();
+    let instructions = make_room_instance_code(InstanceID(100_123), &mut data)?;
+
+    let data = reparse(&data)?;
+    let code = code_by_name(&data, "gml_Script_scr_room_instance")?;
+    assert_eq!(code.instructions, instructions);
+    Ok(())
+}
+
+#[test]
+fn room_instance_out_of_range() -> Result<()> {
+    for instance_id in [99_999, 132_768, 200_000] {
+        let mut data = GMData::default();
+        make_room_instance_code(InstanceID(instance_id), &mut data)?;
+        assert!(
+            build_bytes(&data).is_err(),
+            "instance ID {instance_id} should not build"
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn script_code_linkage() -> Result<()> {
+    let mut data = GMData::default();
+    let old_script: GMRef<Script> = data.make_script("scr_old", vec![Instruction::Exit]);
+    let old_code: GMRef<Code> = data.scripts.by_ref(old_script)?.code;
+
+    // GMS 2.3+ script function declared in a global script
+    let global: GMRef<Code> = data.make_code("gml_GlobalScript_scr_file", vec![Instruction::Exit]);
+    make_child_code("gml_Script_scr_new", global, 0, &mut data);
+    let new_code: GMRef<Code> = data
+        .codes
+        .ref_by_name("gml_Script_scr_new", &data.strings)?;
+    let name: GMRef<String> = data.strings.make("scr_new");
+    let new_script = data.scripts.push(Script {
+        name,
+        is_constructor: false,
+        code: new_code,
+    });
+
+    let name: GMRef<String> = data.strings.make("Empty");
+    let constructor = Script {
+        name,
+        is_constructor: true,
+        code: GMRef::none(),
+    };
+    let event_code: GMRef<Code> = data.make_code("gml_Object_obj_test_Create_0", vec![]);
+
+    let script = data.scripts.by_ref(old_script)?;
+    assert_eq!(script.code(&data), Some(data.codes.by_ref(old_code)?));
+    assert_eq!(script.root_code(&data), Some(data.codes.by_ref(old_code)?));
+    let script = data.scripts.by_ref(new_script)?;
+    assert_eq!(script.code(&data), Some(data.codes.by_ref(new_code)?));
+    assert_eq!(script.root_code(&data), Some(data.codes.by_ref(global)?));
+    assert_eq!(constructor.code(&data), None);
+    assert_eq!(constructor.root_code(&data), None);
+
+    assert_eq!(data.script_of_code(old_code), Some(old_script));
+    assert_eq!(data.script_of_code(new_code), Some(new_script));
+    assert_eq!(data.script_of_code(global), Some(new_script));
+    assert_eq!(data.script_of_code(event_code), None);
+    Ok(())
+}
+
+#[test]
+fn reject_bogus_instructions_offset() -> Result<()> {
+    let mut data = GMData::default();
+    data.make_code("gml_Script_scr_test", vec![Instruction::Exit]);
+    let raw_data: Vec<u8> = build_bytes(&data)?;
+
+    let chunk_pos = find_chunk(&raw_data, b"CODE");
+    let pointer = u32::from_le_bytes(raw_data[chunk_pos + 4..chunk_pos + 8].try_into().unwrap());
+    // Skip name, length, locals count and arguments count.
+    let offset_pos = pointer as usize + 12;
+
+    // Overflowing, pointing into the previous chunk and pointing past the end.
+    let before_chunk = -((offset_pos - chunk_pos) as i32) - 16;
+    for bogus_offset in [i32::MIN, before_chunk, 0x100_0000] {
+        let mut raw_data = raw_data.clone();
+        raw_data[offset_pos..offset_pos + 4].copy_from_slice(&bogus_offset.to_le_bytes());
+        let result = ParsingOptions::LENIENT
+            .assumed_version(Some(data.meta.version))
+            .parse_bytes(raw_data);
+        let Err(error) = result else {
+            panic!("offset {bogus_offset} was accepted");
+        };
+        let chain: String = error.chain();
+        assert!(
+            chain.contains("overflowed") || chain.contains("out of bounds for chunk CODE"),
+            "{chain}"
+        );
+        if chain.contains("out of bounds") {
+            let entry = format!("Code entry at position {pointer} span");
+            assert!(chain.contains(&entry), "{chain}");
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn child_code_offset_fixups() -> Result<()> {
+    let mut data = GMData::default();
+    let instructions = vec![
+        Instruction::Branch { jump_offset: 2 },
+        Instruction::Exit,
+        Instruction::PushImmediate { integer: 1 },
+        Instruction::PopDiscard { data_type: DataType::Int32 },
+        Instruction::Exit,
+    ];
+    let parent: GMRef<Code> = data.make_code("gml_Script_scr_parent", instructions);
+    make_child_code("gml_Script_scr_child", parent, 0, &mut data);
+    let child: GMRef<Code> = data
+        .codes
+        .ref_by_name("gml_Script_scr_child", &data.strings)?;
+    data.set_instruction_offset(child, 2)?;
+    assert_eq!(data.codes.by_ref(child)?.execution_offset(), 8);
+
+    let inserted = [
+        Instruction::PushImmediate { integer: 2 },
+        Instruction::PopDiscard { data_type: DataType::Int32 },
+    ];
+    data.insert_code_instructions(parent, 1, &inserted)?;
+    assert_eq!(data.codes.by_ref(child)?.execution_offset(), 16);
+    assert_eq!(
+        data.codes.by_ref(parent)?.instructions[0],
+        Instruction::Branch { jump_offset: 4 }
+    );
+    data.validate_child_code_offsets()?;
+
+    data.remove_code_instructions(parent, 0..1)?;
+    assert_eq!(data.codes.by_ref(child)?.execution_offset(), 12);
+    assert!(data.remove_code_instructions(parent, 2..4).is_err());
+
+    let data = reparse(&data)?;
+    assert_eq!(
+        code_by_name(&data, "gml_Script_scr_child")?.execution_offset(),
+        12
+    );
+
+    let mut data = data;
+    data.codes
+        .by_ref_mut(child)?
+        .modern_data
+        .as_mut()
+        .unwrap()
+        .execution_offset = 2;
+    assert!(data.validate_child_code_offsets().is_err());
+    Ok(())
+}
+
+#[test]
+fn set_bytecode_version() -> Result<()> {
+    let mut data = GMData::default();
+    let instructions = vec![
+        Instruction::PushImmediate { integer: 1 },
+        Instruction::IsNullishValue,
+        Instruction::PopDiscard { data_type: DataType::Bool },
+        Instruction::Exit,
+    ];
+    data.make_code("gml_Script_scr_bytecode", instructions);
+
+    assert_eq!(data.incompatible_instructions(17), []);
+    let incompatible = data.incompatible_instructions(16);
+    assert_eq!(incompatible.len(), 1);
+    assert_eq!(incompatible[0].index, 1);
+    assert_eq!(incompatible[0].required_version, 17);
+    assert_eq!(data.incompatible_instructions(14).len(), 2);
+
+    let Err(SetBytecodeVersionError::Incompatible(error)) = data.set_bytecode_version(16) else {
+        panic!("Setting bytecode version 16 should fail with incompatible instructions");
+    };
+    assert_eq!(error.bytecode_version, 16);
+    assert_eq!(error.instructions, incompatible);
+    let message: String = error.to_string();
+    assert!(
+        message.contains("Instruction #1 (IsNullishValue)"),
+        "{message}"
+    );
+    assert!(message.contains("\"gml_Script_scr_bytecode\""), "{message}");
+    assert!(matches!(
+        data.set_bytecode_version(14),
+        Err(SetBytecodeVersionError::Invalid(_)),
+    ));
+    assert_eq!(data.general_info.wad_version, 17);
+
+    data.replace_code("gml_Script_scr_bytecode", vec![
+        Instruction::PushImmediate { integer: 1 },
+        Instruction::PopDiscard { data_type: DataType::Int16 },
+        Instruction::Exit,
+    ])?;
+    data.set_bytecode_version(16)?;
+    assert_eq!(data.general_info.wad_version, 16);
+    assert_eq!(data.meta.version, GMVersion::GM2024_14_1);
+
+    // Pre-GMS2 data files can be downgraded, but only with compatible instructions.
+    data.meta.version = GMVersion::Wad16Old;
+    let Err(SetBytecodeVersionError::Incompatible(error)) = data.set_bytecode_version(14) else {
+        panic!("Setting bytecode version 14 should fail with incompatible instructions");
+    };
+    let incompatible = error.instructions;
+    assert_eq!(incompatible.len(), 1);
+    assert_eq!(incompatible[0].index, 0);
+    assert_eq!(incompatible[0].instruction, Instruction::PushImmediate {
+        integer: 1
+    });
+    assert_eq!(data.meta.version, GMVersion::Wad16Old);
+    data.replace_code("gml_Script_scr_bytecode", vec![Instruction::Exit])?;
+    data.set_bytecode_version(14)?;
+    assert_eq!(data.meta.version, GMVersion::Wad14);
+    Ok(())
+}
+
+#[test]
+fn with_statement() -> Result<()> {
+    // with (obj_enemy) { hp -= 1; }
+    let mut data = GMData::default();
+    make_game_object("obj_player", &mut data);
+    let object: GMRef<GameObject> = make_game_object("obj_enemy", &mut data);
+    let hp = make_code_variable("hp", InstanceType::Self_, &mut data)?;
+
+    let instance = [Instruction::PushImmediate { integer: object.as_raw() as i16 }];
+    let body = [
+        Instruction::Push { value: PushValue::Variable(hp) },
+        Instruction::PushImmediate { integer: 1 },
+        Instruction::Subtract {
+            lhs: DataType::Variable,
+            rhs: DataType::Int32,
+        },
+        Instruction::Pop {
+            variable: hp,
+            type1: DataType::Variable,
+            type2: DataType::Variable,
+        },
+    ];
+    let mut instructions = build_with_statement(&instance, &body)?;
+    assert_eq!(instructions[0], Instruction::PushImmediate { integer: 1 });
+    assert_eq!(instructions[1], Instruction::PushWithContext {
+        jump_offset: 7
+    });
+    assert_eq!(instructions[2..6], body);
+    assert_eq!(instructions[6], Instruction::PopWithContext {
+        jump_offset: -6
+    });
+    instructions.push(Instruction::Exit);
+    data.make_code("gml_Object_obj_player_Create_0", instructions.clone());
+
+    let data = reparse(&data)?;
+    let code = code_by_name(&data, "gml_Object_obj_player_Create_0")?;
+    assert_eq!(code.instructions, instructions);
+    Ok(())
+}
+
+#[test]
+fn with_statement_exit() -> Result<()> {
+    let instance = [Instruction::PushImmediate { integer: 7 }];
+    let body = [
+        Instruction::PushImmediate { integer: 1 },
+        // To the exit, which should now target the inserted context exit
+        Instruction::BranchUnless { jump_offset: 3 },
+        // To the end of the body (the PopWithContext), across the exit
+        Instruction::Branch { jump_offset: 4 },
+        // Backwards, not across the exit
+        Instruction::Branch { jump_offset: -3 },
+        Instruction::Exit,
+        // Backwards, across the exit
+        Instruction::Branch { jump_offset: -5 },
+    ];
+    let instructions = build_with_statement(&instance, &body)?;
+    assert_eq!(instructions, [
+        Instruction::PushImmediate { integer: 7 },
+        Instruction::PushWithContext { jump_offset: 8 },
+        Instruction::PushImmediate { integer: 1 },
+        Instruction::BranchUnless { jump_offset: 3 },
+        Instruction::Branch { jump_offset: 5 },
+        Instruction::Branch { jump_offset: -3 },
+        Instruction::PopWithContextExit,
+        Instruction::Exit,
+        Instruction::Branch { jump_offset: -6 },
+        Instruction::PopWithContext { jump_offset: -7 },
+    ]);
+
+    let body = [Instruction::Branch { jump_offset: 2 }];
+    assert!(build_with_statement(&instance, &body).is_err());
+    Ok(())
+}
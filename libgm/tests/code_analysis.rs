@@ -0,0 +1,273 @@
+// SPDX-License-Identifier: GPL-3.0-only
+//! Tests for analyzing code, such as variable uses, function calls and control flow.
+mod common;
+
+use std::collections::HashSet;
+
+use common::make_child_code;
+use common::make_code_variable;
+use common::reparse;
+use libgm::gml::Code;
+use libgm::gml::Instruction;
+use libgm::gml::cfg::EdgeKind;
+use libgm::gml::cfg::build_cfg;
+use libgm::gml::instruction::DataType;
+use libgm::gml::instruction::InstanceType;
+use libgm::prelude::*;
+use libgm::wad::elem::script::Script;
+
+#[test]
+fn variable_and_function_use_counts() -> Result<()> {
+    let mut data = GMData::default();
+    let counter = make_code_variable("counter", InstanceType::Global, &mut data)?;
+    let unused = make_code_variable("unused", InstanceType::Global, &mut data)?;
+    let function = data.functions.make("show_debug_message", &mut data.strings);
+    let instructions = vec![
+        Instruction::PushGlobal { variable: counter },
+        Instruction::Pop {
+            variable: counter,
+            type1: DataType::Variable,
+            type2: DataType::Variable,
+        },
+        Instruction::PushGlobal { variable: counter },
+        Instruction::Call { function, arg_count: 1 },
+        Instruction::PopDiscard { data_type: DataType::Variable },
+        Instruction::Exit,
+    ];
+    data.make_code("gml_Script_scr_counts", instructions);
+
+    let data = reparse(&data)?;
+    assert_eq!(data.variable_use_count(counter.variable), 3);
+    assert_eq!(data.variable_use_count(unused.variable), 0);
+    assert_eq!(data.function_use_count(function), 1);
+    let counts: Vec<usize> = data.variable_use_counts();
+    assert_eq!(counts[counter.variable.index().unwrap()], 3);
+    assert_eq!(data.function_use_counts()[function.index().unwrap()], 1);
+    Ok(())
+}
+
+#[test]
+fn code_string_literals() -> Result<()> {
+    let mut data = GMData::default();
+    let first = vec![
+        data.push_string_instruction("spr_player"),
+        Instruction::PopDiscard { data_type: DataType::String },
+        data.push_string_instruction("Hello"),
+        Instruction::PopDiscard { data_type: DataType::String },
+        Instruction::Exit,
+    ];
+    let second = vec![
+        data.push_int_instruction(3),
+        Instruction::PopDiscard { data_type: DataType::Int32 },
+        data.push_string_instruction("Hello"),
+        Instruction::PopDiscard { data_type: DataType::String },
+        Instruction::Exit,
+    ];
+    let first: GMRef<Code> = data.make_code("gml_Script_scr_first", first);
+    let second: GMRef<Code> = data.make_code("gml_Script_scr_second", second);
+
+    let data = reparse(&data)?;
+    let literals: Vec<(GMRef<Code>, &str)> = data
+        .code_string_literals()
+        .into_iter()
+        .map(|(code, string)| (code, string.display(&data.strings)))
+        .collect();
+    assert_eq!(literals, [
+        (first, "spr_player"),
+        (first, "Hello"),
+        (second, "Hello")
+    ]);
+    Ok(())
+}
+
+#[test]
+fn builtin_function_detection() -> Result<()> {
+    let mut data = GMData::default();
+    let builtin = data.functions.make("array_length", &mut data.strings);
+    let script_function = data
+        .functions
+        .make("gml_Script_scr_modern", &mut data.strings);
+    let old_script = data.functions.make("scr_old", &mut data.strings);
+    let code: GMRef<Code> = data.make_code("gml_Script_scr_old", vec![Instruction::Exit]);
+    let name: GMRef<String> = data.strings.make("scr_old");
+    data.scripts
+        .push(Script { name, is_constructor: false, code });
+
+    assert_eq!(data.function_by_name("array_length")?, builtin);
+    assert!(data.function_by_name("does_not_exist").is_err());
+    assert!(data.is_builtin_function(builtin)?);
+    assert!(!data.is_builtin_function(script_function)?);
+    assert!(!data.is_builtin_function(old_script)?);
+    Ok(())
+}
+
+#[test]
+fn code_cfg() -> Result<()> {
+    let mut data = GMData::default();
+    let pushim = |integer| Instruction::PushImmediate { integer };
+    let popz = Instruction::PopDiscard { data_type: DataType::Int32 };
+    // if (1) { with (7) { 2; } } else { 3; } exit; (followed by dead code)
+    let instructions = vec![
+        pushim(1),
+        Instruction::BranchUnless { jump_offset: 7 },
+        pushim(7),
+        Instruction::PushWithContext { jump_offset: 3 },
+        pushim(2),
+        popz.clone(),
+        Instruction::PopWithContext { jump_offset: -2 },
+        Instruction::Branch { jump_offset: 3 },
+        pushim(3),
+        popz.clone(),
+        Instruction::Exit,
+        pushim(4),
+        popz,
+    ];
+    let code = data.make_code("gml_Script_scr_flow", instructions);
+    make_child_code("gml_Script_scr_flow_child", code, 44, &mut data);
+
+    let cfg = build_cfg(data.codes.by_ref(code)?)?;
+    let ranges: Vec<_> = cfg.blocks.iter().map(|b| b.instructions.clone()).collect();
+    assert_eq!(ranges, [
+        0..2,
+        2..4,
+        4..6,
+        6..7,
+        7..8,
+        8..10,
+        10..11,
+        11..13
+    ]);
+    let edges: Vec<(usize, usize, EdgeKind)> =
+        cfg.edges.iter().map(|e| (e.from, e.to, e.kind)).collect();
+    assert_eq!(edges, [
+        (0, 5, EdgeKind::Branch),
+        (0, 1, EdgeKind::FallThrough),
+        (1, 3, EdgeKind::WithContextSkip),
+        (1, 2, EdgeKind::FallThrough),
+        (2, 3, EdgeKind::FallThrough),
+        (3, 2, EdgeKind::WithContextLoop),
+        (3, 4, EdgeKind::FallThrough),
+        (4, 6, EdgeKind::Branch),
+        (5, 6, EdgeKind::FallThrough),
+    ]);
+    assert_eq!(cfg.block_of(5), Some(2));
+    assert_eq!(cfg.block_of(13), None);
+    assert_eq!(cfg.reachable_blocks(), [
+        true, true, true, true, true, true, true, false
+    ]);
+
+    // The child code entry point makes the trailing code reachable
+    let child = data
+        .codes
+        .ref_by_name("gml_Script_scr_flow_child", &data.strings)?;
+    let cfg = data.code_cfg(child)?;
+    assert_eq!(cfg.entries, [0, 7]);
+    assert!(cfg.reachable_blocks().iter().all(|&reachable| reachable));
+
+    data.codes.by_ref_mut(code)?.instructions[7] = Instruction::Branch { jump_offset: 100 };
+    let err = data.code_cfg(code).unwrap_err();
+    assert!(err.chain().contains("instruction #7"), "{}", err.chain());
+    Ok(())
+}
+
+#[test]
+fn call_graph() -> Result<()> {
+    let mut data = GMData::default();
+    let call = |name: &str, data: &mut GMData| {
+        let function = data.functions.make(name, &mut data.strings);
+        Instruction::Call { function, arg_count: 0 }
+    };
+    let popz = Instruction::PopDiscard { data_type: DataType::Variable };
+
+    let helper = call("gml_Script_helper", &mut data);
+    let leaf = call("gml_Script_leaf", &mut data);
+    let builtin = call("show_debug_message", &mut data);
+    let create = data.make_code("gml_Object_obj_a_Create_0", vec![
+        helper.clone(),
+        popz.clone(),
+        builtin,
+        popz.clone(),
+        Instruction::Exit,
+    ]);
+    let helper_code = data.make_code("gml_Script_helper", vec![
+        leaf.clone(),
+        popz.clone(),
+        Instruction::Exit,
+    ]);
+    let leaf_code = data.make_code("gml_Script_leaf", vec![Instruction::Exit]);
+    call("gml_Script_dead", &mut data);
+    let dead_code = data.make_code("gml_Script_dead", vec![
+        helper,
+        popz.clone(),
+        Instruction::Exit,
+    ]);
+    call("gml_Script_dynamic", &mut data);
+    let dynamic_code = data.make_code("gml_Script_dynamic", vec![
+        Instruction::CallVariable { arg_count: 0 },
+        popz.clone(),
+        Instruction::Exit,
+    ]);
+
+    // A global script declaring a function: the function body is skipped
+    // by the global script itself.
+    let global = data.make_code("gml_GlobalScript_lib", vec![
+        Instruction::Branch { jump_offset: 5 },
+        leaf,
+        popz,
+        Instruction::Exit,
+        Instruction::Exit,
+    ]);
+    make_child_code("gml_Script_lib_func", global, 4, &mut data);
+    call("gml_Script_lib_func", &mut data);
+
+    let data = reparse(&data)?;
+    let lib_func = data
+        .codes
+        .ref_by_name("gml_Script_lib_func", &data.strings)?;
+    let graph = data.call_graph()?;
+    assert_eq!(graph.edges, [
+        (create, helper_code),
+        (helper_code, leaf_code),
+        (dead_code, helper_code),
+        (lib_func, leaf_code),
+    ]);
+    assert_eq!(graph.callers_of(leaf_code).collect::<Vec<_>>(), [
+        helper_code,
+        lib_func
+    ]);
+    assert_eq!(graph.callees_of(create).collect::<Vec<_>>(), [helper_code]);
+    assert_eq!(graph.dynamic_callers, [dynamic_code]);
+    assert_eq!(graph.roots, [create, dynamic_code, global]);
+    let builtin = data.function_by_name("show_debug_message")?;
+    assert_eq!(graph.code_of_function(builtin), None);
+
+    let reachable = graph.reachable();
+    assert!(reachable.contains(&leaf_code));
+    assert!(!reachable.contains(&dead_code));
+    assert!(!reachable.contains(&lib_func));
+    Ok(())
+}
+
+#[test]
+fn functions_called() -> Result<()> {
+    let mut data = GMData::default();
+    let show_message = data.functions.make("show_message", &mut data.strings);
+    let array_sort = data.functions.make("array_sort", &mut data.strings);
+    data.functions.make("unused_function", &mut data.strings);
+    let call = |function| Instruction::Call { function, arg_count: 1 };
+    data.make_code("gml_Object_obj_a_Create_0", vec![
+        call(show_message),
+        call(array_sort),
+        Instruction::Exit,
+    ]);
+    data.make_code("gml_Object_obj_b_Create_0", vec![
+        call(show_message),
+        Instruction::Exit,
+    ]);
+
+    let names: HashSet<&str> = data.functions_called()?;
+    assert_eq!(names, HashSet::from(["show_message", "array_sort"]));
+    assert!(data.code_uses_builtin("array_sort")?);
+    assert!(!data.code_uses_builtin("unused_function")?);
+    Ok(())
+}
@@ -0,0 +1,203 @@
+// SPDX-License-Identifier: GPL-3.0-only
+//! Helpers shared by the integration tests.
+// Not every test uses every helper.
+#![allow(dead_code)]
+
+use image::DynamicImage;
+use image::Rgba;
+use image::RgbaImage;
+use libgm::gml::Code;
+use libgm::gml::ModernData;
+use libgm::gml::instruction::CodeVariable;
+use libgm::gml::instruction::InstanceType;
+use libgm::gml::instruction::VariableType;
+use libgm::prelude::*;
+use libgm::wad::build_bytes;
+use libgm::wad::elem::game_object::CollisionShape;
+use libgm::wad::elem::game_object::EventGroups;
+use libgm::wad::elem::game_object::GameObject;
+use libgm::wad::elem::sprite::SepMaskType;
+use libgm::wad::elem::sprite::Sprite;
+use libgm::wad::elem::texture_page::Data2022_9;
+use libgm::wad::elem::texture_page::GMImage;
+use libgm::wad::elem::texture_page::TexturePage;
+use libgm::wad::elem::texture_page_item::TexturePageItem;
+use libgm::wad::elem::variable;
+use libgm::wad::elem::variable::Variable;
+use libgm::wad::parse::ParsingOptions;
+
+/// Builds and parses the data again.
+///
+/// The default data has a made up IDE version, so it has to be parsed leniently.
+/// Version detection is skipped so that the data file is parsed with the exact same version.
+pub fn reparse(data: &GMData) -> Result<GMData> {
+    let raw_data: Vec<u8> = build_bytes(data).ctx("building data")?;
+    ParsingOptions::LENIENT
+        .assumed_version(Some(data.meta.version))
+        .parse_bytes(raw_data)
+        .ctx("reparsing data")
+}
+
+pub fn code_by_name<'a>(data: &'a GMData, name: &str) -> Result<&'a Code> {
+    data.codes.by_name(name, &data.strings)
+}
+
+/// Creates a child code entry of the given parent, starting at the given execution offset.
+pub fn make_child_code(name: &str, parent: GMRef<Code>, execution_offset: u32, data: &mut GMData) {
+    let code_ref: GMRef<Code> = data.make_code(name, Vec::new());
+    let code: &mut Code = data.codes.by_ref_mut(code_ref).unwrap();
+    code.modern_data = Some(ModernData {
+        execution_offset,
+        parent,
+        ..ModernData::default()
+    });
+}
+
+/// Creates a variable reference with the specified instance type.
+///
+/// Local variables are created directly since they are unique per code entry.
+pub fn make_code_variable(
+    name: &str,
+    instance_type: InstanceType,
+    data: &mut GMData,
+) -> Result<CodeVariable> {
+    let name: GMRef<String> = data.strings.make(name);
+    let variable: GMRef<Variable> = if instance_type == InstanceType::Local {
+        data.variables.push(Variable {
+            name,
+            modern_data: Some(variable::ModernData { instance_type, variable_id: 0 }),
+        })
+    } else {
+        let version = data.meta.version;
+        data.variables.make(name, instance_type, version)?
+    };
+    Ok(CodeVariable {
+        variable,
+        variable_type: VariableType::Normal,
+        instance_type,
+        is_int32: false,
+    })
+}
+
+/// Creates an embedded texture page filled with a single color.
+pub fn make_texture_page(color: [u8; 4]) -> TexturePage {
+    let image = RgbaImage::from_pixel(8, 8, Rgba(color));
+    TexturePage {
+        scaled: 0,
+        generated_mips: Some(0),
+        texture_block_size: Some(0),
+        data_2022_9: Some(Data2022_9 {
+            texture_width: 8,
+            texture_height: 8,
+            index_in_group: 0,
+        }),
+        image: Some(GMImage::from_dynamic_image(DynamicImage::ImageRgba8(image))),
+    }
+}
+
+/// A texture page item showing the specified region of a texture page as is.
+pub fn make_texture_page_item(
+    page: GMRef<TexturePage>,
+    x: u16,
+    y: u16,
+    width: u16,
+    height: u16,
+) -> TexturePageItem {
+    TexturePageItem {
+        source_x: x,
+        source_y: y,
+        source_width: width,
+        source_height: height,
+        target_x: 0,
+        target_y: 0,
+        target_width: width,
+        target_height: height,
+        bounding_width: width,
+        bounding_height: height,
+        texture_page: page,
+    }
+}
+
+/// Creates a visible game object without sprite, parent or events.
+pub fn make_game_object(name: &str, data: &mut GMData) -> GMRef<GameObject> {
+    let name: GMRef<String> = data.strings.make(name);
+    data.game_objects.push(GameObject {
+        name,
+        sprite: GMRef::none(),
+        visible: true,
+        managed: Some(true),
+        solid: false,
+        depth: 0,
+        persistent: false,
+        parent: GMRef::none(),
+        texture_mask: GMRef::none(),
+        uses_physics: false,
+        is_sensor: false,
+        collision_shape: CollisionShape::Box,
+        density: 0.5,
+        restitution: 0.1,
+        group: 0,
+        linear_damping: 0.1,
+        angular_damping: 0.1,
+        friction: 0.2,
+        awake: true,
+        kinematic: false,
+        physics_shape_vertices: vec![],
+        events: EventGroups {
+            create: vec![],
+            destroy: vec![],
+            alarm: vec![],
+            step: vec![],
+            collision: vec![],
+            keyboard: vec![],
+            mouse: vec![],
+            other: vec![],
+            draw: vec![],
+            key_press: vec![],
+            key_release: vec![],
+            trigger: vec![],
+            cleanup: vec![],
+            gesture: vec![],
+            pre_create: vec![],
+        },
+    })
+}
+
+/// Creates an 8x8 sprite without any frames.
+pub fn make_sprite(name: &str, data: &mut GMData) -> GMRef<Sprite> {
+    let name: GMRef<String> = data.strings.make(name);
+    data.sprites.push(Sprite {
+        name,
+        width: 8,
+        height: 8,
+        margin_left: 0,
+        margin_right: 7,
+        margin_bottom: 7,
+        margin_top: 0,
+        transparent: false,
+        smooth: false,
+        preload: false,
+        bbox_mode: 0,
+        sep_masks: SepMaskType::AxisAlignedRect,
+        origin_x: 0,
+        origin_y: 0,
+        textures: vec![],
+        collision_masks: vec![],
+        special_fields: None,
+    })
+}
+
+/// Finds the start position of the chunk's data (after its header).
+pub fn find_chunk(raw_data: &[u8], name: &[u8; 4]) -> usize {
+    let read_u32 = |pos: usize| u32::from_le_bytes(raw_data[pos..pos + 4].try_into().unwrap());
+    let mut pos = 8;
+    while &raw_data[pos..pos + 4] != name {
+        pos += 8 + read_u32(pos + 4) as usize;
+    }
+    pos + 8
+}
+
+/// The number of zero bytes needed to align `pos` to `alignment`.
+pub const fn padding(pos: usize, alignment: usize) -> usize {
+    (alignment - pos % alignment) % alignment
+}
@@ -0,0 +1,460 @@
+// SPDX-License-Identifier: GPL-3.0-only
+//! Tests for building and parsing whole data files.
+mod common;
+
+use common::find_chunk;
+use common::make_texture_page;
+use common::padding;
+use common::reparse;
+use image::Rgba;
+use libgm::gml::Instruction;
+use libgm::gml::instruction::AssetReference;
+use libgm::gml::instruction::DataType;
+use libgm::prelude::*;
+use libgm::wad::Blob;
+use libgm::wad::GMVersion;
+use libgm::wad::build::build_file_checked;
+use libgm::wad::build_bytes;
+use libgm::wad::chunk::ChunkName;
+use libgm::wad::elem::audio::Audio;
+use libgm::wad::elem::general_info::Flags;
+use libgm::wad::elem::general_info::FunctionClassifications;
+use libgm::wad::elem::options::OptionFlags;
+use libgm::wad::elem::options::WindowOptions;
+use libgm::wad::elem::particle_system::ParticleSystem;
+use libgm::wad::elem::texture_page::Format;
+use libgm::wad::parse::ParsingOptions;
+use libgm::wad::scan::MagicKind;
+use libgm::wad::scan::scan_for_magic;
+use libgm::wad::size::SizeBreakdown;
+use libgm::wad::version::version_floor_from_chunks;
+
+#[test]
+fn build_file_checked_rejects_unparseable_output() -> Result<()> {
+    let dir = std::env::temp_dir();
+    let good_path = dir.join(format!("libgm-checked-good-{}.win", std::process::id()));
+    let bad_path = dir.join(format!("libgm-checked-bad-{}.win", std::process::id()));
+
+    let data = GMData::default();
+    build_file_checked(&data, &good_path)?;
+    assert!(good_path.exists());
+    std::fs::remove_file(&good_path).unwrap();
+
+    // This builds fine, but the reparse fails since GEN8 is required.
+    let mut data = GMData::default();
+    data.meta.chunks.remove(ChunkName::GEN8);
+    assert!(build_bytes(&data).is_ok());
+    let error = build_file_checked(&data, &bad_path).unwrap_err();
+    assert!(
+        error.chain().contains("Chunk GEN8 does not exist"),
+        "{}",
+        error.chain()
+    );
+    assert!(!bad_path.exists());
+    Ok(())
+}
+
+#[test]
+fn rebuild_chunk() -> Result<()> {
+    let mut data = GMData::default();
+    data.general_info.window_width += 100;
+    let raw_data: Vec<u8> = build_bytes(&data)?;
+
+    let chunk: Vec<u8> = data.rebuild_chunk(ChunkName::GEN8)?;
+    let start: usize = find_chunk(&raw_data, b"GEN8");
+    let length = u32::from_le_bytes(raw_data[start - 4..start].try_into().unwrap());
+    assert_eq!(chunk, raw_data[start..start + length as usize]);
+
+    let mut data = GMData::default();
+    data.meta.chunks.remove(ChunkName::GEN8);
+    assert!(data.rebuild_chunk(ChunkName::GEN8).is_err());
+    Ok(())
+}
+
+#[test]
+fn recompute_gen8_counts() -> Result<()> {
+    let mut data = GMData::default();
+    assert!(data.check_gen8_counts().is_empty());
+    let room_order = data.general_info.room_order.clone();
+
+    // Nonexistent room
+    data.general_info.room_order.push(GMRef::new(3));
+    assert_eq!(data.check_gen8_counts().len(), 1);
+    data.recompute_gen8_counts();
+    assert!(data.check_gen8_counts().is_empty());
+    assert_eq!(data.general_info.room_order, room_order);
+
+    // Missing rooms
+    data.general_info.room_order.clear();
+    assert_eq!(data.check_gen8_counts().len(), 1);
+    data.recompute_gen8_counts();
+    assert_eq!(data.general_info.room_order, room_order);
+
+    let data = reparse(&data)?;
+    assert!(data.check_gen8_counts().is_empty());
+    Ok(())
+}
+
+#[test]
+fn game_name_and_id() -> Result<()> {
+    let mut data = GMData::default();
+    data.set_game_name("Rebranded");
+    data.set_game_id(0xFFFF_FFFF)?;
+    assert!(data.set_game_id(-1).is_err());
+    assert!(data.set_game_id(0x1_0000_0000).is_err());
+
+    let data = reparse(&data)?;
+    assert_eq!(data.game_name()?, "Rebranded");
+    assert_eq!(data.game_id(), 0xFFFF_FFFF);
+    Ok(())
+}
+
+#[test]
+fn window_options() -> Result<()> {
+    let mut data = GMData::default();
+    let mut window_options: WindowOptions = data.window_options();
+    assert!(window_options.show_cursor);
+    assert!(!window_options.fullscreen);
+
+    window_options.fullscreen = true;
+    window_options.show_cursor = false;
+    window_options.borderless = true;
+    data.set_window_options(window_options);
+
+    let reparsed: GMData = reparse(&data)?;
+    assert_eq!(reparsed.window_options(), window_options);
+    let flags: OptionFlags = reparsed.options.flags;
+    assert!(flags.contains(OptionFlags::FULLSCREEN | OptionFlags::NO_BORDER));
+    assert!(!flags.contains(OptionFlags::SHOW_CURSOR));
+    // Unrelated flags are kept
+    assert!(flags.contains(OptionFlags::VARIABLE_ERRORS));
+    assert!(reparsed.general_info.flags.contains(Flags::SCALE));
+    Ok(())
+}
+
+/// The data of a chunk (without its header).
+fn chunk_data<'a>(raw_data: &'a [u8], name: &[u8; 4]) -> &'a [u8] {
+    let start = find_chunk(raw_data, name);
+    let length = u32::from_le_bytes(raw_data[start - 4..start].try_into().unwrap()) as usize;
+    &raw_data[start..start + length]
+}
+
+#[test]
+fn general_info_preserved() -> Result<()> {
+    let mut data = GMData::default();
+    let info = &mut data.general_info;
+    info.debugger_enabled = true;
+    info.debugger_port = 6502;
+    info.steam_appid = 1_234_567;
+    info.flags |= Flags::FULLSCREEN | Flags::INTERPOLATE;
+    info.function_classifications = FunctionClassifications::INTERNET;
+    info.unknown_trailing_data = Blob::new(vec![1, 2, 3, 4, 5, 6, 7, 8]);
+    let raw_data: Vec<u8> = build_bytes(&data)?;
+
+    let reparsed: GMData = ParsingOptions::STRICT
+        .assumed_version(Some(data.meta.version))
+        .parse_bytes(&raw_data)?;
+    let info = &reparsed.general_info;
+    assert!(info.debugger_enabled);
+    assert_eq!(info.debugger_port, 6502);
+    assert_eq!(info.steam_appid, 1_234_567);
+    assert_eq!(info.flags, data.general_info.flags);
+    assert_eq!(
+        info.function_classifications,
+        FunctionClassifications::INTERNET
+    );
+    assert_eq!(*info.unknown_trailing_data, [1, 2, 3, 4, 5, 6, 7, 8]);
+    let rebuilt: Vec<u8> = build_bytes(&reparsed)?;
+    assert_eq!(
+        chunk_data(&rebuilt, b"GEN8"),
+        chunk_data(&raw_data, b"GEN8")
+    );
+
+    // Zero bytes beyond the chunk alignment are not padding
+    let mut trailing: Vec<u8> = vec![9];
+    trailing.extend([0; 20]);
+    data.general_info.unknown_trailing_data = Blob::new(trailing);
+    let raw_data: Vec<u8> = build_bytes(&data)?;
+    let reparsed: GMData = reparse(&data)?;
+    let trailing: &[u8] = &reparsed.general_info.unknown_trailing_data;
+    assert_eq!(trailing[0], 9);
+    assert!(trailing.len() > 1);
+    let rebuilt: Vec<u8> = build_bytes(&reparsed)?;
+    assert_eq!(
+        chunk_data(&rebuilt, b"GEN8"),
+        chunk_data(&raw_data, b"GEN8")
+    );
+
+    // Unexpected values are kept when parsing leniently
+    data.general_info.active_targets = 0x40;
+    let reparsed: GMData = reparse(&data)?;
+    assert_eq!(reparsed.general_info.active_targets, 0x40);
+    Ok(())
+}
+
+#[test]
+fn misaligned_pointer_error_has_hex_context() -> Result<()> {
+    let data = GMData::default();
+    let mut raw_data: Vec<u8> = build_bytes(&data)?;
+    let read_u32 =
+        |raw: &[u8], pos: usize| u32::from_le_bytes(raw[pos..pos + 4].try_into().unwrap());
+
+    // Move the views pointer of the first room 4 bytes forward
+    let room_pos = read_u32(&raw_data, find_chunk(&raw_data, b"ROOM") + 4) as usize;
+    let views_ptr_pos = room_pos + 44;
+    let views_ptr = read_u32(&raw_data, views_ptr_pos) + 4;
+    raw_data[views_ptr_pos..views_ptr_pos + 4].copy_from_slice(&views_ptr.to_le_bytes());
+
+    let error = ParsingOptions::STRICT
+        .assumed_version(Some(data.meta.version))
+        .parse_bytes(raw_data)
+        .unwrap_err()
+        .chain();
+    assert!(
+        error.contains("Room Views pointer is misaligned in chunk ROOM"),
+        "{error}"
+    );
+    assert!(
+        error.contains(&format!("expected position {views_ptr} ({views_ptr:#X})")),
+        "{error}"
+    );
+    assert!(error.contains("bytes around reader position: "), "{error}");
+    Ok(())
+}
+
+#[test]
+fn chunk_alignment_padding() -> Result<()> {
+    // FEAT (directly before STRG) grows by 4 bytes per flag, which moves its
+    // end through every position modulo 16.
+    for flag_count in 0..8 {
+        let mut data = GMData::default();
+        assert_eq!(data.meta.chunk_padding, 16);
+        for i in 0..flag_count {
+            data.add_feature_flag(&format!("flag_{i}"))?;
+        }
+        let raw_data: Vec<u8> = build_bytes(&data)?;
+
+        let feat_pos = find_chunk(&raw_data, b"FEAT");
+        assert_eq!(feat_pos % 4, 0);
+        let feat_end = feat_pos + 4 + flag_count * 4;
+        let strg_header = find_chunk(&raw_data, b"STRG") - 8;
+        assert_eq!(strg_header % 16, 0);
+        assert_eq!(strg_header - feat_end, padding(feat_end, 16));
+        assert!(
+            raw_data[feat_end..strg_header]
+                .iter()
+                .all(|&byte| byte == 0)
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn size_breakdown() -> Result<()> {
+    let mut data = GMData::default();
+    data.texture_pages.push(make_texture_page([255, 0, 0, 255]));
+    data.audios.push(Audio {
+        data: Blob::new(b"OggS\0\0\0\0".to_vec()),
+    });
+
+    let breakdown: SizeBreakdown = data.size_breakdown()?;
+    assert_eq!(breakdown.audio, 4 + 8);
+
+    // Textures must match the actual serialized image exactly.
+    let raw_data: Vec<u8> = build_bytes(&data)?;
+    let reparsed: GMData = reparse(&data)?;
+    let image = reparsed.texture_pages.elems[0].image.as_ref().unwrap();
+    assert_eq!(image.format(), Format::Qoi);
+    assert_eq!(
+        breakdown.textures,
+        image.serialized_size(data.meta.version)?
+    );
+    assert_eq!(reparsed.size_breakdown()?.textures, breakdown.textures);
+    assert!(breakdown.total() < raw_data.len());
+    Ok(())
+}
+
+#[test]
+fn version_floor_from_chunk_names() {
+    assert_eq!(version_floor_from_chunks(&["GEN8", "STRG"]), None);
+    assert_eq!(
+        version_floor_from_chunks(&["TGIN", "XXXX"]),
+        Some(GMVersion::GMS2_2_1)
+    );
+    assert_eq!(
+        version_floor_from_chunks(&["TGIN", "SEQN", "FEAT"]),
+        Some(GMVersion::GM2022_8)
+    );
+    assert!(GMVersion::GMS2_3.satisfied_by_chunks(&["SEQN"]));
+    assert!(!GMVersion::GMS2_2_1.satisfied_by_chunks(&["SEQN"]));
+}
+
+#[test]
+fn preserve_chunk_order() -> Result<()> {
+    let mut data = GMData::default();
+    // Move some chunks to unusual positions.
+    data.meta.chunks.move_to(ChunkName::STRG, 1);
+    data.meta.chunks.move_to(ChunkName::ROOM, 0);
+    let order: Vec<ChunkName> = data.chunk_order().to_vec();
+    assert_eq!(order[..3], [
+        ChunkName::ROOM,
+        ChunkName::GEN8,
+        ChunkName::STRG
+    ]);
+
+    let raw_data: Vec<u8> = build_bytes(&data)?;
+    let reparsed: GMData = reparse(&data)?;
+    assert_eq!(reparsed.chunk_order(), order);
+    assert_eq!(build_bytes(&reparsed)?, raw_data);
+    Ok(())
+}
+
+#[test]
+fn recover_corrupt_chunk() -> Result<()> {
+    let data = GMData::default();
+    let mut raw_data: Vec<u8> = build_bytes(&data)?;
+    let chunk_pos = find_chunk(&raw_data, b"ROOM");
+    raw_data[chunk_pos..chunk_pos + 4].copy_from_slice(&0xFFFFu32.to_le_bytes());
+
+    let options = ParsingOptions::LENIENT.assumed_version(Some(data.meta.version));
+    assert!(options.parse_bytes(&raw_data).is_err());
+
+    let recovered = options.recover_chunks(true).parse_bytes(&raw_data)?;
+    assert_eq!(recovered.failed_chunks(), [ChunkName::ROOM]);
+    assert!(recovered.rooms.is_empty());
+    assert_eq!(recovered.sounds, data.sounds);
+
+    // The raw chunk is written back verbatim.
+    assert_eq!(build_bytes(&recovered)?, raw_data);
+
+    // Verifying uses the same options, so the raw chunk does not fail the build.
+    let path = std::env::temp_dir().join(format!("libgm-recovered-{}.win", std::process::id()));
+    build_file_checked(&recovered, &path)?;
+    std::fs::remove_file(&path).unwrap();
+    Ok(())
+}
+
+#[test]
+fn scan_for_embedded_magic() -> Result<()> {
+    let mut data = GMData::default();
+    data.texture_pages.push(make_texture_page([0, 255, 0, 255]));
+    let ogg: Vec<u8> = b"OggS\0\x02 not actually vorbis".to_vec();
+    data.audios.push(Audio { data: Blob::new(ogg.clone()) });
+    let raw_data: Vec<u8> = build_bytes(&data)?;
+
+    let matches = scan_for_magic(&raw_data);
+    let ogg_pos = raw_data.windows(ogg.len()).position(|w| w == ogg).unwrap();
+    assert!(matches.contains(&(ogg_pos, MagicKind::Ogg)), "{matches:?}");
+    assert!(
+        matches
+            .iter()
+            .any(|(_, kind)| matches!(kind, MagicKind::Png | MagicKind::Qoi | MagicKind::Bz2Qoi))
+    );
+
+    let path = std::env::temp_dir().join(format!("libgm-scan-{}.win", std::process::id()));
+    std::fs::write(&path, &raw_data).unwrap();
+    let parsed = ParsingOptions::LENIENT
+        .assumed_version(Some(data.meta.version))
+        .parse_file(&path);
+    let rescanned = parsed.and_then(|parsed| parsed.scan_for_magic());
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(rescanned?, matches);
+    assert!(data.scan_for_magic().is_err());
+    Ok(())
+}
+
+#[test]
+fn redetect_version() -> Result<()> {
+    let mut data = GMData::default();
+    assert_eq!(data.redetect_version()?, None);
+    assert_eq!(data.meta.version, GMVersion::GM2024_14_1);
+
+    data.meta.version = GMVersion::GM2022_9;
+    data.meta.chunks.remove(ChunkName::UILR);
+    data.meta.chunks.remove(ChunkName::PSEM);
+    data.meta.chunks.remove(ChunkName::PSYS);
+    assert_eq!(data.redetect_version()?, None);
+
+    data.meta.chunks.push(ChunkName::PSEM);
+    data.meta.chunks.push(ChunkName::PSYS);
+    let version: GMVersion = data
+        .redetect_version()?
+        .expect("version should be upgraded");
+    assert!(version >= GMVersion::GM2023_2, "{version}");
+    assert_eq!(data.meta.version, version);
+    Ok(())
+}
+
+/// Particle system references are counted against `PSYS` (not `SEQN`)
+/// when checking whether the old asset types are out of bounds.
+#[test]
+fn redetect_version_particle_system_reference() -> Result<()> {
+    let mut data = GMData::default();
+    data.meta.version = GMVersion::GM2023_8;
+    data.meta.chunks.remove(ChunkName::UILR);
+    assert_eq!(data.clone().redetect_version()?, None);
+    data.particle_systems.push(ParticleSystem {
+        name: data.strings.make("ps_smoke"),
+        origin_x: 0,
+        origin_y: 0,
+        draw_order: 0,
+        global_space_particles: Some(false),
+        emitters: vec![],
+    });
+    data.make_code("gml_Script_scr_smoke", vec![
+        Instruction::PushReference {
+            asset_reference: AssetReference::ParticleSystem(GMRef::new(0)),
+        },
+        Instruction::PopDiscard { data_type: DataType::Variable },
+        Instruction::Exit,
+    ]);
+    let detected = data.redetect_version()?;
+    assert!(
+        detected.is_none_or(|version| version < GMVersion::GM2024_4),
+        "{detected:?}"
+    );
+    Ok(())
+}
+
+#[test]
+fn into_bytes() -> Result<()> {
+    let mut data = GMData::default();
+    data.texture_pages.push(make_texture_page([0, 0, 255, 255]));
+    // Odd sizes to exercise the padding between audio entries
+    for size in [5, 64, 3] {
+        data.audios
+            .push(Audio { data: Blob::new(vec![0xAB; size]) });
+    }
+
+    let expected: Vec<u8> = build_bytes(&data)?;
+    let raw_data: Vec<u8> = data.into_bytes()?;
+    assert_eq!(raw_data, expected);
+
+    let data: GMData = ParsingOptions::LENIENT.parse_bytes(&raw_data)?;
+    let sizes: Vec<usize> = data.audios.elems.iter().map(|a| a.data.len()).collect();
+    assert_eq!(sizes, [5, 64, 3]);
+    Ok(())
+}
+
+#[test]
+fn clone_metadata_only() -> Result<()> {
+    let mut data = GMData::default();
+    data.texture_pages.push(make_texture_page([0, 0, 255, 255]));
+    data.audios.push(Audio { data: Blob::new(vec![0xAB; 64]) });
+    let expected: Vec<u8> = build_bytes(&data)?;
+
+    let mut fork: GMData = data.clone_metadata_only();
+    assert!(data.audios.elems[0].data.is_shared());
+    assert_eq!(build_bytes(&fork)?, expected);
+
+    fork.audios.elems[0].data.push(0xCD);
+    fork.strings.make("fork_only");
+    let image = fork.texture_pages.elems[0].image.as_mut().unwrap();
+    image.dynamic_image_mut().unwrap().as_mut_rgba8().unwrap()[(0, 0)] = Rgba([255; 4]);
+
+    assert!(!data.audios.elems[0].data.is_shared());
+    assert_eq!(data.audios.elems[0].data.len(), 64);
+    assert_eq!(build_bytes(&data)?, expected);
+    assert_ne!(build_bytes(&fork)?, expected);
+    Ok(())
+}
@@ -0,0 +1,50 @@
+// SPDX-License-Identifier: GPL-3.0-only
+//! Tests for comparing generated data files.
+use libgm::gml::Instruction;
+use libgm::gml::instruction::DataType;
+use libgm::prelude::*;
+use libgm::wad::chunk::ChunkName;
+use libgm::wad::diff::CodeDiff;
+use libgm::wad::diff::DataDiff;
+
+#[test]
+fn diff_unchanged() -> Result<()> {
+    let data = GMData::default();
+    let diff: DataDiff = data.diff(&data.clone())?;
+    assert!(diff.is_empty(), "{diff}");
+    Ok(())
+}
+
+#[test]
+fn diff_code() -> Result<()> {
+    let mut old = GMData::default();
+    old.make_code("gml_Script_scr_modified", vec![Instruction::Exit]);
+    old.make_code("gml_Script_scr_removed", vec![Instruction::Exit]);
+
+    let mut new = old.clone();
+    new.codes.elems.pop();
+    new.replace_code("gml_Script_scr_modified", vec![
+        Instruction::PushImmediate { integer: 7 },
+        Instruction::PopDiscard { data_type: DataType::Int32 },
+        Instruction::Exit,
+    ])?;
+    new.make_code("gml_Script_scr_added", vec![Instruction::Exit]);
+
+    let diff: DataDiff = old.diff(&new)?;
+    assert_eq!(diff.modified_chunks, [ChunkName::STRG]);
+    assert_eq!(diff.assets.len(), 1);
+
+    let assets = &diff.assets[0];
+    assert_eq!(assets.chunk, ChunkName::CODE);
+    assert_eq!(assets.added, ["gml_Script_scr_added"]);
+    assert_eq!(assets.removed, ["gml_Script_scr_removed"]);
+    assert_eq!(assets.modified, ["gml_Script_scr_modified"]);
+
+    let expected = CodeDiff {
+        name: "gml_Script_scr_modified".to_owned(),
+        removed_instructions: 0,
+        added_instructions: 2,
+    };
+    assert_eq!(diff.codes, [expected]);
+    Ok(())
+}
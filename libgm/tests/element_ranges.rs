@@ -0,0 +1,40 @@
+// SPDX-License-Identifier: GPL-3.0-only
+//! Tests for the element byte ranges recorded with the `element-ranges` feature.
+#![cfg(feature = "element-ranges")]
+
+use libgm::prelude::*;
+use libgm::wad::build_bytes;
+use libgm::wad::chunk::ChunkName;
+use libgm::wad::elem::room::Room;
+use libgm::wad::parse::ParsingOptions;
+
+#[test]
+fn element_bytes_match_data_file() -> Result<()> {
+    let mut data = GMData::default();
+    let mut room: Room = data.rooms.by_ref(GMRef::new(0))?.clone();
+    room.name = data.strings.make("room_second");
+    data.rooms.push(room);
+    let raw_data: Vec<u8> = build_bytes(&data).ctx("building data")?;
+
+    let data = ParsingOptions::LENIENT
+        .assumed_version(Some(data.meta.version))
+        .parse_bytes(&raw_data)?;
+
+    let first: &[u8] = data.element_bytes(ChunkName::ROOM, 0).unwrap();
+    let second: &[u8] = data.element_bytes(ChunkName::ROOM, 1).unwrap();
+    let range = data.meta.element_ranges.range(ChunkName::ROOM, 1).unwrap();
+    assert_eq!(second, &raw_data[range.start as usize..range.end as usize]);
+    assert_eq!(first.len(), second.len());
+    assert_ne!(first, second, "room names should differ");
+
+    // Clones share the copy of the data file.
+    let clone: GMData = data.clone();
+    assert!(std::ptr::eq(
+        clone.element_bytes(ChunkName::ROOM, 1).unwrap(),
+        second
+    ));
+
+    assert!(data.element_bytes(ChunkName::ROOM, 2).is_none());
+    assert!(data.element_bytes(ChunkName::SPRT, 0).is_none());
+    Ok(())
+}
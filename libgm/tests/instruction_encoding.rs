@@ -0,0 +1,103 @@
+// SPDX-License-Identifier: GPL-3.0-only
+//! Checks the exact serialized form of instructions with special encodings.
+use libgm::gml::Instruction;
+use libgm::gml::instruction::AssetReference;
+use libgm::gml::instruction::DataType;
+use libgm::prelude::*;
+use libgm::wad::GMVersion;
+use libgm::wad::build_bytes;
+use libgm::wad::parse::ParsingOptions;
+
+/// The header of a `pushref` instruction:
+/// Extended kind -11, data type `Int32` and the extended opcode.
+const PUSHREF_HEADER: [u8; 4] = [0xF5, 0xFF, 0x02, 0xFF];
+
+fn reparse(data: &GMData) -> Result<GMData> {
+    let raw_data: Vec<u8> = build_bytes(data).ctx("building data")?;
+    ParsingOptions::LENIENT
+        .assumed_version(Some(data.meta.version))
+        .parse_bytes(raw_data)
+        .ctx("reparsing data")
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> usize {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+        .unwrap_or_else(|| panic!("Could not find bytes {needle:02X?}"))
+}
+
+fn read_u32(raw_data: &[u8], pos: usize) -> u32 {
+    u32::from_le_bytes(raw_data[pos..pos + 4].try_into().unwrap())
+}
+
+#[test]
+fn popswap_encoding() -> Result<()> {
+    let mut data = GMData::default();
+    let instructions = vec![
+        Instruction::PopSwap { is_array: false },
+        Instruction::PopSwap { is_array: true },
+        Instruction::Exit,
+    ];
+    data.make_code("gml_Script_scr_popswap", instructions.clone());
+
+    // Swap mode (5 or 6), then types Int16 and Variable, then the pop opcode.
+    // The Int16 type is what distinguishes this from a regular pop.
+    let raw_data: Vec<u8> = build_bytes(&data)?;
+    find(&raw_data, &[0x05, 0x00, 0x5F, 0x45, 0x06, 0x00, 0x5F, 0x45]);
+
+    let data = reparse(&data)?;
+    let code = data
+        .codes
+        .by_name("gml_Script_scr_popswap", &data.strings)?;
+    assert_eq!(code.instructions, instructions);
+    Ok(())
+}
+
+#[test]
+fn asset_reference_encoding() {
+    // The asset type is stored in the upper 8 bits, the asset ID in the lower 24.
+    let sprite = AssetReference::Sprite(GMRef::new(0x12_3456));
+    assert_eq!(sprite.build(), 0x0112_3456);
+    assert_eq!(sprite.build_old(), 0x0112_3456);
+
+    let background = AssetReference::Background(GMRef::new(7));
+    assert_eq!(background.build(), 0x0D00_0007);
+    assert_eq!(background.build_old(), 0x0400_0007);
+
+    let object = AssetReference::Object(GMRef::new(3));
+    assert_eq!(object.build(), 0x0000_0003);
+}
+
+#[test]
+fn function_reference_occurrences() -> Result<()> {
+    let mut data = GMData::default();
+    data.meta.version = GMVersion::GM2024_14_1;
+    let function = data.functions.make("scr_target", &mut data.strings);
+    let push = Instruction::PushReference {
+        asset_reference: AssetReference::Function(function),
+    };
+    let instructions = vec![
+        push.clone(),
+        push,
+        Instruction::PopDiscard { data_type: DataType::Variable },
+        Instruction::PopDiscard { data_type: DataType::Variable },
+        Instruction::Exit,
+    ];
+    data.make_code("gml_Script_scr_function_refs", instructions.clone());
+
+    // The operand of the first occurrence points to the second occurrence,
+    // relative to the instruction (not the operand) position.
+    // The second instruction has to stay intact.
+    let raw_data: Vec<u8> = build_bytes(&data)?;
+    let pos: usize = find(&raw_data, &PUSHREF_HEADER);
+    assert_eq!(read_u32(&raw_data, pos + 4), 8);
+    assert_eq!(raw_data[pos + 8..pos + 12], PUSHREF_HEADER);
+
+    let data = reparse(&data)?;
+    let code = data
+        .codes
+        .by_name("gml_Script_scr_function_refs", &data.strings)?;
+    assert_eq!(code.instructions, instructions);
+    Ok(())
+}
@@ -0,0 +1,151 @@
+// SPDX-License-Identifier: GPL-3.0-only
+//! Verifies that [`Instruction::size`] matches the actual serialized size of instructions.
+//!
+//! Branch offsets are computed using these sizes, so any mismatch
+//! would silently corrupt every subsequent branch offset.
+use libgm::gml::Instruction;
+use libgm::gml::instruction::AssetReference;
+use libgm::gml::instruction::CodeVariable;
+use libgm::gml::instruction::ComparisonType;
+use libgm::gml::instruction::DataType;
+use libgm::gml::instruction::InstanceType;
+use libgm::gml::instruction::PushValue;
+use libgm::gml::instruction::VariableType;
+use libgm::prelude::*;
+use libgm::wad::build_bytes;
+
+/// Creates one instruction of every variant, with representative payloads.
+fn all_instructions(data: &mut GMData) -> Result<Vec<Instruction>> {
+    let name: GMRef<String> = data.strings.make("size_test_variable");
+    let version = data.meta.version;
+    let variable = CodeVariable {
+        variable: data.variables.make(name, InstanceType::Global, version)?,
+        variable_type: VariableType::Normal,
+        instance_type: InstanceType::Global,
+        is_int32: false,
+    };
+    let function = data.functions.make("size_test_function", &mut data.strings);
+    let string: GMRef<String> = data.strings.make("size test string");
+
+    let lhs = DataType::Int32;
+    let rhs = DataType::Double;
+    Ok(vec![
+        Instruction::Convert { from: lhs, to: rhs },
+        Instruction::Multiply { lhs, rhs },
+        Instruction::Divide { lhs, rhs },
+        Instruction::Remainder { lhs, rhs },
+        Instruction::Modulus { lhs, rhs },
+        Instruction::Add { lhs, rhs },
+        Instruction::Subtract { lhs, rhs },
+        Instruction::And { lhs, rhs },
+        Instruction::Or { lhs, rhs },
+        Instruction::Xor { lhs, rhs },
+        Instruction::Negate { data_type: lhs },
+        Instruction::Not { data_type: DataType::Bool },
+        Instruction::ShiftLeft { lhs, rhs },
+        Instruction::ShiftRight { lhs, rhs },
+        Instruction::Compare {
+            lhs,
+            rhs,
+            comparison_type: ComparisonType::GreaterOrEqual,
+        },
+        Instruction::Pop {
+            variable,
+            type1: DataType::Variable,
+            type2: DataType::Variable,
+        },
+        Instruction::PopSwap { is_array: false },
+        Instruction::PopSwap { is_array: true },
+        Instruction::Duplicate { data_type: lhs, size: 0 },
+        Instruction::DuplicateSwap { data_type: lhs, size1: 1, size2: 2 },
+        Instruction::Return,
+        Instruction::Exit,
+        Instruction::PopDiscard { data_type: DataType::Variable },
+        Instruction::Branch { jump_offset: 1 },
+        Instruction::BranchIf { jump_offset: -1 },
+        Instruction::BranchUnless { jump_offset: 1 },
+        Instruction::PushWithContext { jump_offset: 1 },
+        Instruction::PopWithContext { jump_offset: -1 },
+        Instruction::PopWithContextExit,
+        Instruction::Push { value: PushValue::Int16(-5) },
+        Instruction::Push { value: PushValue::Int32(100_000) },
+        Instruction::Push { value: PushValue::Int64(1 << 40) },
+        Instruction::Push { value: PushValue::Double(3.5) },
+        Instruction::Push { value: PushValue::Bool(true) },
+        Instruction::Push { value: PushValue::String(string) },
+        Instruction::Push { value: PushValue::Variable(variable) },
+        Instruction::Push { value: PushValue::Function(function) },
+        Instruction::PushLocal { variable },
+        Instruction::PushGlobal { variable },
+        Instruction::PushBuiltin { variable },
+        Instruction::PushImmediate { integer: -1 },
+        Instruction::Call { function, arg_count: 2 },
+        Instruction::CallVariable { arg_count: 3 },
+        Instruction::CheckArrayIndex,
+        Instruction::PushArrayFinal,
+        Instruction::PopArrayFinal,
+        Instruction::PushArrayContainer,
+        Instruction::SetArrayOwner,
+        Instruction::HasStaticInitialized,
+        Instruction::SetStaticInitialized,
+        Instruction::SaveArrayReference,
+        Instruction::RestoreArrayReference,
+        Instruction::IsNullishValue,
+        Instruction::PushReference {
+            asset_reference: AssetReference::Sprite(GMRef::new(0)),
+        },
+        Instruction::PushReference {
+            asset_reference: AssetReference::Function(function),
+        },
+    ])
+}
+
+fn read_u32(raw_data: &[u8], pos: usize) -> u32 {
+    let bytes: [u8; 4] = raw_data[pos..pos + 4].try_into().unwrap();
+    u32::from_le_bytes(bytes)
+}
+
+/// Reads the serialized lengths of all code entries from the raw data file.
+fn code_lengths(raw_data: &[u8]) -> Vec<u32> {
+    // Skip the FORM header
+    let mut pos: usize = 8;
+    while &raw_data[pos..pos + 4] != b"CODE" {
+        pos += 8 + read_u32(raw_data, pos + 4) as usize;
+    }
+    pos += 8;
+
+    let count = read_u32(raw_data, pos) as usize;
+    (0..count)
+        .map(|i| {
+            let entry_pos = read_u32(raw_data, pos + 4 + i * 4) as usize;
+            // The code length comes right after the name
+            read_u32(raw_data, entry_pos + 4)
+        })
+        .collect()
+}
+
+#[test]
+fn instruction_sizes_match_serialized_lengths() -> Result<()> {
+    let mut data = GMData::default();
+    let instructions: Vec<Instruction> = all_instructions(&mut data)?;
+
+    // Put every instruction into its own code entry to compare their sizes individually.
+    for (i, instruction) in instructions.iter().enumerate() {
+        data.make_code(&format!("gml_Script_size_test_{i}"), vec![
+            instruction.clone(),
+        ]);
+    }
+
+    let raw_data: Vec<u8> = build_bytes(&data)?;
+    let lengths: Vec<u32> = code_lengths(&raw_data);
+    assert_eq!(lengths.len(), instructions.len());
+
+    for (instruction, length) in instructions.iter().zip(lengths) {
+        assert_eq!(
+            instruction.size(),
+            length,
+            "size mismatch for instruction {instruction:?}"
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,298 @@
+// SPDX-License-Identifier: GPL-3.0-only
+//! Tests for building, parsing and inspecting instructions.
+mod common;
+
+use common::code_by_name;
+use common::make_code_variable;
+use common::make_game_object;
+use common::reparse;
+use libgm::gml::Code;
+use libgm::gml::Instruction;
+use libgm::gml::assembly::assemble_instruction;
+use libgm::gml::assembly::disassemble_instruction;
+use libgm::gml::instruction::AssetReference;
+use libgm::gml::instruction::CodeVariable;
+use libgm::gml::instruction::DataType;
+use libgm::gml::instruction::InstanceType;
+use libgm::gml::instruction::Operand;
+use libgm::gml::instruction::PushValue;
+use libgm::gml::instruction::StackEffect;
+use libgm::gml::instruction::VariableType;
+use libgm::prelude::*;
+use libgm::wad::GMVersion;
+use libgm::wad::build_bytes;
+use libgm::wad::elem::game_object::GameObject;
+use libgm::wad::parse::ParsingOptions;
+
+#[test]
+fn unknown_instructions_round_trip() -> Result<()> {
+    let mut data = GMData::default();
+    let unknown = Instruction::Unknown { opcode: 0xAB, raw: 0xAB00_1234 };
+    data.make_code("gml_Script_scr_future", vec![
+        unknown.clone(),
+        Instruction::Exit,
+    ]);
+    let raw_data: Vec<u8> = build_bytes(&data)?;
+
+    let strict = ParsingOptions::STRICT.assumed_version(Some(data.meta.version));
+    let error = strict.parse_bytes(&raw_data).unwrap_err().chain();
+    assert!(
+        error.contains("Invalid Instruction Opcode 171 (0xAB)"),
+        "{error}"
+    );
+
+    let data = strict
+        .allow_unknown_instructions(true)
+        .parse_bytes(&raw_data)?;
+    let code: &Code = code_by_name(&data, "gml_Script_scr_future")?;
+    assert_eq!(code.instructions[0], unknown);
+    assert_eq!(build_bytes(&data)?, raw_data);
+
+    let assembly: String = disassemble_instruction(&unknown, &data)?;
+    assert_eq!(assembly, format!("unknown 171 {}", 0xAB00_1234_u32));
+    assert_eq!(assemble_instruction(&assembly, &data)?, unknown);
+    Ok(())
+}
+
+#[test]
+fn instruction_error_names_code_entry() -> Result<()> {
+    let mut data = GMData::default();
+    let mut variable = make_code_variable("broken_global", InstanceType::Global, &mut data)?;
+    variable.variable = GMRef::new(9999);
+    let instructions = vec![
+        Instruction::PushImmediate { integer: 1 },
+        Instruction::Pop {
+            variable,
+            type1: DataType::Variable,
+            type2: DataType::Int32,
+        },
+        Instruction::Exit,
+    ];
+    data.make_code("gml_Script_scr_broken", instructions);
+
+    let error = build_bytes(&data).unwrap_err().chain();
+    assert!(
+        error.contains("instruction #1 of code entry #0 \"gml_Script_scr_broken\""),
+        "{error}"
+    );
+    Ok(())
+}
+
+#[test]
+fn resolved_instruction() -> Result<()> {
+    let mut data = GMData::default();
+    let object: GMRef<GameObject> = make_game_object("obj_player", &mut data);
+    let function = data.functions.make("scr_move", &mut data.strings);
+    let variable = make_code_variable("hp", InstanceType::Self_, &mut data)?;
+    let string: GMRef<String> = data.strings.make("hello");
+    let instructions = vec![
+        Instruction::Push { value: PushValue::String(string) },
+        Instruction::Call { function, arg_count: 1 },
+        Instruction::PushReference {
+            asset_reference: AssetReference::Object(object),
+        },
+        Instruction::Pop {
+            variable,
+            type1: DataType::Variable,
+            type2: DataType::Variable,
+        },
+        Instruction::PushReference {
+            asset_reference: AssetReference::RoomInstance(100_001),
+        },
+        Instruction::Exit,
+    ];
+    let code_ref: GMRef<Code> = data.make_code("gml_Script_scr_test", instructions);
+
+    let operand = |index| -> Result<Option<Operand>> {
+        Ok(data.resolved_instruction(code_ref, index)?.unwrap().operand)
+    };
+    assert_eq!(operand(0)?, Some(Operand::String("hello")));
+    assert_eq!(operand(1)?, Some(Operand::Function("scr_move")));
+    let asset = Operand::Asset { kind: "object", name: "obj_player" };
+    assert_eq!(operand(2)?, Some(asset));
+    let hp = Operand::Variable {
+        name: "hp",
+        instance_type: InstanceType::Self_,
+    };
+    assert_eq!(operand(3)?, Some(hp));
+    assert_eq!(operand(4)?, Some(Operand::RoomInstance(100_001)));
+    assert_eq!(operand(5)?, None);
+
+    let pop = data.resolved_instruction(code_ref, 3)?.unwrap();
+    assert_eq!(pop.offset, 24);
+    assert_eq!(pop.mnemonic, "pop");
+    assert_eq!(pop.assembly, "pop.v.v self.hp");
+    assert!(data.resolved_instruction(code_ref, 6)?.is_none());
+    Ok(())
+}
+
+#[test]
+fn push_variable_variants() -> Result<()> {
+    let mut data = GMData::default();
+    let local = make_code_variable("push_test_local", InstanceType::Local, &mut data)?;
+    let global = make_code_variable("push_test_global", InstanceType::Global, &mut data)?;
+    let builtin = make_code_variable("x", InstanceType::Builtin, &mut data)?;
+    let self_ = make_code_variable("push_test_self", InstanceType::Self_, &mut data)?;
+
+    let pushes = [
+        Instruction::Push { value: PushValue::Variable(self_) },
+        Instruction::Push { value: PushValue::Variable(global) },
+        Instruction::PushLocal { variable: local },
+        Instruction::PushGlobal { variable: global },
+        Instruction::PushBuiltin { variable: builtin },
+    ];
+    let mut names: Vec<String> = Vec::new();
+    for (i, push) in pushes.into_iter().enumerate() {
+        let name = format!("gml_Script_scr_push_{i}");
+        let instructions = vec![
+            push,
+            Instruction::PopDiscard { data_type: DataType::Variable },
+            Instruction::Exit,
+        ];
+        data.make_code(&name, instructions);
+        names.push(name);
+    }
+
+    let reparsed = reparse(&data)?;
+    for name in &names {
+        let expected = code_by_name(&data, name)?;
+        let actual = code_by_name(&reparsed, name)?;
+        assert_eq!(actual.instructions, expected.instructions, "{name}");
+    }
+    Ok(())
+}
+
+#[test]
+fn canonical_instructions() -> Result<()> {
+    let mut data = GMData::default();
+    let variable = make_code_variable("canonical_global", InstanceType::Global, &mut data)?;
+
+    // WAD 15+ encoding and WAD 14 encoding
+    let pairs = [
+        (Instruction::PushGlobal { variable }, Instruction::Push {
+            value: PushValue::Variable(variable),
+        }),
+        (
+            Instruction::PushImmediate { integer: -5 },
+            Instruction::Push { value: PushValue::Int16(-5) },
+        ),
+        (Instruction::Exit, Instruction::Exit),
+    ];
+    for (modern, old) in pairs {
+        assert_eq!(modern.canonical(), old.canonical());
+        assert_eq!(old.canonical().into_instruction(), old);
+    }
+
+    assert_ne!(
+        Instruction::PushImmediate { integer: 1 }.canonical(),
+        Instruction::Push { value: PushValue::Int32(1) }.canonical(),
+    );
+    Ok(())
+}
+
+#[test]
+fn push_constant_instructions() -> Result<()> {
+    let mut data = GMData::default();
+    let instructions = vec![
+        data.push_string_instruction("pushed"),
+        data.push_int_instruction(7),
+        data.push_int_instruction(100_000),
+        data.push_int_instruction(1 << 40),
+        Instruction::from(2.0),
+        Instruction::from(0.5),
+        Instruction::from(-0.0),
+        Instruction::Exit,
+    ];
+    assert_eq!(instructions[1], Instruction::PushImmediate { integer: 7 });
+    assert_eq!(instructions[4], Instruction::Push {
+        value: PushValue::Double(2.0)
+    });
+    assert_eq!(instructions[5], Instruction::Push {
+        value: PushValue::Double(0.5)
+    });
+    data.make_code("gml_Script_scr_constants", instructions.clone());
+
+    let data = reparse(&data)?;
+    let code = code_by_name(&data, "gml_Script_scr_constants")?;
+    assert_eq!(code.instructions, instructions);
+    let Some(PushValue::String(string)) = code.instructions[0].push_value() else {
+        panic!("expected string push");
+    };
+    assert_eq!(string.display(&data.strings), "pushed");
+    let Some(PushValue::Double(zero)) = code.instructions[6].push_value() else {
+        panic!("expected double push");
+    };
+    assert!(zero.is_sign_negative());
+
+    // Immediate pushes only exist since WAD 15
+    let mut data = data;
+    data.meta.version = GMVersion::Wad14;
+    assert_eq!(data.push_int_instruction(7), Instruction::Push {
+        value: PushValue::Int16(7)
+    });
+    Ok(())
+}
+
+#[test]
+fn instruction_stack_effects() -> Result<()> {
+    let mut data = GMData::default();
+    let version = data.meta.version;
+    let name: GMRef<String> = data.strings.make("callback");
+    let variable = CodeVariable {
+        variable: data.variables.make(name, InstanceType::Self_, version)?,
+        variable_type: VariableType::Normal,
+        instance_type: InstanceType::Self_,
+        is_int32: false,
+    };
+    let function = data.functions.make("show_debug_message", &mut data.strings);
+    let int_to_var = Instruction::Convert {
+        from: DataType::Int32,
+        to: DataType::Variable,
+    };
+
+    let call_variable = Instruction::CallVariable { arg_count: 1 };
+    assert_eq!(call_variable.stack_effect(), StackEffect {
+        pops: 3,
+        pushes: 1
+    });
+    let array_variable = CodeVariable {
+        variable_type: VariableType::Array,
+        ..variable
+    };
+    let pop = Instruction::Pop {
+        variable: array_variable,
+        type1: DataType::Variable,
+        type2: DataType::Variable,
+    };
+    assert_eq!(pop.stack_effect(), StackEffect { pops: 3, pushes: 0 });
+    let dup = Instruction::Duplicate { data_type: DataType::Int32, size: 1 };
+    assert_eq!(dup.stack_effect().delta(), 2);
+
+    // show_debug_message(1, 2); self.callback(3);
+    let instructions = [
+        Instruction::PushImmediate { integer: 2 },
+        int_to_var.clone(),
+        Instruction::PushImmediate { integer: 1 },
+        int_to_var.clone(),
+        Instruction::Call { function, arg_count: 2 },
+        Instruction::PopDiscard { data_type: DataType::Variable },
+        Instruction::PushImmediate { integer: 3 },
+        int_to_var,
+        Instruction::PushImmediate { integer: -1 },
+        Instruction::Push { value: PushValue::Variable(variable) },
+        call_variable,
+        Instruction::PopDiscard { data_type: DataType::Variable },
+        Instruction::Exit,
+    ];
+    let mut depth: i64 = 0;
+    for instruction in &instructions {
+        let effect: StackEffect = instruction.stack_effect();
+        assert!(
+            depth >= i64::from(effect.pops),
+            "{instruction:?} underflows the stack"
+        );
+        depth += effect.delta();
+    }
+    assert_eq!(depth, 0);
+    Ok(())
+}
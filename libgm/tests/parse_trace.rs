@@ -0,0 +1,31 @@
+// SPDX-License-Identifier: GPL-3.0-only
+//! Tests for the parse trace written with the `parse-trace` feature.
+#![cfg(feature = "parse-trace")]
+
+use libgm::prelude::*;
+use libgm::wad::build_bytes;
+use libgm::wad::parse::ParsingOptions;
+
+#[test]
+fn parse_trace_lists_elements() -> Result<()> {
+    let data = GMData::default();
+    let raw_data: Vec<u8> = build_bytes(&data).ctx("building data")?;
+    let path = std::env::temp_dir().join(format!("libgm-parse-trace-{}.txt", std::process::id()));
+
+    ParsingOptions::LENIENT
+        .assumed_version(Some(data.meta.version))
+        .trace_file(Some(path.clone()))
+        .parse_bytes(raw_data)?;
+    let trace = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_file(&path).unwrap();
+
+    let lines: Vec<&str> = trace.lines().collect();
+    assert!(lines.iter().any(|line| line.ends_with("  ROOM")));
+    assert!(
+        lines
+            .iter()
+            .any(|line| line.contains("  ROOM  ") && line.ends_with("Room 0/1")),
+        "{trace}"
+    );
+    Ok(())
+}
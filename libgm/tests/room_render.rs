@@ -0,0 +1,126 @@
+// SPDX-License-Identifier: GPL-3.0-only
+//! Tests for room previews rendered with the `room-render` feature.
+#![cfg(feature = "room-render")]
+mod common;
+
+use common::make_game_object;
+use common::make_sprite;
+use common::make_texture_page;
+use common::make_texture_page_item;
+use common::reparse;
+use image::RgbaImage;
+use libgm::prelude::*;
+use libgm::wad::elem::game_object::GameObject;
+use libgm::wad::elem::room::Room;
+use libgm::wad::elem::room::RoomLayer;
+use libgm::wad::elem::room::layer;
+use libgm::wad::elem::sprite::Sprite;
+use libgm::wad::elem::tileset::GMS2Data;
+use libgm::wad::elem::tileset::Tileset;
+
+#[test]
+fn render_room() -> Result<()> {
+    let red = [255, 0, 0, 255];
+    let mut data = GMData::default();
+    let page = data.texture_pages.push(make_texture_page(red));
+    let texture = data
+        .texture_page_items
+        .push(make_texture_page_item(page, 0, 0, 8, 8));
+    let sprite: GMRef<Sprite> = make_sprite("spr_box", &mut data);
+    data.sprites.by_ref_mut(sprite)?.textures.push(texture);
+    let object: GMRef<GameObject> = make_game_object("obj_box", &mut data);
+    data.game_objects.by_ref_mut(object)?.sprite = sprite;
+
+    let name: GMRef<String> = data.strings.make("room_preview");
+    let room_ref: GMRef<Room> = data.rooms.push(Room {
+        name,
+        width: 32,
+        height: 16,
+        draw_background_color: true,
+        background_color: 0xFFFF_0000,
+        ..Room::default()
+    });
+    data.add_object_to_room("room_preview", object, 4, 4)?;
+    data.add_object_to_room("room_preview", object, 16, 0)?;
+    data.rooms.by_ref_mut(room_ref)?.game_objects[1].scale_x = 2.0;
+
+    let data = reparse(&data)?;
+    let image: RgbaImage = data.render_room(room_ref)?;
+    assert_eq!(image.dimensions(), (32, 16));
+    let blue = [0, 0, 255, 255];
+    for (x, y, expected) in [
+        (0, 0, blue),
+        (4, 4, red),
+        (11, 11, red),
+        (12, 12, blue),
+        (15, 0, blue),
+        (16, 0, red),
+        (31, 7, red),
+        (31, 8, blue),
+    ] {
+        assert_eq!(image.get_pixel(x, y).0, expected, "pixel at ({x}, {y})");
+    }
+    assert!(data.render_room(GMRef::new(100)).is_err());
+
+    // Huge scales are clipped to the room.
+    let mut data = data;
+    let instance = &mut data.rooms.by_ref_mut(room_ref)?.game_objects[0];
+    instance.scale_x = 1e30;
+    instance.scale_y = 1e30;
+    let image: RgbaImage = data.render_room(room_ref)?;
+    assert_eq!(image.get_pixel(3, 3).0, blue);
+    assert_eq!(image.get_pixel(4, 4).0, red);
+    assert_eq!(image.get_pixel(31, 15).0, red);
+    data.rooms.by_ref_mut(room_ref)?.game_objects[0].scale_x = f32::INFINITY;
+    data.render_room(room_ref)?;
+
+    // Tile positions outside of the tileset image are rejected instead of overflowing.
+    let tileset: GMRef<Tileset> = data.tilesets.push(Tileset {
+        name: data.strings.make("ts_huge"),
+        transparent: false,
+        smooth: false,
+        preload: false,
+        texture,
+        gms2_data: Some(GMS2Data {
+            tile_width: 8,
+            tile_height: u32::MAX,
+            tile_separation_x: 0,
+            tile_separation_y: 0,
+            output_border_x: 0,
+            output_border_y: 0,
+            tile_columns: 1,
+            items_per_tile_count: 1,
+            exported_sprite_index: 0,
+            frame_length: 0,
+            tile_ids: vec![],
+        }),
+    });
+    let layer = RoomLayer {
+        name: data.strings.make("Tiles"),
+        id: 0,
+        ty: layer::Type::Tiles,
+        depth: 0,
+        x_offset: 0.0,
+        y_offset: 0.0,
+        horizontal_speed: 0.0,
+        vertical_speed: 0.0,
+        is_visible: true,
+        effect_data_2022_1: None,
+        data: layer::Data::Tiles(layer::Tiles {
+            tileset,
+            tile_data: vec![5],
+            width: 1,
+            height: 1,
+        }),
+    };
+    let room: &mut Room = data.rooms.by_ref_mut(room_ref)?;
+    room.layers.push(layer);
+    let error = data.render_room(room_ref).unwrap_err().chain();
+    assert!(error.contains("out of bounds"), "{error}");
+
+    let room: &mut Room = data.rooms.by_ref_mut(room_ref)?;
+    room.width = 100_000;
+    room.height = 100_000;
+    assert!(data.render_room(room_ref).is_err());
+    Ok(())
+}
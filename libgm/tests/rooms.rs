@@ -0,0 +1,387 @@
+// SPDX-License-Identifier: GPL-3.0-only
+//! Tests for rooms, their layers and their instances.
+mod common;
+
+use common::code_by_name;
+use common::make_game_object;
+use common::reparse;
+use libgm::gml::Instruction;
+use libgm::gml::instruction::AssetReference;
+use libgm::gml::instruction::DataType;
+use libgm::prelude::*;
+use libgm::wad::GMVersion;
+use libgm::wad::build_bytes;
+use libgm::wad::chunk::ChunkName;
+use libgm::wad::elem::game_object::GameObject;
+use libgm::wad::elem::general_info::RoomOrderFixes;
+use libgm::wad::elem::room::InstanceID;
+use libgm::wad::elem::room::Room;
+use libgm::wad::elem::room::RoomFlags;
+use libgm::wad::elem::room::RoomLayer;
+use libgm::wad::elem::room::RoomView;
+use libgm::wad::elem::room::ViewFollow;
+use libgm::wad::elem::room::layer;
+
+#[test]
+fn fix_room_order() -> Result<()> {
+    let mut data = GMData::default();
+    let name: GMRef<String> = data.strings.make("room1");
+    let room1: GMRef<Room> = data.rooms.push(Room { name, ..Room::default() });
+    let room0: GMRef<Room> = GMRef::new(0);
+
+    data.general_info.room_order = vec![room0, GMRef::new(5), room0];
+    let fixes: RoomOrderFixes = data.fix_room_order();
+    assert_eq!(fixes.removed, [GMRef::new(5), room0]);
+    assert_eq!(fixes.added, [room1]);
+    assert_eq!(data.general_info.room_order, [room0, room1]);
+    assert!(data.fix_room_order().is_empty());
+
+    // Fixing while building leaves the data itself untouched
+    data.general_info.room_order = vec![room1];
+    data.meta.fix_room_order_on_build = true;
+    let reparsed = reparse(&data)?;
+    assert_eq!(data.general_info.room_order, [room1]);
+    assert_eq!(reparsed.general_info.room_order, [room1, room0]);
+    Ok(())
+}
+
+#[test]
+fn reorder_rooms() -> Result<()> {
+    let mut data = GMData::default();
+    let mut room: Room = data.rooms.by_ref(GMRef::new(0))?.clone();
+    room.name = data.strings.make("room_second");
+    let second: GMRef<Room> = data.rooms.push(room);
+    data.general_info.room_order.push(second);
+    data.make_code("gml_Script_scr_goto_second", vec![
+        Instruction::PushReference {
+            asset_reference: AssetReference::Room(second),
+        },
+        Instruction::PopDiscard { data_type: DataType::Variable },
+        Instruction::Exit,
+    ]);
+
+    assert!(data.reorder_list(ChunkName::ROOM, &[0]).is_err());
+    assert!(data.reorder_list(ChunkName::ROOM, &[1, 1]).is_err());
+    assert!(data.reorder_list(ChunkName::ROOM, &[0, 2]).is_err());
+    assert!(data.reorder_list(ChunkName::STRG, &[]).is_err());
+    assert_eq!(
+        data.rooms.ref_by_name("room_second", &data.strings)?,
+        second
+    );
+
+    data.reorder_list(ChunkName::ROOM, &[1, 0])?;
+    let second: GMRef<Room> = data.rooms.ref_by_name("room_second", &data.strings)?;
+    assert_eq!(second, GMRef::new(0));
+    assert_eq!(data.general_info.room_order, [GMRef::new(1), second]);
+
+    let data = reparse(&data)?;
+    let code = code_by_name(&data, "gml_Script_scr_goto_second")?;
+    assert_eq!(code.instructions[0], Instruction::PushReference {
+        asset_reference: AssetReference::Room(second),
+    });
+    Ok(())
+}
+
+#[test]
+fn assets_layer_builders() -> Result<()> {
+    let mut data = GMData::default();
+    let layer_name: GMRef<String> = data.strings.make("Assets");
+    let sprite_name: GMRef<String> = data.strings.make("graphic_1");
+    let particle_name: GMRef<String> = data.strings.make("particle_1");
+    let text_name: GMRef<String> = data.strings.make("text_1");
+    let text: GMRef<String> = data.strings.make("Hello");
+    let mut layer = RoomLayer {
+        name: layer_name,
+        id: 0,
+        ty: layer::Type::Instances,
+        depth: 0,
+        x_offset: 0.0,
+        y_offset: 0.0,
+        horizontal_speed: 0.0,
+        vertical_speed: 0.0,
+        is_visible: true,
+        effect_data_2022_1: Some(layer::Data2022_1::default()),
+        data: layer::Data::Assets(layer::Assets {
+            legacy_tiles: vec![],
+            sprites: vec![],
+            sequences: vec![],
+            nine_slices: vec![],
+            particle_systems: vec![],
+            text_items: vec![],
+        }),
+    };
+    assert!(layer.assets_mut().is_err());
+    layer.ty = layer::Type::Assets;
+
+    let assets: &mut layer::Assets = layer.assets_mut()?;
+    assets
+        .add_sprite_instance(sprite_name, GMRef::none(), 16, 32)
+        .rotation = 90.0;
+    assets.add_particle_instance(particle_name, GMRef::none(), -8, 4);
+    assets.add_text_item(text_name, GMRef::none(), text, 100, 200);
+    let expected: layer::Assets = assets.clone();
+    data.rooms.by_ref_mut(GMRef::new(0))?.layers.push(layer);
+
+    let data = reparse(&data)?;
+    let room: &Room = data.rooms.by_ref(GMRef::new(0))?;
+    let assets: &layer::Assets = room
+        .layer_by_name("Assets", &data.strings)?
+        .data
+        .as_assets()?;
+    assert_eq!(*assets, expected);
+    assert_eq!(assets.sprites[0].scale_x, 1.0);
+    assert_eq!(assets.sprites[0].color, 0xFFFF_FFFF);
+    assert_eq!(assets.text_items[0].x, 100);
+    Ok(())
+}
+
+#[test]
+fn gmac_tile_padding_bug() -> Result<()> {
+    for emulate in [true, false] {
+        let mut data = GMData::default();
+        data.meta.emulate_gmac_tile_padding_bug = emulate;
+        let mut layers: Vec<RoomLayer> = Vec::new();
+        for (id, tile_data) in [vec![1, 2, 3, 4], vec![5, 6]].into_iter().enumerate() {
+            layers.push(RoomLayer {
+                name: data.strings.make(&format!("Tiles_{id}")),
+                id: id as u32,
+                ty: layer::Type::Tiles,
+                depth: 0,
+                x_offset: 0.0,
+                y_offset: 0.0,
+                horizontal_speed: 0.0,
+                vertical_speed: 0.0,
+                is_visible: true,
+                effect_data_2022_1: Some(layer::Data2022_1::default()),
+                data: layer::Data::Tiles(layer::Tiles {
+                    tileset: GMRef::none(),
+                    width: tile_data.len() as u32,
+                    height: 1,
+                    tile_data,
+                }),
+            });
+        }
+        data.rooms.by_ref_mut(GMRef::new(0))?.layers = layers.clone();
+
+        let reparsed = reparse(&data).ctx(|| format!("reparsing with padding {emulate}"))?;
+        assert_eq!(reparsed.meta.emulate_gmac_tile_padding_bug, emulate);
+        assert_eq!(reparsed.rooms.by_ref(GMRef::new(0))?.layers, layers);
+        assert_eq!(build_bytes(&reparsed)?, build_bytes(&data)?);
+    }
+    Ok(())
+}
+
+/// Like [`gmac_tile_padding_bug`], but with layers ending in an empty (-1) tile.
+/// GameMaker merges the padding into the repeat run of that last tile.
+#[test]
+fn gmac_tile_padding_bug_last_tile_empty() -> Result<()> {
+    for emulate in [true, false] {
+        let mut data = GMData::default();
+        data.meta.emulate_gmac_tile_padding_bug = emulate;
+        let mut layers: Vec<RoomLayer> = Vec::new();
+        let empty = u32::MAX;
+        let tile_datas = [vec![1, empty], vec![empty], vec![2, 3], vec![4, 4, empty]];
+        for (id, tile_data) in tile_datas.into_iter().enumerate() {
+            layers.push(RoomLayer {
+                name: data.strings.make(&format!("Tiles_{id}")),
+                id: id as u32,
+                ty: layer::Type::Tiles,
+                depth: 0,
+                x_offset: 0.0,
+                y_offset: 0.0,
+                horizontal_speed: 0.0,
+                vertical_speed: 0.0,
+                is_visible: true,
+                effect_data_2022_1: Some(layer::Data2022_1::default()),
+                data: layer::Data::Tiles(layer::Tiles {
+                    tileset: GMRef::none(),
+                    width: tile_data.len() as u32,
+                    height: 1,
+                    tile_data,
+                }),
+            });
+        }
+        data.rooms.by_ref_mut(GMRef::new(0))?.layers = layers.clone();
+
+        let reparsed = reparse(&data).ctx(|| format!("reparsing with padding {emulate}"))?;
+        assert_eq!(reparsed.meta.emulate_gmac_tile_padding_bug, emulate);
+        assert_eq!(reparsed.rooms.by_ref(GMRef::new(0))?.layers, layers);
+        assert_eq!(build_bytes(&reparsed)?, build_bytes(&data)?);
+    }
+    Ok(())
+}
+
+#[test]
+fn validate_room_layer_data() -> Result<()> {
+    let mut data = GMData::default();
+    let name: GMRef<String> = data.strings.make("Instances");
+    let mut layer = RoomLayer {
+        name,
+        id: 0,
+        ty: layer::Type::Tiles,
+        depth: 0,
+        x_offset: 0.0,
+        y_offset: 0.0,
+        horizontal_speed: 0.0,
+        vertical_speed: 0.0,
+        is_visible: true,
+        effect_data_2022_1: Some(layer::Data2022_1::default()),
+        data: layer::Data::Instances(layer::Instances { instances: vec![] }),
+    };
+    data.rooms
+        .by_ref_mut(GMRef::new(0))?
+        .layers
+        .push(layer.clone());
+    assert!(data.validate_room_layer_data().is_err());
+    assert!(data.validate().is_err());
+
+    layer.ty = layer::Type::Instances;
+    data.rooms.by_ref_mut(GMRef::new(0))?.layers = vec![layer];
+    data.validate_room_layer_data()?;
+    reparse(&data)?.validate_room_layer_data()?;
+    Ok(())
+}
+
+#[test]
+fn instance_creation_order() -> Result<()> {
+    let mut data = GMData::default();
+    assert!(data.meta.version >= GMVersion::GM2024_13);
+    let object: GMRef<GameObject> = make_game_object("obj_enemy", &mut data);
+    let first: InstanceID = data.add_object_to_room("room0", object, 16, 16)?;
+    data.validate_instance_creation_order()?;
+
+    // Add an instance without updating the instance creation order
+    let second = InstanceID(first.0 + 1);
+    let room: &mut Room = data.rooms.by_name_mut("room0", &data.strings)?;
+    let mut game_object = room.game_objects[0].clone();
+    game_object.instance_id = second;
+    room.game_objects.push(game_object);
+    room.instance_creation_order.insert(0, InstanceID(12345));
+    let error: String = data.validate_instance_creation_order().unwrap_err().chain();
+    assert!(
+        error.contains("Found 2 instance creation order issues"),
+        "{error}"
+    );
+    assert!(error.contains("nonexistent instance ID 12345"), "{error}");
+    let missing = format!("Instance ID {} is missing", second.0);
+    assert!(error.contains(&missing), "{error}");
+    let room: &Room = data.rooms.by_name("room0", &data.strings)?;
+    let issues: Vec<String> = room.check_instance_creation_order();
+    assert_eq!(issues.len(), 2, "{issues:?}");
+    assert!(issues[1].contains(&missing));
+
+    assert_eq!(data.fix_instance_creation_order(), 1);
+    assert_eq!(data.fix_instance_creation_order(), 0);
+    data.validate_instance_creation_order()?;
+
+    let data = reparse(&data)?;
+    let room: &Room = data.rooms.by_name("room0", &data.strings)?;
+    assert_eq!(room.instance_creation_order, [first, second]);
+    Ok(())
+}
+
+#[test]
+fn room_containing_instance() -> Result<()> {
+    let mut data = GMData::default();
+    let object: GMRef<GameObject> = make_game_object("obj_chest", &mut data);
+    let name: GMRef<String> = data.strings.make("room1");
+    let room1: GMRef<Room> = data.rooms.push(Room { name, ..Room::default() });
+    let first: InstanceID = data.add_object_to_room("room0", object, 0, 0)?;
+    let second: InstanceID = data.add_object_to_room("room1", object, 0, 0)?;
+
+    let data = reparse(&data)?;
+    assert_eq!(data.room_containing_instance(first), Some(GMRef::new(0)));
+    assert_eq!(data.room_containing_instance(second), Some(room1));
+    assert_eq!(
+        data.room_containing_instance(InstanceID(second.0 + 1)),
+        None
+    );
+    Ok(())
+}
+
+#[test]
+fn room_summaries() -> Result<()> {
+    let mut data = GMData::default();
+    let object: GMRef<GameObject> = make_game_object("obj_npc", &mut data);
+    let name: GMRef<String> = data.strings.make("room_town");
+    let town: GMRef<Room> = data.rooms.push(Room {
+        name,
+        width: 1280,
+        height: 720,
+        ..Room::default()
+    });
+    data.add_object_to_room("room_town", object, 0, 0)?;
+    data.add_object_to_room("room_town", object, 32, 0)?;
+
+    let data = reparse(&data)?;
+    let rooms: Vec<(GMRef<Room>, &str, (u32, u32))> = data.iter_rooms_with_names().collect();
+    assert_eq!(rooms.len(), 2);
+    assert_eq!(rooms[0].1, "room0");
+    assert_eq!(rooms[1], (town, "room_town", (1280, 720)));
+
+    let room: &Room = data.rooms.by_ref(town)?;
+    let layer_count: usize = room.layers.len();
+    let summary = room.summary(&data.strings);
+    assert_eq!(summary.instance_count, 2);
+    assert_eq!(
+        summary.to_string(),
+        format!(
+            "room_town (1280x720, {layer_count} layer{}, 2 instances)",
+            if layer_count == 1 { "" } else { "s" }
+        )
+    );
+    Ok(())
+}
+
+#[test]
+fn room_view_follow() -> Result<()> {
+    let mut data = GMData::default();
+    let player: GMRef<GameObject> = GMRef::new(0);
+    let room: &mut Room = data.rooms.by_ref_mut(GMRef::new(0))?;
+    room.views.clear();
+    room.flags.remove(RoomFlags::ENABLE_VIEWS);
+
+    let follow = ViewFollow {
+        object: player,
+        border: (100, 80),
+        speed: (8, -1),
+    };
+    room.set_view(2, true, (0, 0, 320, 240), (0, 0, 960, 720), Some(follow))?;
+    assert!(
+        room.set_view(Room::MAX_VIEWS, true, (0, 0, 1, 1), (0, 0, 1, 1), None)
+            .is_err()
+    );
+    assert_eq!(room.views.len(), 3);
+    assert!(room.flags.contains(RoomFlags::ENABLE_VIEWS));
+    assert!(!room.views[0].enabled);
+
+    let data = reparse(&data)?;
+    let view: &RoomView = &data.rooms.by_ref(GMRef::new(0))?.views[2];
+    assert!(view.enabled);
+    assert_eq!((view.view_width, view.view_height), (320, 240));
+    assert_eq!((view.port_width, view.port_height), (960, 720));
+    assert_eq!((view.border_x, view.border_y), (100, 80));
+    assert_eq!((view.speed_x, view.speed_y), (8, -1));
+    assert_eq!(view.object, player);
+    Ok(())
+}
+
+#[test]
+fn swap_room_order() -> Result<()> {
+    let mut data = GMData::default();
+    let first: GMRef<Room> = GMRef::new(0);
+    let mut room: Room = data.rooms.by_ref(first)?.clone();
+    room.name = data.strings.make("room_second");
+    let second: GMRef<Room> = data.rooms.push(room);
+    data.set_room_order(vec![first, second])?;
+
+    data.set_room_order(vec![second, first])?;
+    let reparsed = reparse(&data)?;
+    assert_eq!(reparsed.room_order(), [second, first]);
+    assert_eq!(reparsed.startup_room(), second);
+
+    assert!(data.set_room_order(vec![first, first]).is_err());
+    assert!(data.set_room_order(vec![GMRef::new(2)]).is_err());
+    assert_eq!(data.room_order(), [second, first]);
+    Ok(())
+}
@@ -0,0 +1,271 @@
+// SPDX-License-Identifier: GPL-3.0-only
+//! Tests for sprites and their frames.
+mod common;
+
+use std::collections::HashMap;
+
+use common::make_game_object;
+use common::make_sprite;
+use common::make_texture_page;
+use common::make_texture_page_item;
+use common::reparse;
+use image::RgbaImage;
+use libgm::prelude::*;
+use libgm::wad::Blob;
+use libgm::wad::GMVersion;
+use libgm::wad::chunk::ChunkName;
+use libgm::wad::elem::game_object::GameObject;
+use libgm::wad::elem::sequence::PlaybackType;
+use libgm::wad::elem::sequence::Sequence;
+use libgm::wad::elem::sequence::SpeedType;
+use libgm::wad::elem::sequence::Track;
+use libgm::wad::elem::sequence::track::Keyframe;
+use libgm::wad::elem::sequence::track::Keyframes;
+use libgm::wad::elem::sequence::track::keyframe;
+use libgm::wad::elem::sequence::track::keyframe::Graphic;
+use libgm::wad::elem::sprite::MaskEntry;
+use libgm::wad::elem::sprite::NineSlice;
+use libgm::wad::elem::sprite::SepMaskType;
+use libgm::wad::elem::sprite::Special;
+use libgm::wad::elem::sprite::SpecialData;
+use libgm::wad::elem::sprite::Sprite;
+use libgm::wad::elem::sprite::nine_slice::Slice;
+use libgm::wad::elem::sprite::nine_slice::TileMode;
+use libgm::wad::elem::texture_page_item::TexturePageItem;
+
+#[test]
+fn reorder_sprites_in_sprite_sequence() -> Result<()> {
+    let mut data = GMData::default();
+    let first: GMRef<Sprite> = make_sprite("spr_first", &mut data);
+    let second: GMRef<Sprite> = make_sprite("spr_second", &mut data);
+    let track = Track::new(
+        data.strings.make("GMGraphicTrack"),
+        data.strings.make("frames"),
+        Keyframes::Graphic(keyframe::Data {
+            keyframes: vec![Keyframe::new(0.0, 1.0, [Graphic { sprite: first }])],
+        }),
+    );
+    let sequence = Sequence {
+        name: data.strings.make("seq_second"),
+        playback: PlaybackType::Loop,
+        playback_speed: 1.0,
+        playback_speed_type: SpeedType::FramesPerGameFrame,
+        length: 1.0,
+        origin_x: 0,
+        origin_y: 0,
+        volume: 1.0,
+        width: None,
+        height: None,
+        broadcast_messages: Vec::new(),
+        tracks: vec![track],
+        function_ids: HashMap::new(),
+        moments: Vec::new(),
+    };
+    data.sprites.by_ref_mut(second)?.special_fields = Some(Special {
+        special_version: 2,
+        data: SpecialData::Normal,
+        playback_speed: 1.0,
+        playback_speed_type: SpeedType::FramesPerGameFrame,
+        sequence: Some(sequence),
+        nine_slice: None,
+        swf: None,
+    });
+
+    let count: usize = data.sprites.len();
+    let mut permutation: Vec<usize> = (0..count).collect();
+    permutation.swap(first.index().unwrap(), second.index().unwrap());
+    data.reorder_list(ChunkName::SPRT, &permutation)?;
+
+    let first: GMRef<Sprite> = data.sprites.ref_by_name("spr_first", &data.strings)?;
+    let second: &Sprite = data.sprites.by_name("spr_second", &data.strings)?;
+    let sequence: &Sequence = second
+        .special_fields
+        .as_ref()
+        .unwrap()
+        .sequence
+        .as_ref()
+        .unwrap();
+    let keyframes = &sequence.tracks[0].keyframes;
+    let Keyframes::Graphic(keyframes) = keyframes else {
+        panic!("Expected graphic keyframes");
+    };
+    assert_eq!(keyframes.keyframes[0].channels[0].value.sprite, first);
+    Ok(())
+}
+
+#[test]
+fn set_object_sprite() -> Result<()> {
+    let mut data = GMData::default();
+    make_game_object("obj_player", &mut data);
+    make_sprite("spr_player", &mut data);
+    let reskin: GMRef<Sprite> = make_sprite("spr_reskin", &mut data);
+    assert!(data.object_sprite("obj_player")?.is_none());
+
+    data.set_object_sprite("obj_player", "spr_reskin")?;
+    assert!(data.set_object_sprite("obj_player", "spr_missing").is_err());
+    assert!(data.set_object_sprite("obj_missing", "spr_reskin").is_err());
+    assert!(data.object_sprite("obj_missing").is_err());
+
+    let data = reparse(&data)?;
+    let object: &GameObject = data.game_objects.by_name("obj_player", &data.strings)?;
+    assert_eq!(object.sprite, reskin);
+    let sprite: &Sprite = data.object_sprite("obj_player")?.ok_or("Sprite not set")?;
+    assert_eq!(sprite.name.display(&data.strings), "spr_reskin");
+    Ok(())
+}
+
+#[test]
+fn sprite_spritesheet() -> Result<()> {
+    let red = [255, 0, 0, 255];
+    let blue = [0, 0, 255, 255];
+    let mut data = GMData::default();
+    let mut textures: Vec<GMRef<TexturePageItem>> = Vec::new();
+    // Each frame is on its own page and only covers the top left quarter.
+    for color in [blue, red] {
+        let page = data.texture_pages.push(make_texture_page(color));
+        textures.push(data.texture_page_items.push(TexturePageItem {
+            bounding_width: 8,
+            bounding_height: 8,
+            ..make_texture_page_item(page, 0, 0, 4, 4)
+        }));
+    }
+    textures.push(GMRef::none());
+
+    let sprite: GMRef<Sprite> = make_sprite("spr_test", &mut data);
+    data.sprites.by_ref_mut(sprite)?.textures = textures;
+
+    let sheet: RgbaImage = data.sprite_spritesheet(sprite)?;
+    assert_eq!(sheet.dimensions(), (24, 8));
+    assert_eq!(sheet.get_pixel(0, 0).0, blue);
+    assert_eq!(sheet.get_pixel(5, 5).0, [0; 4]);
+    assert_eq!(sheet.get_pixel(8, 0).0, red);
+    assert_eq!(sheet.get_pixel(16, 0).0, [0; 4]);
+    Ok(())
+}
+
+#[test]
+fn sprite_nine_slice() -> Result<()> {
+    let mut data = GMData::default();
+    let sprite: GMRef<Sprite> = make_sprite("spr_panel", &mut data);
+    data.sprites.by_ref_mut(sprite)?.special_fields = Some(Special {
+        special_version: 1,
+        data: SpecialData::Normal,
+        playback_speed: 1.0,
+        playback_speed_type: SpeedType::FramesPerGameFrame,
+        sequence: None,
+        nine_slice: None,
+        swf: None,
+    });
+
+    data.set_sprite_nine_slice_enabled(sprite, true)?;
+    let nine_slice: &mut NineSlice = data.sprites.by_ref_mut(sprite)?.nine_slice_mut().unwrap();
+    nine_slice.set_margins(4, 6, 8, 10)?;
+    nine_slice.set_tile_mode(Slice::Center, TileMode::Repeat);
+    nine_slice.set_tile_mode(Slice::Top, TileMode::Mirror);
+    assert!(nine_slice.set_margins(-1, 0, 0, 0).is_err());
+    assert_eq!(nine_slice.margins(), (4, 6, 8, 10));
+
+    let reparsed: GMData = reparse(&data)?;
+    let sprite: &Sprite = reparsed.sprites.by_ref(sprite)?;
+    assert_eq!(sprite.special_fields.as_ref().unwrap().special_version, 3);
+    assert!(sprite.is_nine_slice_enabled());
+    let nine_slice: &NineSlice = sprite.nine_slice().unwrap();
+    assert_eq!(nine_slice.margins(), (4, 6, 8, 10));
+    assert_eq!(nine_slice.tile_mode(Slice::Center), TileMode::Repeat);
+    assert_eq!(nine_slice.tile_mode(Slice::Top), TileMode::Mirror);
+    assert_eq!(nine_slice.tile_mode(Slice::Left), TileMode::Stretch);
+    assert_eq!(data.sprites, reparsed.sprites);
+
+    // Nine slice data does not exist before GMS 2.3.2
+    let mut data = GMData::default();
+    data.meta.version = GMVersion::GMS2_3_1;
+    let sprite: GMRef<Sprite> = make_sprite("spr_old_panel", &mut data);
+    assert!(data.set_sprite_nine_slice_enabled(sprite, true).is_err());
+    assert!(data.sprites.by_ref(sprite)?.nine_slice().is_none());
+    Ok(())
+}
+
+#[test]
+fn set_sprite_frame_image() -> Result<()> {
+    let green = [0, 255, 0, 255];
+    let mut data = GMData::default();
+    let page = data.texture_pages.push(make_texture_page([255, 0, 0, 255]));
+    let texture = data
+        .texture_page_items
+        .push(make_texture_page_item(page, 0, 0, 8, 8));
+    let sprite: GMRef<Sprite> = make_sprite("spr_swap", &mut data);
+    data.sprites.by_ref_mut(sprite)?.textures = vec![texture, GMRef::none()];
+    let single: GMRef<Sprite> = make_sprite("spr_single", &mut data);
+    data.sprites.by_ref_mut(single)?.textures = vec![GMRef::none()];
+
+    // Frame 0 fits into its current region, so it is drawn in place.
+    let pixels: Vec<u8> = green.repeat(8 * 8);
+    data.set_sprite_frame_image(sprite, 0, 8, 8, &pixels)?;
+    assert_eq!(data.texture_pages.len(), 1);
+    // Frame 1 has no texture yet, so it gets a new page.
+    data.set_sprite_frame_image(sprite, 1, 8, 8, &pixels)?;
+    assert_eq!(data.texture_pages.len(), 2);
+
+    assert!(
+        data.set_sprite_frame_image(sprite, 1, 4, 4, &green.repeat(16))
+            .is_err()
+    );
+    assert!(
+        data.set_sprite_frame_image(sprite, 2, 8, 8, &pixels)
+            .is_err()
+    );
+    assert!(
+        data.set_sprite_frame_image(sprite, 0, 8, 8, &pixels[4..])
+            .is_err()
+    );
+
+    // Single frame sprites are resized to the image.
+    data.set_sprite_frame_image(single, 0, 16, 4, &green.repeat(16 * 4))?;
+
+    let reparsed: GMData = reparse(&data)?;
+    for frame in reparsed.sprite_frames(sprite)? {
+        assert_eq!(frame.dimensions(), (8, 8));
+        assert!(frame.pixels().all(|pixel| pixel.0 == green));
+    }
+    let single_sprite: &Sprite = reparsed.sprites.by_ref(single)?;
+    assert_eq!((single_sprite.width, single_sprite.height), (16, 4));
+    assert_eq!(single_sprite.margin_bottom, 3);
+    let frames: Vec<RgbaImage> = reparsed.sprite_frames(single)?;
+    assert!(frames[0].pixels().all(|pixel| pixel.0 == green));
+    Ok(())
+}
+
+#[test]
+fn set_sprite_frame_image_regenerates_masks() -> Result<()> {
+    let mut data = GMData::default();
+    let page = data.texture_pages.push(make_texture_page([255, 0, 0, 255]));
+    let texture = data
+        .texture_page_items
+        .push(make_texture_page_item(page, 0, 0, 8, 8));
+    let sprite: GMRef<Sprite> = make_sprite("spr_precise", &mut data);
+    let sprite_mut: &mut Sprite = data.sprites.by_ref_mut(sprite)?;
+    sprite_mut.sep_masks = SepMaskType::Precise;
+    sprite_mut.textures = vec![texture];
+    sprite_mut.collision_masks = vec![MaskEntry {
+        data: Blob::new(vec![0xFF; 8]),
+        width: 8,
+        height: 8,
+    }];
+
+    // Only the left quarter of the new image is opaque.
+    let mut pixels: Vec<u8> = Vec::new();
+    for _ in 0..4 {
+        pixels.extend([0, 0, 255, 255].repeat(4));
+        pixels.extend([0; 4].repeat(12));
+    }
+    data.set_sprite_frame_image(sprite, 0, 16, 4, &pixels)?;
+
+    let reparsed: GMData = reparse(&data)?;
+    let sprite: &Sprite = reparsed.sprites.by_ref(sprite)?;
+    assert_eq!((sprite.width, sprite.height), (16, 4));
+    // Since GM 2024.6, masks only cover the bounding box (8x4 here).
+    let mask: &MaskEntry = &sprite.collision_masks[0];
+    assert_eq!((mask.width, mask.height), (8, 4));
+    assert_eq!(*mask.data, [0xF0; 4]);
+    Ok(())
+}
@@ -0,0 +1,116 @@
+// SPDX-License-Identifier: GPL-3.0-only
+//! Tests for strings and their layout.
+mod common;
+
+use std::collections::HashSet;
+
+use common::find_chunk;
+use common::make_game_object;
+use common::padding;
+use common::reparse;
+use libgm::gml::Code;
+use libgm::gml::Instruction;
+use libgm::gml::instruction::DataType;
+use libgm::gml::instruction::PushValue;
+use libgm::prelude::*;
+use libgm::wad::build_bytes;
+
+#[test]
+fn merge_strings_from() -> Result<()> {
+    let mut original = GMData::default();
+    original.strings.make("Hello");
+    original.strings.make("Goodbye");
+    let mut translated = original.clone();
+    *translated
+        .strings
+        .by_ref_mut(original.strings.find("Hello")?)? = "Hallo".to_owned();
+    *translated
+        .strings
+        .by_ref_mut(original.strings.find("Goodbye")?)? = "Tschüss".to_owned();
+
+    let mut updated = GMData::default();
+    let new_line: GMRef<String> = updated.strings.make("New line");
+    let goodbye: GMRef<String> = updated.strings.make("Goodbye");
+    let hello: GMRef<String> = updated.strings.make("Hello");
+
+    assert_eq!(updated.merge_strings_from(&original, &translated), 2);
+    assert_eq!(updated.strings.by_ref(hello)?, "Hallo");
+    assert_eq!(updated.strings.by_ref(goodbye)?, "Tschüss");
+    assert_eq!(updated.strings.by_ref(new_line)?, "New line");
+    Ok(())
+}
+
+#[test]
+fn string_alignment_padding() -> Result<()> {
+    let mut data = GMData::default();
+    data.strings.align = true;
+    // Every string length modulo 4 leads to a different amount of padding.
+    for length in 0..8 {
+        data.strings.make(&"x".repeat(length));
+    }
+    let raw_data: Vec<u8> = build_bytes(&data)?;
+    let read_u32 = |pos: usize| u32::from_le_bytes(raw_data[pos..pos + 4].try_into().unwrap());
+
+    let chunk_pos = find_chunk(&raw_data, b"STRG");
+    let count = read_u32(chunk_pos) as usize;
+    let mut end = chunk_pos + 4 + count * 4;
+    for i in 0..count {
+        let pos = read_u32(chunk_pos + 4 + i * 4) as usize;
+        assert_eq!(pos % 4, 0, "string #{i} is not aligned");
+        assert_eq!(pos - end, padding(end, 4), "string #{i} has wrong padding");
+        assert!(raw_data[end..pos].iter().all(|&byte| byte == 0));
+        end = pos + 4 + read_u32(pos) as usize + 1;
+    }
+    Ok(())
+}
+
+#[test]
+fn dedupe_strings() -> Result<()> {
+    let mut data = GMData::default();
+    assert_eq!(data.dedupe_strings()?, 0);
+
+    let string_count: usize = data.strings.len();
+    let shared: GMRef<String> = data.strings.make_new("shared".to_owned());
+    let other: GMRef<String> = data.strings.make_new("other".to_owned());
+    let shared2: GMRef<String> = data.strings.make_new("shared".to_owned());
+    let other2: GMRef<String> = data.strings.make_new("other".to_owned());
+    let shared3: GMRef<String> = data.strings.make_new("shared".to_owned());
+
+    let object = make_game_object("obj_shared", &mut data);
+    data.game_objects.by_ref_mut(object)?.name = shared2;
+    data.rooms.by_ref_mut(GMRef::new(0))?.caption = other2;
+    data.general_info.display_name = shared2;
+    let code = data.make_code("gml_Script_scr_shared", vec![
+        Instruction::Push { value: PushValue::String(shared3) },
+        Instruction::PopDiscard { data_type: DataType::String },
+        Instruction::Push { value: PushValue::String(other) },
+        Instruction::PopDiscard { data_type: DataType::String },
+        Instruction::Exit,
+    ]);
+
+    assert_eq!(data.dedupe_strings()?, 3);
+    // "obj_shared" and the code name come after the merged strings
+    assert_eq!(data.strings.len(), string_count + 4);
+    let strings: HashSet<&String> = data.strings.elems.iter().collect();
+    assert_eq!(strings.len(), data.strings.len());
+
+    assert_eq!(data.game_objects.by_ref(object)?.name, shared);
+    assert_eq!(data.rooms.by_ref(GMRef::new(0))?.caption, other);
+    assert_eq!(data.general_info.display_name, shared);
+    let code: &Code = data.codes.by_ref(code)?;
+    assert_eq!(data.strings.by_ref(code.name)?, "gml_Script_scr_shared");
+    assert_eq!(
+        code.instructions[0].push_value(),
+        Some(PushValue::String(shared))
+    );
+    assert_eq!(
+        code.instructions[2].push_value(),
+        Some(PushValue::String(other))
+    );
+    assert_eq!(data.strings.by_ref(shared)?, "shared");
+    assert_eq!(data.strings.by_ref(other)?, "other");
+
+    let data = reparse(&data)?;
+    assert_eq!(data.game_objects.by_ref(object)?.name, shared);
+    Ok(())
+}
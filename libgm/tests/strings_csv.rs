@@ -0,0 +1,55 @@
+// SPDX-License-Identifier: GPL-3.0-only
+//! Tests for exporting and importing strings as CSV.
+use libgm::prelude::*;
+
+#[test]
+fn export_import_strings() -> Result<()> {
+    let mut data = GMData::default();
+    let tricky = [
+        "comma, separated",
+        "\"quoted\" text",
+        "multiple\nlines\r\nhere",
+        "non-ASCII: äöü 日本語 ★",
+        "",
+    ];
+    for string in tricky {
+        data.strings.make_new(string.to_owned());
+    }
+
+    let csv: String = data.export_strings_csv();
+    let original: Vec<String> = data.strings.elems.clone();
+    for string in &mut data.strings.elems {
+        string.clear();
+    }
+
+    data.import_strings_csv(&csv)?;
+    assert_eq!(data.strings.elems, original);
+    Ok(())
+}
+
+#[test]
+fn import_strings_partial() -> Result<()> {
+    let mut data = GMData::default();
+    let first = data.strings.make_new("first".to_owned());
+    let second = data.strings.make_new("second".to_owned());
+
+    let csv = format!("{},\"edited, \"\"first\"\"\"\n", first.index().unwrap());
+    assert_eq!(data.import_strings_csv(&csv)?, 1);
+    assert_eq!(data.strings.by_ref(first)?, "edited, \"first\"");
+    assert_eq!(data.strings.by_ref(second)?, "second");
+    Ok(())
+}
+
+#[test]
+fn import_strings_out_of_range() {
+    let mut data = GMData::default();
+    let count = data.strings.elems.len();
+    let original = data.strings.elems.clone();
+
+    let csv = format!("index,text\n0,changed\n{count},out of range\n");
+    assert!(data.import_strings_csv(&csv).is_err());
+    assert_eq!(data.strings.elems, original);
+
+    assert!(data.import_strings_csv("0,\"unterminated\n").is_err());
+    assert!(data.import_strings_csv("0,too,many\n").is_err());
+}
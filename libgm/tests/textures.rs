@@ -0,0 +1,324 @@
+// SPDX-License-Identifier: GPL-3.0-only
+//! Tests for texture pages, texture page items and texture groups.
+mod common;
+
+use common::make_sprite;
+use common::make_texture_page;
+use common::make_texture_page_item;
+use common::reparse;
+use image::Rgba;
+use image::RgbaImage;
+use libgm::prelude::*;
+use libgm::wad::GMVersion;
+use libgm::wad::elem::font::Font;
+use libgm::wad::elem::font::FontSize;
+use libgm::wad::elem::font::Glyph;
+use libgm::wad::elem::sprite::Sprite;
+use libgm::wad::elem::texture_page::TexturePage;
+use libgm::wad::elem::texture_page_item::TexturePageItem;
+use libgm::wad::elem::tileset::Tileset;
+
+/// Creates a texture group containing the first sprite, font and texture page.
+fn make_texture_group(data: &mut GMData) -> Result<()> {
+    let group_ref = data.make_texture_group("TestGroup");
+    assert_eq!(data.make_texture_group("TestGroup"), group_ref);
+    let group = data.texture_group_infos.by_ref_mut(group_ref)?;
+    group.sprites.push(GMRef::new(0));
+    group.fonts.push(GMRef::new(0));
+    group.texture_pages.push(GMRef::new(0));
+    Ok(())
+}
+
+#[test]
+fn texture_group_layouts() -> Result<()> {
+    // Before 2022.9 (with spine sprites), 2022.9 (with directory and extension)
+    // and 2023.1+ (without spine sprites).
+    for version in [
+        GMVersion::GM2022_1,
+        GMVersion::GM2022_9,
+        GMVersion::GM2024_14_1,
+    ] {
+        let mut data = GMData::default();
+        data.meta.version = version;
+        make_texture_group(&mut data)?;
+
+        let reparsed = reparse(&data).ctx(|| format!("reparsing with version {version}"))?;
+        assert_eq!(reparsed.texture_group_infos, data.texture_group_infos);
+        let group_ref = reparsed
+            .texture_group_infos
+            .ref_by_name("TestGroup", &reparsed.strings)?;
+        assert_eq!(
+            reparsed.texture_group_infos.group_of_sprite(GMRef::new(0)),
+            Some(group_ref),
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn assign_sprite_to_group() -> Result<()> {
+    let mut data = GMData::default();
+    let sprite: GMRef<Sprite> = make_sprite("spr_large", &mut data);
+    make_texture_group(&mut data)?;
+    let old_group = data
+        .texture_group_infos
+        .ref_by_name("TestGroup", &data.strings)?;
+    let new_group = data.make_texture_group("LargeGroup");
+
+    let previous = data.assign_sprite_to_group(sprite, "LargeGroup")?;
+    assert_eq!(previous, Some(old_group));
+    assert!(data.assign_sprite_to_group(sprite, "MissingGroup").is_err());
+    assert!(
+        data.assign_sprite_to_group(GMRef::new(999), "TestGroup")
+            .is_err()
+    );
+
+    let data = reparse(&data)?;
+    let groups = &data.texture_group_infos;
+    assert_eq!(groups.group_of_sprite(sprite), Some(new_group));
+    assert!(groups.by_ref(old_group)?.sprites.is_empty());
+    assert_eq!(groups.by_ref(new_group)?.sprites, [sprite]);
+    Ok(())
+}
+
+/// The color of the top left pixel of a texture page item.
+fn texture_item_color(data: &GMData, item: &TexturePageItem) -> Result<[u8; 4]> {
+    let page = data.texture_pages.by_ref(item.texture_page)?;
+    let image = page.image.as_ref().ok_or("Texture page is external")?;
+    let image = image.to_dynamic_image()?.to_rgba8();
+    let pixel = image.get_pixel(u32::from(item.source_x), u32::from(item.source_y));
+    Ok(pixel.0)
+}
+
+#[test]
+fn texture_items() -> Result<()> {
+    let mut data = GMData::default();
+    let page = data.texture_pages.push(make_texture_page([1, 2, 3, 255]));
+    let item_count: usize = data.texture_page_items.elems.len();
+    for (x, y) in [(0, 0), (4, 2)] {
+        data.texture_page_items.push(TexturePageItem {
+            target_x: 1,
+            target_y: 1,
+            bounding_width: 6,
+            bounding_height: 6,
+            ..make_texture_page_item(page, x, y, 4, 4)
+        });
+    }
+
+    let mut data = reparse(&data)?;
+    // Images are never needed
+    for page in &mut data.texture_pages.elems {
+        page.image = None;
+    }
+    let items: Vec<(u16, u16, GMRef<TexturePage>)> = data
+        .texture_items()
+        .skip(item_count)
+        .map(|item| (item.source_x, item.source_y, item.texture_page))
+        .collect();
+    assert_eq!(items, [(0, 0, page), (4, 2, page)]);
+    Ok(())
+}
+
+#[test]
+fn texture_page_free_space() -> Result<()> {
+    let mut data = GMData::default();
+    let page = data.texture_pages.push(make_texture_page([1, 2, 3, 255]));
+    let index: usize = page.index().unwrap();
+    assert_eq!(data.texture_page_free_space(index)?, [(0, 0, 8, 8)]);
+
+    for (x, y) in [(0, 0), (4, 2)] {
+        data.texture_page_items
+            .push(make_texture_page_item(page, x, y, 4, 4));
+    }
+
+    let data = reparse(&data)?;
+    assert_eq!(data.texture_page_free_space(index)?, [
+        (4, 0, 4, 2),
+        (0, 4, 4, 2),
+        (0, 6, 8, 2)
+    ]);
+    assert!(data.texture_page_free_space(index + 1).is_err());
+    Ok(())
+}
+
+#[test]
+fn repack_textures() -> Result<()> {
+    let red = [255, 0, 0, 255];
+    let blue = [0, 0, 255, 255];
+    let mut data = GMData::default();
+    for color in [red, blue] {
+        let page = data.texture_pages.push(make_texture_page(color));
+        // Two items sharing the same region
+        for _ in 0..2 {
+            data.texture_page_items
+                .push(make_texture_page_item(page, 0, 0, 8, 8));
+        }
+    }
+
+    let original = data.clone();
+    assert!(data.repack_textures(4).is_err());
+    assert_eq!(data.texture_page_items, original.texture_page_items);
+
+    let report = data.repack_textures(64)?;
+    assert_eq!(report.old_page_count, 2);
+    assert_eq!(report.new_page_count, 1);
+    assert_eq!(report.region_count, 2);
+    assert_eq!(report.old_area, 128);
+    assert_eq!(report.new_area, 32 * 8);
+    assert_eq!(data.texture_pages.len(), 1);
+
+    let data = reparse(&data)?;
+    for (item, original_item) in data
+        .texture_page_items
+        .elements()
+        .zip(original.texture_page_items.elements())
+    {
+        assert_eq!(item.texture_page, GMRef::new(0));
+        assert_eq!(
+            texture_item_color(&data, item)?,
+            texture_item_color(&original, original_item)?,
+        );
+    }
+    Ok(())
+}
+
+#[test]
+fn background_texture_item() -> Result<()> {
+    let mut data = GMData::default();
+    let page = data.texture_pages.push(make_texture_page([0, 255, 0, 255]));
+    let texture = data
+        .texture_page_items
+        .push(make_texture_page_item(page, 2, 0, 6, 8));
+    let mut background = Tileset {
+        name: data.strings.make("bg_sky"),
+        transparent: false,
+        smooth: false,
+        preload: false,
+        texture,
+        gms2_data: None,
+    };
+    let textured: GMRef<Tileset> = data.tilesets.push(background.clone());
+    let item = data.texture_item_for_background(textured)?.unwrap();
+    assert_eq!((item.source_x, item.source_width), (2, 6));
+    assert_eq!(item.texture_page, page);
+
+    background.texture = GMRef::none();
+    let untextured: GMRef<Tileset> = data.tilesets.push(background.clone());
+    assert!(data.texture_item_for_background(untextured)?.is_none());
+
+    background.texture = GMRef::new(100);
+    let invalid: GMRef<Tileset> = data.tilesets.push(background);
+    assert!(data.texture_item_for_background(invalid).is_err());
+    assert!(data.texture_item_for_background(GMRef::new(5)).is_err());
+    Ok(())
+}
+
+#[test]
+fn export_texture_page() -> Result<()> {
+    let mut data = GMData::default();
+    data.texture_pages
+        .push(make_texture_page([10, 20, 30, 255]));
+    let data = reparse(&data)?;
+
+    let image: RgbaImage = data.texture_page_image(0)?;
+    assert_eq!(image.get_pixel(3, 3), &Rgba([10, 20, 30, 255]));
+
+    let path = std::env::temp_dir().join(format!("libgm-texture-page-{}.png", std::process::id()));
+    data.export_texture_page(0, &path)?;
+    let exported = image::open(&path).unwrap().to_rgba8();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(exported, image);
+
+    assert!(data.texture_page_image(1).is_err());
+    Ok(())
+}
+
+fn make_glyph(character: char, x: u16) -> Glyph {
+    Glyph {
+        character: Some(character),
+        x,
+        y: 0,
+        width: 4,
+        height: 4,
+        shift_modifier: 5,
+        offset: 1,
+        kernings: vec![],
+    }
+}
+
+#[test]
+fn set_font_atlas() -> Result<()> {
+    let mut data = GMData::default();
+    let page = data.texture_pages.push(make_texture_page([0, 0, 0, 255]));
+    let texture = data
+        .texture_page_items
+        .push(make_texture_page_item(page, 0, 0, 8, 8));
+    let name: GMRef<String> = data.strings.make("fnt_main");
+    let font = data.fonts.push(Font {
+        name,
+        display_name: name,
+        em_size: FontSize::Float(12.0),
+        bold: false,
+        italic: false,
+        range_start: 65,
+        charset: 0,
+        anti_alias: 1,
+        range_end: 66,
+        texture,
+        scale_x: 1.0,
+        scale_y: 1.0,
+        ascender_offset: Some(0),
+        ascender: Some(0),
+        sdf_spread: Some(0),
+        line_height: Some(0),
+        glyphs: vec![make_glyph('A', 0), make_glyph('B', 4)],
+    });
+
+    // 4x2 atlas: left half red, right half green
+    let mut rgba: Vec<u8> = Vec::new();
+    for _ in 0..2 {
+        rgba.extend([
+            255, 0, 0, 255, 255, 0, 0, 255, 0, 255, 0, 255, 0, 255, 0, 255,
+        ]);
+    }
+    let err = data
+        .set_font_atlas(font, 4, 2, &rgba, &[('A', Some((0, 0, 2, 2)))])
+        .unwrap_err();
+    assert!(err.chain().contains("'B'"), "{}", err.chain());
+    let err = data
+        .set_font_atlas(font, 4, 2, &rgba, &[('A', Some((3, 0, 2, 2))), ('B', None)])
+        .unwrap_err();
+    assert!(err.chain().contains("out of bounds"), "{}", err.chain());
+
+    let rects = [
+        ('C', Some((2, 0, 2, 2))),
+        ('A', Some((0, 0, 2, 2))),
+        ('B', None),
+    ];
+    data.set_font_atlas(font, 4, 2, &rgba, &rects)?;
+
+    let data = reparse(&data)?;
+    let font: &Font = data.fonts.by_ref(font)?;
+    assert_eq!((font.range_start, font.range_end), (65, 67));
+    let chars: Vec<Option<char>> = font.glyphs.iter().map(|g| g.character).collect();
+    assert_eq!(chars, [Some('A'), Some('C')]);
+    let [a, c] = &font.glyphs[..] else {
+        unreachable!()
+    };
+    assert_eq!(
+        (a.x, a.y, a.width, a.height, a.shift_modifier),
+        (0, 0, 2, 2, 5)
+    );
+    assert_eq!(
+        (c.x, c.y, c.width, c.height, c.shift_modifier),
+        (2, 0, 2, 2, 2)
+    );
+
+    let item: &TexturePageItem = data.texture_page_items.by_ref(font.texture)?;
+    assert_eq!((item.source_width, item.source_height), (4, 2));
+    assert_eq!(texture_item_color(&data, item)?, [255, 0, 0, 255]);
+    let mut glyph_item: TexturePageItem = item.clone();
+    glyph_item.source_x += c.x;
+    assert_eq!(texture_item_color(&data, &glyph_item)?, [0, 255, 0, 255]);
+    Ok(())
+}